@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use tauri::{AppHandle, Manager};
+use tokio::time::sleep;
+
+use crate::backend_auth;
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::redact;
+use crate::session_store::{AdkSessionRef, SessionStore};
+use crate::stream;
+use crate::types::AdkSessionsGc;
+
+const GC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const RETENTION_ENV: &str = "PV_DESKTOP_ADK_SESSION_RETENTION_HOURS";
+const DEFAULT_RETENTION_HOURS: u64 = 24;
+
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(GC_INTERVAL).await;
+            match collect(&app).await {
+                Ok(result) if result.deleted > 0 || result.failed > 0 => eprintln!(
+                    "[adk-gc] deleted {} ADK sessions ({} failed)",
+                    result.deleted, result.failed
+                ),
+                Ok(_) => {}
+                Err(err) => redact::log("[adk-gc] cleanup pass failed:", err),
+            }
+        }
+    });
+}
+
+pub fn retention(raw: Option<String>) -> Duration {
+    let hours = raw
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_RETENTION_HOURS);
+    Duration::from_secs(hours * 60 * 60)
+}
+
+// Deletes finished execution sessions, plus unfinished ones past retention (e.g. left behind by a
+// crash). Sessions held open by /run_live or by an active run are never touched.
+pub async fn collect(app: &AppHandle) -> Result<AdkSessionsGc, AppError> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| AppError::Internal("App state is not available".to_string()))?;
+    let store = SessionStore::from_app(app)?;
+
+    let (status, _) = state.backend.lock().await.status().await?;
+    if !status.running || !status.health {
+        return Ok(AdkSessionsGc {
+            deleted: 0,
+            failed: 0,
+            remaining: store.adk_sessions_tracked()?,
+            skipped_reason: Some("Backend is not running".to_string()),
+        });
+    }
+
+    let live: HashSet<String> = stream::live_adk_session_ids(&state.live_sessions)
+        .await
+        .into_iter()
+        .collect();
+    let active_runs: HashSet<String> = state.stream_tokens.lock().await.keys().cloned().collect();
+
+    let cutoff = cutoff_ms(retention(std::env::var(RETENTION_ENV).ok()));
+    let due: Vec<AdkSessionRef> = store
+        .adk_sessions_due(cutoff)?
+        .into_iter()
+        .filter(|session| !live.contains(&session.id))
+        .filter(|session| session.finished || !active_runs.contains(&session.request_id))
+        .collect();
+
+    let client = crate::http::backend_client_builder(&status.base_url)
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to build HTTP client: {e}")))?;
+    let (mut deleted, mut failed) = (0, 0);
+    for session in &due {
+        match delete_session(&client, &status.base_url, session).await {
+            Ok(()) => {
+                store.adk_session_forget(&session.id)?;
+                deleted += 1;
+            }
+            Err(err) => {
+                redact::log("[adk-gc]", err);
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(AdkSessionsGc {
+        deleted,
+        failed,
+        remaining: store.adk_sessions_tracked()?,
+        skipped_reason: None,
+    })
+}
+
+fn cutoff_ms(retention: Duration) -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.saturating_sub(retention).as_millis() as i64
+}
+
+async fn delete_session(
+    client: &Client,
+    base_url: &str,
+    session: &AdkSessionRef,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/apps/{}/users/{}/sessions/{}",
+        base_url, session.app_name, session.user_id, session.id
+    );
+    let response = client
+        .delete(&url)
+        .headers(backend_auth::headers_for(base_url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete ADK session {}: {e}", session.id))?;
+
+    // Already gone (e.g. the backend restarted with in-memory sessions) counts as cleaned up.
+    if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
+        return Ok(());
+    }
+    Err(format!(
+        "Failed to delete ADK session {} (HTTP {})",
+        session.id,
+        response.status()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::retention;
+
+    #[test]
+    fn parses_retention_hours() {
+        assert_eq!(retention(None), Duration::from_secs(24 * 3600));
+        assert_eq!(
+            retention(Some(" 2 ".to_string())),
+            Duration::from_secs(7200)
+        );
+        assert_eq!(
+            retention(Some("0".to_string())),
+            Duration::from_secs(24 * 3600)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::types::{AnalyticsFunnel, FunnelStage, MonthlyThroughput, PhaseDuration, SessionPhase};
+
+const PHASE_ORDER: [SessionPhase; 5] = [
+    SessionPhase::IdeaInput,
+    SessionPhase::AwaitingApproval,
+    SessionPhase::Running,
+    SessionPhase::Completed,
+    SessionPhase::Failed,
+];
+
+#[derive(Debug, Clone)]
+pub struct AnalyticsSession {
+    pub id: String,
+    pub created_month: String,
+    pub has_idea: bool,
+    pub pursued: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseEntry {
+    pub session_id: String,
+    pub phase: SessionPhase,
+    pub entered_at_ms: i64,
+    pub month: String,
+}
+
+// `entries` must be ordered by session and entry time. A session counts as pursued once a
+// completed report has at least one next-step task checked off.
+pub fn funnel(sessions: &[AnalyticsSession], entries: &[PhaseEntry]) -> AnalyticsFunnel {
+    let mut reached: HashMap<&str, HashSet<SessionPhase>> = HashMap::new();
+    for entry in entries {
+        reached
+            .entry(entry.session_id.as_str())
+            .or_default()
+            .insert(entry.phase);
+    }
+    let has = |session: &AnalyticsSession, phases: &[SessionPhase]| {
+        reached
+            .get(session.id.as_str())
+            .is_some_and(|seen| phases.iter().any(|p| seen.contains(p)))
+    };
+
+    let after_plan = [
+        SessionPhase::Running,
+        SessionPhase::Completed,
+        SessionPhase::Failed,
+    ];
+    let ideas: Vec<&AnalyticsSession> = sessions
+        .iter()
+        .filter(|s| s.has_idea || has(s, &[SessionPhase::AwaitingApproval]) || has(s, &after_plan))
+        .collect();
+    let approved = ideas.iter().filter(|s| has(s, &after_plan)).count();
+    let completed: Vec<&&AnalyticsSession> = ideas
+        .iter()
+        .filter(|s| has(s, &[SessionPhase::Completed]))
+        .collect();
+    let pursued = completed.iter().filter(|s| s.pursued).count();
+
+    let mut stages = Vec::new();
+    let mut previous: Option<usize> = None;
+    for (stage, count) in [
+        ("ideas_entered", ideas.len()),
+        ("plans_approved", approved),
+        ("completed", completed.len()),
+        ("pursued", pursued),
+    ] {
+        stages.push(FunnelStage {
+            stage: stage.to_string(),
+            count: count as u64,
+            conversion_from_previous: previous.filter(|p| *p > 0).map(|p| count as f64 / p as f64),
+        });
+        previous = Some(count);
+    }
+
+    let mut durations: HashMap<SessionPhase, (i64, u64)> = HashMap::new();
+    for pair in entries.windows(2) {
+        let (current, next) = (&pair[0], &pair[1]);
+        if current.session_id != next.session_id {
+            continue;
+        }
+        let total = durations.entry(current.phase).or_default();
+        total.0 += (next.entered_at_ms - current.entered_at_ms).max(0);
+        total.1 += 1;
+    }
+    let phase_durations = PHASE_ORDER
+        .iter()
+        .filter_map(|phase| {
+            let (total_ms, samples) = durations.get(phase)?;
+            Some(PhaseDuration {
+                phase: *phase,
+                average_ms: total_ms / *samples as i64,
+                samples: *samples,
+            })
+        })
+        .collect();
+
+    let mut monthly: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for session in &ideas {
+        monthly.entry(session.created_month.as_str()).or_default().0 += 1;
+    }
+    for entry in entries
+        .iter()
+        .filter(|e| e.phase == SessionPhase::Completed)
+    {
+        monthly.entry(entry.month.as_str()).or_default().1 += 1;
+    }
+
+    AnalyticsFunnel {
+        stages,
+        phase_durations,
+        monthly: monthly
+            .into_iter()
+            .map(|(month, (ideas, completed))| MonthlyThroughput {
+                month: month.to_string(),
+                ideas,
+                completed,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{funnel, AnalyticsSession, PhaseEntry};
+    use crate::types::SessionPhase;
+
+    fn session(id: &str, has_idea: bool, pursued: bool) -> AnalyticsSession {
+        AnalyticsSession {
+            id: id.to_string(),
+            created_month: "2026-01".to_string(),
+            has_idea,
+            pursued,
+        }
+    }
+
+    fn entry(id: &str, phase: SessionPhase, at: i64, month: &str) -> PhaseEntry {
+        PhaseEntry {
+            session_id: id.to_string(),
+            phase,
+            entered_at_ms: at,
+            month: month.to_string(),
+        }
+    }
+
+    #[test]
+    fn computes_conversion_durations_and_monthly_throughput() {
+        let sessions = vec![
+            session("a", true, true),
+            session("b", true, false),
+            session("c", false, false),
+        ];
+        let entries = vec![
+            entry("a", SessionPhase::IdeaInput, 0, "2026-01"),
+            entry("a", SessionPhase::AwaitingApproval, 100, "2026-01"),
+            entry("a", SessionPhase::Running, 300, "2026-01"),
+            entry("a", SessionPhase::Completed, 1_300, "2026-02"),
+            entry("b", SessionPhase::IdeaInput, 0, "2026-01"),
+            entry("b", SessionPhase::AwaitingApproval, 300, "2026-01"),
+            entry("c", SessionPhase::IdeaInput, 0, "2026-01"),
+        ];
+
+        let result = funnel(&sessions, &entries);
+        let counts: Vec<u64> = result.stages.iter().map(|s| s.count).collect();
+        assert_eq!(counts, vec![2, 1, 1, 1]);
+        assert_eq!(result.stages[0].conversion_from_previous, None);
+        assert_eq!(result.stages[1].conversion_from_previous, Some(0.5));
+
+        let idea = &result.phase_durations[0];
+        assert_eq!(
+            (idea.phase, idea.average_ms, idea.samples),
+            (SessionPhase::IdeaInput, 200, 2)
+        );
+        assert_eq!(result.phase_durations[2].average_ms, 1_000);
+
+        assert_eq!(result.monthly.len(), 2);
+        assert_eq!(
+            (result.monthly[0].ideas, result.monthly[0].completed),
+            (2, 0)
+        );
+        assert_eq!(
+            (result.monthly[1].ideas, result.monthly[1].completed),
+            (0, 1)
+        );
+    }
+}
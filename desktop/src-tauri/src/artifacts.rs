@@ -0,0 +1,321 @@
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::types::StreamRunInput;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactPart {
+    pub name: Option<String>,
+    pub mime_type: String,
+    pub data: Option<String>,
+    pub uri: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactRef {
+    pub name: String,
+    pub version: Option<i64>,
+}
+
+pub fn extract_artifact_parts(event: &Value) -> Vec<ArtifactPart> {
+    let Some(parts) = event
+        .get("content")
+        .and_then(|content| content.get("parts"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    parts.iter().filter_map(part_artifact).collect()
+}
+
+fn part_artifact(part: &Value) -> Option<ArtifactPart> {
+    let field = |camel: &str, snake: &str| part.get(camel).or_else(|| part.get(snake));
+    let str_field = |value: &Value, camel: &str, snake: &str| {
+        value
+            .get(camel)
+            .or_else(|| value.get(snake))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    if let Some(inline) = field("inlineData", "inline_data") {
+        return Some(ArtifactPart {
+            name: str_field(inline, "displayName", "display_name"),
+            mime_type: str_field(inline, "mimeType", "mime_type")
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            data: Some(str_field(inline, "data", "data")?),
+            uri: None,
+        });
+    }
+
+    let file = field("fileData", "file_data")?;
+    Some(ArtifactPart {
+        name: str_field(file, "displayName", "display_name"),
+        mime_type: str_field(file, "mimeType", "mime_type")
+            .unwrap_or_else(|| "application/octet-stream".to_string()),
+        data: None,
+        uri: Some(str_field(file, "fileUri", "file_uri")?),
+    })
+}
+
+pub fn extract_artifact_refs(event: &Value) -> Vec<ArtifactRef> {
+    let Some(delta) = event
+        .get("actions")
+        .and_then(|actions| {
+            actions
+                .get("artifactDelta")
+                .or_else(|| actions.get("artifact_delta"))
+        })
+        .and_then(Value::as_object)
+    else {
+        return Vec::new();
+    };
+    delta
+        .iter()
+        .map(|(name, version)| ArtifactRef {
+            name: name.clone(),
+            version: version.as_i64(),
+        })
+        .collect()
+}
+
+// ADK serializes bytes as URL-safe base64, but hand-built events often use the standard alphabet.
+pub fn decode_data(encoded: &str) -> Option<Vec<u8>> {
+    let trimmed = encoded.trim();
+    [&URL_SAFE, &URL_SAFE_NO_PAD, &STANDARD, &STANDARD_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(trimmed).ok())
+}
+
+pub fn file_name(name: Option<&str>, mime_type: &str, index: usize) -> String {
+    let base = name
+        .and_then(|n| n.rsplit(['/', '\\']).next())
+        .map(|n| {
+            n.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .map(|n| n.trim_start_matches('.').to_string())
+        .filter(|n| !n.is_empty());
+    match base {
+        Some(base) if base.contains('.') => base,
+        Some(base) => format!("{base}.{}", extension_for(mime_type)),
+        None => format!("artifact-{index}.{}", extension_for(mime_type)),
+    }
+}
+
+fn extension_for(mime_type: &str) -> &'static str {
+    match mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        "text/csv" => "csv",
+        "text/markdown" => "md",
+        "text/plain" => "txt",
+        "text/html" => "html",
+        "application/json" => "json",
+        _ => "bin",
+    }
+}
+
+pub fn save(
+    app: &AppHandle,
+    request_id: &str,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("artifacts")
+        .join(file_name_component(request_id));
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create artifact dir {:?}: {e}", dir))?;
+    write_unique(&dir, file_name, bytes)
+}
+
+// A later artifact with the same name (e.g. a new version of a chart) is saved next to the
+// earlier one as `name-2.ext`, `name-3.ext`, ... instead of replacing it.
+fn write_unique(dir: &Path, file_name: &str, bytes: &[u8]) -> Result<PathBuf, String> {
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (file_name, String::new()),
+    };
+    for index in 1u32.. {
+        let path = match index {
+            1 => dir.join(file_name),
+            n => dir.join(format!("{stem}-{n}{ext}")),
+        };
+        let mut file = match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(format!("Failed to create artifact {:?}: {err}", path)),
+        };
+        file.write_all(bytes)
+            .map_err(|e| format!("Failed to write artifact {:?}: {e}", path))?;
+        return Ok(path);
+    }
+    Err(format!("No free file name for artifact '{file_name}'"))
+}
+
+// Artifact names are free text from the agent; anything outside the unreserved set is escaped so
+// a `/`, `?` or `#` stays part of the name.
+fn encode_path_segment(raw: &str) -> String {
+    raw.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn file_name_component(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub async fn fetch_artifact(
+    base_url: &str,
+    input: &StreamRunInput,
+    artifact: &ArtifactRef,
+) -> Result<ArtifactPart, String> {
+    let mut url = format!(
+        "{}/apps/{}/users/{}/sessions/{}/artifacts/{}",
+        base_url,
+        input.app_name,
+        input.user_id,
+        input.session_id,
+        encode_path_segment(&artifact.name)
+    );
+    if let Some(version) = artifact.version {
+        url.push_str(&format!("?version={version}"));
+    }
+
+    let response = crate::http::backend_client_builder(&url)
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build artifact client: {e}"))?
+        .get(&url)
+        .headers(crate::backend_auth::headers_for(&url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch artifact '{}': {e}", artifact.name))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Artifact '{}' lookup returned HTTP {}",
+            artifact.name,
+            response.status()
+        ));
+    }
+
+    let part = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse artifact '{}': {e}", artifact.name))?;
+    let mut parsed = part_artifact(&part)
+        .ok_or_else(|| format!("Artifact '{}' has no file data.", artifact.name))?;
+    parsed.name.get_or_insert_with(|| artifact.name.clone());
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        decode_data, encode_path_segment, extract_artifact_parts, extract_artifact_refs, file_name,
+        write_unique,
+    };
+
+    #[test]
+    fn extracts_inline_and_file_parts() {
+        let event = json!({
+            "content": { "role": "model", "parts": [
+                { "text": "Here is the chart" },
+                { "inlineData": { "mimeType": "image/png", "data": "iVBORw0KGgo=", "displayName": "tam chart" } },
+                { "file_data": { "mime_type": "application/pdf", "file_uri": "gs://bucket/report.pdf" } }
+            ]},
+            "actions": { "artifactDelta": { "competitors.csv": 2 } }
+        });
+
+        let parts = extract_artifact_parts(&event);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].mime_type, "image/png");
+        assert_eq!(
+            decode_data(parts[0].data.as_deref().unwrap()).unwrap()[..4],
+            [0x89, b'P', b'N', b'G']
+        );
+        assert_eq!(parts[1].uri.as_deref(), Some("gs://bucket/report.pdf"));
+
+        let refs = extract_artifact_refs(&event);
+        assert_eq!(refs[0].name, "competitors.csv");
+        assert_eq!(refs[0].version, Some(2));
+    }
+
+    #[test]
+    fn builds_safe_file_names() {
+        assert_eq!(
+            file_name(Some("tam chart"), "image/png", 0),
+            "tam_chart.png"
+        );
+        assert_eq!(
+            file_name(Some("../../etc/passwd"), "text/plain", 0),
+            "passwd.txt"
+        );
+        assert_eq!(file_name(None, "application/pdf", 3), "artifact-3.pdf");
+        assert_eq!(
+            encode_path_segment("q3 plan/v2?#.csv"),
+            "q3%20plan%2Fv2%3F%23.csv"
+        );
+    }
+
+    #[test]
+    fn saving_an_existing_name_adds_an_index() {
+        let dir = std::env::temp_dir().join(format!("pv-artifacts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = write_unique(&dir, "chart.png", b"one").unwrap();
+        let second = write_unique(&dir, "chart.png", b"two").unwrap();
+        let third = write_unique(&dir, "chart.png", b"three").unwrap();
+        assert_eq!(first, dir.join("chart.png"));
+        assert_eq!(second, dir.join("chart-2.png"));
+        assert_eq!(third, dir.join("chart-3.png"));
+        assert_eq!(std::fs::read(&first).unwrap(), b"one");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Value};
+
+use crate::types::MessagePartInput;
+
+const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+
+// Types Gemini accepts as inline data; spreadsheets have to arrive as CSV.
+const SUPPORTED_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/heic",
+    "image/heif",
+    "application/pdf",
+    "text/csv",
+    "text/plain",
+    "text/markdown",
+    "text/html",
+    "application/json",
+];
+
+fn mime_for_path(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "heif" => "image/heif",
+        "pdf" => "application/pdf",
+        "csv" => "text/csv",
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "xlsx" | "xls" | "ods" | "numbers" => "spreadsheet",
+        _ => return None,
+    })
+}
+
+fn check_mime(mime_type: &str, label: &str) -> Result<(), String> {
+    if mime_type == "spreadsheet" {
+        return Err(format!(
+            "{label} is a spreadsheet workbook; export the sheet as CSV and attach that instead."
+        ));
+    }
+    if !SUPPORTED_MIME_TYPES.contains(&mime_type) {
+        return Err(format!(
+            "{label} has unsupported type {mime_type}; attach an image, PDF, CSV or text file."
+        ));
+    }
+    Ok(())
+}
+
+fn check_size(bytes: usize, label: &str) -> Result<(), String> {
+    if bytes > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "{label} is {:.1} MB; attachments are limited to {} MB.",
+            bytes as f64 / (1024.0 * 1024.0),
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+// Reads file parts into inline data up front so a bad path fails the command instead of the run.
+pub fn resolve(parts: &[MessagePartInput]) -> Result<Vec<MessagePartInput>, String> {
+    parts
+        .iter()
+        .map(|part| match part {
+            MessagePartInput::Text { .. } => Ok(part.clone()),
+            MessagePartInput::File { path, mime_type } => {
+                let path = Path::new(path);
+                let label = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                let mime_type = match mime_type {
+                    Some(mime_type) => mime_type.trim().to_ascii_lowercase(),
+                    None => mime_for_path(path)
+                        .ok_or_else(|| format!("Cannot tell the file type of {label}."))?
+                        .to_string(),
+                };
+                check_mime(&mime_type, &label)?;
+                let bytes = std::fs::read(path)
+                    .map_err(|e| format!("Failed to read attachment {label}: {e}"))?;
+                check_size(bytes.len(), &label)?;
+                Ok(MessagePartInput::InlineData {
+                    data: STANDARD.encode(&bytes),
+                    mime_type,
+                    display_name: Some(label),
+                })
+            }
+            MessagePartInput::InlineData {
+                data,
+                mime_type,
+                display_name,
+            } => {
+                let label = display_name.as_deref().unwrap_or("Attachment");
+                check_mime(mime_type, label)?;
+                let decoded = STANDARD
+                    .decode(data.trim())
+                    .map_err(|_| format!("{label} is not valid base64."))?;
+                check_size(decoded.len(), label)?;
+                Ok(part.clone())
+            }
+        })
+        .collect()
+}
+
+// Builds `new_message.parts`: the prompt text first, then any attachments in order.
+pub fn message_parts(text: &str, parts: Option<&[MessagePartInput]>) -> Vec<Value> {
+    let mut out = vec![json!({ "text": text })];
+    for part in parts.unwrap_or_default() {
+        out.push(match part {
+            MessagePartInput::Text { text } => json!({ "text": text }),
+            MessagePartInput::File { path, .. } => {
+                json!({ "text": format!("[attachment: {path}]") })
+            }
+            MessagePartInput::InlineData {
+                data,
+                mime_type,
+                display_name,
+            } => {
+                let mut inline = json!({ "mime_type": mime_type, "data": data.trim() });
+                if let Some(name) = display_name {
+                    inline["display_name"] = json!(name);
+                }
+                json!({ "inline_data": inline })
+            }
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_parts, resolve};
+    use crate::types::MessagePartInput;
+
+    #[test]
+    fn resolves_files_and_builds_inline_parts() {
+        let dir = std::env::temp_dir().join(format!("pv-attach-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        let csv = dir.join("pricing.csv");
+        std::fs::write(&csv, "plan,price\npro,12\n").expect("write csv");
+
+        let resolved = resolve(&[
+            MessagePartInput::File {
+                path: csv.to_string_lossy().to_string(),
+                mime_type: None,
+            },
+            MessagePartInput::Text {
+                text: "Landing page copy attached.".to_string(),
+            },
+        ])
+        .expect("resolves");
+        let parts = message_parts("Validate my idea", Some(&resolved));
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0]["text"], "Validate my idea");
+        assert_eq!(parts[1]["inline_data"]["mime_type"], "text/csv");
+        assert_eq!(parts[1]["inline_data"]["display_name"], "pricing.csv");
+        assert_eq!(parts[1]["inline_data"]["data"], "cGxhbixwcmljZQpwcm8sMTIK");
+
+        let workbook = resolve(&[MessagePartInput::File {
+            path: dir.join("model.xlsx").to_string_lossy().to_string(),
+            mime_type: None,
+        }]);
+        assert!(workbook.unwrap_err().contains("export the sheet as CSV"));
+        let bad = resolve(&[MessagePartInput::InlineData {
+            data: "not base64!".to_string(),
+            mime_type: "image/png".to_string(),
+            display_name: None,
+        }]);
+        assert!(bad.is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
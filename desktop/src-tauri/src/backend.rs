@@ -1,21 +1,259 @@
-use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use reqwest::Client;
+use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::time::{sleep, Duration};
 
-use crate::keyring_store::KeyEnv;
-use crate::types::{BackendStartConfig, BackendStatus};
+use crate::backend_auth;
+use crate::backend_version::{self, BackendVersion};
+use crate::error::{AppError, ErrorCode};
+use crate::events;
+use crate::http;
+use crate::keyring_store::{self, KeyEnv};
+use crate::log_files;
+use crate::orphans;
+use crate::redact;
+use crate::settings;
+use crate::startup_failure;
+use crate::types::{
+    BackendLogLine, BackendResources, BackendStartConfig, BackendStatus, PortStrategy,
+    StartupFailure, StartupFailureKind,
+};
 
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8765;
 const MAX_LOG_LINES: usize = 200;
 const LOG_TAIL_LINES: usize = 40;
+const APPS_CACHE_TTL: Duration = Duration::from_secs(30);
+const HEALTH_PATH_ENV: &str = "PV_DESKTOP_HEALTH_PATH";
+const HEALTH_STATUS_ENV: &str = "PV_DESKTOP_HEALTH_STATUS";
+pub(crate) const STARTUP_TIMEOUT_ENV: &str = "PV_DESKTOP_STARTUP_TIMEOUT_SECS";
+const HEALTH_PROBE_PATHS: &[&str] = &["/health", "/list-apps", "/docs"];
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(12);
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const ORPHAN_RELEASE_DELAY: Duration = Duration::from_millis(500);
+pub(crate) const SUPERVISE_ENV: &str = "PV_DESKTOP_BACKEND_SUPERVISE";
+const PORT_STRATEGY_ENV: &str = "PV_DESKTOP_BACKEND_PORTS";
+// Opt-out: serve plain `adk web` without the per-launch shared secret.
+const OPEN_BACKEND_ENV: &str = "PV_DESKTOP_BACKEND_OPEN";
+const LAUNCH_TOKEN_ENV: &str = "PV_DESKTOP_BACKEND_TOKEN";
+const GUARDED_SERVER_MODULE: &str = "product_validator_search.desktop_server";
+pub(crate) const UNIX_SOCKET_ENV: &str = "PV_DESKTOP_BACKEND_UNIX_SOCKET";
+const MAX_SUPERVISED_RESTARTS: u32 = 5;
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+// A process that stayed up this long is considered recovered, so its next crash starts a new streak.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+const FINGERPRINT_SKIP_DIRS: &[&str] =
+    &["desktop", "tests", "node_modules", "target", "__pycache__"];
+
+pub const DEFAULT_INSTANCE: &str = "default";
+const MAX_INSTANCE_NAME_LEN: usize = 40;
+
+// Extra backends running next to the default one, e.g. two agent checkouts compared side by side.
+#[derive(Debug, Default)]
+pub struct BackendInstances {
+    named: Mutex<BTreeMap<String, Arc<tokio::sync::Mutex<BackendManager>>>>,
+}
+
+impl BackendInstances {
+    pub fn get(&self, name: &str) -> Option<Arc<tokio::sync::Mutex<BackendManager>>> {
+        self.named
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    // New instances take an OS-assigned port so they never collide with the default backend.
+    pub fn get_or_insert(&self, name: &str) -> Arc<tokio::sync::Mutex<BackendManager>> {
+        self.named
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                Arc::new(tokio::sync::Mutex::new(BackendManager {
+                    port_strategy: PortStrategy::Ephemeral,
+                    supervise: false,
+                    ..BackendManager::default()
+                }))
+            })
+            .clone()
+    }
+
+    pub fn remove(&self, name: &str) -> Option<Arc<tokio::sync::Mutex<BackendManager>>> {
+        self.named
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+    }
+
+    pub fn all(&self) -> Vec<(String, Arc<tokio::sync::Mutex<BackendManager>>)> {
+        self.named
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, manager)| (name.clone(), manager.clone()))
+            .collect()
+    }
+}
+
+pub fn validate_instance_name(raw: &str) -> Result<String, AppError> {
+    let name = raw.trim();
+    if name.is_empty() || name.len() > MAX_INSTANCE_NAME_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "Backend instance names must be 1-{MAX_INSTANCE_NAME_LEN} characters."
+        )));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::InvalidInput(format!(
+            "Backend instance name '{name}' may only contain letters, digits, '-' and '_'."
+        )));
+    }
+    Ok(name.to_string())
+}
+
+// Cheap shared view of where the backend listens. Run tasks resolve the URL at request time, so
+// queued and retried work follows a restart onto a new port.
+#[derive(Debug, Clone)]
+pub struct BackendHandle {
+    base_url: Arc<Mutex<String>>,
+    generation: Arc<AtomicU64>,
+}
+
+impl BackendHandle {
+    fn new(base_url: String) -> Self {
+        Self {
+            base_url: Arc::new(Mutex::new(base_url)),
+            generation: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        self.base_url
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // Bumped on every successful (re)start; a restarted backend has lost its in-memory ADK sessions.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    fn retarget(&self, base_url: String) {
+        *self.base_url.lock().unwrap_or_else(|e| e.into_inner()) = base_url;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// Where a spawned backend listens. Unix sockets skip port selection entirely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Bind {
+    Tcp {
+        host: String,
+        port: u16,
+        attempted: Vec<u16>,
+    },
+    Unix(PathBuf),
+}
+
+impl Bind {
+    fn args(&self) -> Vec<String> {
+        match self {
+            Self::Tcp { host, port, .. } => vec![
+                "--host".to_string(),
+                host.clone(),
+                "--port".to_string(),
+                port.to_string(),
+            ],
+            Self::Unix(path) => vec!["--uds".to_string(), path.display().to_string()],
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Tcp { attempted, .. } => format!("Ports tried: {}.", join_ports(attempted)),
+            Self::Unix(path) => format!("Socket: {}.", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SocketBinding {
+    path: PathBuf,
+    base_url: String,
+}
+
+// Last /list-apps answer; the agent directory rarely changes while a backend is up.
+#[derive(Debug, Clone)]
+struct AppsCache {
+    apps: Vec<String>,
+    fetched_at: Instant,
+}
+
+impl AppsCache {
+    fn fresh(&self, now: Instant, ttl: Duration) -> Option<&[String]> {
+        (now.saturating_duration_since(self.fetched_at) < ttl).then_some(self.apps.as_slice())
+    }
+}
+
+// How the backend process gets started, in probe order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Launcher {
+    Uv(PathBuf),
+    Venv(PathBuf),
+    Python(PathBuf),
+}
+
+impl Launcher {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Uv(_) => "uv",
+            Self::Venv(_) => "venv",
+            Self::Python(_) => "python",
+        }
+    }
+
+    // The repo's desktop server wrapper (token check, unix sockets) replaces `adk web` when either
+    // feature is needed; both accept the agents dir plus `--host/--port`.
+    fn command(&self, desktop_server: bool) -> Command {
+        match self {
+            Self::Uv(uv) => {
+                let mut cmd = Command::new(uv);
+                if desktop_server {
+                    cmd.args(["run", "python", "-m", GUARDED_SERVER_MODULE]);
+                } else {
+                    cmd.args(["run", "adk", "web"]);
+                }
+                cmd
+            }
+            Self::Venv(python) | Self::Python(python) => {
+                let mut cmd = Command::new(python);
+                if desktop_server {
+                    cmd.args(["-m", GUARDED_SERVER_MODULE]);
+                } else {
+                    cmd.args(["-m", "google.adk.cli", "web"]);
+                }
+                cmd
+            }
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct BackendManager {
@@ -25,7 +263,49 @@ pub struct BackendManager {
     repo_root: PathBuf,
     app_name: Option<String>,
     log_lines: Arc<Mutex<VecDeque<String>>>,
+    // The port uvicorn reported binding, which some configurations pick over the requested one.
+    bound_port: Arc<Mutex<Option<u16>>>,
     last_error: Option<String>,
+    startup_failure: Option<StartupFailure>,
+    started_fingerprint: Option<String>,
+    run_env: BTreeMap<String, String>,
+    handle: BackendHandle,
+    // Remote mode: drive an already-running backend at this URL instead of spawning one.
+    remote_url: Option<String>,
+    remote_connected: bool,
+    launcher: Option<Launcher>,
+    supervise: bool,
+    started_at: Option<Instant>,
+    crash_streak: u32,
+    restart_pending: bool,
+    restart_count: u32,
+    health_probe: HealthProbe,
+    port_strategy: PortStrategy,
+    version: BackendVersion,
+    apps_cache: Option<AppsCache>,
+    resources: Option<BackendResources>,
+    launch_token: Option<String>,
+    unix_socket: bool,
+    socket: Option<SocketBinding>,
+    reported_health: HealthState,
+    key_profile: Option<String>,
+}
+
+// What the health watcher last pushed to the frontend; a new `backend-status` goes out only when
+// this changes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HealthState {
+    pub running: bool,
+    pub health: bool,
+}
+
+impl From<&BackendStatus> for HealthState {
+    fn from(status: &BackendStatus) -> Self {
+        Self {
+            running: status.running,
+            health: status.health,
+        }
+    }
 }
 
 impl Default for BackendManager {
@@ -37,17 +317,60 @@ impl Default for BackendManager {
             repo_root: discover_repo_root(),
             app_name: None,
             log_lines: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_LOG_LINES))),
+            bound_port: Arc::new(Mutex::new(None)),
             last_error: None,
+            startup_failure: None,
+            started_fingerprint: None,
+            run_env: BTreeMap::new(),
+            handle: BackendHandle::new(format!("http://{DEFAULT_HOST}:{DEFAULT_PORT}")),
+            remote_url: None,
+            remote_connected: false,
+            launcher: None,
+            supervise: settings::env_flag_enabled(SUPERVISE_ENV),
+            started_at: None,
+            crash_streak: 0,
+            restart_pending: false,
+            restart_count: 0,
+            health_probe: HealthProbe::from_env(),
+            port_strategy: std::env::var(PORT_STRATEGY_ENV)
+                .ok()
+                .and_then(|raw| parse_port_strategy(&raw))
+                .unwrap_or_default(),
+            version: BackendVersion::default(),
+            apps_cache: None,
+            resources: None,
+            launch_token: None,
+            unix_socket: settings::env_flag_enabled(UNIX_SOCKET_ENV),
+            socket: None,
+            reported_health: HealthState::default(),
+            key_profile: None,
         }
     }
 }
 
 impl BackendManager {
+    // Per-run environment applied to the next spawned backend process.
+    pub fn run_env(&self) -> &BTreeMap<String, String> {
+        &self.run_env
+    }
+
+    pub fn set_run_env(&mut self, env: BTreeMap<String, String>) {
+        self.run_env = env;
+    }
+
+    pub fn key_profile(&self) -> Option<&str> {
+        self.key_profile.as_deref()
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.remote_url.is_some()
+    }
+
     pub async fn start(
         &mut self,
         config: Option<BackendStartConfig>,
         keys: &KeyEnv,
-    ) -> Result<BackendStatus, String> {
+    ) -> Result<BackendStatus, AppError> {
         let mut force_restart = false;
         if let Some(cfg) = config {
             if let Some(host) = cfg.host {
@@ -56,10 +379,65 @@ impl BackendManager {
             if let Some(port) = cfg.port {
                 self.port = port;
             }
+            if let Some(strategy) = cfg.port_strategy {
+                self.port_strategy = strategy;
+            }
             if let Some(repo_root) = cfg.repo_root {
                 self.repo_root = PathBuf::from(repo_root);
             }
             force_restart = cfg.force_restart.unwrap_or(false);
+            if let Some(unix_socket) = cfg.unix_socket {
+                if unix_socket != self.unix_socket {
+                    self.unix_socket = unix_socket;
+                    force_restart = true;
+                }
+            }
+            if let Some(supervise) = cfg.supervise {
+                self.supervise = supervise;
+            }
+            self.health_probe = self.health_probe.clone().with_overrides(
+                cfg.health_path.as_deref(),
+                cfg.health_status,
+                cfg.startup_timeout_secs,
+            );
+            if cfg.ca_cert_path.is_some() || cfg.accept_invalid_certs.is_some() {
+                let current = http::BackendTls::current();
+                let tls = http::BackendTls {
+                    ca_cert_path: match cfg.ca_cert_path.as_deref().map(str::trim) {
+                        Some("") => None,
+                        Some(path) => Some(PathBuf::from(path)),
+                        None => current.ca_cert_path.clone(),
+                    },
+                    accept_invalid_certs: cfg
+                        .accept_invalid_certs
+                        .unwrap_or(current.accept_invalid_certs),
+                };
+                if tls != current {
+                    http::BackendTls::set(tls).map_err(AppError::InvalidInput)?;
+                    force_restart = true;
+                }
+            }
+            // An empty URL switches back to the locally spawned backend.
+            match cfg.remote_url.as_deref().map(str::trim) {
+                Some("") => {
+                    self.remote_url = None;
+                    self.remote_connected = false;
+                }
+                Some(raw) => {
+                    let (base_url, host, port) = parse_remote_url(raw)?;
+                    if self.remote_url.as_deref() != Some(base_url.as_str()) {
+                        force_restart = true;
+                    }
+                    self.remote_url = Some(base_url);
+                    self.host = host;
+                    self.port = port;
+                }
+                None => {}
+            }
+        }
+
+        if self.remote_url.is_some() {
+            return self.connect_remote(force_restart).await;
         }
 
         let (current, _) = self.status().await?;
@@ -71,100 +449,364 @@ impl BackendManager {
         self.last_error = None;
         self.clear_logs();
 
-        if !is_port_available(&self.host, self.port) {
-            return Err(format!(
-                "Port {} is already in use on host {}. Fixed-port mode is enabled; stop the process using this port and retry.",
-                self.port, self.host
-            ));
+        let reaped = orphans::reap_once();
+        if !reaped.is_empty() {
+            for note in &reaped {
+                push_log_line(&self.log_lines, "desktop", note);
+            }
+            // Give the killed servers a moment to release their ports before one is picked.
+            sleep(ORPHAN_RELEASE_DELAY).await;
         }
 
+        let bind = if self.unix_socket {
+            Bind::Unix(self.open_socket()?)
+        } else {
+            let attempted = self.select_port()?;
+            Bind::Tcp {
+                host: self.host.clone(),
+                port: self.port,
+                attempted,
+            }
+        };
+
+        let launcher = choose_launcher(&self.repo_root, find_on_path).ok_or_else(|| {
+            AppError::BackendStartup(
+                "No backend launcher found. Install uv, create a .venv in the backend repo, or install Python with google-adk on PATH."
+                    .to_string(),
+            )
+            .with_code(ErrorCode::BackendNoLauncher)
+        })?;
+        let fingerprint = repo_fingerprint(&self.repo_root);
+        let launch_token = (!settings::flag(OPEN_BACKEND_ENV, settings::current().backend_open))
+            .then(|| uuid::Uuid::new_v4().simple().to_string());
+        backend_auth::set_launch_token(&self.base_url(), launch_token.as_deref());
+        *self.bound_port.lock().unwrap_or_else(|e| e.into_inner()) = None;
         let child = match spawn_backend(
-            &self.host,
-            self.port,
+            &launcher,
+            &bind,
             &self.repo_root,
             keys,
-            self.log_lines.clone(),
+            &self.run_env,
+            launch_token.as_deref(),
+            ChildOutput {
+                log_lines: self.log_lines.clone(),
+                bound_port: self.bound_port.clone(),
+            },
         )
         .await
         {
-            Ok(child) => child,
-            Err(err) => {
-                if err.contains("No such file or directory") {
-                    return Err(
-                        "`uv` not found. Install uv and ensure it is on PATH before starting desktop backend."
-                            .to_string(),
-                    );
+            Ok(child) => {
+                if let Some(pid) = child.id() {
+                    orphans::record(pid, &self.base_url());
                 }
+                child
+            }
+            Err(err) => {
+                backend_auth::set_launch_token(&self.base_url(), None);
+                self.close_socket();
                 return Err(err);
             }
         };
 
-        if await_health(&self.host, self.port).await {
+        let probe = self.health_probe.clone();
+        let reconcile_token = launch_token.clone();
+        if await_health(
+            || self.reconcile_bound_port(reconcile_token.as_deref()),
+            &probe,
+        )
+        .await
+        {
             self.child = Some(child);
+            self.launch_token = launch_token;
+            self.launcher = Some(launcher);
+            self.key_profile = keys.profile.clone();
+            self.started_at = Some(Instant::now());
+            self.started_fingerprint = Some(fingerprint);
+            self.handle.retarget(self.base_url());
 
-            let apps = self.list_apps().await.unwrap_or_default();
+            let apps = self.cached_apps(true).await.unwrap_or_default();
             self.app_name = choose_default_app(&apps);
+            self.version = backend_version::detect(&self.base_url(), &self.recent_logs()).await;
 
             let (status, _) = self.status().await?;
             return Ok(status);
         }
 
         let mut child = child;
-        let startup_failure = match child.try_wait() {
-            Ok(Some(exit_status)) => {
-                format!("Backend exited during startup (status: {exit_status}).")
-            }
-            Ok(None) => format!(
-                "Backend did not become healthy at http://{}:{} within startup timeout.",
-                self.host, self.port
+        let (startup_failure, fallback) = match child.try_wait() {
+            Ok(Some(exit_status)) => (
+                format!("Backend exited during startup (status: {exit_status})."),
+                StartupFailureKind::Exited,
+            ),
+            Ok(None) => (
+                format!(
+                    "Backend did not become healthy at {} within {}s (probed {}).",
+                    self.base_url(),
+                    self.health_probe.startup_timeout.as_secs(),
+                    self.health_probe.paths.join(", ")
+                ),
+                StartupFailureKind::Timeout,
+            ),
+            Err(err) => (
+                format!("Failed to inspect backend process during startup: {err}"),
+                StartupFailureKind::Unknown,
             ),
-            Err(err) => format!("Failed to inspect backend process during startup: {err}"),
         };
 
-        let detailed = self.compose_error_with_log_tail(startup_failure);
+        let detailed =
+            self.compose_error_with_log_tail(format!("{startup_failure} {}", bind.describe()));
         self.last_error = Some(detailed.clone());
+        let captured: Vec<String> = self
+            .log_lines
+            .lock()
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default();
+        let failure = startup_failure::analyze(&captured, fallback, self.log_tail(LOG_TAIL_LINES));
+        self.startup_failure = Some(failure.clone());
 
+        let pid = child.id();
         let _ = child.kill().await;
         let _ = child.wait().await;
+        if let Some(pid) = pid {
+            orphans::forget(pid);
+        }
+        backend_auth::set_launch_token(&self.base_url(), None);
+        self.close_socket();
+
+        Err(AppError::StartupFailed {
+            message: detailed,
+            failure,
+        })
+    }
+
+    // Follows the port the server reports binding, re-keying the launch token to the new URL, and
+    // returns the URL to probe.
+    fn reconcile_bound_port(&mut self, launch_token: Option<&str>) -> String {
+        let bound = *self.bound_port.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(port) = bound.filter(|port| *port != self.port && self.socket.is_none()) {
+            push_log_line(
+                &self.log_lines,
+                "desktop",
+                &format!(
+                    "Backend bound port {port} instead of the requested {}; following it.",
+                    self.port
+                ),
+            );
+            backend_auth::set_launch_token(&self.base_url(), None);
+            self.port = port;
+            backend_auth::set_launch_token(&self.base_url(), launch_token);
+        }
+        self.base_url()
+    }
+
+    // Reserves a fresh socket path and routes this backend's URL through it.
+    #[cfg(unix)]
+    fn open_socket(&mut self) -> Result<PathBuf, AppError> {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let id = &id[..12];
+        let path = std::env::temp_dir().join(format!("pv-backend-{id}.sock"));
+        let _ = fs::remove_file(&path);
+        let base_url = format!("http://pv-backend-{id}.localhost");
+        crate::http::set_unix_socket(&base_url, Some(path.clone()));
+        self.socket = Some(SocketBinding {
+            path: path.clone(),
+            base_url,
+        });
+        Ok(path)
+    }
 
-        Err(detailed)
+    #[cfg(not(unix))]
+    fn open_socket(&mut self) -> Result<PathBuf, AppError> {
+        Err(AppError::InvalidInput(
+            "The unix socket transport is not available on this platform; uvicorn cannot serve on Windows named pipes.".to_string(),
+        ))
+    }
+
+    fn close_socket(&mut self) {
+        if let Some(socket) = self.socket.take() {
+            crate::http::set_unix_socket(&socket.base_url, None);
+            let _ = fs::remove_file(&socket.path);
+        }
     }
 
-    pub async fn stop(&mut self) -> Result<(), String> {
+    // Picks the port to bind according to the port strategy; returns every port considered.
+    fn select_port(&mut self) -> Result<Vec<u16>, AppError> {
+        let host = self.host.clone();
+        Ok(match &self.port_strategy {
+            PortStrategy::Ephemeral => match ephemeral_port(&host) {
+                Some(port) => {
+                    self.port = port;
+                    vec![port]
+                }
+                None => {
+                    return Err(AppError::Conflict(format!(
+                        "Could not get an ephemeral port from host {host}."
+                    ))
+                    .with_code(ErrorCode::BackendNoFreePort { host }))
+                }
+            },
+            strategy => {
+                let candidates = candidate_ports(strategy, self.port);
+                match candidates
+                    .iter()
+                    .position(|port| is_port_available(&host, *port))
+                {
+                    Some(index) => {
+                        self.port = candidates[index];
+                        candidates[..=index].to_vec()
+                    }
+                    None if *strategy == PortStrategy::Fixed => {
+                        return Err(AppError::Conflict(format!(
+                            "Port {} is already in use on host {host}. Fixed-port mode is enabled; stop the process using this port or configure a port range, then retry.",
+                            self.port
+                        ))
+                        .with_code(ErrorCode::BackendPortInUse {
+                            host,
+                            port: self.port,
+                        }))
+                    }
+                    None => {
+                        return Err(AppError::Conflict(format!(
+                            "No free port on host {host}; tried {}.",
+                            join_ports(&candidates)
+                        ))
+                        .with_code(ErrorCode::BackendNoFreePort { host }))
+                    }
+                }
+            }
+        })
+    }
+
+    async fn connect_remote(&mut self, force_reconnect: bool) -> Result<BackendStatus, AppError> {
+        let (current, _) = self.status().await?;
+        if current.running && current.health && !force_reconnect {
+            return Ok(current);
+        }
+
+        self.stop().await?;
+        let base_url = self.base_url();
+        if !await_health(|| base_url.clone(), &self.health_probe).await {
+            let message = format!("Remote backend at {base_url} did not pass its health check.");
+            self.last_error = Some(message.clone());
+            return Err(AppError::BackendUnavailable(message)
+                .with_code(ErrorCode::BackendUnhealthy { base_url }));
+        }
+
+        self.remote_connected = true;
+        self.handle.retarget(base_url);
+        let apps = self.cached_apps(true).await.unwrap_or_default();
+        self.app_name = choose_default_app(&apps);
+        self.version = backend_version::detect(&self.base_url(), &self.recent_logs()).await;
+
+        let (status, _) = self.status().await?;
+        Ok(status)
+    }
+
+    pub async fn stop(&mut self) -> Result<(), AppError> {
         if let Some(mut child) = self.child.take() {
+            let pid = child.id();
             let _ = child.kill().await;
             let _ = child.wait().await;
+            if let Some(pid) = pid {
+                orphans::forget(pid);
+            }
+        }
+        if self.launch_token.take().is_some() {
+            backend_auth::set_launch_token(&self.base_url(), None);
         }
+        self.close_socket();
+        self.remote_connected = false;
+        self.started_at = None;
+        self.restart_pending = false;
         self.app_name = None;
         self.last_error = None;
+        self.startup_failure = None;
+        self.started_fingerprint = None;
+        self.version = BackendVersion::default();
+        self.apps_cache = None;
+        self.resources = None;
         self.clear_logs();
         Ok(())
     }
 
-    pub async fn status(&mut self) -> Result<(BackendStatus, bool), String> {
-        let mut exited = false;
-
-        if let Some(child) = self.child.as_mut() {
-            match child.try_wait() {
-                Ok(Some(exit_status)) => {
-                    self.child = None;
-                    self.app_name = None;
-                    self.last_error = Some(self.compose_error_with_log_tail(format!(
-                        "Local backend process exited unexpectedly (status: {exit_status})."
-                    )));
-                    exited = true;
+    // Reaps an exited child without touching the network; returns true once per unexpected exit.
+    pub fn check_exited(&mut self) -> Result<bool, AppError> {
+        let Some(child) = self.child.as_mut() else {
+            return Ok(false);
+        };
+        let pid = child.id();
+        match child.try_wait() {
+            Ok(Some(exit_status)) => {
+                self.child = None;
+                if let Some(pid) = pid {
+                    orphans::forget(pid);
                 }
-                Ok(None) => {}
-                Err(err) => return Err(format!("Failed to check backend process status: {err}")),
+                self.app_name = None;
+                self.started_fingerprint = None;
+                self.last_error = Some(self.compose_error_with_log_tail(format!(
+                    "Local backend process exited unexpectedly (status: {exit_status})."
+                )));
+                let stable = self
+                    .started_at
+                    .take()
+                    .is_some_and(|at| at.elapsed() >= STABLE_UPTIME);
+                self.crash_streak = if stable { 1 } else { self.crash_streak + 1 };
+                self.restart_pending = self.supervise;
+                Ok(true)
             }
+            Ok(None) => Ok(false),
+            Err(err) => Err(AppError::Internal(format!(
+                "Failed to check backend process status: {err}"
+            ))),
         }
+    }
 
-        let running = self.child.is_some();
+    // How long the supervisor should wait before restarting, or None when no restart is due.
+    pub fn restart_delay(&self) -> Option<Duration> {
+        if !self.restart_pending || self.child.is_some() || self.restarts_exhausted() {
+            return None;
+        }
+        Some(restart_backoff(self.crash_streak))
+    }
+
+    pub fn restarts_exhausted(&self) -> bool {
+        self.restart_pending && self.crash_streak > MAX_SUPERVISED_RESTARTS
+    }
+
+    pub async fn restart_after_crash(&mut self, keys: &KeyEnv) -> Result<BackendStatus, AppError> {
+        self.restart_count += 1;
+        let result = self
+            .start(
+                Some(BackendStartConfig {
+                    force_restart: Some(true),
+                    ..BackendStartConfig::default()
+                }),
+                keys,
+            )
+            .await;
+        if result.is_err() {
+            // A failed start counts as another crash so the backoff keeps growing.
+            self.crash_streak += 1;
+            self.restart_pending = true;
+        }
+        result
+    }
+
+    pub async fn status(&mut self) -> Result<(BackendStatus, bool), AppError> {
+        let exited = self.check_exited()?;
+
+        let running = self.child.is_some() || self.remote_connected;
         let health = if running {
-            health_check(&self.base_url()).await
+            health_check(&self.base_url(), &self.health_probe).await
         } else {
             false
         };
+        let tls = http::BackendTls::current();
+        let stale = self.child.is_some()
+            && self
+                .started_fingerprint
+                .as_ref()
+                .is_some_and(|started| *started != repo_fingerprint(&self.repo_root));
 
         Ok((
             BackendStatus {
@@ -175,13 +817,70 @@ impl BackendManager {
                 host: self.host.clone(),
                 base_url: self.base_url(),
                 last_error: self.last_error.clone(),
+                stale,
+                remote: self.remote_url.is_some(),
+                launcher: self
+                    .child
+                    .as_ref()
+                    .and(self.launcher.as_ref())
+                    .map(|launcher| launcher.name().to_string()),
+                supervised: self.supervise,
+                restart_count: self.restart_count,
+                adk_version: self.version.version.clone(),
+                run_sse_supported: self.version.run_sse_supported,
+                compat_warning: self.version.warning.clone(),
+                custom_ca: tls.ca_cert_path.is_some(),
+                insecure_tls: tls.accept_invalid_certs,
+                guarded: self.child.is_some() && self.launch_token.is_some(),
+                unix_socket: self
+                    .child
+                    .as_ref()
+                    .and(self.socket.as_ref())
+                    .map(|socket| socket.path.display().to_string()),
+                resources: self.child.as_ref().and(self.resources.clone()),
+                startup_failure: self.startup_failure.clone(),
+                key_profile: self.child.as_ref().and(self.key_profile.clone()),
             },
             exited,
         ))
     }
 
     pub fn base_url(&self) -> String {
-        format!("http://{}:{}", self.host, self.port)
+        match (&self.remote_url, &self.socket) {
+            (Some(url), _) => url.clone(),
+            (None, Some(socket)) => socket.base_url.clone(),
+            (None, None) => format!("http://{}:{}", self.host, self.port),
+        }
+    }
+
+    // Records the latest state and reports whether it differs from what was last observed.
+    pub fn observe_health(&mut self, next: HealthState) -> bool {
+        let changed = next != self.reported_health;
+        self.reported_health = next;
+        changed
+    }
+
+    pub fn handle(&self) -> BackendHandle {
+        self.handle.clone()
+    }
+
+    pub fn child_pid(&self) -> Option<u32> {
+        self.child.as_ref().and_then(Child::id)
+    }
+
+    // Drops samples taken from a process that has since been replaced or stopped.
+    pub fn set_resources(&mut self, pid: u32, resources: Option<BackendResources>) {
+        if self.child_pid() == Some(pid) {
+            self.resources = resources;
+        }
+    }
+
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    pub fn version(&self) -> &BackendVersion {
+        &self.version
     }
 
     pub fn app_name(&self) -> Option<String> {
@@ -192,6 +891,26 @@ impl BackendManager {
         self.app_name = app_name;
     }
 
+    pub fn recent_logs(&self) -> String {
+        self.log_tail(MAX_LOG_LINES)
+    }
+
+    pub fn filtered_logs(
+        &self,
+        limit: Option<usize>,
+        contains: Option<&str>,
+        stderr_only: bool,
+    ) -> Vec<String> {
+        match self.log_lines.lock() {
+            Ok(logs) => filter_log_lines(logs.iter(), limit, contains, stderr_only),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn repo_fingerprint(&self) -> String {
+        repo_fingerprint(&self.repo_root)
+    }
+
     fn clear_logs(&self) {
         if let Ok(mut logs) = self.log_lines.lock() {
             logs.clear();
@@ -226,55 +945,122 @@ impl BackendManager {
         format!("{base_message}\nRecent backend logs:\n{tail}")
     }
 
-    pub async fn list_apps(&self) -> Result<Vec<String>, String> {
+    pub fn fresh_apps(&self) -> Option<Vec<String>> {
+        self.apps_cache
+            .as_ref()
+            .and_then(|cache| cache.fresh(Instant::now(), APPS_CACHE_TTL))
+            .map(<[String]>::to_vec)
+    }
+
+    // Serves the cached list while it is within the TTL; `force_refresh` always asks the backend.
+    pub async fn cached_apps(&mut self, force_refresh: bool) -> Result<Vec<String>, AppError> {
+        if !force_refresh {
+            if let Some(apps) = self.fresh_apps() {
+                return Ok(apps);
+            }
+        }
+        let apps = self.list_apps().await?;
+        self.apps_cache = Some(AppsCache {
+            apps: apps.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(apps)
+    }
+
+    async fn list_apps(&self) -> Result<Vec<String>, AppError> {
         let url = format!("{}/list-apps", self.base_url());
-        let response = client()
-            .get(url)
+        let response = client(&url)
+            .get(&url)
+            .headers(backend_auth::headers_for(&url))
             .send()
             .await
-            .map_err(|e| format!("Failed to call /list-apps: {e}"))?;
+            .map_err(|e| AppError::from_reqwest(&e, "Failed to call /list-apps"))?;
 
-        if !response.status().is_success() {
-            return Err(format!("/list-apps returned HTTP {}", response.status()));
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AppError::from_status(
+                status.as_u16(),
+                format!("/list-apps returned HTTP {status}"),
+            ));
         }
 
         response
             .json::<Vec<String>>()
             .await
-            .map_err(|e| format!("Failed to parse /list-apps response: {e}"))
+            .map_err(|e| AppError::Internal(format!("Failed to parse /list-apps response: {e}")))
     }
 }
 
-fn client() -> Client {
-    Client::builder()
+fn client(url: &str) -> Client {
+    crate::http::backend_client_builder(url)
         .timeout(Duration::from_secs(30))
         .build()
         .expect("reqwest client should build")
 }
 
+// Probes uv, then the repo's .venv, then a system Python that has google-adk installed.
+fn choose_launcher(repo_root: &Path, find: impl Fn(&str) -> Option<PathBuf>) -> Option<Launcher> {
+    if let Some(uv) = find("uv") {
+        return Some(Launcher::Uv(uv));
+    }
+    let venv_python = venv_python(repo_root);
+    if venv_python.is_file() {
+        return Some(Launcher::Venv(venv_python));
+    }
+    find("python3")
+        .or_else(|| find("python"))
+        .map(Launcher::Python)
+}
+
+pub fn venv_python(repo_root: &Path) -> PathBuf {
+    if cfg!(windows) {
+        repo_root.join(".venv").join("Scripts").join("python.exe")
+    } else {
+        repo_root.join(".venv").join("bin").join("python")
+    }
+}
+
+// The uv binary and the Python interpreter the backend would run under, for preflight checks.
+pub fn interpreters(repo_root: &Path) -> (Option<PathBuf>, Option<PathBuf>) {
+    let venv = venv_python(repo_root);
+    let python = if venv.is_file() {
+        Some(venv)
+    } else {
+        find_on_path("python3").or_else(|| find_on_path("python"))
+    };
+    (find_on_path("uv"), python)
+}
+
+pub fn find_on_path(name: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(windows) {
+        format!("{name}.exe")
+    } else {
+        name.to_string()
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
 async fn spawn_backend(
-    host: &str,
-    port: u16,
+    launcher: &Launcher,
+    bind: &Bind,
     repo_root: &Path,
     keys: &KeyEnv,
-    log_lines: Arc<Mutex<VecDeque<String>>>,
-) -> Result<Child, String> {
-    let mut cmd = Command::new("uv");
-    cmd.args([
-        "run",
-        "adk",
-        "web",
-        ".",
-        "--host",
-        host,
-        "--port",
-        &port.to_string(),
-    ])
-    .current_dir(repo_root)
-    .stdin(Stdio::null())
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
+    run_env: &BTreeMap<String, String>,
+    launch_token: Option<&str>,
+    output: ChildOutput,
+) -> Result<Child, AppError> {
+    let mut cmd = launcher.command(launch_token.is_some() || matches!(bind, Bind::Unix(_)));
+    cmd.arg(".")
+        .args(bind.args())
+        .current_dir(repo_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
 
+    cmd.envs(&keys.extra_env);
+    cmd.envs(keyring_store::external_fallback_env(keys, repo_root));
     if let Some(v) = &keys.google_api_key {
         cmd.env("GOOGLE_API_KEY", v);
     }
@@ -284,22 +1070,39 @@ async fn spawn_backend(
     if let Some(v) = &keys.gemini_api_key {
         cmd.env("GEMINI_API_KEY", v);
     }
+    cmd.envs(&keys.credential_env);
+    cmd.envs(run_env);
+    match launch_token {
+        Some(token) => cmd.env(LAUNCH_TOKEN_ENV, token),
+        None => cmd.env_remove(LAUNCH_TOKEN_ENV),
+    };
+    crate::http::ProxyConfig::from_env().apply_to_child(&mut cmd);
 
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn backend process: {e}"))?;
+    let mut child = cmd.spawn().map_err(|e| {
+        AppError::BackendStartup(format!(
+            "Failed to spawn backend process with {}: {e}",
+            launcher.name()
+        ))
+    })?;
 
     if let Some(stdout) = child.stdout.take() {
-        spawn_log_reader(stdout, log_lines.clone(), "stdout");
+        spawn_log_reader(stdout, output.clone(), "stdout");
     }
     if let Some(stderr) = child.stderr.take() {
-        spawn_log_reader(stderr, log_lines, "stderr");
+        spawn_log_reader(stderr, output, "stderr");
     }
 
     Ok(child)
 }
 
-fn spawn_log_reader<R>(reader: R, log_lines: Arc<Mutex<VecDeque<String>>>, stream: &'static str)
+// Where the log readers deliver a spawned backend's output.
+#[derive(Clone)]
+struct ChildOutput {
+    log_lines: Arc<Mutex<VecDeque<String>>>,
+    bound_port: Arc<Mutex<Option<u16>>>,
+}
+
+fn spawn_log_reader<R>(reader: R, output: ChildOutput, stream: &'static str)
 where
     R: tokio::io::AsyncRead + Unpin + Send + 'static,
 {
@@ -307,10 +1110,15 @@ where
         let mut lines = BufReader::new(reader).lines();
         loop {
             match lines.next_line().await {
-                Ok(Some(line)) => push_log_line(&log_lines, format!("[{stream}] {line}")),
+                Ok(Some(line)) => {
+                    if let Some(port) = parse_bound_port(&line) {
+                        *output.bound_port.lock().unwrap_or_else(|e| e.into_inner()) = Some(port);
+                    }
+                    push_log_line(&output.log_lines, stream, &line);
+                }
                 Ok(None) => break,
                 Err(err) => {
-                    push_log_line(&log_lines, format!("[{stream}] <read error: {err}>"));
+                    push_log_line(&output.log_lines, stream, &format!("<read error: {err}>"));
                     break;
                 }
             }
@@ -318,7 +1126,58 @@ where
     });
 }
 
-fn push_log_line(log_lines: &Arc<Mutex<VecDeque<String>>>, line: String) {
+// Reads the port from uvicorn's startup banner, e.g.
+// `INFO:     Uvicorn running on http://127.0.0.1:8000 (Press CTRL+C to quit)`.
+fn parse_bound_port(line: &str) -> Option<u16> {
+    let (_, rest) = line.split_once("Uvicorn running on ")?;
+    let url = reqwest::Url::parse(rest.split_whitespace().next()?).ok()?;
+    url.port_or_known_default()
+}
+
+// Keeps the newest `limit` matching lines, oldest first; the substring match ignores case.
+fn filter_log_lines<'a>(
+    lines: impl DoubleEndedIterator<Item = &'a String>,
+    limit: Option<usize>,
+    contains: Option<&str>,
+    stderr_only: bool,
+) -> Vec<String> {
+    let needle = contains
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_lowercase);
+    let mut out: Vec<String> = lines
+        .rev()
+        .filter(|line| !stderr_only || line.starts_with("[stderr] "))
+        .filter(|line| {
+            needle
+                .as_deref()
+                .is_none_or(|needle| line.to_lowercase().contains(needle))
+        })
+        .take(limit.unwrap_or(MAX_LOG_LINES))
+        .cloned()
+        .collect();
+    out.reverse();
+    out
+}
+
+fn log_emitter() -> &'static OnceLock<AppHandle> {
+    static EMITTER: OnceLock<AppHandle> = OnceLock::new();
+    &EMITTER
+}
+
+// Captured lines are forwarded as `backend-log` events from here on, for the UI's live console.
+pub fn attach_log_emitter(app: AppHandle) {
+    let _ = log_emitter().set(app);
+}
+
+fn push_log_line(log_lines: &Arc<Mutex<VecDeque<String>>>, stream: &str, text: &str) {
+    let text = redact::scrub(text);
+    let line = format!("[{stream}] {text}");
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    log_files::append(&format!("{timestamp_ms} {line}"));
     if passthrough_backend_logs_enabled() {
         eprintln!("[backend] {line}");
     }
@@ -329,39 +1188,191 @@ fn push_log_line(log_lines: &Arc<Mutex<VecDeque<String>>>, line: String) {
         }
         logs.push_back(line);
     }
+
+    if let Some(app) = log_emitter().get() {
+        let _ = app.emit(
+            &events::name(events::BACKEND_LOG),
+            BackendLogLine {
+                stream: stream.to_string(),
+                line: text,
+                timestamp_ms,
+            },
+        );
+    }
 }
 
 fn passthrough_backend_logs_enabled() -> bool {
-    matches!(
-        std::env::var("PV_DESKTOP_BACKEND_STDIO")
-            .unwrap_or_default()
-            .to_ascii_lowercase()
-            .as_str(),
-        "1" | "true" | "yes" | "on"
-    )
+    settings::env_flag_enabled("PV_DESKTOP_BACKEND_STDIO")
 }
 
-fn is_port_available(host: &str, port: u16) -> bool {
+pub fn is_port_available(host: &str, port: u16) -> bool {
     TcpListener::bind((host, port)).is_ok()
 }
 
-async fn await_health(host: &str, port: u16) -> bool {
-    let base_url = format!("http://{}:{}", host, port);
-    for _ in 0..48u8 {
-        if health_check(&base_url).await {
+// Lets the OS pick a free port; it is released again right before the backend binds it.
+fn ephemeral_port(host: &str) -> Option<u16> {
+    TcpListener::bind((host, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .ok()
+}
+
+// Range mode tries the current port first (so restarts keep their URL), then the rest in order.
+fn candidate_ports(strategy: &PortStrategy, current: u16) -> Vec<u16> {
+    match strategy {
+        PortStrategy::Range { start, end } => {
+            let (start, end) = (*start.min(end), *start.max(end));
+            let mut ports: Vec<u16> = (start..=end).collect();
+            if let Some(index) = ports.iter().position(|port| *port == current) {
+                ports.remove(index);
+                ports.insert(0, current);
+            }
+            ports
+        }
+        PortStrategy::Fixed | PortStrategy::Ephemeral => vec![current],
+    }
+}
+
+// Accepts `fixed`, `ephemeral`, or a `start-end` range such as `8765-8780`.
+fn parse_port_strategy(raw: &str) -> Option<PortStrategy> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "" => None,
+        "fixed" => Some(PortStrategy::Fixed),
+        "ephemeral" | "0" => Some(PortStrategy::Ephemeral),
+        range => {
+            let (start, end) = range.split_once('-')?;
+            Some(PortStrategy::Range {
+                start: start.trim().parse().ok()?,
+                end: end.trim().parse().ok()?,
+            })
+        }
+    }
+}
+
+fn join_ports(ports: &[u16]) -> String {
+    ports
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Readiness probe: the configured path (if any) is tried first, then the routes different ADK
+// versions are known to serve. A probe passes on the expected status, or any 2xx when unset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HealthProbe {
+    paths: Vec<String>,
+    expected_status: Option<u16>,
+    startup_timeout: Duration,
+}
+
+impl Default for HealthProbe {
+    fn default() -> Self {
+        Self {
+            paths: HEALTH_PROBE_PATHS.iter().map(|p| p.to_string()).collect(),
+            expected_status: None,
+            startup_timeout: DEFAULT_STARTUP_TIMEOUT,
+        }
+    }
+}
+
+impl HealthProbe {
+    fn from_env() -> Self {
+        Self::default().with_overrides(
+            std::env::var(HEALTH_PATH_ENV).ok().as_deref(),
+            std::env::var(HEALTH_STATUS_ENV)
+                .ok()
+                .and_then(|raw| raw.trim().parse().ok()),
+            std::env::var(STARTUP_TIMEOUT_ENV)
+                .ok()
+                .and_then(|raw| raw.trim().parse().ok()),
+        )
+    }
+
+    fn with_overrides(
+        mut self,
+        path: Option<&str>,
+        expected_status: Option<u16>,
+        startup_timeout_secs: Option<u64>,
+    ) -> Self {
+        if let Some(path) = path.map(str::trim).filter(|p| !p.is_empty()) {
+            let path = format!("/{}", path.trim_start_matches('/'));
+            self.paths.retain(|candidate| *candidate != path);
+            self.paths.insert(0, path);
+        }
+        if let Some(status) = expected_status.filter(|s| (100..600).contains(s)) {
+            self.expected_status = Some(status);
+        }
+        if let Some(secs) = startup_timeout_secs.filter(|secs| *secs > 0) {
+            self.startup_timeout = Duration::from_secs(secs);
+        }
+        self
+    }
+
+    fn accepts(&self, status: u16) -> bool {
+        match self.expected_status {
+            Some(expected) => status == expected,
+            None => (200..300).contains(&status),
+        }
+    }
+}
+
+// `base_url` is re-evaluated on every attempt so a spawned server can be followed to the port it
+// actually bound.
+async fn await_health(mut base_url: impl FnMut() -> String, probe: &HealthProbe) -> bool {
+    let deadline = Instant::now() + probe.startup_timeout;
+    loop {
+        if health_check(&base_url(), probe).await {
             return true;
         }
-        sleep(Duration::from_millis(250)).await;
+        if Instant::now() >= deadline {
+            return false;
+        }
+        sleep(HEALTH_POLL_INTERVAL).await;
+    }
+}
+
+async fn health_check(base_url: &str, probe: &HealthProbe) -> bool {
+    for path in &probe.paths {
+        let url = format!("{base_url}{path}");
+        if let Ok(response) = client(&url)
+            .get(&url)
+            .headers(backend_auth::headers_for(&url))
+            .send()
+            .await
+        {
+            if probe.accepts(response.status().as_u16()) {
+                return true;
+            }
+        }
     }
     false
 }
 
-async fn health_check(base_url: &str) -> bool {
-    let url = format!("{base_url}/health");
-    match client().get(url).send().await {
-        Ok(response) => response.status().is_success(),
-        Err(_) => false,
+fn restart_backoff(crash_streak: u32) -> Duration {
+    let exponent = crash_streak.saturating_sub(1).min(16);
+    RESTART_BASE_DELAY
+        .saturating_mul(1 << exponent)
+        .min(RESTART_MAX_DELAY)
+}
+
+// Returns the normalized base URL plus the host and port it resolves to.
+fn parse_remote_url(raw: &str) -> Result<(String, String, u16), AppError> {
+    let url = reqwest::Url::parse(raw.trim())
+        .map_err(|e| AppError::InvalidInput(format!("Invalid remote backend URL '{raw}': {e}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::InvalidInput(format!(
+            "Remote backend URL must use http or https, got '{}'.",
+            url.scheme()
+        )));
     }
+    let host = url
+        .host_str()
+        .ok_or_else(|| AppError::InvalidInput(format!("Remote backend URL '{raw}' has no host.")))?
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(DEFAULT_PORT);
+    let base_url = url.as_str().trim_end_matches('/').to_string();
+    Ok((base_url, host, port))
 }
 
 pub fn choose_default_app(apps: &[String]) -> Option<String> {
@@ -402,6 +1413,64 @@ fn discover_repo_root() -> PathBuf {
     current
 }
 
+// Hashes the checked-out commit, dependency manifests, and the size/mtime of agent sources so a
+// running server can be flagged once the code it loaded no longer matches what is on disk.
+fn repo_fingerprint(repo_root: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    git_head(repo_root).hash(&mut hasher);
+    for manifest in ["pyproject.toml", "uv.lock"] {
+        fs::read(repo_root.join(manifest)).ok().hash(&mut hasher);
+    }
+
+    let mut sources = Vec::new();
+    collect_sources(repo_root, 0, &mut sources);
+    sources.sort();
+    sources.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+fn git_head(repo_root: &Path) -> Option<String> {
+    let git_dir = repo_root.join(".git");
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let Some(reference) = head.trim().strip_prefix("ref: ") else {
+        return Some(head.trim().to_string());
+    };
+    if let Ok(commit) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+    fs::read_to_string(git_dir.join("packed-refs"))
+        .ok()?
+        .lines()
+        .find_map(|line| {
+            let (commit, name) = line.split_once(' ')?;
+            (name == reference).then(|| commit.to_string())
+        })
+}
+
+fn collect_sources(dir: &Path, depth: usize, out: &mut Vec<(PathBuf, u64, Option<SystemTime>)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            if depth < 6
+                && !name.starts_with('.')
+                && !FINGERPRINT_SKIP_DIRS.contains(&name.as_ref())
+            {
+                collect_sources(&path, depth + 1, out);
+            }
+        } else if depth > 0 && name.ends_with(".py") {
+            out.push((path, meta.len(), meta.modified().ok()));
+        }
+    }
+}
+
 pub fn run_fallback_url(base_url: &str) -> String {
     format!("{base_url}/run")
 }
@@ -410,9 +1479,91 @@ pub fn run_sse_url(base_url: &str) -> String {
     format!("{base_url}/run_sse")
 }
 
+pub fn run_live_url(base_url: &str, app_name: &str, user_id: &str, session_id: &str) -> String {
+    let ws_base = if let Some(rest) = base_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        base_url.to_string()
+    };
+    format!(
+        "{ws_base}/run_live?app_name={app_name}&user_id={user_id}&session_id={session_id}&modalities=TEXT"
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use super::choose_default_app;
+    use std::collections::VecDeque;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use crate::types::PortStrategy;
+
+    use super::{
+        candidate_ports, choose_default_app, choose_launcher, filter_log_lines, parse_bound_port,
+        parse_port_strategy, parse_remote_url, push_log_line, repo_fingerprint, restart_backoff,
+        run_live_url, validate_instance_name, AppsCache, BackendHandle, BackendInstances,
+        BackendManager, Bind, HealthProbe, HealthState, Launcher, MAX_LOG_LINES,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn named_instances_are_separate_and_validated() {
+        assert_eq!(validate_instance_name(" agent-v2 ").unwrap(), "agent-v2");
+        assert!(validate_instance_name("").is_err());
+        assert_eq!(
+            validate_instance_name("has space").unwrap_err().kind(),
+            "invalid_input"
+        );
+        assert!(validate_instance_name(&"x".repeat(41)).is_err());
+
+        let instances = BackendInstances::default();
+        let first = instances.get_or_insert("agent-v2");
+        assert!(std::sync::Arc::ptr_eq(
+            &first,
+            &instances.get_or_insert("agent-v2")
+        ));
+        assert_eq!(
+            first.try_lock().unwrap().port_strategy,
+            PortStrategy::Ephemeral
+        );
+        instances.get_or_insert("agent-v1");
+        let names: Vec<String> = instances.all().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["agent-v1", "agent-v2"]);
+        assert!(instances.remove("agent-v2").is_some());
+        assert!(instances.get("agent-v2").is_none());
+    }
+
+    #[test]
+    fn bind_args_match_the_desktop_server_cli() {
+        let tcp = Bind::Tcp {
+            host: "127.0.0.1".to_string(),
+            port: 8766,
+            attempted: vec![8765, 8766],
+        };
+        assert_eq!(tcp.args(), vec!["--host", "127.0.0.1", "--port", "8766"]);
+        assert_eq!(tcp.describe(), "Ports tried: 8765, 8766.");
+
+        let unix = Bind::Unix(std::path::PathBuf::from("/tmp/pv-backend-1.sock"));
+        assert_eq!(unix.args(), vec!["--uds", "/tmp/pv-backend-1.sock"]);
+        assert_eq!(unix.describe(), "Socket: /tmp/pv-backend-1.sock.");
+    }
+
+    #[test]
+    fn apps_cache_expires_after_ttl() {
+        let fetched_at = Instant::now();
+        let cache = AppsCache {
+            apps: vec!["product_validator_search".to_string()],
+            fetched_at,
+        };
+        let ttl = Duration::from_secs(30);
+        assert_eq!(
+            cache.fresh(fetched_at + Duration::from_secs(29), ttl),
+            Some(&["product_validator_search".to_string()][..])
+        );
+        assert_eq!(cache.fresh(fetched_at + ttl, ttl), None);
+    }
 
     #[test]
     fn picks_product_validator_search_if_present() {
@@ -441,4 +1592,220 @@ mod tests {
         let apps = vec!["reports".to_string(), "tests".to_string()];
         assert_eq!(choose_default_app(&apps).as_deref(), Some("reports"));
     }
+
+    #[test]
+    fn live_url_switches_scheme_to_websocket() {
+        assert_eq!(
+            run_live_url("http://127.0.0.1:8765", "app", "u1", "adk-1"),
+            "ws://127.0.0.1:8765/run_live?app_name=app&user_id=u1&session_id=adk-1&modalities=TEXT"
+        );
+        assert!(
+            run_live_url("https://example.com", "a", "u", "s").starts_with("wss://example.com/")
+        );
+    }
+
+    #[test]
+    fn fingerprint_changes_when_agent_sources_change() {
+        let root = std::env::temp_dir().join(format!("pv-desktop-fp-{}", uuid::Uuid::new_v4()));
+        let agent_dir = root.join("product_validator_search");
+        std::fs::create_dir_all(&agent_dir).unwrap();
+        std::fs::write(root.join("pyproject.toml"), "[project]\nname = \"pv\"\n").unwrap();
+        std::fs::write(agent_dir.join("agent.py"), "root_agent = None\n").unwrap();
+
+        let before = repo_fingerprint(&root);
+        assert_eq!(before, repo_fingerprint(&root));
+
+        std::fs::write(agent_dir.join("agent.py"), "root_agent = build()\n").unwrap();
+        assert_ne!(before, repo_fingerprint(&root));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn launcher_chain_prefers_uv_then_venv_then_python() {
+        let root = std::env::temp_dir().join(format!("pv-desktop-launch-{}", uuid::Uuid::new_v4()));
+        let on_path = |names: &'static [&'static str]| {
+            move |name: &str| {
+                names
+                    .contains(&name)
+                    .then(|| PathBuf::from(format!("/bin/{name}")))
+            }
+        };
+
+        assert_eq!(
+            choose_launcher(&root, on_path(&["uv", "python3"])),
+            Some(Launcher::Uv(PathBuf::from("/bin/uv")))
+        );
+        assert_eq!(
+            choose_launcher(&root, on_path(&["python"])),
+            Some(Launcher::Python(PathBuf::from("/bin/python")))
+        );
+        assert_eq!(choose_launcher(&root, on_path(&[])), None);
+
+        let bin = root
+            .join(".venv")
+            .join(if cfg!(windows) { "Scripts" } else { "bin" });
+        std::fs::create_dir_all(&bin).unwrap();
+        let python = bin.join(if cfg!(windows) {
+            "python.exe"
+        } else {
+            "python"
+        });
+        std::fs::write(&python, "").unwrap();
+        let chosen = choose_launcher(&root, on_path(&["python3"])).unwrap();
+        assert_eq!((chosen.name(), chosen), ("venv", Launcher::Venv(python)));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn parses_the_port_uvicorn_actually_bound() {
+        assert_eq!(
+            parse_bound_port(
+                "INFO:     Uvicorn running on http://127.0.0.1:8123 (Press CTRL+C to quit)"
+            ),
+            Some(8123)
+        );
+        assert_eq!(
+            parse_bound_port("INFO:     Uvicorn running on http://0.0.0.0"),
+            Some(80)
+        );
+        assert_eq!(
+            parse_bound_port("INFO:     Uvicorn running on unix socket /tmp/pv.sock"),
+            None
+        );
+        assert_eq!(
+            parse_bound_port("INFO:     Started server process [42]"),
+            None
+        );
+    }
+
+    #[test]
+    fn log_lines_keep_stream_prefix_and_cap() {
+        let logs = Arc::new(Mutex::new(VecDeque::new()));
+        for n in 0..=MAX_LOG_LINES {
+            push_log_line(&logs, "stderr", &format!("line {n}"));
+        }
+        let logs = logs.lock().unwrap();
+        assert_eq!(logs.len(), MAX_LOG_LINES);
+        assert_eq!(logs.front().map(String::as_str), Some("[stderr] line 1"));
+    }
+
+    #[test]
+    fn filters_logs_by_stream_substring_and_limit() {
+        let lines: Vec<String> = [
+            "[stdout] INFO started",
+            "[stderr] WARNING slow tool",
+            "[stderr] ERROR quota exceeded",
+            "[stdout] INFO error handler registered",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        assert_eq!(
+            filter_log_lines(lines.iter(), None, Some("error"), false),
+            vec![lines[2].clone(), lines[3].clone()]
+        );
+        assert_eq!(
+            filter_log_lines(lines.iter(), Some(1), None, true),
+            vec![lines[2].clone()]
+        );
+        assert_eq!(
+            filter_log_lines(lines.iter(), None, Some("  "), false).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn observe_health_reports_only_transitions() {
+        let mut manager = BackendManager::default();
+        let healthy = HealthState {
+            running: true,
+            health: true,
+        };
+        let unhealthy = HealthState {
+            running: true,
+            health: false,
+        };
+        assert!(!manager.observe_health(HealthState::default()));
+        assert!(manager.observe_health(healthy));
+        assert!(!manager.observe_health(healthy));
+        assert!(manager.observe_health(unhealthy));
+        assert!(manager.observe_health(HealthState::default()));
+        assert!(!manager.observe_health(HealthState::default()));
+    }
+
+    #[test]
+    fn health_probe_puts_configured_path_first() {
+        let probe = HealthProbe::default().with_overrides(Some("list-apps"), Some(204), Some(30));
+        assert_eq!(probe.paths, vec!["/list-apps", "/health", "/docs"]);
+        assert_eq!(probe.startup_timeout.as_secs(), 30);
+        assert!(probe.accepts(204));
+        assert!(!probe.accepts(200));
+
+        let defaults = HealthProbe::default().with_overrides(Some("  "), Some(999), Some(0));
+        assert_eq!(defaults, HealthProbe::default());
+        assert!(defaults.accepts(200) && !defaults.accepts(404));
+    }
+
+    #[test]
+    fn port_strategies_parse_and_order_candidates() {
+        let range = parse_port_strategy(" 8765-8768 ").unwrap();
+        assert_eq!(
+            range,
+            PortStrategy::Range {
+                start: 8765,
+                end: 8768
+            }
+        );
+        assert_eq!(candidate_ports(&range, 8767), vec![8767, 8765, 8766, 8768]);
+        assert_eq!(candidate_ports(&range, 9000), vec![8765, 8766, 8767, 8768]);
+        assert_eq!(
+            candidate_ports(&PortStrategy::Range { start: 10, end: 8 }, 1),
+            vec![8, 9, 10]
+        );
+        assert_eq!(candidate_ports(&PortStrategy::Fixed, 8765), vec![8765]);
+        assert_eq!(
+            parse_port_strategy("Ephemeral"),
+            Some(PortStrategy::Ephemeral)
+        );
+        assert_eq!(parse_port_strategy("fixed"), Some(PortStrategy::Fixed));
+        assert_eq!(parse_port_strategy("8765"), None);
+        assert_eq!(parse_port_strategy("a-b"), None);
+    }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_cap() {
+        let secs: Vec<u64> = (1..=7).map(|n| restart_backoff(n).as_secs()).collect();
+        assert_eq!(secs, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(restart_backoff(0).as_secs(), 1);
+    }
+
+    #[test]
+    fn parses_remote_backend_urls() {
+        assert_eq!(
+            parse_remote_url(" https://adk.example.com/ ").unwrap(),
+            (
+                "https://adk.example.com".to_string(),
+                "adk.example.com".to_string(),
+                443
+            )
+        );
+        assert_eq!(
+            parse_remote_url("http://10.0.0.5:9000/api/").unwrap().0,
+            "http://10.0.0.5:9000/api"
+        );
+        assert!(parse_remote_url("ftp://example.com").is_err());
+        assert!(parse_remote_url("not a url").is_err());
+    }
+
+    #[test]
+    fn backend_handle_clones_follow_retarget() {
+        let handle = BackendHandle::new("http://127.0.0.1:8765".to_string());
+        let queued = handle.clone();
+        handle.retarget("http://127.0.0.1:8766".to_string());
+        assert_eq!(queued.base_url(), "http://127.0.0.1:8766");
+        assert_eq!(queued.generation(), 1);
+    }
 }
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use tauri::AppHandle;
+
+use crate::keyring_store::mask_secret;
+use crate::session_store::SessionStore;
+use crate::types::{BackendAuth, BackendAuthSummary};
+
+pub const LAUNCH_TOKEN_HEADER: &str = "x-pv-desktop-token";
+
+fn registry() -> MutexGuard<'static, HashMap<String, HeaderMap>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, HeaderMap>>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn launch_tokens() -> MutexGuard<'static, HashMap<String, HeaderValue>> {
+    static TOKENS: OnceLock<Mutex<HashMap<String, HeaderValue>>> = OnceLock::new();
+    TOKENS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+// Secret handed to a locally spawned backend for one launch; `None` forgets it once the process
+// is gone.
+pub fn set_launch_token(base_url: &str, token: Option<&str>) {
+    let key = normalize_base_url(base_url);
+    match token.and_then(|token| HeaderValue::from_str(token).ok()) {
+        Some(mut value) => {
+            value.set_sensitive(true);
+            launch_tokens().insert(key, value);
+        }
+        None => {
+            launch_tokens().remove(&key);
+        }
+    }
+}
+
+pub fn matches_base(url: &str, base: &str) -> bool {
+    url.strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+}
+
+pub fn normalize_base_url(base_url: &str) -> String {
+    base_url.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+pub fn build_headers(auth: &BackendAuth) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    for header in &auth.headers {
+        let name = HeaderName::from_bytes(header.name.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name '{}'.", header.name.trim()))?;
+        let value = HeaderValue::from_str(header.value.trim())
+            .map_err(|_| format!("Header '{}' has an invalid value.", name))?;
+        headers.insert(name, value);
+    }
+    if let Some(token) = auth
+        .bearer_token
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .map_err(|_| "Bearer token contains characters that are not allowed in a header.")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    Ok(headers)
+}
+
+pub fn install(auth: &BackendAuth) -> Result<(), String> {
+    let headers = build_headers(auth)?;
+    let key = normalize_base_url(&auth.base_url);
+    if headers.is_empty() {
+        registry().remove(&key);
+        return Ok(());
+    }
+
+    registry().insert(key, headers);
+    Ok(())
+}
+
+pub fn load(app: &AppHandle) {
+    let stored = SessionStore::from_app(app).and_then(|store| store.backend_auth_all());
+    match stored {
+        Ok(entries) => {
+            for auth in entries {
+                if let Err(err) = install(&auth) {
+                    eprintln!(
+                        "[backend-auth] skipping settings for {}: {err}",
+                        auth.base_url
+                    );
+                }
+            }
+        }
+        Err(err) => eprintln!("[backend-auth] failed to load backend auth settings: {err}"),
+    }
+}
+
+pub fn headers_for(url: &str) -> HeaderMap {
+    let url = url.to_ascii_lowercase();
+    let mut headers = registry()
+        .iter()
+        .filter(|(base, _)| matches_base(&url, base))
+        .max_by_key(|(base, _)| base.len())
+        .map(|(_, headers)| headers.clone())
+        .unwrap_or_default();
+    if let Some(token) = launch_tokens()
+        .iter()
+        .filter(|(base, _)| matches_base(&url, base))
+        .max_by_key(|(base, _)| base.len())
+        .map(|(_, token)| token.clone())
+    {
+        headers.insert(LAUNCH_TOKEN_HEADER, token);
+    }
+    headers
+}
+
+pub fn summarize(auth: Option<&BackendAuth>, base_url: &str) -> BackendAuthSummary {
+    let token = auth
+        .and_then(|a| a.bearer_token.as_deref())
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    BackendAuthSummary {
+        base_url: normalize_base_url(base_url),
+        bearer_token_set: token.is_some(),
+        bearer_token_masked: token.map(mask_secret),
+        header_names: auth
+            .map(|a| {
+                a.headers
+                    .iter()
+                    .map(|h| h.name.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_headers, headers_for, install, set_launch_token, LAUNCH_TOKEN_HEADER};
+    use crate::types::{BackendAuth, BackendHeader};
+
+    #[test]
+    fn launch_token_is_sent_until_cleared() {
+        set_launch_token("http://127.0.0.1:18999", Some("launch-abc"));
+        let headers = headers_for("http://127.0.0.1:18999/list-apps");
+        assert_eq!(headers[LAUNCH_TOKEN_HEADER], "launch-abc");
+        assert!(headers_for("http://127.0.0.1:189990/list-apps").is_empty());
+
+        set_launch_token("http://127.0.0.1:18999", None);
+        assert!(headers_for("http://127.0.0.1:18999/list-apps").is_empty());
+    }
+
+    #[test]
+    fn applies_headers_only_to_matching_backend() {
+        let auth = BackendAuth {
+            base_url: "https://agents.example.com/".to_string(),
+            bearer_token: Some("tok-123".to_string()),
+            headers: vec![BackendHeader {
+                name: "X-Org".to_string(),
+                value: "acme".to_string(),
+            }],
+        };
+        install(&auth).unwrap();
+
+        let headers = headers_for("https://agents.example.com/run_sse");
+        assert_eq!(headers["authorization"], "Bearer tok-123");
+        assert_eq!(headers["x-org"], "acme");
+        assert!(headers_for("https://agents.example.com.evil/run").is_empty());
+        assert!(headers_for("http://127.0.0.1:8765/run").is_empty());
+
+        let invalid = BackendAuth {
+            headers: vec![BackendHeader {
+                name: "bad header".to_string(),
+                value: "x".to_string(),
+            }],
+            ..auth
+        };
+        assert!(build_headers(&invalid).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::backend_auth;
+
+// Oldest google-adk release this client is tested against; older servers still run, with a warning.
+const MIN_ADK_VERSION: &str = "1.0.0";
+pub const STRICT_ENV: &str = "PV_DESKTOP_STRICT_BACKEND_VERSION";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendVersion {
+    pub version: Option<String>,
+    pub run_sse_supported: Option<bool>,
+    pub outdated: bool,
+    pub warning: Option<String>,
+}
+
+fn client(base_url: &str) -> Option<Client> {
+    crate::http::backend_client_builder(base_url)
+        .timeout(Duration::from_secs(5))
+        .build()
+        .ok()
+}
+
+// Asks the server for its version, falling back to the startup banner in `logs`, and checks the
+// OpenAPI schema for the streaming route the desktop client depends on.
+pub async fn detect(base_url: &str, logs: &str) -> BackendVersion {
+    let (version, run_sse_supported) = match client(base_url) {
+        Some(client) => (
+            fetch_version(&client, base_url).await,
+            fetch_run_sse_support(&client, base_url).await,
+        ),
+        None => (None, None),
+    };
+    assess(
+        version.or_else(|| version_from_logs(logs)),
+        run_sse_supported,
+    )
+}
+
+pub fn assess(version: Option<String>, run_sse_supported: Option<bool>) -> BackendVersion {
+    let outdated = version
+        .as_deref()
+        .is_some_and(|v| is_older(v, MIN_ADK_VERSION));
+    let warning = if run_sse_supported == Some(false) {
+        Some("Backend does not expose /run_sse; upgrade google-adk to stream runs.".to_string())
+    } else {
+        version
+            .as_deref()
+            .filter(|v| is_older(v, MIN_ADK_VERSION))
+            .map(|v| {
+                format!("Backend runs google-adk {v}; this app expects {MIN_ADK_VERSION} or newer.")
+            })
+    };
+    BackendVersion {
+        version,
+        run_sse_supported,
+        outdated,
+        warning,
+    }
+}
+
+async fn fetch_version(client: &Client, base_url: &str) -> Option<String> {
+    let url = format!("{base_url}/version");
+    let response = client
+        .get(&url)
+        .headers(backend_auth::headers_for(&url))
+        .send()
+        .await
+        .ok()
+        .filter(|response| response.status().is_success())?;
+    let body = response.text().await.ok()?;
+    match serde_json::from_str::<Value>(&body) {
+        Ok(value) => value
+            .get("version")
+            .or_else(|| value.get("adk_version"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        Err(_) => version_token(&body),
+    }
+}
+
+async fn fetch_run_sse_support(client: &Client, base_url: &str) -> Option<bool> {
+    let url = format!("{base_url}/openapi.json");
+    let response = client
+        .get(&url)
+        .headers(backend_auth::headers_for(&url))
+        .send()
+        .await
+        .ok()
+        .filter(|response| response.status().is_success())?;
+    let schema = response.json::<Value>().await.ok()?;
+    Some(schema.get("paths")?.get("/run_sse").is_some())
+}
+
+// Picks the version out of banner lines such as "google-adk 1.4.2" or "ADK version: 1.4.2".
+pub fn version_from_logs(logs: &str) -> Option<String> {
+    logs.lines()
+        .filter(|line| line.to_ascii_lowercase().contains("adk"))
+        .find_map(version_token)
+}
+
+fn version_token(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .map(|token| token.trim_start_matches('v').trim_matches('.'))
+        .find(|token| {
+            let parts: Vec<&str> = token.split('.').collect();
+            parts.len() >= 2 && parts[0].parse::<u32>().is_ok() && parts[1].parse::<u32>().is_ok()
+        })
+        .map(str::to_string)
+}
+
+fn numeric_parts(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map(|part| {
+            part.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn is_older(version: &str, minimum: &str) -> bool {
+    let (mut have, mut want) = (numeric_parts(version), numeric_parts(minimum));
+    let len = have.len().max(want.len());
+    have.resize(len, 0);
+    want.resize(len, 0);
+    have < want
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assess, is_older, version_from_logs};
+
+    #[test]
+    fn parses_banner_and_gates_old_servers() {
+        let logs =
+            "[stderr] INFO: Started server process [42]\n[stderr] google-adk v0.5.0 web server";
+        assert_eq!(version_from_logs(logs).as_deref(), Some("0.5.0"));
+        assert_eq!(
+            version_from_logs("[stdout] listening on 127.0.0.1:8765"),
+            None
+        );
+
+        assert!(is_older("0.5.0", "1.0.0"));
+        assert!(!is_older("1.10.0", "1.9.3"));
+        assert!(!is_older("1.0", "1.0.0"));
+        assert!(is_older("1.0.0rc1", "1.0.1"));
+
+        let old = assess(Some("0.5.0".to_string()), Some(true));
+        assert!(old.outdated);
+        assert!(old.warning.unwrap().contains("0.5.0"));
+        let missing_sse = assess(Some("1.2.0".to_string()), Some(false));
+        assert!(missing_sse.warning.unwrap().contains("/run_sse"));
+        assert_eq!(assess(None, None).warning, None);
+    }
+}
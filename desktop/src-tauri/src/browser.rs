@@ -0,0 +1,19 @@
+use std::process::Command;
+
+// Hands a URL or file path to the OS, which opens it in the user's default browser.
+pub fn open(target: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut cmd = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("rundll32");
+        cmd.arg("url.dll,FileProtocolHandler");
+        cmd
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut cmd = Command::new("xdg-open");
+    cmd.arg(target)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open the default browser: {e}"))
+}
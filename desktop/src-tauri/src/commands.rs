@@ -1,43 +1,181 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use tauri::{AppHandle, Emitter, State};
-use tokio::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{watch, Mutex};
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
-use crate::backend::{choose_default_app, BackendManager};
-use crate::keyring_store::KeyStore;
-use crate::session_store::{phase_after_run, SessionStore};
-use crate::stream::{self, StreamOutcome};
+use crate::adk_gc;
+use crate::analytics;
+use crate::attachments;
+use crate::backend::{self, choose_default_app, BackendInstances, BackendManager};
+use crate::backend_auth;
+use crate::backend_version;
+use crate::browser;
+use crate::debug_bundle::{self, BundleEntry};
+use crate::debug_events::DebugEvents;
+use crate::deep_link;
+use crate::degradation::{self, DegradationLog};
+use crate::doctor::{self, DoctorInput};
+use crate::env_export;
+use crate::error::{AppError, ErrorCode};
+use crate::event_buffer::EventBuffer;
+use crate::events;
+use crate::google_oauth;
+use crate::indexer::{self, Indexer};
+use crate::installer;
+use crate::key_lock::{self, LockConfig};
+use crate::key_validation;
+use crate::keyring_store::{self, KeyEnv, KeyStore};
+use crate::log_files;
+use crate::memory;
+use crate::models::{self, ModelCatalogCache};
+use crate::multi_run::{self, MultiRunTags};
+use crate::prompt_estimate;
+use crate::quota;
+use crate::redact;
+use crate::repo_update;
+use crate::report_blocks;
+use crate::report_export;
+use crate::run_admission::{self, BackendRecovery};
+use crate::run_budget::{self, RunDeadlines};
+use crate::run_env;
+use crate::scheduler::{Admission, RunScheduler, RunSlot};
+use crate::session_store::{phase_after_run, ReplayMessage, SessionStore, StoreError};
+use crate::settings;
+use crate::stream::{self, EmitThrottle, LiveSessions, StreamOutcome, StreamPauses, StreamSummary};
+use crate::tasks;
+use crate::transcript;
+use crate::translate;
+use crate::tray;
 use crate::types::{
-    Ack, BackendStartConfig, BackendStatus, KeyPresence, KeysInput, RunMode, SessionCreateInput,
-    SessionDeleteInput, SessionListInput, SessionMessage, SessionMessageAppendInput,
-    SessionMessagesGetInput, SessionMeta, SessionPhase, SessionPhaseGetInput, SessionPhaseSetInput,
-    SessionPhaseState, StreamRunInput,
+    Ack, AdkSessionsGc, AnalyticsFunnel, BackendAuth, BackendAuthGetInput, BackendAuthSummary,
+    BackendInstallResult, BackendInstanceStartInput, BackendInstanceStatus, BackendLogExport,
+    BackendLogsInput, BackendStartConfig, BackendStatus, BackendUpdateResult, DebugBundle,
+    DoctorReport, EnvKeyCandidate, EnvKeysImportInput, EventsCapabilities, EventsHandshakeInput,
+    GoogleOAuthSignInInput, GoogleOAuthStatus, IndexingStatus, InterruptedRun, KeyLockConfigInput,
+    KeyLockStatus, KeyPresence, KeyProfileInput, KeyProfileSummary, KeyUnlockInput, KeyValidation,
+    KeysEnvExport, KeysExportEnvInput, KeysInput, MemoryDeleteInput, MemoryEntry, MemorySettings,
+    ModelCatalog, MultiRunTarget, PortStrategy, PromptEstimate, PromptEstimateInput,
+    ReportBlocksGetInput, ReportExport, ReportExportInput, ReportFormat, ReportOpenInput,
+    ReportTranslateInput, ReportTranslation, ReportTranslationsGetInput, RevalidationGetInput,
+    RevalidationSchedule, RevalidationScheduleInput, RunMode, RunResumeInput, RunResumeResult,
+    RunStatus, RunTranscriptExport, RunTranscriptExportInput, SecretSetInput, SecretSummary,
+    SessionCreateInput, SessionDebugBundleInput, SessionDeleteInput, SessionListInput,
+    SessionMessage, SessionMessageAppendInput, SessionMessagesGetInput, SessionMeta,
+    SessionNavigation, SessionPhase, SessionPhaseGetInput, SessionPhaseSetInput, SessionPhaseState,
+    SessionTask, SessionTaskUpdateInput, SessionTasksExportInput, SessionTasksGetInput,
+    SessionWindow, SessionWindowInput, Settings, StoredReportBlocks, StreamDebugInput,
+    StreamRunInput, StreamRunMultiInput, StreamTransport, TaskExportResult, TaskExportTarget,
+    ToolQuota,
 };
+use crate::windows;
 
 const REPLAY_DEPTH: usize = 20;
+const CONTEXT_WINDOW_ENV: &str = "PV_DESKTOP_CONTEXT_WINDOW_TOKENS";
+const GOOGLE_ADC_FILE: &str = "google-adc.json";
 
 #[derive(Clone)]
 pub struct AppState {
     pub backend: Arc<Mutex<BackendManager>>,
+    pub backend_instances: Arc<BackendInstances>,
     pub stream_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    pub live_sessions: LiveSessions,
+    pub stream_pauses: StreamPauses,
+    pub run_scheduler: Arc<RunScheduler>,
+    pub model_catalog: ModelCatalogCache,
+    pub event_buffer: Arc<EventBuffer>,
+    pub emit_throttle: Arc<EmitThrottle>,
+    pub degradations: Arc<DegradationLog>,
+    pub multi_runs: Arc<MultiRunTags>,
+    pub run_deadlines: Arc<RunDeadlines>,
+    pub debug_events: Arc<DebugEvents>,
+    pub indexer: Arc<Indexer>,
     pub key_store: KeyStore,
+    pub interrupted_runs: Arc<std::sync::Mutex<Vec<InterruptedRun>>>,
+    pub backend_recovery: Arc<BackendRecovery>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
             backend: Arc::new(Mutex::new(BackendManager::default())),
+            backend_instances: Arc::new(BackendInstances::default()),
             stream_tokens: Arc::new(Mutex::new(HashMap::new())),
+            live_sessions: Arc::new(Mutex::new(HashMap::new())),
+            stream_pauses: Arc::new(Mutex::new(HashMap::new())),
+            run_scheduler: Arc::new(RunScheduler::from_env()),
+            model_catalog: Arc::new(Mutex::new(None)),
+            event_buffer: Arc::new(EventBuffer::default()),
+            emit_throttle: Arc::new(EmitThrottle::from_env()),
+            degradations: Arc::new(DegradationLog::default()),
+            multi_runs: Arc::new(MultiRunTags::default()),
+            run_deadlines: Arc::new(RunDeadlines::default()),
+            debug_events: Arc::new(DebugEvents::default()),
+            indexer: Arc::new(Indexer::default()),
             key_store: KeyStore::default(),
+            interrupted_runs: Arc::new(std::sync::Mutex::new(Vec::new())),
+            backend_recovery: Arc::new(BackendRecovery::default()),
         }
     }
+
+    pub fn backend_for(
+        &self,
+        instance: Option<&str>,
+    ) -> Result<Arc<Mutex<BackendManager>>, AppError> {
+        match instance.map(str::trim) {
+            None | Some("") | Some(backend::DEFAULT_INSTANCE) => Ok(self.backend.clone()),
+            Some(name) => self.backend_instances.get(name).ok_or_else(|| {
+                AppError::NotFound(format!("Backend instance '{name}' is not running."))
+            }),
+        }
+    }
+}
+
+fn local_store(app: &AppHandle) -> Result<SessionStore, AppError> {
+    Ok(SessionStore::from_app(app)?)
+}
+
+// Everything a spawned backend needs: keychain API keys (from `profile` when given), named
+// secrets, Google sign-in credentials, plus the user's extra environment.
+pub fn backend_keys(
+    app: &AppHandle,
+    state: &AppState,
+    profile: Option<&str>,
+) -> Result<KeyEnv, AppError> {
+    let store = local_store(app)?;
+    let mut keys = match profile {
+        Some(name) => {
+            if !store.key_profiles_get()?.iter().any(|p| p == name) {
+                return Err(AppError::NotFound(format!(
+                    "Key profile '{name}' does not exist."
+                )));
+            }
+            state.key_store.profile_env_values(name)?
+        }
+        None => state.key_store.read_env_values()?,
+    };
+    keys.extra_env = store.backend_env_get()?;
+    for name in store.secret_names_get()? {
+        if let Some(value) = state.key_store.secret(&name)? {
+            keys.credential_env.insert(name, value);
+        }
+    }
+    if let Some(credentials) = state.key_store.google_credentials()? {
+        let path = google_adc_path(app)?;
+        google_oauth::write_adc_file(&path, &credentials).map_err(AppError::Storage)?;
+        keys.credential_env = google_oauth::backend_env(&path, &credentials);
+    }
+    Ok(keys)
 }
 
-fn local_store(app: &AppHandle) -> Result<SessionStore, String> {
-    SessionStore::from_app(app)
+fn google_adc_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(GOOGLE_ADC_FILE))
+        .map_err(|e| AppError::Storage(format!("Failed to resolve app data dir: {e}")))
 }
 
 #[tauri::command]
@@ -45,25 +183,123 @@ pub async fn backend_start(
     app: AppHandle,
     state: State<'_, AppState>,
     config: Option<BackendStartConfig>,
-) -> Result<BackendStatus, String> {
-    let keys = state.key_store.read_env_values()?;
+) -> Result<BackendStatus, AppError> {
+    if let Some(cfg) = &config {
+        remember_remote_token(&app, cfg)?;
+    }
+    let profile = config.as_ref().and_then(|cfg| cfg.key_profile.clone());
+    let keys = backend_keys(&app, &state, profile.as_deref())?;
     let mut backend = state.backend.lock().await;
+    let config = if backend.is_remote() {
+        config
+    } else {
+        settings::backend_defaults(config, &settings::current())
+    };
     let status = backend.start(config, &keys).await?;
-    app.emit("backend-status", &status)
-        .map_err(|e| format!("failed to emit backend-status: {e}"))?;
+    tray::show_backend_status(&app, &status);
+    app.emit(&events::name(events::BACKEND_STATUS), &status)
+        .map_err(|e| AppError::Internal(format!("failed to emit backend-status: {e}")))?;
     Ok(status)
 }
 
+// A token given alongside a remote URL is stored like one set through backend_auth_set, keeping
+// any custom headers already saved for that URL.
+fn remember_remote_token(app: &AppHandle, cfg: &BackendStartConfig) -> Result<(), AppError> {
+    let (Some(base_url), Some(token)) = (
+        cfg.remote_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty()),
+        cfg.bearer_token.as_deref(),
+    ) else {
+        return Ok(());
+    };
+    let store = local_store(app)?;
+    let mut auth = store.backend_auth_get(base_url)?.unwrap_or(BackendAuth {
+        base_url: base_url.to_string(),
+        bearer_token: None,
+        headers: Vec::new(),
+    });
+    auth.bearer_token = Some(token.to_string());
+    backend_auth::build_headers(&auth).map_err(AppError::InvalidInput)?;
+    store.backend_auth_set(&auth)?;
+    backend_auth::install(&auth)?;
+    Ok(())
+}
+
+// Starts (or reconfigures) a named backend; `default` addresses the main one.
+#[tauri::command]
+pub async fn backend_instance_start(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: BackendInstanceStartInput,
+) -> Result<BackendInstanceStatus, AppError> {
+    let name = backend::validate_instance_name(&input.name)?;
+    let mut config = input.config;
+    if let Some(cfg) = config.as_mut() {
+        remember_remote_token(&app, cfg)?;
+        if cfg.port.is_some() && cfg.port_strategy.is_none() {
+            cfg.port_strategy = Some(PortStrategy::Fixed);
+        }
+    }
+    let profile = config.as_ref().and_then(|cfg| cfg.key_profile.clone());
+    let keys = backend_keys(&app, &state, profile.as_deref())?;
+    let manager = if name == backend::DEFAULT_INSTANCE {
+        state.backend.clone()
+    } else {
+        state.backend_instances.get_or_insert(&name)
+    };
+    let status = manager.lock().await.start(config, &keys).await?;
+    Ok(BackendInstanceStatus { name, status })
+}
+
+#[tauri::command]
+pub async fn backend_instance_stop(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Ack, AppError> {
+    let name = name.trim();
+    if name == backend::DEFAULT_INSTANCE {
+        return Err(AppError::InvalidInput(
+            "Use backend_stop for the default backend.".to_string(),
+        ));
+    }
+    let manager = state
+        .backend_instances
+        .remove(name)
+        .ok_or_else(|| AppError::NotFound(format!("Backend instance '{name}' is not running.")))?;
+    manager.lock().await.stop().await?;
+    Ok(Ack {
+        ok: true,
+        message: Some(format!("Stopped backend instance '{name}'.")),
+    })
+}
+
+#[tauri::command]
+pub async fn backend_instances(
+    state: State<'_, AppState>,
+) -> Result<Vec<BackendInstanceStatus>, AppError> {
+    let mut instances = vec![(backend::DEFAULT_INSTANCE.to_string(), state.backend.clone())];
+    instances.extend(state.backend_instances.all());
+    let mut out = Vec::with_capacity(instances.len());
+    for (name, manager) in instances {
+        let (status, _) = manager.lock().await.status().await?;
+        out.push(BackendInstanceStatus { name, status });
+    }
+    Ok(out)
+}
+
 #[tauri::command]
 pub async fn backend_stop(
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<BackendStatus, String> {
+) -> Result<BackendStatus, AppError> {
     let mut backend = state.backend.lock().await;
     backend.stop().await?;
     let (status, _) = backend.status().await?;
-    app.emit("backend-status", &status)
-        .map_err(|e| format!("failed to emit backend-status: {e}"))?;
+    tray::show_backend_status(&app, &status);
+    app.emit(&events::name(events::BACKEND_STATUS), &status)
+        .map_err(|e| AppError::Internal(format!("failed to emit backend-status: {e}")))?;
     Ok(status)
 }
 
@@ -71,7 +307,7 @@ pub async fn backend_stop(
 pub async fn backend_status(
     app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<BackendStatus, String> {
+) -> Result<BackendStatus, AppError> {
     let mut backend = state.backend.lock().await;
     let (status, exited) = backend.status().await?;
 
@@ -80,39 +316,52 @@ pub async fn backend_status(
             .last_error
             .clone()
             .unwrap_or_else(|| "Local backend process exited unexpectedly.".to_string());
-        app.emit("backend-exited", serde_json::json!({ "message": message }))
-            .map_err(|e| format!("failed to emit backend-exited: {e}"))?;
+        app.emit(
+            &events::name(events::BACKEND_EXITED),
+            serde_json::json!({ "message": message }),
+        )
+        .map_err(|e| AppError::Internal(format!("failed to emit backend-exited: {e}")))?;
     }
 
     Ok(status)
 }
 
 #[tauri::command]
-pub async fn backend_list_apps(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+pub async fn backend_list_apps(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<Vec<String>, AppError> {
+    let force_refresh = force_refresh.unwrap_or(false);
     let mut backend = state.backend.lock().await;
+    if !force_refresh {
+        if let Some(apps) = backend.fresh_apps() {
+            return Ok(apps);
+        }
+    }
     let (status, _) = backend.status().await?;
     if !status.running || !status.health {
-        let keys = state.key_store.read_env_values()?;
+        let keys = backend_keys(&app, &state, backend.key_profile())?;
         let restarted = backend
             .start(
                 Some(BackendStartConfig {
                     host: Some(status.host),
                     port: Some(status.port),
-                    repo_root: None,
                     force_restart: Some(true),
+                    ..BackendStartConfig::default()
                 }),
                 &keys,
             )
             .await?;
 
         if !restarted.running || !restarted.health {
-            return Err(
+            return Err(AppError::BackendUnavailable(
                 "Backend is unavailable; failed to recover before listing apps.".to_string(),
-            );
+            ));
         }
     }
 
-    let apps = backend.list_apps().await?;
+    let apps = backend.cached_apps(force_refresh).await?;
 
     if backend.app_name().is_none() {
         backend.set_app_name(choose_default_app(&apps));
@@ -121,27 +370,89 @@ pub async fn backend_list_apps(state: State<'_, AppState>) -> Result<Vec<String>
     Ok(apps)
 }
 
+#[tauri::command]
+pub async fn backend_logs(
+    state: State<'_, AppState>,
+    input: Option<BackendLogsInput>,
+) -> Result<Vec<String>, AppError> {
+    let input = input.unwrap_or_default();
+    let backend = state.backend.lock().await;
+    Ok(backend.filtered_logs(
+        input.limit,
+        input.contains.as_deref(),
+        input.stderr_only.unwrap_or(false),
+    ))
+}
+
+#[tauri::command]
+pub async fn backend_log_export(app: AppHandle) -> Result<BackendLogExport, AppError> {
+    let (path, current_path, size_bytes) = log_files::export(&app)?;
+    Ok(BackendLogExport {
+        path: path.to_string_lossy().to_string(),
+        current_path: current_path.to_string_lossy().to_string(),
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub async fn backend_auth_get(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: Option<BackendAuthGetInput>,
+) -> Result<BackendAuthSummary, AppError> {
+    let base_url = match input.and_then(|i| i.base_url) {
+        Some(base_url) => base_url,
+        None => state.backend.lock().await.base_url(),
+    };
+    let auth = local_store(&app)?.backend_auth_get(&base_url)?;
+    Ok(backend_auth::summarize(auth.as_ref(), &base_url))
+}
+
+#[tauri::command]
+pub async fn backend_auth_set(
+    app: AppHandle,
+    input: BackendAuth,
+) -> Result<BackendAuthSummary, AppError> {
+    if input.base_url.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "A backend base URL is required.".to_string(),
+        ));
+    }
+    backend_auth::build_headers(&input)?;
+    local_store(&app)?.backend_auth_set(&input)?;
+    backend_auth::install(&input)?;
+    Ok(backend_auth::summarize(Some(&input), &input.base_url))
+}
+
 #[tauri::command]
 pub async fn session_create(
     app: AppHandle,
     input: SessionCreateInput,
-) -> Result<SessionMeta, String> {
-    local_store(&app)?.create_session(&input)
+) -> Result<SessionMeta, AppError> {
+    Ok(local_store(&app)?.create_session(&input)?)
 }
 
 #[tauri::command]
 pub async fn session_list(
     app: AppHandle,
     input: SessionListInput,
-) -> Result<Vec<SessionMeta>, String> {
-    local_store(&app)?.list_sessions(&input)
+) -> Result<Vec<SessionMeta>, AppError> {
+    Ok(local_store(&app)?.list_sessions(&input)?)
 }
 
 #[tauri::command]
-pub async fn session_delete(app: AppHandle, input: SessionDeleteInput) -> Result<Ack, String> {
+pub async fn session_delete(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: SessionDeleteInput,
+) -> Result<Ack, AppError> {
     let deleted = local_store(&app)?.delete_session(&input.session_id)?;
+    state.live_sessions.lock().await.remove(&input.session_id);
     if !deleted {
-        return Err(format!("Session '{}' was not found.", input.session_id));
+        return Err(AppError::NotFound(format!(
+            "Session '{}' was not found.",
+            input.session_id
+        )));
     }
 
     Ok(Ack {
@@ -154,189 +465,1831 @@ pub async fn session_delete(app: AppHandle, input: SessionDeleteInput) -> Result
 pub async fn session_messages_get(
     app: AppHandle,
     input: SessionMessagesGetInput,
-) -> Result<Vec<SessionMessage>, String> {
-    local_store(&app)?.messages_get(&input.session_id)
+) -> Result<Vec<SessionMessage>, AppError> {
+    Ok(local_store(&app)?.messages_get(&input.session_id)?)
 }
 
 #[tauri::command]
 pub async fn session_messages_append(
     app: AppHandle,
     input: SessionMessageAppendInput,
-) -> Result<SessionMessage, String> {
-    local_store(&app)?.message_append(&input)
+) -> Result<SessionMessage, AppError> {
+    Ok(local_store(&app)?.message_append(&input)?)
 }
 
 #[tauri::command]
 pub async fn session_phase_get(
     app: AppHandle,
     input: SessionPhaseGetInput,
-) -> Result<SessionPhaseState, String> {
-    local_store(&app)?.phase_get(&input.session_id)
+) -> Result<SessionPhaseState, AppError> {
+    Ok(local_store(&app)?.phase_get(&input.session_id)?)
 }
 
 #[tauri::command]
 pub async fn session_phase_set(
     app: AppHandle,
     input: SessionPhaseSetInput,
-) -> Result<SessionPhaseState, String> {
-    local_store(&app)?.phase_set(&input.session_id, input.phase, input.read_only)
+) -> Result<SessionPhaseState, AppError> {
+    Ok(local_store(&app)?.phase_set(&input.session_id, input.phase, input.read_only)?)
 }
 
 #[tauri::command]
-pub async fn stream_run(
+pub async fn revalidation_schedule(
     app: AppHandle,
-    state: State<'_, AppState>,
-    input: StreamRunInput,
-) -> Result<Ack, String> {
-    if input.text.trim().is_empty() {
-        return Err("Message text is required.".to_string());
-    }
+    input: RevalidationScheduleInput,
+) -> Result<RevalidationSchedule, AppError> {
+    Ok(local_store(&app)?.revalidation_schedule(&input.session_id, input.months)?)
+}
 
-    let store = local_store(&app)?;
-    store.validate_run_mode(&input.session_id, input.run_mode)?;
+#[tauri::command]
+pub async fn revalidation_get(
+    app: AppHandle,
+    input: RevalidationGetInput,
+) -> Result<Option<RevalidationSchedule>, AppError> {
+    Ok(local_store(&app)?.revalidation_get(&input.session_id)?)
+}
 
-    let replay_messages = store.replay_messages(&input.session_id, &input.text, REPLAY_DEPTH)?;
-    let session_store_path = store.db_path();
+#[tauri::command]
+pub async fn revalidation_cancel(
+    app: AppHandle,
+    input: RevalidationGetInput,
+) -> Result<Ack, AppError> {
+    let cancelled = local_store(&app)?.revalidation_cancel(&input.session_id)?;
+    Ok(Ack {
+        ok: true,
+        message: Some(if cancelled {
+            "Re-validation cancelled".to_string()
+        } else {
+            "No pending re-validation for session".to_string()
+        }),
+    })
+}
 
-    let base_url = {
+// Preflight checklist for onboarding; reports problems instead of failing on them.
+#[tauri::command]
+pub async fn backend_doctor(state: State<'_, AppState>) -> Result<DoctorReport, AppError> {
+    let (status, repo_root) = {
         let mut backend = state.backend.lock().await;
         let (status, _) = backend.status().await?;
-        if !status.running {
-            return Err("Backend is not running. Start backend before streaming.".to_string());
-        }
+        (status, backend.repo_root().to_path_buf())
+    };
+    let keys = state.key_store.key_presence_with_external(&repo_root)?;
+    let (uv, python) = backend::interpreters(&repo_root);
+    let local_running = status.running && !status.remote;
+    Ok(doctor::run(DoctorInput {
+        repo_root: &repo_root,
+        uv,
+        python,
+        keys: &keys,
+        host: &status.host,
+        port: status.port,
+        port_available: backend::is_port_available(&status.host, status.port),
+        backend_running: local_running,
+    })
+    .await)
+}
 
-        if status.health {
-            status.base_url
-        } else {
-            let keys = state.key_store.read_env_values()?;
-            let restarted = backend
-                .start(
-                    Some(BackendStartConfig {
-                        host: Some(status.host),
-                        port: Some(status.port),
-                        repo_root: None,
-                        force_restart: Some(true),
-                    }),
-                    &keys,
-                )
-                .await?;
+// Checks a directory the user picked as the repo root without changing the running backend.
+#[tauri::command]
+pub async fn backend_validate_repo(path: String) -> Result<DoctorReport, AppError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Repository path is required.".to_string(),
+        ));
+    }
+    let root = std::fs::canonicalize(trimmed).unwrap_or_else(|_| trimmed.into());
+    let (uv, python) = backend::interpreters(&root);
+    Ok(doctor::validate_repo(&root, python, uv.is_some()).await)
+}
 
-            if !restarted.health {
-                return Err(
-                    "Backend is unhealthy and restart failed. Please restart the desktop app."
-                        .to_string(),
-                );
-            }
-            restarted.base_url
+// Sets up the Python environment in the repo root; progress streams as `backend-install` events.
+#[tauri::command]
+pub async fn backend_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BackendInstallResult, AppError> {
+    let repo_root = state.backend.lock().await.repo_root().to_path_buf();
+    let argv = installer::plan(
+        &repo_root,
+        installer::configured_installer().as_deref(),
+        backend::find_on_path,
+    )
+    .map_err(AppError::InvalidInput)?;
+    installer::run(&app, &repo_root, argv).await
+}
+
+// Pulls agent updates into the backend checkout (output streams as `backend-update` events) and
+// restarts a running backend onto the new code unless runs are still in flight.
+#[tauri::command]
+pub async fn backend_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<BackendUpdateResult, AppError> {
+    let repo_root = {
+        let backend = state.backend.lock().await;
+        if backend.is_remote() {
+            return Err(AppError::InvalidInput(
+                "The backend is remote; update it where it is hosted.".to_string(),
+            ));
         }
+        backend.repo_root().to_path_buf()
     };
+    let mut result = repo_update::update(&app, &repo_root).await?;
+    if !result.ok || !result.updated {
+        return Ok(result);
+    }
 
-    let token = CancellationToken::new();
-    {
-        let mut map = state.stream_tokens.lock().await;
-        if let Some(existing) = map.insert(input.request_id.clone(), token.clone()) {
-            existing.cancel();
-        }
+    let mut backend = state.backend.lock().await;
+    let (status, _) = backend.status().await?;
+    if !status.running {
+        result.backend = Some(status);
+    } else if !state.stream_tokens.lock().await.is_empty() {
+        result.message =
+            Some("Restart the backend once active runs finish to load the update.".to_string());
+        result.backend = Some(status);
+    } else {
+        let keys = backend_keys(&app, &state, backend.key_profile())?;
+        let restarted = backend
+            .start(
+                Some(BackendStartConfig {
+                    host: Some(status.host),
+                    port: Some(status.port),
+                    force_restart: Some(true),
+                    ..BackendStartConfig::default()
+                }),
+                &keys,
+            )
+            .await?;
+        tray::show_backend_status(&app, &restarted);
+        let _ = app.emit(&events::name(events::BACKEND_STATUS), &restarted);
+        result.restarted = true;
+        result.backend = Some(restarted);
     }
+    Ok(result)
+}
 
-    let app_handle = app.clone();
-    let request_id = input.request_id.clone();
-    let stream_map = state.stream_tokens.clone();
-    let run_mode = input.run_mode;
-    let desktop_session_id = input.session_id.clone();
-    let mut adk_input = input.clone();
-    adk_input.session_id = format!("adk-{}", Uuid::new_v4());
+#[tauri::command]
+pub async fn backend_env_get(app: AppHandle) -> Result<BTreeMap<String, String>, AppError> {
+    Ok(local_store(&app)?.backend_env_get()?)
+}
 
-    if run_mode == RunMode::Approve {
-        store.phase_set(&desktop_session_id, SessionPhase::Running, true)?;
-    }
+// Takes effect on the next backend start; a running backend keeps its current environment.
+#[tauri::command]
+pub async fn backend_env_set(
+    app: AppHandle,
+    env: BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, AppError> {
+    let env = run_env::backend_env(&env).map_err(AppError::InvalidInput)?;
+    local_store(&app)?.backend_env_set(&env)?;
+    Ok(env)
+}
 
-    tokio::spawn(async move {
-        let task_store = SessionStore::from_path(session_store_path);
-        let outcome = stream::run_stream_task(
-            app_handle.clone(),
-            base_url,
-            adk_input,
-            replay_messages,
-            token,
-        )
-        .await;
+// Returns and clears the session link that opened the app, if the UI has not handled it yet.
+#[tauri::command]
+pub async fn deep_link_take() -> Result<Option<SessionNavigation>, AppError> {
+    Ok(deep_link::take_pending())
+}
 
-        let succeeded = match outcome {
-            Ok(StreamOutcome::Completed) => true,
-            Ok(StreamOutcome::Failed) => false,
-            Err(err) => {
-                let event_name = format!("agent-stream:{}", request_id);
-                let _ = app_handle.emit(
-                    &event_name,
-                    serde_json::json!({
-                        "kind": "stream_error",
-                        "requestId": request_id,
-                        "message": err,
-                        "retryable": true
-                    }),
-                );
-                let _ = app_handle.emit(
-                    &event_name,
-                    serde_json::json!({
-                        "kind": "stream_done",
-                        "requestId": request_id,
-                        "usage": null
-                    }),
-                );
-                false
-            }
-        };
+// One window per session: runs for other sessions never reach it, so a validation can be watched
+// while another idea is drafted in the main window.
+#[tauri::command]
+pub async fn session_open_window(
+    app: AppHandle,
+    input: SessionWindowInput,
+) -> Result<SessionWindow, AppError> {
+    let title = local_store(&app)?.session_title(&input.session_id)?;
+    let label =
+        windows::open_session(&app, &input.session_id, &title).map_err(AppError::InvalidInput)?;
+    Ok(SessionWindow {
+        label,
+        session_id: input.session_id,
+    })
+}
 
-        let (phase, read_only) = phase_after_run(run_mode, succeeded);
-        let _ = task_store.phase_set(&desktop_session_id, phase, read_only);
+#[tauri::command]
+pub async fn settings_get(app: AppHandle) -> Result<Settings, AppError> {
+    Ok(local_store(&app)?.settings_get()?)
+}
 
-        let mut map = stream_map.lock().await;
-        map.remove(&request_id);
-    });
+// Backend defaults take effect on the next start; everything else applies immediately.
+#[tauri::command]
+pub async fn settings_set(app: AppHandle, settings: Settings) -> Result<Settings, AppError> {
+    let settings = settings::validate(settings).map_err(AppError::InvalidInput)?;
+    local_store(&app)?.settings_set(&settings)?;
+    settings::apply(&app, settings.clone());
+    Ok(settings)
+}
 
-    Ok(Ack {
-        ok: true,
-        message: Some("Stream started".to_string()),
+#[tauri::command]
+pub async fn memory_settings_get() -> Result<MemorySettings, AppError> {
+    Ok(MemorySettings {
+        enabled: settings::current().memory_enabled.unwrap_or(false),
     })
 }
 
 #[tauri::command]
-pub async fn stream_cancel(state: State<'_, AppState>, request_id: String) -> Result<Ack, String> {
-    let mut map = state.stream_tokens.lock().await;
-    if let Some(token) = map.remove(&request_id) {
-        token.cancel();
-        return Ok(Ack {
-            ok: true,
-            message: Some("Cancelled stream".to_string()),
-        });
+pub async fn memory_settings_set(
+    app: AppHandle,
+    settings: MemorySettings,
+) -> Result<MemorySettings, AppError> {
+    let store = local_store(&app)?;
+    let mut stored = store.settings_get()?;
+    stored.memory_enabled = Some(settings.enabled);
+    store.settings_set(&stored)?;
+    settings::apply(&app, stored);
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn memory_list(
+    app: AppHandle,
+    input: SessionListInput,
+) -> Result<Vec<MemoryEntry>, AppError> {
+    Ok(local_store(&app)?.memory_list(&input.app_name, &input.user_id)?)
+}
+
+#[tauri::command]
+pub async fn memory_delete(app: AppHandle, input: MemoryDeleteInput) -> Result<Ack, AppError> {
+    let deleted = local_store(&app)?.memory_delete(&input.entry_id)?;
+    if !deleted {
+        return Err(AppError::NotFound(format!(
+            "Memory entry '{}' was not found.",
+            input.entry_id
+        )));
     }
 
     Ok(Ack {
         ok: true,
-        message: Some("No active stream for request".to_string()),
+        message: Some("Memory entry deleted".to_string()),
     })
 }
 
 #[tauri::command]
-pub async fn keys_set(state: State<'_, AppState>, keys: KeysInput) -> Result<Ack, String> {
-    state.key_store.set_keys(keys)?;
+pub async fn memory_clear(app: AppHandle, input: SessionListInput) -> Result<Ack, AppError> {
+    let cleared = local_store(&app)?.memory_clear(&input.app_name, &input.user_id)?;
     Ok(Ack {
         ok: true,
-        message: Some("Keys saved to OS keychain".to_string()),
+        message: Some(format!("Cleared {cleared} memory entries")),
     })
 }
 
 #[tauri::command]
-pub async fn keys_get_masked(state: State<'_, AppState>) -> Result<KeyPresence, String> {
-    state.key_store.key_presence()
+pub async fn session_tasks_get(
+    app: AppHandle,
+    input: SessionTasksGetInput,
+) -> Result<Vec<SessionTask>, AppError> {
+    Ok(local_store(&app)?.tasks_get(&input.session_id)?)
 }
 
 #[tauri::command]
-pub async fn keys_clear(state: State<'_, AppState>) -> Result<Ack, String> {
-    state.key_store.clear_keys()?;
+pub async fn session_task_update(
+    app: AppHandle,
+    input: SessionTaskUpdateInput,
+) -> Result<Ack, AppError> {
+    let updated = local_store(&app)?.task_set_done(&input.task_id, input.done)?;
+    if !updated {
+        return Err(AppError::NotFound(format!(
+            "Task '{}' was not found.",
+            input.task_id
+        )));
+    }
+
     Ok(Ack {
         ok: true,
-        message: Some("Keys cleared".to_string()),
+        message: Some("Task updated".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn session_tasks_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: SessionTasksExportInput,
+) -> Result<TaskExportResult, AppError> {
+    let store = local_store(&app)?;
+    let session_tasks = store.tasks_get(&input.session_id)?;
+    if session_tasks.is_empty() {
+        return Err(AppError::NotFound(
+            "This session has no extracted next steps to export.".to_string(),
+        ));
+    }
+
+    let (exported, markdown) = match input.target {
+        TaskExportTarget::Markdown => {
+            let title = store.session_title(&input.session_id)?;
+            (
+                session_tasks.len(),
+                Some(tasks::to_markdown_checklist(&title, &session_tasks)),
+            )
+        }
+        TaskExportTarget::Todoist => {
+            let token = state.key_store.todoist_api_token()?.ok_or_else(|| {
+                AppError::MissingCredentials(
+                    "Add a Todoist API token before exporting.".to_string(),
+                )
+            })?;
+            let exported =
+                tasks::export_todoist(&token, input.todoist_project_id.as_deref(), &session_tasks)
+                    .await?;
+            (exported, None)
+        }
+        TaskExportTarget::Linear => {
+            let api_key = state.key_store.linear_api_key()?.ok_or_else(|| {
+                AppError::MissingCredentials("Add a Linear API key before exporting.".to_string())
+            })?;
+            let team_id = input.linear_team_id.as_deref().ok_or_else(|| {
+                AppError::InvalidInput("A Linear team id is required for export.".to_string())
+            })?;
+            (
+                tasks::export_linear(&api_key, team_id, &session_tasks).await?,
+                None,
+            )
+        }
+    };
+
+    Ok(TaskExportResult {
+        target: input.target,
+        exported,
+        markdown,
+    })
+}
+
+#[tauri::command]
+pub async fn report_translate(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: ReportTranslateInput,
+) -> Result<ReportTranslation, AppError> {
+    let lang = translate::normalize_lang(&input.lang)?;
+    let store = local_store(&app)?;
+    let report = store.latest_report(&input.session_id)?.ok_or_else(|| {
+        AppError::NotFound("This session has no completed report to translate.".to_string())
+    })?;
+
+    let keys = state.key_store.read_env_values()?;
+    let api_key = keys.gemini_api_key.or(keys.google_api_key).ok_or_else(|| {
+        AppError::MissingCredentials(
+            "Add a Gemini or Google API key before translating.".to_string(),
+        )
+    })?;
+    let model = translate::translation_model();
+    let text = translate::translate_report(&api_key, &model, &report.text, &lang).await?;
+
+    Ok(store.translation_save(&report, &lang, &model, &text)?)
+}
+
+#[tauri::command]
+pub async fn report_translations_get(
+    app: AppHandle,
+    input: ReportTranslationsGetInput,
+) -> Result<Vec<ReportTranslation>, AppError> {
+    Ok(local_store(&app)?.translations_get(&input.session_id)?)
+}
+
+#[tauri::command]
+pub async fn models_list(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<ModelCatalog, AppError> {
+    let keys = state.key_store.read_env_values()?;
+    Ok(models::cached_catalog(&state.model_catalog, &keys, refresh.unwrap_or(false)).await?)
+}
+
+#[tauri::command]
+pub async fn tool_quota_get(app: AppHandle) -> Result<ToolQuota, AppError> {
+    Ok(quota::brave_quota(&local_store(&app)?)?)
+}
+
+#[tauri::command]
+pub async fn report_blocks_get(
+    app: AppHandle,
+    input: ReportBlocksGetInput,
+) -> Result<Option<StoredReportBlocks>, AppError> {
+    Ok(local_store(&app)?.report_blocks_get(&input.session_id)?)
+}
+
+fn finished_report(app: &AppHandle, session_id: &str) -> Result<report_export::Report, AppError> {
+    report_export::build(&local_store(app)?, session_id)?.ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Session {session_id} has no finished report to export yet."
+        ))
+    })
+}
+
+#[tauri::command]
+pub async fn report_export(
+    app: AppHandle,
+    input: ReportExportInput,
+) -> Result<ReportExport, AppError> {
+    let report = finished_report(&app, &input.session_id)?;
+    let default_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Storage(format!("Failed to resolve app data dir: {e}")))?
+        .join("reports");
+    let path =
+        report_export::destination(&default_dir, input.path.as_deref(), &report, input.format);
+    let size_bytes = report_export::write(&path, &report_export::render(&report, input.format))?;
+    Ok(ReportExport {
+        path: path.to_string_lossy().to_string(),
+        format: input.format,
+        size_bytes,
     })
 }
+
+// Writes a throwaway self-contained HTML copy and hands it to the default browser, which gives
+// a print-friendly view outside the app window.
+#[tauri::command]
+pub async fn report_open_in_browser(
+    app: AppHandle,
+    input: ReportOpenInput,
+) -> Result<ReportExport, AppError> {
+    let report = finished_report(&app, &input.session_id)?;
+    let dir = std::env::temp_dir().join("product-validator-reports");
+    let path = report_export::destination(&dir, None, &report, ReportFormat::Html);
+    let size_bytes =
+        report_export::write(&path, &report_export::render(&report, ReportFormat::Html))?;
+    browser::open(&path.to_string_lossy()).map_err(AppError::Internal)?;
+    Ok(ReportExport {
+        path: path.to_string_lossy().to_string(),
+        format: ReportFormat::Html,
+        size_bytes,
+    })
+}
+
+#[tauri::command]
+pub async fn session_debug_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: SessionDebugBundleInput,
+) -> Result<DebugBundle, AppError> {
+    let store = local_store(&app)?;
+    let session_id = input.session_id.as_str();
+    let title = store.session_title(session_id)?;
+    let (backend_status, backend_logs, repo_fingerprint) = {
+        let mut backend = state.backend.lock().await;
+        let (status, _) = backend.status().await?;
+        (status, backend.recent_logs(), backend.repo_fingerprint())
+    };
+    let keys = state.key_store.key_presence()?;
+    let keys_set = serde_json::json!({
+        "googleApiKey": keys.google_api_key_set,
+        "braveApiKey": keys.brave_api_key_set,
+        "geminiApiKey": keys.gemini_api_key_set,
+        "todoistApiToken": keys.todoist_api_token_set,
+        "linearApiKey": keys.linear_api_key_set
+    });
+
+    let messages = store.messages_get(session_id)?;
+    let mut entries = vec![
+        BundleEntry::content(
+            "session.json",
+            &serde_json::json!({
+                "sessionId": session_id,
+                "title": title,
+                "phase": store.phase_get(session_id)?
+            }),
+        )?,
+        BundleEntry::content("messages.json", &messages)?,
+        BundleEntry::content("tasks.json", &store.tasks_get(session_id)?)?,
+        BundleEntry::content("report_blocks.json", &store.report_blocks_get(session_id)?)?,
+        BundleEntry::json(
+            "environment.json",
+            &debug_bundle::environment(
+                serde_json::to_value(&backend_status).unwrap_or_default(),
+                keys_set,
+                Some(repo_fingerprint),
+                std::env::vars(),
+            ),
+        )?,
+        BundleEntry::text("backend.log", &backend_logs),
+    ];
+    for (request_id, events) in state.event_buffer.session_runs(session_id) {
+        let stem = debug_bundle::entry_stem(&request_id);
+        entries.push(BundleEntry::content(&format!("runs/{stem}.json"), &events)?);
+        if let Some((run_session, timed)) = state.event_buffer.run_events(&request_id) {
+            let transcript = transcript::build(&request_id, run_session, &timed, &messages);
+            entries.push(BundleEntry::content(
+                &format!("runs/{stem}.transcript.json"),
+                &transcript,
+            )?);
+        }
+    }
+
+    let path = debug_bundle::bundle_path(&app, session_id)?;
+    let size_bytes = debug_bundle::write_zip(&path, &entries)?;
+    Ok(DebugBundle {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        files: entries.into_iter().map(|entry| entry.name).collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn run_transcript_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: RunTranscriptExportInput,
+) -> Result<RunTranscriptExport, AppError> {
+    let (session_id, events) = state
+        .event_buffer
+        .run_events(&input.request_id)
+        .filter(|(_, events)| !events.is_empty())
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "No events are buffered for run {}; transcripts are only available for recent runs.",
+                input.request_id
+            ))
+        })?;
+    let messages = match &session_id {
+        Some(session_id) => local_store(&app)?.messages_get(session_id)?,
+        None => Vec::new(),
+    };
+
+    let transcript = transcript::build(&input.request_id, session_id, &events, &messages);
+    let (path, size_bytes) = transcript::write(&app, &transcript, input.format)?;
+    Ok(RunTranscriptExport {
+        path: path.to_string_lossy().to_string(),
+        format: input.format,
+        size_bytes,
+    })
+}
+
+// Frontends call this first; passing `scheme` switches the event names used process-wide.
+#[tauri::command]
+pub async fn events_handshake(
+    input: Option<EventsHandshakeInput>,
+) -> Result<EventsCapabilities, AppError> {
+    if let Some(input) = input {
+        if let Some(client) = input
+            .client_schema_version
+            .filter(|v| *v > events::EVENT_SCHEMA_VERSION)
+        {
+            return Err(AppError::InvalidInput(format!(
+                "Client expects event schema v{client}, but this build only provides v{}.",
+                events::EVENT_SCHEMA_VERSION
+            )));
+        }
+        if let Some(scheme) = input.scheme {
+            events::set_scheme(scheme);
+        }
+    }
+    Ok(events::capabilities(events::scheme()))
+}
+
+#[tauri::command]
+pub async fn analytics_funnel_get(app: AppHandle) -> Result<AnalyticsFunnel, AppError> {
+    let (sessions, entries) = local_store(&app)?.analytics_rows()?;
+    Ok(analytics::funnel(&sessions, &entries))
+}
+
+#[tauri::command]
+pub async fn indexing_status(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<IndexingStatus, AppError> {
+    indexer::status(&app, &state.indexer)
+}
+
+#[tauri::command]
+pub async fn adk_sessions_gc(app: AppHandle) -> Result<AdkSessionsGc, AppError> {
+    adk_gc::collect(&app).await
+}
+
+#[tauri::command]
+pub async fn stream_run(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: StreamRunInput,
+) -> Result<Ack, AppError> {
+    if input.text.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Message text is required.".to_string(),
+        ));
+    }
+
+    // A resubmitted request (e.g. a double click or a retried IPC call) must not start a second run.
+    if state
+        .stream_tokens
+        .lock()
+        .await
+        .contains_key(&input.request_id)
+    {
+        let queued = state.run_scheduler.position(&input.request_id).is_some();
+        return Ok(Ack {
+            ok: true,
+            message: Some(format!(
+                "Request is already {}; attach to its stream instead of starting it again",
+                if queued { "queued" } else { "running" }
+            )),
+        });
+    }
+
+    launch_run(&app, &state, input, true).await?;
+    Ok(Ack {
+        ok: true,
+        message: Some("Stream started".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn stream_run_multi(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: StreamRunMultiInput,
+) -> Result<Vec<MultiRunTarget>, AppError> {
+    if input.run.text.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Message text is required.".to_string(),
+        ));
+    }
+    let app_names = multi_run::unique_app_names(&input.app_names);
+    if app_names.len() < 2 {
+        return Err(AppError::InvalidInput(
+            "Pick at least two agents to compare.".to_string(),
+        ));
+    }
+
+    let mut targets: Vec<MultiRunTarget> = Vec::with_capacity(app_names.len());
+    for app_name in app_names {
+        let request_id = state.multi_runs.register(&input.run.request_id, &app_name);
+        let mut run = input.run.clone();
+        run.request_id = request_id.clone();
+        run.app_name = app_name.clone();
+
+        // Comparison runs share the session's history but must not overwrite its phase or report.
+        if let Err(err) = launch_run(&app, &state, run, false).await {
+            state.multi_runs.release(&request_id);
+            let mut tokens = state.stream_tokens.lock().await;
+            for target in &targets {
+                if let Some(token) = tokens.remove(&target.request_id) {
+                    token.cancel();
+                }
+            }
+            return Err(err.context(&format!("Failed to start {app_name}")));
+        }
+        targets.push(MultiRunTarget {
+            app_name,
+            request_id,
+        });
+    }
+
+    Ok(targets)
+}
+
+fn composed_replay(
+    store: &SessionStore,
+    session_id: &str,
+    text: &str,
+    run_mode: RunMode,
+    app_name: &str,
+    user_id: &str,
+) -> Result<Vec<ReplayMessage>, StoreError> {
+    let depth = settings::current().replay_depth.unwrap_or(REPLAY_DEPTH);
+    let mut replay_messages = store.replay_messages(session_id, text, depth)?;
+    if run_mode == RunMode::Idea && settings::current().memory_enabled.unwrap_or(false) {
+        let entries = store.memory_list(app_name, user_id)?;
+        if let Some(context) = memory::context_message(&memory::related_entries(text, &entries)) {
+            replay_messages.insert(0, context);
+        }
+    }
+    Ok(replay_messages)
+}
+
+#[tauri::command]
+pub async fn prompt_estimate(
+    app: AppHandle,
+    input: PromptEstimateInput,
+) -> Result<PromptEstimate, AppError> {
+    let store = local_store(&app)?;
+    let replay_messages = composed_replay(
+        &store,
+        &input.session_id,
+        &input.text,
+        input.run_mode,
+        &input.app_name,
+        &input.user_id,
+    )?;
+    Ok(prompt_estimate::estimate(
+        &input.text,
+        &replay_messages,
+        prompt_estimate::context_window(
+            input.context_window_tokens,
+            std::env::var(CONTEXT_WINDOW_ENV).ok(),
+        ),
+    ))
+}
+
+// The token doubles as the "already running" marker for a request id.
+async fn register_run(
+    tokens: &Mutex<HashMap<String, CancellationToken>>,
+    request_id: &str,
+    token: CancellationToken,
+) -> Result<(), AppError> {
+    let mut map = tokens.lock().await;
+    if map.contains_key(request_id) {
+        return Err(AppError::Conflict(format!(
+            "Request {request_id} is already running."
+        )));
+    }
+    map.insert(request_id.to_string(), token);
+    Ok(())
+}
+
+// Undoes `register_run` for a run that failed before its task started, so a retry of the same
+// request id is not rejected as already running.
+async fn unregister_run(
+    tokens: &Mutex<HashMap<String, CancellationToken>>,
+    pauses: &StreamPauses,
+    request_id: &str,
+) {
+    tokens.lock().await.remove(request_id);
+    pauses.lock().await.remove(request_id);
+}
+
+async fn launch_run(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    mut input: StreamRunInput,
+    record_outcome: bool,
+) -> Result<(), AppError> {
+    let store = local_store(app)?;
+    store.validate_run_mode(&input.session_id, input.run_mode)?;
+    if let Some(parts) = &input.parts {
+        input.parts = Some(attachments::resolve(parts).map_err(AppError::InvalidInput)?);
+    }
+
+    let replay_messages = composed_replay(
+        &store,
+        &input.session_id,
+        &input.text,
+        input.run_mode,
+        &input.app_name,
+        &input.user_id,
+    )?;
+    let estimate = prompt_estimate::estimate(
+        &input.text,
+        &replay_messages,
+        prompt_estimate::context_window(None, std::env::var(CONTEXT_WINDOW_ENV).ok()),
+    );
+    let _ = prompt_estimate::emit(app, &input.request_id, &estimate);
+    let env_plan = run_env::plan(input.env_overrides.as_ref())?;
+    let session_store_path = store.db_path();
+    state
+        .event_buffer
+        .tag_session(&input.request_id, &input.session_id);
+
+    let manager = state.backend_for(input.backend_instance.as_deref())?;
+    let backend_handle = run_admission::admit(
+        app,
+        state,
+        input.backend_instance.as_deref(),
+        &env_plan.process_env,
+        input.key_profile.as_deref(),
+    )
+    .await?;
+
+    let transport = input.transport.unwrap_or_default();
+    let version = manager.lock().await.version().clone();
+    if transport == StreamTransport::Sse && version.run_sse_supported == Some(false) {
+        return Err(AppError::BackendUnavailable(
+            "Backend does not expose /run_sse; upgrade google-adk or use the live transport."
+                .to_string(),
+        ));
+    }
+    if let Some(warning) = version.warning.filter(|_| version.outdated) {
+        if settings::flag(
+            backend_version::STRICT_ENV,
+            settings::current().strict_backend_version,
+        ) {
+            return Err(AppError::BackendUnavailable(warning));
+        }
+        degradation::note(app, &input.request_id, "backend_version", warning);
+    }
+
+    let token = CancellationToken::new();
+    register_run(&state.stream_tokens, &input.request_id, token.clone()).await?;
+
+    let (pause_tx, pause_rx) = watch::channel(false);
+    if transport == StreamTransport::Sse {
+        state
+            .stream_pauses
+            .lock()
+            .await
+            .insert(input.request_id.clone(), pause_tx);
+    }
+
+    if let Some(warning) = quota::brave_quota(&store)
+        .ok()
+        .as_ref()
+        .and_then(quota::run_warning)
+    {
+        degradation::note(app, &input.request_id, "quota_throttling", warning.clone());
+        let _ = stream::emit(
+            app,
+            &input.request_id,
+            serde_json::json!({
+                "kind": "stream_tool",
+                "requestId": input.request_id,
+                "phase": "info",
+                "name": "brave_quota",
+                "query": null,
+                "detail": warning
+            }),
+        );
+    }
+
+    let app_handle = app.clone();
+    let request_id = input.request_id.clone();
+    let stream_map = state.stream_tokens.clone();
+    let stream_pauses = state.stream_pauses.clone();
+    let live_sessions = state.live_sessions.clone();
+    let run_scheduler = state.run_scheduler.clone();
+    let multi_runs = state.multi_runs.clone();
+    let run_deadlines = state.run_deadlines.clone();
+    let debug_events = state.debug_events.clone();
+    if input.debug_events == Some(true) {
+        debug_events.set_run(&input.request_id, true);
+    }
+    let run_budget = run_budget::max_run_duration(
+        input.max_duration_secs,
+        settings::env_or(
+            "PV_DESKTOP_MAX_RUN_MINUTES",
+            settings::current().max_run_minutes,
+        ),
+    );
+    let run_mode = input.run_mode;
+    let desktop_session_id = input.session_id.clone();
+    let mut adk_input = input.clone();
+    adk_input.session_id = format!("adk-{}", Uuid::new_v4());
+
+    if record_outcome && run_mode == RunMode::Approve {
+        let begun = store
+            .phase_set(&desktop_session_id, SessionPhase::Running, true)
+            .and_then(|_| {
+                store.pending_run_begin(&desktop_session_id, &adk_input.session_id, &input)
+            });
+        if let Err(err) = begun {
+            unregister_run(
+                &state.stream_tokens,
+                &state.stream_pauses,
+                &input.request_id,
+            )
+            .await;
+            return Err(err.into());
+        }
+    }
+
+    tokio::spawn(async move {
+        let task_store = SessionStore::from_path(session_store_path);
+        let outcome =
+            match wait_for_run_slot(&app_handle, &run_scheduler, &request_id, &token).await {
+                None => Ok(StreamSummary::failed()),
+                Some(_slot) => {
+                    let watchdog = run_budget.map(|limit| {
+                        tokio::spawn(run_budget::enforce(
+                            app_handle.clone(),
+                            request_id.clone(),
+                            limit,
+                            token.clone(),
+                        ))
+                    });
+                    let outcome = match transport {
+                        StreamTransport::Sse => {
+                            stream::run_stream_task(
+                                app_handle.clone(),
+                                backend_handle,
+                                adk_input,
+                                replay_messages,
+                                token,
+                                pause_rx,
+                            )
+                            .await
+                        }
+                        StreamTransport::Live => {
+                            stream::run_live_task(
+                                app_handle.clone(),
+                                backend_handle,
+                                adk_input,
+                                replay_messages,
+                                token,
+                                live_sessions,
+                                desktop_session_id.clone(),
+                                run_mode != RunMode::Approve,
+                            )
+                            .await
+                        }
+                    };
+                    if let Some(watchdog) = watchdog {
+                        watchdog.abort();
+                    }
+                    outcome
+                }
+            };
+        let timed_out = run_deadlines.take(&request_id);
+        if let Err(err) = task_store.adk_sessions_finish(&request_id) {
+            redact::log("[adk-gc] failed to mark ADK sessions finished:", err);
+        }
+
+        if let Ok(summary) = &outcome {
+            if let Err(err) = task_store.tool_usage_record(quota::BRAVE_TOOL, summary.brave_calls) {
+                redact::log("[quota] failed to record tool usage:", err);
+            }
+        }
+        let (run_outcome, metrics) = match &outcome {
+            Ok(summary) => (summary.outcome.as_str(), summary.metrics.as_ref()),
+            Err(_) => ("error", None),
+        };
+        if let Err(err) = task_store.run_record(
+            &request_id,
+            &desktop_session_id,
+            run_mode,
+            run_outcome,
+            metrics,
+        ) {
+            redact::log("[runs] failed to record run metrics:", err);
+        }
+
+        let (succeeded, plan_detected) = match outcome {
+            Ok(summary) if summary.outcome == StreamOutcome::Completed => {
+                if record_outcome && run_mode == RunMode::Approve {
+                    remember_findings(&task_store, &desktop_session_id, &summary.final_text);
+                    record_next_steps(&task_store, &desktop_session_id, &summary.final_text);
+                    publish_report_blocks(
+                        &app_handle,
+                        &task_store,
+                        &request_id,
+                        &desktop_session_id,
+                        &summary.final_text,
+                    );
+                }
+                (true, summary.plan_detected)
+            }
+            Ok(summary) => {
+                if timed_out {
+                    keep_partial_report(&task_store, &desktop_session_id, &summary.final_text);
+                }
+                (false, false)
+            }
+            Err(err) => {
+                let (code, params) = err.code().parts();
+                let _ = stream::emit(
+                    &app_handle,
+                    &request_id,
+                    serde_json::json!({
+                        "kind": "stream_error",
+                        "requestId": request_id,
+                        "message": err.to_string(),
+                        "detail": err.detail(),
+                        "retryable": err.retryable(),
+                        "category": err.kind(),
+                        "hint": err.hint(),
+                        "code": code,
+                        "params": params
+                    }),
+                );
+                let _ = stream::emit(
+                    &app_handle,
+                    &request_id,
+                    serde_json::json!({
+                        "kind": "stream_done",
+                        "requestId": request_id,
+                        "usage": null,
+                        "degradations": degradation::take(&app_handle, &request_id)
+                    }),
+                );
+                (false, false)
+            }
+        };
+
+        if record_outcome && run_mode == RunMode::Approve {
+            if let Err(err) = task_store.pending_run_end(&desktop_session_id, &request_id) {
+                redact::log("[run-resume] failed to clear pending run:", err);
+            }
+        }
+        if record_outcome {
+            let (phase, read_only) = phase_after_run(run_mode, succeeded, plan_detected);
+            let _ = task_store.phase_set(&desktop_session_id, phase, read_only);
+        }
+
+        degradation::take(&app_handle, &request_id);
+        multi_runs.release(&request_id);
+        debug_events.release(&request_id);
+        stream_pauses.lock().await.remove(&request_id);
+        let mut map = stream_map.lock().await;
+        map.remove(&request_id);
+    });
+
+    Ok(())
+}
+
+async fn wait_for_run_slot(
+    app: &AppHandle,
+    scheduler: &Arc<RunScheduler>,
+    request_id: &str,
+    cancel: &CancellationToken,
+) -> Option<RunSlot> {
+    let (mut ready, mut queue_changed, mut position) = match scheduler.admit(request_id) {
+        Admission::Started(slot) => return Some(slot),
+        Admission::Queued {
+            position,
+            ready,
+            queue_changed,
+        } => (ready, queue_changed, position),
+    };
+
+    let emit_queued = |position: usize| {
+        let _ = stream::emit(
+            app,
+            request_id,
+            serde_json::json!({
+                "kind": "stream_queued",
+                "requestId": request_id,
+                "position": position,
+                "limit": scheduler.limit()
+            }),
+        );
+    };
+    emit_queued(position);
+
+    loop {
+        tokio::select! {
+            slot = &mut ready => return slot.ok(),
+            _ = cancel.cancelled() => {
+                scheduler.withdraw(request_id);
+                let _ = stream::emit(
+                    app,
+                    request_id,
+                    serde_json::json!({
+                        "kind": "stream_error",
+                        "requestId": request_id,
+                        "message": "Run cancelled while queued.",
+                        "retryable": false,
+                        "code": ErrorCode::RunCancelledWhileQueued.parts().0
+                    }),
+                );
+                let _ = stream::emit(
+                    app,
+                    request_id,
+                    serde_json::json!({
+                        "kind": "stream_done",
+                        "requestId": request_id,
+                        "usage": null,
+                        "degradations": degradation::take(app, request_id)
+                    }),
+                );
+                return None;
+            }
+            Ok(()) = queue_changed.changed() => {
+                if let Some(current) = scheduler.position(request_id) {
+                    if current != position {
+                        position = current;
+                        emit_queued(position);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn remember_findings(store: &SessionStore, session_id: &str, report: &str) {
+    if !settings::current().memory_enabled.unwrap_or(false) {
+        return;
+    }
+
+    let findings = memory::extract_findings(report);
+    if findings.is_empty() {
+        return;
+    }
+
+    let idea = store
+        .first_user_text(session_id)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    if let Err(err) = store.memory_save_findings(session_id, &idea, &findings) {
+        redact::log("[memory] failed to save findings:", err);
+    }
+}
+
+fn keep_partial_report(store: &SessionStore, session_id: &str, partial: &str) {
+    if partial.trim().is_empty() {
+        return;
+    }
+    let input = SessionMessageAppendInput {
+        session_id: session_id.to_string(),
+        role: "assistant".to_string(),
+        text: partial.to_string(),
+        status: run_budget::TIMED_OUT_STATUS.to_string(),
+        created_at_ms: None,
+    };
+    if let Err(err) = store.message_append(&input) {
+        redact::log("[run-budget] failed to keep partial results:", err);
+    }
+}
+
+fn record_next_steps(store: &SessionStore, session_id: &str, report: &str) {
+    let steps = tasks::extract_next_steps(report);
+    if steps.is_empty() {
+        return;
+    }
+    if let Err(err) = store.tasks_replace(session_id, &steps) {
+        redact::log("[tasks] failed to save next steps:", err);
+    }
+}
+
+fn publish_report_blocks(
+    app: &AppHandle,
+    store: &SessionStore,
+    request_id: &str,
+    session_id: &str,
+    report: &str,
+) {
+    let blocks = report_blocks::markdown_to_blocks(report);
+    if blocks.is_empty() {
+        return;
+    }
+    let stats = match store.report_blocks_save(session_id, report, &blocks) {
+        Ok(stats) => Some(stats),
+        Err(err) => {
+            redact::log("[report] failed to save report blocks:", err);
+            None
+        }
+    };
+    let _ = stream::emit(
+        app,
+        request_id,
+        serde_json::json!({
+            "kind": "stream_report_blocks",
+            "requestId": request_id,
+            "sessionId": session_id,
+            "blocks": blocks,
+            "stats": stats
+        }),
+    );
+}
+
+#[tauri::command]
+pub async fn stream_cancel(
+    state: State<'_, AppState>,
+    request_id: String,
+) -> Result<Ack, AppError> {
+    let mut map = state.stream_tokens.lock().await;
+    if let Some(token) = map.remove(&request_id) {
+        token.cancel();
+        return Ok(Ack {
+            ok: true,
+            message: Some("Cancelled stream".to_string()),
+        });
+    }
+
+    let children = state.multi_runs.children(&request_id);
+    let cancelled = children
+        .iter()
+        .filter_map(|child| map.remove(child))
+        .map(|token| token.cancel())
+        .count();
+    if cancelled > 0 {
+        return Ok(Ack {
+            ok: true,
+            message: Some(format!("Cancelled {cancelled} comparison stream(s)")),
+        });
+    }
+
+    Ok(Ack {
+        ok: true,
+        message: Some("No active stream for request".to_string()),
+    })
+}
+
+#[tauri::command]
+pub async fn stream_debug_enable(
+    state: State<'_, AppState>,
+    input: StreamDebugInput,
+) -> Result<Ack, AppError> {
+    let scope = match input.request_id.as_deref().map(str::trim) {
+        Some(request_id) if !request_id.is_empty() => {
+            state.debug_events.set_run(request_id, input.enabled);
+            format!("run {request_id}")
+        }
+        _ => {
+            state.debug_events.set_global(input.enabled);
+            "all runs".to_string()
+        }
+    };
+    Ok(Ack {
+        ok: true,
+        message: Some(format!(
+            "Raw stream events {} for {scope}",
+            if input.enabled { "enabled" } else { "disabled" }
+        )),
+    })
+}
+
+#[tauri::command]
+pub async fn stream_attach(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+    after_seq: Option<u64>,
+) -> Result<Ack, AppError> {
+    let event_name = events::agent_stream(&request_id);
+    let session_id = state.event_buffer.session_of(&request_id);
+    let replayed = state
+        .event_buffer
+        .replay(&request_id, after_seq.unwrap_or(0), |event| {
+            app.emit_filter(&event_name, event, |target| {
+                windows::receives(target, session_id.as_deref())
+            })
+            .map_err(|e| AppError::Internal(format!("failed to emit stream event: {e}")))
+        })?;
+    let active = state.stream_tokens.lock().await.contains_key(&request_id);
+
+    Ok(Ack {
+        ok: true,
+        message: Some(format!(
+            "Replayed {replayed} buffered event(s); stream is {}",
+            if active { "still running" } else { "finished" }
+        )),
+    })
+}
+
+// Runs at startup, before any run can start, so every session still marked running was cut off by
+// the previous process exiting.
+pub fn detect_interrupted_runs(app: &AppHandle) {
+    let runs = match SessionStore::from_app(app).and_then(|store| store.interrupted_runs()) {
+        Ok(runs) => runs,
+        Err(err) => {
+            redact::log("[run-resume] failed to look for interrupted runs:", err);
+            return;
+        }
+    };
+    if !runs.is_empty() {
+        eprintln!("[run-resume] found {} interrupted run(s)", runs.len());
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        *state
+            .interrupted_runs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = runs;
+    }
+}
+
+#[tauri::command]
+pub async fn runs_interrupted(state: State<'_, AppState>) -> Result<Vec<InterruptedRun>, AppError> {
+    Ok(state
+        .interrupted_runs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone())
+}
+
+// The backend dies with the app, so an interrupted approve run is re-issued from its stored input
+// rather than continued; a run still alive in this process is reported for stream_attach instead.
+#[tauri::command]
+pub async fn run_resume(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: RunResumeInput,
+) -> Result<RunResumeResult, AppError> {
+    let store = local_store(&app)?;
+    let pending = store.pending_run_get(&input.session_id)?.ok_or_else(|| {
+        AppError::NotFound("No interrupted approve run is recorded for this session.".to_string())
+    })?;
+    if state
+        .stream_tokens
+        .lock()
+        .await
+        .contains_key(&pending.request_id)
+    {
+        return Ok(RunResumeResult {
+            request_id: pending.request_id,
+            reattached: true,
+        });
+    }
+
+    let phase = store.phase_get(&input.session_id)?;
+    if phase.phase != SessionPhase::Running {
+        return Err(AppError::Conflict(format!(
+            "Session is in phase '{}', not running.",
+            phase.phase.as_str()
+        )));
+    }
+
+    // Let the ADK GC reclaim the execution session the interrupted run left behind.
+    if let Err(err) = store.adk_sessions_finish(&pending.request_id) {
+        redact::log(
+            &format!(
+                "[run-resume] failed to release ADK session {}:",
+                pending.adk_session_id
+            ),
+            err,
+        );
+    }
+    store.phase_set(&input.session_id, SessionPhase::AwaitingApproval, false)?;
+    let mut run = pending.input;
+    run.request_id = input.request_id.clone();
+    if let Err(err) = launch_run(&app, &state, run, true).await {
+        let _ = store.phase_set(&input.session_id, SessionPhase::Running, true);
+        return Err(err.context("Failed to resume run"));
+    }
+    state
+        .interrupted_runs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|run| run.session_id != input.session_id);
+
+    Ok(RunResumeResult {
+        request_id: input.request_id,
+        reattached: false,
+    })
+}
+
+// Lets a reloaded frontend find out which runs are still going before it re-attaches listeners.
+#[tauri::command]
+pub async fn stream_status(
+    state: State<'_, AppState>,
+    request_id: Option<String>,
+) -> Result<Vec<RunStatus>, AppError> {
+    let active: HashSet<String> = state.stream_tokens.lock().await.keys().cloned().collect();
+    let paused: HashSet<String> = state
+        .stream_pauses
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, tx)| *tx.borrow())
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    let request_ids: Vec<String> = match request_id {
+        Some(request_id) => vec![request_id],
+        None => {
+            let mut ids: Vec<String> = state
+                .event_buffer
+                .request_ids()
+                .into_iter()
+                .chain(active.iter().cloned())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            ids.sort();
+            ids
+        }
+    };
+
+    let mut statuses = Vec::with_capacity(request_ids.len());
+    for request_id in request_ids {
+        let buffered = state.event_buffer.run_events(&request_id);
+        let is_active = active.contains(&request_id);
+        if buffered.is_none() && !is_active {
+            return Err(AppError::NotFound(format!(
+                "No run with request id {request_id} is active or buffered."
+            )));
+        }
+        let (session_id, events) = buffered.unwrap_or_default();
+        let events: Vec<serde_json::Value> = events.into_iter().map(|(_, event)| event).collect();
+        statuses.push(stream::run_status(
+            &request_id,
+            session_id,
+            &events,
+            is_active,
+            state.run_scheduler.position(&request_id),
+            paused.contains(&request_id),
+        ));
+    }
+    Ok(statuses)
+}
+
+#[tauri::command]
+pub async fn stream_pause(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+) -> Result<Ack, AppError> {
+    Ok(set_stream_paused(&app, &state, &request_id, true).await)
+}
+
+#[tauri::command]
+pub async fn stream_resume(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+) -> Result<Ack, AppError> {
+    Ok(set_stream_paused(&app, &state, &request_id, false).await)
+}
+
+async fn set_stream_paused(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    request_id: &str,
+    paused: bool,
+) -> Ack {
+    let pauses = state.stream_pauses.lock().await;
+    let Some(pause) = pauses.get(request_id) else {
+        return Ack {
+            ok: false,
+            message: Some("No pausable stream for request".to_string()),
+        };
+    };
+
+    let changed = pause.send_if_modified(|current| std::mem::replace(current, paused) != paused);
+    if changed {
+        let _ = stream::emit(
+            app,
+            request_id,
+            serde_json::json!({
+                "kind": "stream_paused",
+                "requestId": request_id,
+                "paused": paused
+            }),
+        );
+    }
+
+    Ack {
+        ok: true,
+        message: Some(
+            if paused {
+                "Stream paused"
+            } else {
+                "Stream resumed"
+            }
+            .to_string(),
+        ),
+    }
+}
+
+#[tauri::command]
+pub async fn keys_set(state: State<'_, AppState>, keys: KeysInput) -> Result<Ack, AppError> {
+    state.key_store.set_keys(keys)?;
+    Ok(Ack {
+        ok: true,
+        message: Some("Keys saved to OS keychain".to_string()),
+    })
+}
+
+// Checks pasted keys against their providers before they are saved; fields left empty fall back
+// to the stored key so the saved set can be re-checked too.
+#[tauri::command]
+pub async fn keys_validate(
+    state: State<'_, AppState>,
+    keys: Option<KeysInput>,
+) -> Result<Vec<KeyValidation>, AppError> {
+    let pasted = keys.unwrap_or_default();
+    let stored = state.key_store.read_env_values()?;
+    let pick = |pasted: Option<String>, stored: Option<String>| {
+        pasted.filter(|v| !v.trim().is_empty()).or(stored)
+    };
+    let keys = KeysInput {
+        google_api_key: pick(pasted.google_api_key, stored.google_api_key),
+        brave_api_key: pick(pasted.brave_api_key, stored.brave_api_key),
+        gemini_api_key: pick(pasted.gemini_api_key, stored.gemini_api_key),
+        todoist_api_token: pick(
+            pasted.todoist_api_token,
+            state.key_store.todoist_api_token()?,
+        ),
+        linear_api_key: pick(pasted.linear_api_key, state.key_store.linear_api_key()?),
+    };
+    Ok(key_validation::validate(&keys).await?)
+}
+
+#[tauri::command]
+pub async fn keys_get_masked(state: State<'_, AppState>) -> Result<KeyPresence, AppError> {
+    let repo_root = state.backend.lock().await.repo_root().to_path_buf();
+    Ok(state.key_store.key_presence_with_external(&repo_root)?)
+}
+
+#[tauri::command]
+pub async fn keys_env_detect(state: State<'_, AppState>) -> Result<Vec<EnvKeyCandidate>, AppError> {
+    Ok(state.key_store.env_candidates()?)
+}
+
+#[tauri::command]
+pub async fn keys_env_import(
+    state: State<'_, AppState>,
+    input: EnvKeysImportInput,
+) -> Result<Ack, AppError> {
+    let imported = state
+        .key_store
+        .import_env_keys(&input.names, input.persist)?;
+    Ok(Ack {
+        ok: true,
+        message: Some(if input.persist {
+            format!("Saved {} to OS keychain", imported.join(", "))
+        } else {
+            format!(
+                "Using {} from the environment for this session",
+                imported.join(", ")
+            )
+        }),
+    })
+}
+
+// Copies the stored keys into the repo's .env so a terminal `adk web` uses the same credentials.
+// Writes plaintext secrets to disk, so the caller must pass `confirm: true`.
+#[tauri::command]
+pub async fn keys_export_env(
+    state: State<'_, AppState>,
+    input: KeysExportEnvInput,
+) -> Result<KeysEnvExport, AppError> {
+    if !input.confirm {
+        return Err(AppError::InvalidInput(
+            "Exporting keys writes them to .env in plain text; confirm to continue.".to_string(),
+        ));
+    }
+    let values = state.key_store.env_exports()?;
+    if values.is_empty() {
+        return Err(AppError::MissingCredentials(
+            "No keys are stored yet; add them in Settings first.".to_string(),
+        ));
+    }
+    let repo_root = state.backend.lock().await.repo_root().to_path_buf();
+    env_export::write(&repo_root, &values).map_err(AppError::Storage)
+}
+
+#[tauri::command]
+pub async fn key_profiles_list(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<KeyProfileSummary>, AppError> {
+    let names = local_store(&app)?.key_profiles_get()?;
+    let mut out = Vec::with_capacity(names.len());
+    for name in names {
+        out.push(state.key_store.profile_summary(&name)?);
+    }
+    Ok(out)
+}
+
+// Creates or updates a named set of backend API keys that runs can opt into.
+#[tauri::command]
+pub async fn key_profile_set(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: KeyProfileInput,
+) -> Result<KeyProfileSummary, AppError> {
+    let name = keyring_store::validate_profile_name(&input.name).map_err(AppError::InvalidInput)?;
+    state.key_store.set_profile_keys(&name, &input.keys)?;
+    let store = local_store(&app)?;
+    let mut names = store.key_profiles_get()?;
+    if !names.contains(&name) {
+        names.push(name.clone());
+        store.key_profiles_set(&names)?;
+    }
+    Ok(state.key_store.profile_summary(&name)?)
+}
+
+#[tauri::command]
+pub async fn key_profile_delete(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Ack, AppError> {
+    let store = local_store(&app)?;
+    let mut names = store.key_profiles_get()?;
+    let Some(index) = names.iter().position(|p| *p == name.trim()) else {
+        return Err(AppError::NotFound(format!(
+            "Key profile '{}' does not exist.",
+            name.trim()
+        )));
+    };
+    let name = names.remove(index);
+    state.key_store.clear_profile(&name)?;
+    store.key_profiles_set(&names)?;
+    Ok(Ack {
+        ok: true,
+        message: Some(format!("Key profile '{name}' deleted")),
+    })
+}
+
+#[tauri::command]
+pub async fn google_oauth_status(
+    state: State<'_, AppState>,
+) -> Result<GoogleOAuthStatus, AppError> {
+    Ok(state.key_store.google_oauth_status()?)
+}
+
+// Signs in through the browser and keeps the refresh token in the key store. Takes effect the
+// next time the backend starts.
+#[tauri::command]
+pub async fn google_oauth_sign_in(
+    state: State<'_, AppState>,
+    input: GoogleOAuthSignInInput,
+) -> Result<GoogleOAuthStatus, AppError> {
+    state.key_store.ensure_unlocked()?;
+    let project_id = input.project_id.trim();
+    if project_id.is_empty() {
+        return Err(AppError::InvalidInput(
+            "A Google Cloud project ID is required for sign-in.".to_string(),
+        ));
+    }
+    let location = input
+        .location
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .unwrap_or(google_oauth::DEFAULT_LOCATION);
+    let credentials = google_oauth::sign_in(project_id, location).await?;
+    state
+        .key_store
+        .set_google_credentials(Some(&credentials))
+        .map_err(AppError::Storage)?;
+    Ok(state.key_store.google_oauth_status()?)
+}
+
+#[tauri::command]
+pub async fn google_oauth_sign_out(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<GoogleOAuthStatus, AppError> {
+    if let Some(credentials) = state.key_store.google_credentials()? {
+        google_oauth::revoke(&credentials).await;
+    }
+    state
+        .key_store
+        .set_google_credentials(None)
+        .map_err(AppError::Storage)?;
+    let path = google_adc_path(&app)?;
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| AppError::Storage(format!("Failed to remove {}: {e}", path.display())))?;
+    }
+    Ok(state.key_store.google_oauth_status()?)
+}
+
+// Reports which keychain backend is in use and whether it round-trips, with platform hints.
+#[tauri::command]
+pub async fn keys_doctor(state: State<'_, AppState>) -> Result<DoctorReport, AppError> {
+    let diag = keyring_store::diagnose(&state.key_store)?;
+    Ok(doctor::keys_report(&diag))
+}
+
+#[tauri::command]
+pub async fn key_lock_status(state: State<'_, AppState>) -> Result<KeyLockStatus, AppError> {
+    Ok(state.key_store.lock_status()?)
+}
+
+// Sets up the app-level key lock, or changes it. Passing neither a passphrase nor Touch ID
+// removes the lock. Only allowed while unlocked.
+#[tauri::command]
+pub async fn key_lock_configure(
+    state: State<'_, AppState>,
+    input: KeyLockConfigInput,
+) -> Result<KeyLockStatus, AppError> {
+    state.key_store.ensure_unlocked()?;
+    let passphrase_hash = match input.passphrase.as_deref() {
+        Some(passphrase) => {
+            Some(key_lock::hash_passphrase(passphrase).map_err(AppError::InvalidInput)?)
+        }
+        None => None,
+    };
+    if input.biometric && !key_lock::biometric_available() {
+        return Err(AppError::InvalidInput(
+            "Touch ID unlock is only available on macOS.".to_string(),
+        ));
+    }
+    let config = (passphrase_hash.is_some() || input.biometric).then_some(LockConfig {
+        passphrase_hash,
+        biometric: input.biometric,
+    });
+    state
+        .key_store
+        .set_lock_config(config)
+        .map_err(AppError::Storage)?;
+    Ok(state.key_store.lock_status()?)
+}
+
+#[tauri::command]
+pub async fn key_lock_unlock(
+    state: State<'_, AppState>,
+    input: KeyUnlockInput,
+) -> Result<KeyLockStatus, AppError> {
+    state.key_store.unlock(input.passphrase.as_deref())?;
+    Ok(state.key_store.lock_status()?)
+}
+
+#[tauri::command]
+pub async fn key_lock_lock(state: State<'_, AppState>) -> Result<KeyLockStatus, AppError> {
+    state.key_store.lock_keys();
+    Ok(state.key_store.lock_status()?)
+}
+
+#[tauri::command]
+pub async fn secrets_list(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<SecretSummary>, AppError> {
+    let names = local_store(&app)?.secret_names_get()?;
+    let mut out = Vec::with_capacity(names.len());
+    for name in names {
+        out.push(state.key_store.secret_summary(&name)?);
+    }
+    Ok(out)
+}
+
+// Stores a secret for backend tools that have no dedicated key field; the backend receives it
+// as an environment variable named after the secret on its next start.
+#[tauri::command]
+pub async fn secret_set(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    input: SecretSetInput,
+) -> Result<SecretSummary, AppError> {
+    let name = keyring_store::validate_secret_name(&input.name).map_err(AppError::InvalidInput)?;
+    state
+        .key_store
+        .set_secret(&name, &input.value)
+        .map_err(AppError::InvalidInput)?;
+    let store = local_store(&app)?;
+    let mut names = store.secret_names_get()?;
+    if !names.contains(&name) {
+        names.push(name.clone());
+        store.secret_names_set(&names)?;
+    }
+    Ok(state.key_store.secret_summary(&name)?)
+}
+
+#[tauri::command]
+pub async fn secret_get_masked(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<SecretSummary, AppError> {
+    let name = name.trim();
+    if !local_store(&app)?
+        .secret_names_get()?
+        .iter()
+        .any(|n| n == name)
+    {
+        return Err(AppError::NotFound(format!(
+            "Secret '{name}' does not exist."
+        )));
+    }
+    Ok(state.key_store.secret_summary(name)?)
+}
+
+#[tauri::command]
+pub async fn secret_delete(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Ack, AppError> {
+    let store = local_store(&app)?;
+    let mut names = store.secret_names_get()?;
+    let Some(index) = names.iter().position(|n| *n == name.trim()) else {
+        return Err(AppError::NotFound(format!(
+            "Secret '{}' does not exist.",
+            name.trim()
+        )));
+    };
+    let name = names.remove(index);
+    state.key_store.delete_secret(&name)?;
+    store.secret_names_set(&names)?;
+    Ok(Ack {
+        ok: true,
+        message: Some(format!("Secret '{name}' deleted")),
+    })
+}
+
+#[tauri::command]
+pub async fn keys_clear(state: State<'_, AppState>) -> Result<Ack, AppError> {
+    state.key_store.clear_keys()?;
+    Ok(Ack {
+        ok: true,
+        message: Some("Keys cleared".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use tokio::sync::{watch, Mutex};
+    use tokio_util::sync::CancellationToken;
+
+    use super::{register_run, unregister_run};
+    use crate::error::AppError;
+
+    #[tokio::test]
+    async fn a_run_that_fails_to_start_can_be_retried() {
+        let tokens = Mutex::new(HashMap::new());
+        let pauses = Arc::new(Mutex::new(HashMap::new()));
+        register_run(&tokens, "r1", CancellationToken::new())
+            .await
+            .unwrap();
+        pauses
+            .lock()
+            .await
+            .insert("r1".to_string(), watch::channel(false).0);
+        assert!(matches!(
+            register_run(&tokens, "r1", CancellationToken::new()).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        unregister_run(&tokens, &pauses, "r1").await;
+        assert!(pauses.lock().await.is_empty());
+        assert!(register_run(&tokens, "r1", CancellationToken::new())
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,225 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::redact;
+
+const SECRET_ENV_MARKERS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "PROXY"];
+// Fields that describe a session's shape rather than its content; under privacy mode every other
+// string in a content entry is replaced by its fingerprint.
+const STRUCTURAL_FIELDS: &[&str] = &[
+    "id",
+    "sessionId",
+    "requestId",
+    "kind",
+    "role",
+    "phase",
+    "status",
+    "category",
+    "code",
+    "outcome",
+    "stage",
+    "name",
+    "author",
+];
+
+pub struct BundleEntry {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+impl BundleEntry {
+    pub fn json<T: Serialize>(name: &str, value: &T) -> Result<Self, String> {
+        let text = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize {name} for debug bundle: {e}"))?;
+        Ok(Self::text(name, &text))
+    }
+
+    // Session content (messages, tasks, reports, run events). Scrubbing raw strings cannot catch
+    // text once it is JSON-escaped, so privacy mode fingerprints the values before serializing.
+    pub fn content<T: Serialize>(name: &str, value: &T) -> Result<Self, String> {
+        Self::content_with(name, value, redact::privacy_mode_enabled())
+    }
+
+    fn content_with<T: Serialize>(name: &str, value: &T, private: bool) -> Result<Self, String> {
+        let mut value = serde_json::to_value(value)
+            .map_err(|e| format!("Failed to serialize {name} for debug bundle: {e}"))?;
+        if private {
+            fingerprint_content(&mut value);
+        }
+        Self::json(name, &value)
+    }
+
+    // Log lines are scrubbed when they are captured, so text entries are written as given.
+    pub fn text(name: &str, text: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            bytes: text.as_bytes().to_vec(),
+        }
+    }
+}
+
+fn fingerprint_content(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = redact::fingerprint(text),
+        Value::Array(items) => items.iter_mut().for_each(fingerprint_content),
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if !(field.is_string() && STRUCTURAL_FIELDS.contains(&key.as_str())) {
+                    fingerprint_content(field);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn environment(
+    backend_status: Value,
+    keys_set: Value,
+    repo_fingerprint: Option<String>,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Value {
+    let mut env: Vec<(String, String)> = vars
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("PV_") || name.ends_with("_PROXY"))
+        .map(|(name, value)| {
+            let upper = name.to_ascii_uppercase();
+            let value = if SECRET_ENV_MARKERS.iter().any(|m| upper.contains(m)) {
+                redact::fingerprint(&value)
+            } else {
+                redact::scrub(&value)
+            };
+            (name, value)
+        })
+        .collect();
+    env.sort();
+
+    json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "backend": backend_status,
+        "repoFingerprint": repo_fingerprint,
+        "keysSet": keys_set,
+        "env": Value::Object(env.into_iter().map(|(k, v)| (k, Value::from(v))).collect()),
+    })
+}
+
+pub fn bundle_path(app: &AppHandle, session_id: &str) -> Result<PathBuf, String> {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("debug-bundles")
+        .join(format!("session-{}-{stamp}.zip", entry_stem(session_id))))
+}
+
+pub fn entry_stem(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn write_zip(path: &Path, entries: &[BundleEntry]) -> Result<u64, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create debug bundle dir {:?}: {e}", parent))?;
+    }
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create debug bundle {:?}: {e}", path))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    for entry in entries {
+        zip.start_file(entry.name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to debug bundle: {e}", entry.name))?;
+        zip.write_all(&entry.bytes)
+            .map_err(|e| format!("Failed to write {} to debug bundle: {e}", entry.name))?;
+    }
+    let file = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish debug bundle: {e}"))?;
+    file.metadata()
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read debug bundle size: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{environment, write_zip, BundleEntry};
+
+    #[test]
+    fn privacy_mode_fingerprints_session_content() {
+        let idea = "a delivery app for dog owners";
+        let messages = json!([
+            {"id": "m1", "role": "user", "text": format!("Validate:\n\"{idea}\"")},
+            {"id": "m2", "role": "assistant", "text": "Verdict: GO for dog owners", "meta": {"title": idea}}
+        ]);
+
+        let entry = BundleEntry::content_with("messages.json", &messages, true).unwrap();
+        let text = String::from_utf8(entry.bytes).unwrap();
+        assert!(!text.contains("dog"));
+        assert!(text.contains("\"role\": \"assistant\""));
+        assert!(text.contains("[redacted len="));
+
+        let entry = BundleEntry::content_with("messages.json", &messages, false).unwrap();
+        assert!(String::from_utf8(entry.bytes)
+            .unwrap()
+            .contains("dog owners"));
+    }
+
+    #[test]
+    fn redacts_secret_env_and_writes_zip() {
+        let env = environment(
+            json!({ "running": true }),
+            json!({ "googleApiKey": true }),
+            Some("abc123".to_string()),
+            vec![
+                (
+                    "PV_DESKTOP_LITELLM_API_KEY".to_string(),
+                    "sk-live-123".to_string(),
+                ),
+                ("PV_DESKTOP_MAX_RUN_MINUTES".to_string(), "20".to_string()),
+                ("HOME".to_string(), "/home/me".to_string()),
+            ],
+        );
+        let vars = env["env"].as_object().unwrap();
+        assert_eq!(vars.len(), 2);
+        assert!(vars["PV_DESKTOP_LITELLM_API_KEY"]
+            .as_str()
+            .unwrap()
+            .starts_with("[redacted"));
+        assert_eq!(vars["PV_DESKTOP_MAX_RUN_MINUTES"], "20");
+
+        let path = std::env::temp_dir().join(format!("pv-bundle-{}.zip", uuid::Uuid::new_v4()));
+        let size = write_zip(
+            &path,
+            &[
+                BundleEntry::text("README.txt", "hello"),
+                BundleEntry::json("environment.json", &env).unwrap(),
+            ],
+        )
+        .unwrap();
+        assert!(size > 0);
+        let _ = std::fs::remove_file(path);
+    }
+}
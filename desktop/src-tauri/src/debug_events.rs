@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Manager};
+
+use crate::commands::AppState;
+
+pub const DEBUG_EVENTS_ENV: &str = "PV_DESKTOP_DEBUG_EVENTS";
+
+#[derive(Default)]
+pub struct DebugEvents {
+    global: AtomicBool,
+    runs: Mutex<HashSet<String>>,
+}
+
+impl DebugEvents {
+    pub fn set_global(&self, enabled: bool) {
+        self.global.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn set_run(&self, request_id: &str, enabled: bool) {
+        if enabled {
+            self.lock().insert(request_id.to_string());
+        } else {
+            self.lock().remove(request_id);
+        }
+    }
+
+    pub fn is_enabled(&self, request_id: &str) -> bool {
+        self.global.load(Ordering::Relaxed) || self.lock().contains(request_id)
+    }
+
+    pub fn release(&self, request_id: &str) {
+        self.lock().remove(request_id);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashSet<String>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub fn enabled(app: &AppHandle, request_id: &str) -> bool {
+    app.try_state::<AppState>()
+        .is_some_and(|state| state.debug_events.is_enabled(request_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DebugEvents;
+
+    #[test]
+    fn raw_events_are_off_unless_enabled_globally_or_per_run() {
+        let flags = DebugEvents::default();
+        assert!(!flags.is_enabled("req-1"));
+
+        flags.set_run("req-1", true);
+        assert!(flags.is_enabled("req-1"));
+        assert!(!flags.is_enabled("req-2"));
+        flags.release("req-1");
+        assert!(!flags.is_enabled("req-1"));
+
+        flags.set_global(true);
+        assert!(flags.is_enabled("req-2"));
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::events;
+use crate::tray;
+use crate::types::SessionNavigation;
+
+pub const SCHEME: &str = "idea-validator";
+
+// The last session link that arrived, kept until the frontend takes it so a link that launched
+// the app is not lost before the UI starts listening.
+fn pending() -> MutexGuard<'static, Option<String>> {
+    static PENDING: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    PENDING
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn init(app: &AppHandle) -> Result<(), String> {
+    // macOS and installed Windows builds register the scheme from the bundle; Linux and Windows
+    // dev builds have to do it at runtime.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    app.deep_link()
+        .register_all()
+        .map_err(|e| format!("failed to register {SCHEME}:// links: {e}"))?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open(&handle, url.as_str());
+        }
+    });
+    let launched_with = app
+        .deep_link()
+        .get_current()
+        .map_err(|e| format!("failed to read the launch link: {e}"))?;
+    for url in launched_with.unwrap_or_default() {
+        open(app, url.as_str());
+    }
+    Ok(())
+}
+
+pub fn session_url(session_id: &str) -> String {
+    format!("{SCHEME}://session/{session_id}")
+}
+
+pub fn open(app: &AppHandle, url: &str) {
+    let Some(session_id) = parse_session_id(url) else {
+        eprintln!("[deep-link] ignoring unsupported link {url}");
+        return;
+    };
+    tray::show_main_window(app);
+    *pending() = Some(session_id.clone());
+    let _ = app.emit(
+        &events::name(events::NAVIGATE_SESSION),
+        SessionNavigation { session_id },
+    );
+}
+
+pub fn take_pending() -> Option<SessionNavigation> {
+    pending()
+        .take()
+        .map(|session_id| SessionNavigation { session_id })
+}
+
+fn parse_session_id(url: &str) -> Option<String> {
+    let rest = url
+        .trim()
+        .strip_prefix(SCHEME)?
+        .strip_prefix("://")?
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default();
+    let mut parts = rest.trim_end_matches('/').split('/');
+    if parts.next() != Some("session") {
+        return None;
+    }
+    let id = parts.next().filter(|id| {
+        !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })?;
+    parts.next().is_none().then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_session_id, session_url};
+
+    #[test]
+    fn parses_session_links_and_rejects_anything_else() {
+        let id = "7f1c2a9e-0b7d-4c1e-9d3a-5b2f8e6a1c40";
+        assert_eq!(parse_session_id(&session_url(id)).as_deref(), Some(id));
+        assert_eq!(
+            parse_session_id("idea-validator://session/abc/?from=report").as_deref(),
+            Some("abc")
+        );
+        assert_eq!(parse_session_id("idea-validator://session/"), None);
+        assert_eq!(parse_session_id("idea-validator://session/a/b"), None);
+        assert_eq!(parse_session_id("idea-validator://session/..%2Fx"), None);
+        assert_eq!(parse_session_id("idea-validator://settings/abc"), None);
+        assert_eq!(parse_session_id("https://session/abc"), None);
+    }
+}
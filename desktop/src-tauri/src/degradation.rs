@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::AppState;
+use crate::redact;
+use crate::types::Degradation;
+
+const MAX_DEGRADATIONS_PER_RUN: usize = 50;
+
+#[derive(Default)]
+pub struct DegradationLog {
+    runs: Mutex<HashMap<String, Vec<Degradation>>>,
+}
+
+impl DegradationLog {
+    pub fn note(&self, request_id: &str, kind: &str, detail: String) {
+        let mut runs = self.lock();
+        let entries = runs.entry(request_id.to_string()).or_default();
+        if entries.len() >= MAX_DEGRADATIONS_PER_RUN
+            || entries.iter().any(|d| d.kind == kind && d.detail == detail)
+        {
+            return;
+        }
+        entries.push(Degradation {
+            kind: kind.to_string(),
+            detail,
+        });
+    }
+
+    pub fn take(&self, request_id: &str) -> Vec<Degradation> {
+        self.lock().remove(request_id).unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Vec<Degradation>>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub fn note(app: &AppHandle, request_id: &str, kind: &str, detail: impl Into<String>) {
+    if let Some(state) = app.try_state::<AppState>() {
+        state
+            .degradations
+            .note(request_id, kind, redact::scrub(&detail.into()));
+    }
+}
+
+pub fn take(app: &AppHandle, request_id: &str) -> Vec<Degradation> {
+    app.try_state::<AppState>()
+        .map(|state| state.degradations.take(request_id))
+        .unwrap_or_default()
+}
+
+pub fn tool_problem(response: &Value) -> Option<(&'static str, String)> {
+    let body = response.get("result").unwrap_or(response);
+    let message = body
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.trim().is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            let errors = body.get("errors")?.as_array()?;
+            let first = errors.first()?.as_str()?;
+            Some(if errors.len() > 1 {
+                format!("{first} (+{} more)", errors.len() - 1)
+            } else {
+                first.to_string()
+            })
+        })
+        .or_else(|| {
+            matches!(
+                body.get("status").and_then(Value::as_str),
+                Some("skipped" | "error")
+            )
+            .then(|| "tool reported it was skipped".to_string())
+        })?;
+
+    let lower = message.to_ascii_lowercase();
+    let kind = if lower.contains("429") || lower.contains("rate limit") || lower.contains("quota") {
+        "quota_throttling"
+    } else {
+        "tool_skipped"
+    };
+    Some((kind, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{tool_problem, DegradationLog};
+
+    #[test]
+    fn classifies_tool_errors() {
+        assert_eq!(
+            tool_problem(&json!({ "error": "HTTP 429 Too Many Requests" })).map(|p| p.0),
+            Some("quota_throttling")
+        );
+        assert_eq!(
+            tool_problem(&json!({ "errors": ["a: timeout", "b: timeout"] })),
+            Some(("tool_skipped", "a: timeout (+1 more)".to_string()))
+        );
+        assert_eq!(tool_problem(&json!({ "results": [] })), None);
+    }
+
+    #[test]
+    fn log_deduplicates_and_drains_per_request() {
+        let log = DegradationLog::default();
+        log.note("req", "replay_failed", "timeout".to_string());
+        log.note("req", "replay_failed", "timeout".to_string());
+        log.note("other", "tool_skipped", "x".to_string());
+        assert_eq!(log.take("req").len(), 1);
+        assert!(log.take("req").is_empty());
+        assert_eq!(log.take("other").len(), 1);
+    }
+}
@@ -0,0 +1,613 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use crate::keyring_store::KeyringDiagnostics;
+use crate::types::{DoctorCheck, DoctorReport, DoctorStatus, KeyPresence, KeyStorageKind};
+
+const MIN_PYTHON: (u32, u32) = (3, 9);
+const AGENT_DIR: &str = "product_validator_search";
+const PYTHON_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+const ADK_IMPORT_TIMEOUT: Duration = Duration::from_secs(15);
+const ADK_REQUIREMENT: &str = "google-adk";
+
+pub struct DoctorInput<'a> {
+    pub repo_root: &'a Path,
+    pub uv: Option<PathBuf>,
+    pub python: Option<PathBuf>,
+    pub keys: &'a KeyPresence,
+    pub host: &'a str,
+    pub port: u16,
+    pub port_available: bool,
+    pub backend_running: bool,
+}
+
+pub async fn run(input: DoctorInput<'_>) -> DoctorReport {
+    let python_version = match &input.python {
+        Some(python) => python_version(python).await,
+        None => None,
+    };
+    report(vec![
+        uv_check(input.uv.as_deref()),
+        python_check(input.python.as_deref(), python_version.as_deref()),
+        agent_check(input.repo_root),
+        keys_check(input.keys),
+        port_check(
+            input.host,
+            input.port,
+            input.port_available,
+            input.backend_running,
+        ),
+    ])
+}
+
+// Checks a candidate repo root before it is saved, so a wrong pick is explained up front instead
+// of surfacing as a failed spawn.
+pub async fn validate_repo(root: &Path, python: Option<PathBuf>, uv: bool) -> DoctorReport {
+    if !root.is_dir() {
+        return report(vec![check(
+            "root",
+            "Repository folder",
+            DoctorStatus::Fail,
+            format!("{} is not a directory.", root.display()),
+        )]);
+    }
+    let pyproject = std::fs::read_to_string(root.join("pyproject.toml")).ok();
+    let declared = pyproject
+        .as_deref()
+        .is_some_and(|text| text.contains(ADK_REQUIREMENT));
+    let importable = match &python {
+        Some(python) => Some(adk_importable(python, root).await),
+        None => None,
+    };
+    report(vec![
+        package_check(root),
+        pyproject_check(pyproject.is_some()),
+        adk_check(importable, declared, uv),
+    ])
+}
+
+// Explains where keys are stored and whether that store works, so a failed save has a cause.
+pub fn keys_report(diag: &KeyringDiagnostics) -> DoctorReport {
+    let file = diag.storage != KeyStorageKind::Keychain;
+    let mut storage_detail = format!("Keychain backend: {}.", diag.platform_backend);
+    if let Some(detail) = &diag.storage_detail {
+        storage_detail = format!("{storage_detail} {detail}");
+    }
+    let mut checks = vec![if diag.storage == KeyStorageKind::ObfuscatedFile {
+        with_hint(
+            check("key_storage", "Key storage", DoctorStatus::Warn, storage_detail),
+            "Keys are in a file sealed with a passphrase derived from this machine, which only obfuscates them. Set PV_DESKTOP_KEYSTORE_PASSPHRASE to encrypt them.",
+        )
+    } else if file {
+        with_hint(
+            check(
+                "key_storage",
+                "Key storage",
+                DoctorStatus::Warn,
+                storage_detail,
+            ),
+            "Keys are in an encrypted file instead of the OS keychain.",
+        )
+    } else {
+        check(
+            "key_storage",
+            "Key storage",
+            DoctorStatus::Pass,
+            storage_detail,
+        )
+    }];
+
+    checks.push(match &diag.keychain_round_trip {
+        Ok(()) => check(
+            "keychain_round_trip",
+            "Keychain read/write",
+            DoctorStatus::Pass,
+            "A test value was written, read back, and removed.".to_string(),
+        ),
+        Err(err) => with_hint(
+            check(
+                "keychain_round_trip",
+                "Keychain read/write",
+                // With the file fallback active, keys still save; the keychain is just unused.
+                if file {
+                    DoctorStatus::Warn
+                } else {
+                    DoctorStatus::Fail
+                },
+                format!("Round-trip failed: {err}"),
+            ),
+            keychain_hint(err),
+        ),
+    });
+
+    if let Some(result) = &diag.file_round_trip {
+        checks.push(match result {
+            Ok(()) => check(
+                "file_round_trip",
+                "Encrypted file read/write",
+                DoctorStatus::Pass,
+                "A test value was written, read back, and removed.".to_string(),
+            ),
+            Err(err) => with_hint(
+                check(
+                    "file_round_trip",
+                    "Encrypted file read/write",
+                    DoctorStatus::Fail,
+                    format!("Round-trip failed: {err}"),
+                ),
+                "Check that the app data folder is writable and that PV_DESKTOP_KEYSTORE_PASSPHRASE matches the one the file was created with.",
+            ),
+        });
+    }
+
+    if diag.security_cli == Some(false) {
+        checks.push(with_hint(
+            check(
+                "security_cli",
+                "security tool",
+                DoctorStatus::Warn,
+                "/usr/bin/security was not found.".to_string(),
+            ),
+            "Keys saved by older versions cannot be migrated without it; re-enter them in Settings.",
+        ));
+    }
+    if diag.session_bus == Some(false) {
+        checks.push(with_hint(
+            check(
+                "session_bus",
+                "D-Bus session",
+                DoctorStatus::Warn,
+                "DBUS_SESSION_BUS_ADDRESS is not set.".to_string(),
+            ),
+            "Secret Service needs a desktop session; on headless machines keys use the encrypted file.",
+        ));
+    }
+    if diag.locked {
+        checks.push(with_hint(
+            check(
+                "key_lock",
+                "Key lock",
+                DoctorStatus::Warn,
+                "Stored keys are locked for this session.".to_string(),
+            ),
+            "Unlock your keys before starting the backend.",
+        ));
+    }
+    report(checks)
+}
+
+fn keychain_hint(err: &str) -> &'static str {
+    let lower = err.to_ascii_lowercase();
+    if lower.contains("interaction is not allowed") || lower.contains("locked") {
+        "The keychain is locked. Unlock it (log in again, or run `security unlock-keychain`) and retry."
+    } else if lower.contains("entitlement") {
+        "This build is not signed with keychain access; use a signed release build."
+    } else if lower.contains("no keychain") || lower.contains("could not be found") {
+        "No default keychain is set. Create or select one in Keychain Access."
+    } else if lower.contains("do not persist") {
+        "The keychain accepted a value but did not keep it, so there is no usable OS keychain here."
+    } else if lower.contains("dbus") || lower.contains("secret service") {
+        "Start a Secret Service provider (gnome-keyring or KWallet) in this session."
+    } else {
+        "Check the OS keychain is available and unlocked, then run this check again."
+    }
+}
+
+fn report(checks: Vec<DoctorCheck>) -> DoctorReport {
+    DoctorReport {
+        ok: checks
+            .iter()
+            .all(|check| check.status != DoctorStatus::Fail),
+        checks,
+    }
+}
+
+fn check(id: &str, label: &str, status: DoctorStatus, detail: String) -> DoctorCheck {
+    DoctorCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        detail,
+        hint: None,
+    }
+}
+
+fn with_hint(mut check: DoctorCheck, hint: &str) -> DoctorCheck {
+    check.hint = Some(hint.to_string());
+    check
+}
+
+fn uv_check(uv: Option<&Path>) -> DoctorCheck {
+    match uv {
+        Some(path) => check(
+            "uv",
+            "uv on PATH",
+            DoctorStatus::Pass,
+            format!("Found {}", path.display()),
+        ),
+        None => with_hint(
+            check(
+                "uv",
+                "uv on PATH",
+                DoctorStatus::Warn,
+                "uv was not found; the backend falls back to .venv or system Python.".to_string(),
+            ),
+            "Install uv from https://docs.astral.sh/uv/ for reproducible backend environments.",
+        ),
+    }
+}
+
+fn python_check(python: Option<&Path>, version: Option<&str>) -> DoctorCheck {
+    let label = "Python version";
+    let minimum = format!("{}.{}", MIN_PYTHON.0, MIN_PYTHON.1);
+    let Some(python) = python else {
+        return with_hint(
+            check(
+                "python",
+                label,
+                DoctorStatus::Fail,
+                "No Python interpreter was found.".to_string(),
+            ),
+            &format!("Install Python {minimum} or newer, or create the repo's .venv."),
+        );
+    };
+    let Some(version) = version else {
+        return check(
+            "python",
+            label,
+            DoctorStatus::Warn,
+            format!("Could not read the version of {}.", python.display()),
+        );
+    };
+    if parse_python_version(version).is_some_and(|found| found >= MIN_PYTHON) {
+        check(
+            "python",
+            label,
+            DoctorStatus::Pass,
+            format!("Python {version} ({})", python.display()),
+        )
+    } else {
+        with_hint(
+            check(
+                "python",
+                label,
+                DoctorStatus::Fail,
+                format!(
+                    "Python {version} at {} is older than {minimum}.",
+                    python.display()
+                ),
+            ),
+            &format!("Install Python {minimum} or newer."),
+        )
+    }
+}
+
+fn agent_check(repo_root: &Path) -> DoctorCheck {
+    let label = "Agent package";
+    let agent_dir = repo_root.join(AGENT_DIR);
+    if agent_dir.is_dir() {
+        check(
+            "agent",
+            label,
+            DoctorStatus::Pass,
+            format!("Found {}", agent_dir.display()),
+        )
+    } else {
+        with_hint(
+            check(
+                "agent",
+                label,
+                DoctorStatus::Fail,
+                format!("{AGENT_DIR} is missing from {}.", repo_root.display()),
+            ),
+            "Launch the app from inside the product validator repository checkout.",
+        )
+    }
+}
+
+fn package_check(root: &Path) -> DoctorCheck {
+    let label = "Agent package";
+    if root.join(AGENT_DIR).is_dir() {
+        return check(
+            "package",
+            label,
+            DoctorStatus::Pass,
+            format!("Found {AGENT_DIR}."),
+        );
+    }
+    let missing = check(
+        "package",
+        label,
+        DoctorStatus::Fail,
+        format!("{AGENT_DIR} is missing from {}.", root.display()),
+    );
+    match root
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(AGENT_DIR).is_dir())
+    {
+        Some(parent) => with_hint(
+            missing,
+            &format!("Pick the repository root instead: {}", parent.display()),
+        ),
+        None => with_hint(
+            missing,
+            "Pick the folder of your product validator repository checkout.",
+        ),
+    }
+}
+
+fn pyproject_check(found: bool) -> DoctorCheck {
+    let label = "pyproject.toml";
+    if found {
+        check(
+            "pyproject",
+            label,
+            DoctorStatus::Pass,
+            "Found pyproject.toml.".to_string(),
+        )
+    } else {
+        with_hint(
+            check(
+                "pyproject",
+                label,
+                DoctorStatus::Fail,
+                "pyproject.toml is missing.".to_string(),
+            ),
+            "The repository root is the folder that contains pyproject.toml.",
+        )
+    }
+}
+
+// `importable` is None when no interpreter could be found to ask.
+fn adk_check(importable: Option<bool>, declared: bool, uv: bool) -> DoctorCheck {
+    let label = "ADK";
+    if importable == Some(true) {
+        return check(
+            "adk",
+            label,
+            DoctorStatus::Pass,
+            "google.adk is importable.".to_string(),
+        );
+    }
+    if !declared {
+        return with_hint(
+            check(
+                "adk",
+                label,
+                DoctorStatus::Fail,
+                format!("{ADK_REQUIREMENT} is not installed or declared in pyproject.toml."),
+            ),
+            "Check that this is the product validator repository.",
+        );
+    }
+    let detail = if uv {
+        format!(
+            "{ADK_REQUIREMENT} is declared but not installed yet; uv installs it on first launch."
+        )
+    } else {
+        format!("{ADK_REQUIREMENT} is declared but not installed.")
+    };
+    with_hint(
+        check("adk", label, DoctorStatus::Warn, detail),
+        "Run Install backend to set up the Python environment.",
+    )
+}
+
+fn keys_check(keys: &KeyPresence) -> DoctorCheck {
+    let label = "API keys";
+    let external = |name: &str| keys.external_keys.iter().any(|key| key.name == name);
+    let mut missing = Vec::new();
+    if !keys.google_api_key_set
+        && !keys.gemini_api_key_set
+        && !keys.google_oauth_set
+        && !external("googleApiKey")
+        && !external("geminiApiKey")
+    {
+        missing.push("Google or Gemini API key (or Google sign-in)");
+    }
+    if !keys.brave_api_key_set && !external("braveApiKey") {
+        missing.push("Brave Search API key");
+    }
+    if missing.is_empty() {
+        check(
+            "keys",
+            label,
+            DoctorStatus::Pass,
+            "Required keys are set.".to_string(),
+        )
+    } else {
+        with_hint(
+            check(
+                "keys",
+                label,
+                DoctorStatus::Fail,
+                format!("Missing: {}.", missing.join(", ")),
+            ),
+            "Add the missing keys in Settings.",
+        )
+    }
+}
+
+fn port_check(host: &str, port: u16, available: bool, backend_running: bool) -> DoctorCheck {
+    let label = "Backend port";
+    if available {
+        check(
+            "port",
+            label,
+            DoctorStatus::Pass,
+            format!("{host}:{port} is free."),
+        )
+    } else if backend_running {
+        check(
+            "port",
+            label,
+            DoctorStatus::Pass,
+            format!("{host}:{port} is in use by the running backend."),
+        )
+    } else {
+        with_hint(
+            check(
+                "port",
+                label,
+                DoctorStatus::Fail,
+                format!("{host}:{port} is already in use by another process."),
+            ),
+            "Stop the other process or pick a port range in the backend settings.",
+        )
+    }
+}
+
+async fn python_version(python: &Path) -> Option<String> {
+    let output = tokio::time::timeout(
+        PYTHON_VERSION_TIMEOUT,
+        Command::new(python).arg("--version").output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    // Python 2 printed its version to stderr.
+    let text = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+    String::from_utf8_lossy(&text)
+        .trim()
+        .strip_prefix("Python ")
+        .map(str::to_string)
+}
+
+async fn adk_importable(python: &Path, root: &Path) -> bool {
+    tokio::time::timeout(
+        ADK_IMPORT_TIMEOUT,
+        Command::new(python)
+            .args(["-c", "import google.adk"])
+            .current_dir(root)
+            .output(),
+    )
+    .await
+    .is_ok_and(|output| output.is_ok_and(|output| output.status.success()))
+}
+
+fn parse_python_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adk_check, keys_check, keys_report, package_check, parse_python_version, port_check,
+        pyproject_check, python_check, report, AGENT_DIR,
+    };
+    use crate::keyring_store::KeyringDiagnostics;
+    use crate::types::{DoctorStatus, KeyPresence, KeyStorageKind};
+    use std::path::Path;
+
+    #[test]
+    fn flags_old_python_missing_keys_and_busy_port() {
+        assert_eq!(parse_python_version("3.12.1"), Some((3, 12)));
+        assert_eq!(parse_python_version("3.13rc1"), Some((3, 13)));
+        assert_eq!(parse_python_version("garbage"), None);
+
+        let python = Path::new("/usr/bin/python3");
+        assert_eq!(
+            python_check(Some(python), Some("3.11.4")).status,
+            DoctorStatus::Pass
+        );
+        assert_eq!(
+            python_check(Some(python), Some("3.8.10")).status,
+            DoctorStatus::Fail
+        );
+        assert_eq!(python_check(None, None).status, DoctorStatus::Fail);
+
+        let keys = KeyPresence {
+            gemini_api_key_set: true,
+            ..KeyPresence::default()
+        };
+        let missing = keys_check(&keys);
+        assert_eq!(missing.status, DoctorStatus::Fail);
+        assert_eq!(missing.detail, "Missing: Brave Search API key.");
+
+        assert_eq!(
+            port_check("127.0.0.1", 8765, false, true).status,
+            DoctorStatus::Pass
+        );
+        let busy = port_check("127.0.0.1", 8765, false, false);
+        assert_eq!(busy.status, DoctorStatus::Fail);
+        assert!(!report(vec![busy]).ok);
+    }
+
+    #[test]
+    fn repo_checks_point_at_the_enclosing_root() {
+        let root = std::env::temp_dir().join(format!("pv-validate-{}", std::process::id()));
+        let nested = root.join(AGENT_DIR).join("sub_agents");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(package_check(&root).status, DoctorStatus::Pass);
+        let wrong = package_check(&nested);
+        assert_eq!(wrong.status, DoctorStatus::Fail);
+        assert!(wrong.hint.unwrap().ends_with(&root.display().to_string()));
+        assert_eq!(pyproject_check(false).status, DoctorStatus::Fail);
+
+        assert_eq!(
+            adk_check(Some(true), false, false).status,
+            DoctorStatus::Pass
+        );
+        assert_eq!(
+            adk_check(Some(false), true, true).status,
+            DoctorStatus::Warn
+        );
+        assert_eq!(adk_check(None, false, true).status, DoctorStatus::Fail);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn keys_report_explains_a_failing_keychain() {
+        let mut diag = KeyringDiagnostics {
+            storage: KeyStorageKind::Keychain,
+            storage_detail: None,
+            platform_backend: "macOS Keychain (Security framework)".to_string(),
+            keychain_round_trip: Err("User interaction is not allowed.".to_string()),
+            file_round_trip: None,
+            locked: false,
+            security_cli: Some(false),
+            session_bus: None,
+        };
+        let failing = keys_report(&diag);
+        assert!(!failing.ok);
+        let round_trip = &failing.checks[1];
+        assert_eq!(round_trip.status, DoctorStatus::Fail);
+        assert!(round_trip.hint.as_deref().unwrap().contains("locked"));
+        assert!(failing.checks.iter().any(|c| c.id == "security_cli"));
+
+        diag.storage = KeyStorageKind::EncryptedFile;
+        diag.file_round_trip = Some(Ok(()));
+        let fallback = keys_report(&diag);
+        assert!(fallback.ok);
+        assert_eq!(fallback.checks[1].status, DoctorStatus::Warn);
+        assert_eq!(fallback.checks[2].id, "file_round_trip");
+
+        diag.storage = KeyStorageKind::ObfuscatedFile;
+        let obfuscated = keys_report(&diag);
+        assert_eq!(obfuscated.checks[0].status, DoctorStatus::Warn);
+        assert!(obfuscated.checks[0]
+            .hint
+            .as_deref()
+            .unwrap()
+            .contains("only obfuscates"));
+        assert_eq!(obfuscated.checks[1].status, DoctorStatus::Warn);
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+
+const FORMAT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+type Salt = [u8; SALT_LEN];
+
+// On-disk envelope: the account -> secret map as JSON, sealed with ChaCha20-Poly1305 under a key
+// derived from the passphrase with Argon2id. Every write uses a fresh nonce.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    version: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// Key store used when the OS keychain is unavailable. The derived key is cached for the life of
+// the process so Argon2 runs once per salt rather than on every read.
+#[derive(Debug)]
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: String,
+    key: Mutex<Option<(Salt, [u8; 32])>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, passphrase: String) -> Self {
+        Self {
+            path,
+            passphrase,
+            key: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self, account: &str) -> Result<Option<String>, String> {
+        Ok(self
+            .load()?
+            .1
+            .remove(account)
+            .filter(|v| !v.trim().is_empty()))
+    }
+
+    pub fn set(&self, account: &str, value: &str) -> Result<(), String> {
+        let (salt, mut entries) = self.load()?;
+        entries.insert(account.to_string(), value.to_string());
+        self.save(salt, &entries)
+    }
+
+    pub fn delete(&self, account: &str) -> Result<(), String> {
+        let (salt, mut entries) = self.load()?;
+        if entries.remove(account).is_some() {
+            self.save(salt, &entries)?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<(Option<Salt>, BTreeMap<String, String>), String> {
+        let raw = match fs::read(&self.path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Ok((None, BTreeMap::new()))
+            }
+            Err(err) => return Err(format!("Failed to read {}: {err}", self.path.display())),
+        };
+        let envelope: Envelope = serde_json::from_slice(&raw)
+            .map_err(|e| format!("Key file {} is corrupt: {e}", self.path.display()))?;
+        if envelope.version != FORMAT_VERSION {
+            return Err(format!(
+                "Key file {} uses unsupported format version {}.",
+                self.path.display(),
+                envelope.version
+            ));
+        }
+        let salt: Salt = decode(&envelope.salt)?
+            .try_into()
+            .map_err(|_| "Key file salt has the wrong length.".to_string())?;
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != 12 {
+            return Err("Key file nonce has the wrong length.".to_string());
+        }
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.derive(salt)?));
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), decode(&envelope.ciphertext)?.as_slice())
+            .map_err(|_| {
+                format!(
+                    "Could not decrypt {}; the passphrase does not match the one it was written with.",
+                    self.path.display()
+                )
+            })?;
+        let entries = serde_json::from_slice(&plaintext)
+            .map_err(|e| format!("Decrypted key file is unreadable: {e}"))?;
+        Ok((Some(salt), entries))
+    }
+
+    fn save(&self, salt: Option<Salt>, entries: &BTreeMap<String, String>) -> Result<(), String> {
+        let salt = salt.unwrap_or_else(|| {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            salt
+        });
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.derive(salt)?));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext =
+            serde_json::to_vec(entries).map_err(|e| format!("Failed to encode keys: {e}"))?;
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt keys.".to_string())?;
+        let envelope = Envelope {
+            version: FORMAT_VERSION,
+            salt: BASE64.encode(salt),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let body =
+            serde_json::to_vec(&envelope).map_err(|e| format!("Failed to encode key file: {e}"))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        // Write-then-rename so a crash mid-write never leaves a truncated key file.
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, body).map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600));
+        }
+        fs::rename(&tmp, &self.path)
+            .map_err(|e| format!("Failed to replace {}: {e}", self.path.display()))
+    }
+
+    fn derive(&self, salt: Salt) -> Result<[u8; 32], String> {
+        let mut cached = self.key.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_salt, key)) = *cached {
+            if cached_salt == salt {
+                return Ok(key);
+            }
+        }
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| format!("Failed to derive key file key: {e}"))?;
+        *cached = Some((salt, key));
+        Ok(key)
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, String> {
+    BASE64
+        .decode(value)
+        .map_err(|e| format!("Key file field is not valid base64: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EncryptedFileStore;
+
+    #[test]
+    fn round_trips_and_rejects_the_wrong_passphrase() {
+        let path = std::env::temp_dir()
+            .join(format!("pv-keys-{}", uuid::Uuid::new_v4()))
+            .join("keys.enc");
+        let store = EncryptedFileStore::new(path.clone(), "correct horse".to_string());
+        assert_eq!(store.get("google_api_key").unwrap(), None);
+
+        store.set("google_api_key", "g-123").unwrap();
+        store.set("brave_search_api_key", "b-456").unwrap();
+        store.delete("brave_search_api_key").unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("g-123"));
+
+        let reopened = EncryptedFileStore::new(path.clone(), "correct horse".to_string());
+        assert_eq!(
+            reopened.get("google_api_key").unwrap().as_deref(),
+            Some("g-123")
+        );
+        assert_eq!(reopened.get("brave_search_api_key").unwrap(), None);
+
+        let wrong = EncryptedFileStore::new(path.clone(), "battery staple".to_string());
+        assert!(wrong.get("google_api_key").is_err());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::types::KeysEnvExport;
+
+const ENV_FILE: &str = ".env";
+
+// Writes `values` into `<repo_root>/.env`, replacing lines for the same variables in place and
+// keeping everything else the user put there.
+pub fn write(repo_root: &Path, values: &[(&'static str, String)]) -> Result<KeysEnvExport, String> {
+    let path = path(repo_root);
+    let existing = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("Failed to read {}: {err}", path.display())),
+    };
+    let merged = merge(&existing, values);
+    write_owner_only(&path, &merged)?;
+
+    Ok(KeysEnvExport {
+        path: path.display().to_string(),
+        variables: values.iter().map(|(name, _)| name.to_string()).collect(),
+        warning: (!git_ignored(repo_root, &path)).then(|| {
+            format!("{ENV_FILE} is not ignored by git; add it to .gitignore so the keys are never committed.")
+        }),
+    })
+}
+
+// A new file is created owner-only and an existing one is restricted before the keys go in; if
+// that is not possible nothing is written.
+fn write_owner_only(path: &Path, text: &str) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(false);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {e}", path.display()))?;
+    }
+    file.set_len(0)
+        .and_then(|()| file.write_all(text.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+pub fn path(repo_root: &Path) -> PathBuf {
+    repo_root.join(ENV_FILE)
+}
+
+// Variables from `<repo_root>/.env`; a missing or unreadable file reads as empty.
+pub fn read(repo_root: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(path(repo_root))
+        .map(|text| parse(&text))
+        .unwrap_or_default()
+}
+
+// Accepts the subset `merge` writes plus common hand edits: `export`, comments, single quotes.
+fn parse(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with('#') {
+                return None;
+            }
+            let (name, value) = line.trim_start_matches("export ").split_once('=')?;
+            let value = value.trim();
+            let value = if let Some(inner) =
+                value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+            {
+                unescape(inner)
+            } else if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\''))
+            {
+                inner.to_string()
+            } else {
+                value
+                    .split(" #")
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+            Some((name.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn unescape(quoted: &str) -> String {
+    let mut out = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        out.push(match c {
+            '\\' => chars.next().unwrap_or('\\'),
+            c => c,
+        });
+    }
+    out
+}
+
+fn merge(existing: &str, values: &[(&str, String)]) -> String {
+    let mut pending: Vec<&(&str, String)> = values.iter().collect();
+    let mut lines: Vec<String> = existing
+        .lines()
+        .map(|line| {
+            let name = line
+                .trim_start()
+                .trim_start_matches("export ")
+                .split('=')
+                .next()
+                .unwrap_or_default()
+                .trim();
+            match pending.iter().position(|(var, _)| *var == name) {
+                Some(index) => {
+                    let (var, value) = pending.remove(index);
+                    format!("{var}={}", quote(value))
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    lines.extend(
+        pending
+            .into_iter()
+            .map(|(var, value)| format!("{var}={}", quote(value))),
+    );
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+fn quote(value: &str) -> String {
+    if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.:/+@".contains(c))
+    {
+        return value.to_string();
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Treats "not a git checkout" and "git unavailable" as not ignored; the warning is advisory.
+fn git_ignored(repo_root: &Path, path: &Path) -> bool {
+    Command::new("git")
+        .arg("check-ignore")
+        .arg("-q")
+        .arg(path)
+        .current_dir(repo_root)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge, parse, write};
+
+    #[test]
+    fn merge_replaces_known_keys_and_keeps_the_rest() {
+        let existing = "# local settings\nGOOGLE_API_KEY=old\nGITHUB_TOKEN=ghp_x\nexport BRAVE_SEARCH_API_KEY=stale";
+        let merged = merge(
+            existing,
+            &[
+                ("GOOGLE_API_KEY", "new-key".to_string()),
+                ("BRAVE_SEARCH_API_KEY", "b 1".to_string()),
+                ("LINEAR_API_KEY", "lin_api_1".to_string()),
+            ],
+        );
+        assert_eq!(
+            merged,
+            "# local settings\nGOOGLE_API_KEY=new-key\nGITHUB_TOKEN=ghp_x\nBRAVE_SEARCH_API_KEY=\"b 1\"\nLINEAR_API_KEY=lin_api_1\n"
+        );
+        assert_eq!(merge("", &[("A", "1".to_string())]), "A=1\n");
+    }
+
+    #[test]
+    fn parse_reads_back_what_merge_writes() {
+        let written = merge(
+            "# comment\nexport GEMINI_API_KEY='single quoted'\nPLAIN=x # trailing",
+            &[("GOOGLE_API_KEY", "a \"b\" \\c".to_string())],
+        );
+        let parsed = parse(&written);
+        assert_eq!(parsed["GOOGLE_API_KEY"], "a \"b\" \\c");
+        assert_eq!(parsed["GEMINI_API_KEY"], "single quoted");
+        assert_eq!(parsed["PLAIN"], "x");
+        assert_eq!(parsed.len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_restricts_an_existing_env_file_before_adding_keys() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("pv-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join(".env");
+        std::fs::write(&path, "KEEP=1\nGOOGLE_API_KEY=old-and-longer\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        write(&root, &[("GOOGLE_API_KEY", "new".to_string())]).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "KEEP=1\nGOOGLE_API_KEY=new\n"
+        );
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,567 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::session_store::StoreError;
+use crate::types::{StartupFailure, StartupFailureKind};
+
+// What went wrong, as a stable code plus the values a sentence about it needs, so the UI can
+// word and translate it. Serializes as `{ code, params }`; unit codes have no `params`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "code",
+    content = "params",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ErrorCode {
+    // One per error kind, used when nothing more specific applies.
+    InvalidInput,
+    NotFound,
+    MissingCredentials,
+    KeysLocked,
+    Conflict,
+    BackendStartup,
+    BackendUnavailable,
+    Network,
+    Storage,
+    Internal,
+
+    // The local session DB stayed locked by another write; retrying usually succeeds.
+    StorageBusy,
+
+    BackendStartupFailed {
+        reason: StartupFailureKind,
+    },
+    BackendNoLauncher,
+    BackendPortInUse {
+        host: String,
+        port: u16,
+    },
+    BackendNoFreePort {
+        host: String,
+    },
+    BackendUnhealthy {
+        base_url: String,
+    },
+    BackendRecovering {
+        retry_after_ms: Option<u64>,
+    },
+    BackendHttp {
+        status: u16,
+    },
+    RateLimited {
+        retry_after_ms: Option<u64>,
+    },
+
+    // Reported on `agent-stream:*` as `stream_error`.
+    RunCancelled,
+    RunCancelledWhileQueued,
+    RunTimedOut,
+    EmptyResponse,
+    StreamFailed,
+    FallbackFailed {
+        status: u16,
+        sse_status: Option<u16>,
+    },
+    AgentError,
+}
+
+impl ErrorCode {
+    // The `code` and `params` fields on their own, for payloads that are built as JSON.
+    pub fn parts(&self) -> (String, Option<Value>) {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        let code = value
+            .get("code")
+            .and_then(Value::as_str)
+            .unwrap_or("internal")
+            .to_string();
+        (code, value.get_mut("params").map(Value::take))
+    }
+}
+
+// Crosses the IPC boundary as `{ kind, code, params, message, detail, retryable, hint }`. The UI
+// branches on `kind` and words the error from `code` and `params`; `message` is the English
+// fallback for codes it does not know and `detail` the raw cause (source chain, backend body) for
+// logs and debug views, or null when there is none. Classified startup failures add
+// `startupFailure`.
+#[derive(Debug, Clone, Error)]
+pub enum AppError {
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    MissingCredentials(String),
+    // Stored keys exist but the app-level key lock has not been unlocked this session.
+    #[error("{0}")]
+    KeysLocked(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    BackendStartup(String),
+    // A spawned backend that failed to come up, with the cause read from its output.
+    #[error("{message}")]
+    StartupFailed {
+        message: String,
+        failure: StartupFailure,
+    },
+    #[error("{0}")]
+    BackendUnavailable(String),
+    #[error("{message}")]
+    BackendRecovering {
+        message: String,
+        retry_after_ms: Option<u64>,
+    },
+    #[error("{message}")]
+    Backend { status: u16, message: String },
+    #[error("{0}")]
+    Network(String),
+    #[error("{message}")]
+    RateLimited {
+        message: String,
+        retry_after_ms: Option<u64>,
+    },
+    #[error("{0}")]
+    Storage(String),
+    #[error("{0}")]
+    Internal(String),
+    // Any of the above with a more specific code than its kind implies.
+    #[error("{error}")]
+    Coded {
+        error: Box<AppError>,
+        code: ErrorCode,
+    },
+    // Any of the above with the raw cause its message was worded from.
+    #[error("{error}")]
+    Detailed {
+        error: Box<AppError>,
+        detail: String,
+    },
+}
+
+impl AppError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Coded { error, .. } | Self::Detailed { error, .. } => error.kind(),
+            Self::InvalidInput(_) => "invalid_input",
+            Self::NotFound(_) => "not_found",
+            Self::MissingCredentials(_) => "missing_credentials",
+            Self::KeysLocked(_) => "keys_locked",
+            Self::Conflict(_) => "conflict",
+            Self::BackendStartup(_) | Self::StartupFailed { .. } => "backend_startup",
+            Self::BackendUnavailable(_) => "backend_unavailable",
+            Self::BackendRecovering { .. } => "backend_recovering",
+            Self::Backend { .. } => "backend",
+            Self::Network(_) => "network",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Storage(_) => "storage",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Coded { code, .. } => code.clone(),
+            Self::Detailed { error, .. } => error.code(),
+            Self::InvalidInput(_) => ErrorCode::InvalidInput,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::MissingCredentials(_) => ErrorCode::MissingCredentials,
+            Self::KeysLocked(_) => ErrorCode::KeysLocked,
+            Self::Conflict(_) => ErrorCode::Conflict,
+            Self::BackendStartup(_) => ErrorCode::BackendStartup,
+            Self::StartupFailed { failure, .. } => ErrorCode::BackendStartupFailed {
+                reason: failure.kind,
+            },
+            Self::BackendUnavailable(_) => ErrorCode::BackendUnavailable,
+            Self::BackendRecovering { retry_after_ms, .. } => ErrorCode::BackendRecovering {
+                retry_after_ms: *retry_after_ms,
+            },
+            Self::Backend { status, .. } => ErrorCode::BackendHttp { status: *status },
+            Self::Network(_) => ErrorCode::Network,
+            Self::RateLimited { retry_after_ms, .. } => ErrorCode::RateLimited {
+                retry_after_ms: *retry_after_ms,
+            },
+            Self::Storage(_) => ErrorCode::Storage,
+            Self::Internal(_) => ErrorCode::Internal,
+        }
+    }
+
+    pub fn with_code(self, code: ErrorCode) -> Self {
+        let error = match self {
+            Self::Detailed { error, detail } => {
+                return Self::Detailed {
+                    error: Box::new(error.with_code(code)),
+                    detail,
+                }
+            }
+            Self::Coded { error, .. } => error,
+            error => Box::new(error),
+        };
+        Self::Coded { error, code }
+    }
+
+    pub fn with_detail(self, detail: impl Into<String>) -> Self {
+        let error = match self {
+            Self::Detailed { error, .. } => error,
+            error => Box::new(error),
+        };
+        Self::Detailed {
+            error,
+            detail: detail.into(),
+        }
+    }
+
+    // The raw cause behind the message; startup failures fall back to the backend's log tail.
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Detailed { detail, .. } => Some(detail),
+            Self::Coded { error, .. } => error.detail(),
+            Self::StartupFailed { failure, .. } if !failure.log_tail.is_empty() => {
+                Some(&failure.log_tail)
+            }
+            _ => None,
+        }
+    }
+
+    // The error under any code or detail wrappers.
+    fn inner(&self) -> &AppError {
+        match self {
+            Self::Coded { error, .. } | Self::Detailed { error, .. } => error.inner(),
+            error => error,
+        }
+    }
+
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Coded {
+                code: ErrorCode::StorageBusy,
+                ..
+            } => true,
+            Self::Coded { error, .. } | Self::Detailed { error, .. } => error.retryable(),
+            Self::Conflict(_)
+            | Self::BackendStartup(_)
+            | Self::StartupFailed { .. }
+            | Self::BackendUnavailable(_)
+            | Self::BackendRecovering { .. }
+            | Self::Network(_)
+            | Self::RateLimited { .. } => true,
+            Self::Backend { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            Self::Coded { error, .. } | Self::Detailed { error, .. } => return error.hint(),
+            Self::MissingCredentials(_) => "Add the missing key in Settings, then retry.",
+            Self::KeysLocked(_) => "Unlock your API keys, then retry.",
+            Self::Conflict(_) => "Wait for active runs to finish or cancel them, then retry.",
+            Self::BackendStartup(_) => {
+                "Check that uv is installed and the backend repo path is correct; the message includes recent backend logs."
+            }
+            Self::StartupFailed { failure, .. } => return Some(failure.hint.clone()),
+            Self::BackendUnavailable(_) => "Start the local backend, then retry.",
+            Self::BackendRecovering {
+                retry_after_ms: Some(ms),
+                ..
+            } => {
+                return Some(format!(
+                    "The backend is recovering; retry in {}s.",
+                    ms.div_ceil(1_000)
+                ))
+            }
+            Self::BackendRecovering { .. } => "The backend is recovering; retry shortly.",
+            Self::Network(_) => "Check that the backend is reachable, then retry.",
+            Self::RateLimited {
+                retry_after_ms: Some(ms),
+                ..
+            } => return Some(format!("Wait {}s before retrying.", ms.div_ceil(1_000))),
+            Self::RateLimited { .. } => "Wait a moment before retrying.",
+            _ => return None,
+        };
+        Some(hint.to_string())
+    }
+
+    pub fn context(self, context: &str) -> Self {
+        let prefix = |message: String| format!("{context}: {message}");
+        match self {
+            Self::Coded { error, code } => Self::Coded {
+                error: Box::new(error.context(context)),
+                code,
+            },
+            Self::Detailed { error, detail } => Self::Detailed {
+                error: Box::new(error.context(context)),
+                detail,
+            },
+            Self::InvalidInput(m) => Self::InvalidInput(prefix(m)),
+            Self::NotFound(m) => Self::NotFound(prefix(m)),
+            Self::MissingCredentials(m) => Self::MissingCredentials(prefix(m)),
+            Self::KeysLocked(m) => Self::KeysLocked(prefix(m)),
+            Self::Conflict(m) => Self::Conflict(prefix(m)),
+            Self::BackendStartup(m) => Self::BackendStartup(prefix(m)),
+            Self::StartupFailed { message, failure } => Self::StartupFailed {
+                message: prefix(message),
+                failure,
+            },
+            Self::BackendUnavailable(m) => Self::BackendUnavailable(prefix(m)),
+            Self::BackendRecovering {
+                message,
+                retry_after_ms,
+            } => Self::BackendRecovering {
+                message: prefix(message),
+                retry_after_ms,
+            },
+            Self::Backend { status, message } => Self::Backend {
+                status,
+                message: prefix(message),
+            },
+            Self::Network(m) => Self::Network(prefix(m)),
+            Self::RateLimited {
+                message,
+                retry_after_ms,
+            } => Self::RateLimited {
+                message: prefix(message),
+                retry_after_ms,
+            },
+            Self::Storage(m) => Self::Storage(prefix(m)),
+            Self::Internal(m) => Self::Internal(prefix(m)),
+        }
+    }
+
+    // Maps a non-success backend response, keeping 429 distinct so callers can back off.
+    pub fn from_status(status: u16, message: String) -> Self {
+        match status {
+            429 => Self::RateLimited {
+                message,
+                retry_after_ms: None,
+            },
+            _ => Self::Backend { status, message },
+        }
+    }
+
+    // Words the failure for the user and keeps reqwest's own text, with its causes, as the detail.
+    pub fn from_reqwest(err: &reqwest::Error, context: &str) -> Self {
+        let summary = if err.is_timeout() {
+            "the request timed out".to_string()
+        } else if err.is_connect() {
+            "could not connect".to_string()
+        } else if let Some(status) = err.status() {
+            format!("HTTP {status}")
+        } else if err.is_decode() || err.is_body() {
+            "the response could not be read".to_string()
+        } else {
+            "the request failed".to_string()
+        };
+        let message = format!("{context}: {summary}");
+        let error = match err.status() {
+            Some(status) => Self::from_status(status.as_u16(), message),
+            None => Self::Network(message),
+        };
+        error.with_detail(source_chain(err))
+    }
+}
+
+// `err` followed by each of its causes, the way they are usually logged.
+fn source_chain(err: &dyn std::error::Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (code, params) = self.code().parts();
+        let message = self.to_string();
+        let mut state = serializer.serialize_struct("AppError", 9)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("code", &code)?;
+        match params {
+            Some(params) => state.serialize_field("params", &params)?,
+            None => state.skip_field("params")?,
+        }
+        state.serialize_field("message", &message)?;
+        state.serialize_field("detail", &self.detail())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.serialize_field("hint", &self.hint())?;
+        let startup_failure = match self.inner() {
+            Self::StartupFailed { failure, .. } => Some(failure),
+            _ => None,
+        };
+        match startup_failure {
+            Some(failure) => state.serialize_field("startupFailure", failure)?,
+            None => state.skip_field("startupFailure")?,
+        }
+        state.end()
+    }
+}
+
+impl From<StoreError> for AppError {
+    fn from(err: StoreError) -> Self {
+        match err {
+            StoreError::Busy { .. } => {
+                Self::Storage(err.to_string()).with_code(ErrorCode::StorageBusy)
+            }
+            StoreError::Sqlite { action, source } => {
+                Self::Storage(format!("Failed to {action}.")).with_detail(source_chain(&source))
+            }
+            StoreError::Other(message) => Self::Storage(message),
+        }
+    }
+}
+
+// Untyped errors from helper modules surface as `internal` until they are classified.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::Internal(message)
+    }
+}
+
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{AppError, ErrorCode};
+    use crate::session_store::StoreError;
+    use crate::types::{StartupFailure, StartupFailureKind};
+
+    #[test]
+    fn serializes_kind_retryable_and_hint() {
+        let err = AppError::RateLimited {
+            message: "Too many requests".to_string(),
+            retry_after_ms: Some(2_500),
+        };
+        let value = serde_json::to_value(&err).expect("serializes");
+        assert_eq!(value["kind"], "rate_limited");
+        assert_eq!(value["message"], "Too many requests");
+        assert_eq!(value["retryable"], true);
+        assert_eq!(value["hint"], "Wait 3s before retrying.");
+
+        let value = serde_json::to_value(AppError::from("boom".to_string())).expect("serializes");
+        assert_eq!(
+            (value["kind"].as_str(), value["retryable"].as_bool()),
+            (Some("internal"), Some(false))
+        );
+        assert!(value["hint"].is_null());
+        assert_eq!(
+            AppError::from_status(503, "down".to_string()).kind(),
+            "backend"
+        );
+        assert!(AppError::from_status(503, "down".to_string()).retryable());
+        assert!(matches!(
+            AppError::from_status(429, String::new()),
+            AppError::RateLimited { .. }
+        ));
+
+        let err = AppError::StartupFailed {
+            message: "Backend exited during startup".to_string(),
+            failure: StartupFailure {
+                kind: StartupFailureKind::AddressInUse,
+                hint: "Free the port.".to_string(),
+                log_tail: "[stderr] address already in use".to_string(),
+            },
+        };
+        let value = serde_json::to_value(&err).expect("serializes");
+        assert_eq!(value["kind"], "backend_startup");
+        assert_eq!(value["hint"], "Free the port.");
+        assert_eq!(value["startupFailure"]["kind"], "address_in_use");
+        assert_eq!(value["code"], "backend_startup_failed");
+        assert_eq!(value["params"], json!({"reason": "address_in_use"}));
+        assert!(
+            serde_json::to_value(AppError::Internal(String::new())).expect("serializes")
+                ["startupFailure"]
+                .is_null()
+        );
+    }
+
+    #[test]
+    fn store_contention_is_a_retryable_storage_error() {
+        let busy = AppError::from(StoreError::Busy {
+            action: "append message",
+            attempts: 5,
+        });
+        assert_eq!(busy.kind(), "storage");
+        assert_eq!(busy.code(), ErrorCode::StorageBusy);
+        assert!(busy.retryable());
+
+        let other = AppError::from(StoreError::Other(
+            "Stored settings are unreadable".to_string(),
+        ));
+        assert_eq!(other.code(), ErrorCode::Storage);
+        assert!(!other.retryable());
+    }
+
+    #[test]
+    fn detail_carries_the_raw_cause_not_the_message() {
+        let err = AppError::from(StoreError::Sqlite {
+            action: "load settings",
+            source: rusqlite::Error::QueryReturnedNoRows,
+        });
+        let value = serde_json::to_value(&err).expect("serializes");
+        assert_eq!(value["message"], "Failed to load settings.");
+        assert_eq!(value["detail"], "Query returned no rows");
+        assert_ne!(value["message"], value["detail"]);
+
+        let err = AppError::from_status(502, "Replay request 1 of 2 returned 502".to_string())
+            .with_detail("upstream connect error");
+        assert_eq!(err.to_string(), "Replay request 1 of 2 returned 502");
+        assert_eq!(err.detail(), Some("upstream connect error"));
+        assert_eq!(err.kind(), "backend");
+        assert!(err.retryable());
+
+        let value =
+            serde_json::to_value(AppError::Internal("boom".to_string())).expect("serializes");
+        assert!(value["detail"].is_null());
+    }
+
+    #[test]
+    fn specific_codes_keep_kind_and_raw_detail() {
+        let err = AppError::Conflict("Port 8765 is already in use on host 127.0.0.1.".to_string())
+            .with_detail("bind: Address already in use (os error 98)")
+            .with_code(ErrorCode::BackendPortInUse {
+                host: "127.0.0.1".to_string(),
+                port: 8765,
+            })
+            .context("Backend start");
+        let value = serde_json::to_value(&err).expect("serializes");
+        assert_eq!(value["kind"], "conflict");
+        assert_eq!(value["retryable"], true);
+        assert_eq!(value["code"], "backend_port_in_use");
+        assert_eq!(value["params"], json!({"host": "127.0.0.1", "port": 8765}));
+        assert_eq!(
+            value["message"],
+            "Backend start: Port 8765 is already in use on host 127.0.0.1."
+        );
+        assert_eq!(
+            value["detail"],
+            "bind: Address already in use (os error 98)"
+        );
+
+        let value =
+            serde_json::to_value(AppError::NotFound("gone".to_string())).expect("serializes");
+        assert_eq!(value["code"], "not_found");
+        assert!(value.get("params").is_none());
+        assert_eq!(
+            ErrorCode::RateLimited {
+                retry_after_ms: Some(1_500)
+            }
+            .parts(),
+            (
+                "rate_limited".to_string(),
+                Some(json!({"retryAfterMs": 1_500}))
+            )
+        );
+    }
+}
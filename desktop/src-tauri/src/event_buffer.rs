@@ -0,0 +1,165 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+const MAX_EVENTS_PER_REQUEST: usize = 2_000;
+const IDLE_RETENTION: Duration = Duration::from_secs(15 * 60);
+
+pub type TimedEvents = Vec<(i64, Value)>;
+
+#[derive(Default)]
+pub struct EventBuffer {
+    runs: Mutex<HashMap<String, BufferedRun>>,
+}
+
+struct BufferedRun {
+    next_seq: u64,
+    events: VecDeque<(u64, i64, Value)>,
+    last_event_at: Instant,
+    session_id: Option<String>,
+}
+
+impl BufferedRun {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_seq: 1,
+            events: VecDeque::new(),
+            last_event_at: now,
+            session_id: None,
+        }
+    }
+}
+
+impl EventBuffer {
+    // Stamps `payload` with a per-request sequence number, stores it, then hands it to `deliver`
+    // while the buffer lock is held so replays and live events cannot interleave.
+    pub fn record<F, E>(&self, request_id: &str, mut payload: Value, deliver: F) -> Result<(), E>
+    where
+        F: FnOnce(&Value) -> Result<(), E>,
+    {
+        let mut runs = self.lock();
+        let now = Instant::now();
+        runs.retain(|id, run| id == request_id || now - run.last_event_at < IDLE_RETENTION);
+
+        let run = runs
+            .entry(request_id.to_string())
+            .or_insert_with(|| BufferedRun::new(now));
+        let seq = run.next_seq;
+        run.next_seq += 1;
+        run.last_event_at = now;
+        if let Value::Object(map) = &mut payload {
+            map.insert("seq".to_string(), Value::from(seq));
+        }
+
+        let at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        run.events.push_back((seq, at_ms, payload));
+        if run.events.len() > MAX_EVENTS_PER_REQUEST {
+            run.events.pop_front();
+        }
+        let (_, _, stored) = run.events.back().expect("event was just pushed");
+        deliver(stored)
+    }
+
+    pub fn replay<F, E>(&self, request_id: &str, after_seq: u64, mut deliver: F) -> Result<usize, E>
+    where
+        F: FnMut(&Value) -> Result<(), E>,
+    {
+        let runs = self.lock();
+        let Some(run) = runs.get(request_id) else {
+            return Ok(0);
+        };
+
+        let mut replayed = 0;
+        for (_, _, event) in run.events.iter().filter(|(seq, _, _)| *seq > after_seq) {
+            deliver(event)?;
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    pub fn tag_session(&self, request_id: &str, session_id: &str) {
+        self.lock()
+            .entry(request_id.to_string())
+            .or_insert_with(|| BufferedRun::new(Instant::now()))
+            .session_id = Some(session_id.to_string());
+    }
+
+    pub fn session_of(&self, request_id: &str) -> Option<String> {
+        self.lock().get(request_id)?.session_id.clone()
+    }
+
+    pub fn session_runs(&self, session_id: &str) -> Vec<(String, Vec<Value>)> {
+        let mut runs: Vec<(String, Vec<Value>)> = self
+            .lock()
+            .iter()
+            .filter(|(_, run)| run.session_id.as_deref() == Some(session_id))
+            .map(|(id, run)| {
+                (
+                    id.clone(),
+                    run.events.iter().map(|(_, _, e)| e.clone()).collect(),
+                )
+            })
+            .collect();
+        runs.sort_by(|a, b| a.0.cmp(&b.0));
+        runs
+    }
+
+    // Events with their wall-clock record time, plus the session the run was tagged with.
+    pub fn run_events(&self, request_id: &str) -> Option<(Option<String>, TimedEvents)> {
+        let runs = self.lock();
+        let run = runs.get(request_id)?;
+        Some((
+            run.session_id.clone(),
+            run.events
+                .iter()
+                .map(|(_, at_ms, e)| (*at_ms, e.clone()))
+                .collect(),
+        ))
+    }
+
+    pub fn request_ids(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, BufferedRun>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::EventBuffer;
+
+    #[test]
+    fn stamps_sequence_numbers_and_replays_after_cursor() {
+        let buffer = EventBuffer::default();
+        for kind in ["stream_open", "stream_message", "stream_done"] {
+            buffer
+                .record("req-1", json!({ "kind": kind }), |_| Ok::<_, String>(()))
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let replayed = buffer
+            .replay("req-1", 1, |event| {
+                seen.push(event["seq"].as_u64().unwrap());
+                Ok::<_, String>(())
+            })
+            .unwrap();
+        assert_eq!(replayed, 2);
+        assert_eq!(seen, vec![2, 3]);
+        assert_eq!(
+            buffer
+                .replay("missing", 0, |_| Ok::<_, String>(()))
+                .unwrap(),
+            0
+        );
+    }
+}
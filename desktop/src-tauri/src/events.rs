@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+
+use crate::types::{EventNames, EventScheme, EventsCapabilities};
+
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+const SCHEME_ENV: &str = "PV_DESKTOP_EVENT_SCHEME";
+
+pub const BACKEND_STATUS: &str = "backend-status";
+pub const BACKEND_EXITED: &str = "backend-exited";
+pub const BACKEND_LOG: &str = "backend-log";
+pub const BACKEND_INSTALL: &str = "backend-install";
+pub const BACKEND_METRICS: &str = "backend-metrics";
+pub const BACKEND_UPDATE: &str = "backend-update";
+pub const BACKEND_RECOVERY: &str = "backend-recovery";
+pub const REVALIDATION_DUE: &str = "revalidation-due";
+pub const NEW_IDEA: &str = "new-idea";
+pub const NAVIGATE_SESSION: &str = "navigate-session";
+const AGENT_STREAM: &str = "agent-stream";
+
+pub const STREAM_EVENT_KINDS: &[&str] = &[
+    "prompt_estimate",
+    "stream_agent_progress",
+    "stream_artifact",
+    "stream_batch",
+    "stream_delta",
+    "stream_done",
+    "stream_error",
+    "stream_event_raw",
+    "stream_finish",
+    "stream_message",
+    "stream_meta",
+    "stream_open",
+    "stream_paused",
+    "stream_plan",
+    "stream_progress",
+    "stream_queued",
+    "stream_report_blocks",
+    "stream_section",
+    "stream_thought",
+    "stream_tool",
+];
+
+pub const FEATURES: &[&str] = &[
+    "analytics_funnel",
+    "artifacts",
+    "backend_auth",
+    "debug_bundle",
+    "indexing",
+    "multi_run",
+    "prompt_estimate",
+    "run_budget",
+    "stream_attach",
+];
+
+static SCHEME_OVERRIDE: Mutex<Option<EventScheme>> = Mutex::new(None);
+
+// `Legacy` keeps the original bare names; `Versioned` prefixes every name with `v{EVENT_SCHEMA_VERSION}/`.
+impl EventScheme {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "legacy" => Some(Self::Legacy),
+            "versioned" => Some(Self::Versioned),
+            _ => None,
+        }
+    }
+
+    pub fn name(self, base: &str) -> String {
+        match self {
+            Self::Legacy => base.to_string(),
+            Self::Versioned => format!("v{EVENT_SCHEMA_VERSION}/{base}"),
+        }
+    }
+}
+
+pub fn scheme() -> EventScheme {
+    if let Some(scheme) = *SCHEME_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) {
+        return scheme;
+    }
+    std::env::var(SCHEME_ENV)
+        .ok()
+        .and_then(|raw| EventScheme::parse(&raw))
+        .unwrap_or_default()
+}
+
+pub fn set_scheme(scheme: EventScheme) {
+    *SCHEME_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = Some(scheme);
+}
+
+pub fn name(base: &str) -> String {
+    scheme().name(base)
+}
+
+pub fn agent_stream(request_id: &str) -> String {
+    format!("{}:{request_id}", name(AGENT_STREAM))
+}
+
+pub fn capabilities(scheme: EventScheme) -> EventsCapabilities {
+    EventsCapabilities {
+        schema_version: EVENT_SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        scheme,
+        names: EventNames {
+            backend_status: scheme.name(BACKEND_STATUS),
+            backend_exited: scheme.name(BACKEND_EXITED),
+            backend_log: scheme.name(BACKEND_LOG),
+            backend_install: scheme.name(BACKEND_INSTALL),
+            backend_metrics: scheme.name(BACKEND_METRICS),
+            backend_update: scheme.name(BACKEND_UPDATE),
+            backend_recovery: scheme.name(BACKEND_RECOVERY),
+            revalidation_due: scheme.name(REVALIDATION_DUE),
+            new_idea: scheme.name(NEW_IDEA),
+            navigate_session: scheme.name(NAVIGATE_SESSION),
+            agent_stream_prefix: format!("{}:", scheme.name(AGENT_STREAM)),
+        },
+        stream_event_kinds: STREAM_EVENT_KINDS.iter().map(|k| k.to_string()).collect(),
+        features: FEATURES.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capabilities, EventScheme};
+
+    #[test]
+    fn versioned_scheme_prefixes_every_event_name() {
+        let legacy = capabilities(EventScheme::Legacy);
+        assert_eq!(legacy.names.backend_status, "backend-status");
+        assert_eq!(legacy.names.agent_stream_prefix, "agent-stream:");
+
+        let versioned = capabilities(EventScheme::Versioned);
+        assert_eq!(versioned.names.backend_status, "v1/backend-status");
+        assert_eq!(versioned.names.agent_stream_prefix, "v1/agent-stream:");
+        assert!(versioned
+            .stream_event_kinds
+            .contains(&"stream_done".to_string()));
+        assert_eq!(
+            EventScheme::parse(" Versioned "),
+            Some(EventScheme::Versioned)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::browser;
+use crate::error::AppError;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+const SCOPES: &str = "openid email https://www.googleapis.com/auth/cloud-platform";
+const CLIENT_ID_ENV: &str = "PV_DESKTOP_GOOGLE_OAUTH_CLIENT_ID";
+const CLIENT_SECRET_ENV: &str = "PV_DESKTOP_GOOGLE_OAUTH_CLIENT_SECRET";
+const CALLBACK_PATH: &str = "/callback";
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(20);
+pub const DEFAULT_LOCATION: &str = "us-central1";
+
+// What sign-in leaves in the keyring. The client secret of an installed-app OAuth client is not
+// confidential, but it is needed to refresh, so it travels with the refresh token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+    pub email: Option<String>,
+    pub project_id: String,
+    pub location: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    refresh_token: Option<String>,
+    id_token: Option<String>,
+}
+
+// Runs the installed-app flow: open the consent page in the browser, wait for Google to redirect
+// to a one-shot listener on a loopback port, then trade the code (with its PKCE verifier) for a
+// refresh token.
+pub async fn sign_in(project_id: &str, location: &str) -> Result<GoogleCredentials, AppError> {
+    let (client_id, client_secret) = client_config()?;
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to open OAuth callback port: {e}")))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| AppError::Internal(format!("Failed to read OAuth callback port: {e}")))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{CALLBACK_PATH}");
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+
+    let url = auth_url(
+        &client_id,
+        &redirect_uri,
+        &state,
+        &pkce_challenge(&verifier),
+    );
+    browser::open(url.as_str()).map_err(|e| AppError::Internal(format!("Google sign-in: {e}")))?;
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, await_callback(&listener, &state))
+        .await
+        .map_err(|_| {
+            AppError::InvalidInput("Google sign-in timed out waiting for the browser.".to_string())
+        })?
+        .map_err(AppError::InvalidInput)?;
+
+    let client = crate::http::client_builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {e}")))?;
+    let response = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("code_verifier", verifier.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", client_id.as_str()),
+            ("client_secret", client_secret.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| AppError::from_reqwest(&e, "Google token exchange failed"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError::from_status(
+            status.as_u16(),
+            format!("Google token exchange failed (HTTP {status})"),
+        )
+        .with_detail(body.trim()));
+    }
+    let tokens: TokenResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::Internal(format!("Unexpected token response: {e}")))?;
+    let refresh_token = tokens.refresh_token.ok_or_else(|| {
+        AppError::Internal("Google did not return a refresh token; try signing in again.".into())
+    })?;
+    Ok(GoogleCredentials {
+        client_id,
+        client_secret,
+        refresh_token,
+        email: tokens.id_token.as_deref().and_then(email_from_id_token),
+        project_id: project_id.to_string(),
+        location: location.to_string(),
+    })
+}
+
+// Best effort: a token that fails to revoke still stops being used once it leaves the keyring.
+pub async fn revoke(credentials: &GoogleCredentials) {
+    let Ok(client) = crate::http::client_builder().timeout(HTTP_TIMEOUT).build() else {
+        return;
+    };
+    if let Err(err) = client
+        .post(REVOKE_URL)
+        .form(&[("token", credentials.refresh_token.as_str())])
+        .send()
+        .await
+    {
+        eprintln!("[google-oauth] failed to revoke token: {err}");
+    }
+}
+
+// The backend reaches Vertex AI through Application Default Credentials, which only accept an
+// `authorized_user` file, so the refresh token is written out (owner-only) for the child process.
+pub fn write_adc_file(path: &Path, credentials: &GoogleCredentials) -> Result<(), String> {
+    let body = serde_json::json!({
+        "type": "authorized_user",
+        "client_id": credentials.client_id,
+        "client_secret": credentials.client_secret,
+        "refresh_token": credentials.refresh_token,
+    });
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    // The token goes into a freshly created owner-only file that then replaces the old one, so it
+    // is never readable by others, not even between the write and a chmod.
+    let tmp = path.with_extension("tmp");
+    let _ = fs::remove_file(&tmp);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&tmp)
+        .map_err(|e| format!("Failed to create {}: {e}", tmp.display()))?;
+    file.write_all(body.to_string().as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to replace {}: {e}", path.display()))
+}
+
+pub fn backend_env(adc_path: &Path, credentials: &GoogleCredentials) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        (
+            "GOOGLE_APPLICATION_CREDENTIALS".to_string(),
+            adc_path.display().to_string(),
+        ),
+        ("GOOGLE_GENAI_USE_VERTEXAI".to_string(), "TRUE".to_string()),
+        (
+            "GOOGLE_CLOUD_PROJECT".to_string(),
+            credentials.project_id.clone(),
+        ),
+        (
+            "GOOGLE_CLOUD_LOCATION".to_string(),
+            credentials.location.clone(),
+        ),
+    ])
+}
+
+fn client_config() -> Result<(String, String), AppError> {
+    let read = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    match (read(CLIENT_ID_ENV), read(CLIENT_SECRET_ENV)) {
+        (Some(id), Some(secret)) => Ok((id, secret)),
+        _ => Err(AppError::MissingCredentials(format!(
+            "Google sign-in needs a desktop OAuth client; set {CLIENT_ID_ENV} and {CLIENT_SECRET_ENV}."
+        ))),
+    }
+}
+
+fn auth_url(client_id: &str, redirect_uri: &str, state: &str, challenge: &str) -> Url {
+    let mut url = Url::parse(AUTH_URL).expect("static OAuth URL parses");
+    url.query_pairs_mut()
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("response_type", "code")
+        .append_pair("scope", SCOPES)
+        .append_pair("state", state)
+        .append_pair("code_challenge", challenge)
+        .append_pair("code_challenge_method", "S256")
+        // Forces a refresh token even when the user has consented before.
+        .append_pair("access_type", "offline")
+        .append_pair("prompt", "consent");
+    url
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+// Serves requests until one carries our `state`; stray hits (favicon, a stale tab) are answered
+// and ignored.
+async fn await_callback(listener: &TcpListener, state: &str) -> Result<String, String> {
+    loop {
+        let (mut socket, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("OAuth callback failed: {e}"))?;
+        let mut buf = vec![0u8; 8192];
+        let n = socket.read(&mut buf).await.unwrap_or(0);
+        let head = String::from_utf8_lossy(&buf[..n]);
+        let outcome = head
+            .lines()
+            .next()
+            .and_then(|line| parse_callback(line, state));
+        let page = match &outcome {
+            Some(Ok(_)) => "Signed in. You can close this tab and return to the app.",
+            Some(Err(_)) => "Sign-in did not complete. Return to the app for details.",
+            None => "Not found.",
+        };
+        let status = if outcome.is_some() {
+            "200 OK"
+        } else {
+            "404 Not Found"
+        };
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+    }
+}
+
+// Reads `GET /callback?code=...&state=... HTTP/1.1`. None means the request was not the callback.
+fn parse_callback(request_line: &str, state: &str) -> Option<Result<String, String>> {
+    let target = request_line.strip_prefix("GET ")?.split(' ').next()?;
+    let url = Url::parse(&format!("http://127.0.0.1{target}")).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    let params: BTreeMap<_, _> = url.query_pairs().into_owned().collect();
+    if params.get("state").map(String::as_str) != Some(state) {
+        return None;
+    }
+    if let Some(error) = params.get("error") {
+        return Some(Err(format!("Google sign-in was not completed: {error}.")));
+    }
+    params.get("code").cloned().map(Ok).or_else(|| {
+        Some(Err(
+            "Google sign-in returned no authorization code.".to_string()
+        ))
+    })
+}
+
+// The ID token arrives straight from the token endpoint over TLS, so its payload is trusted for
+// display without verifying the signature.
+fn email_from_id_token(id_token: &str) -> Option<String> {
+    let payload = id_token.split('.').nth(1)?;
+    let claims: serde_json::Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    claims.get("email")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        auth_url, email_from_id_token, parse_callback, pkce_challenge, write_adc_file,
+        GoogleCredentials,
+    };
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+
+    #[test]
+    fn builds_a_pkce_request_and_reads_the_callback() {
+        // RFC 7636 appendix B.
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let url = auth_url("id", "http://127.0.0.1:5000/callback", "s1", "ch");
+        let query = url.query().unwrap();
+        assert!(query.contains("code_challenge_method=S256"));
+        assert!(query.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A5000%2Fcallback"));
+
+        assert_eq!(
+            parse_callback("GET /callback?state=s1&code=4%2Fabc HTTP/1.1", "s1"),
+            Some(Ok("4/abc".to_string()))
+        );
+        assert!(matches!(
+            parse_callback("GET /callback?state=s1&error=access_denied HTTP/1.1", "s1"),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse_callback("GET /callback?state=other&code=x HTTP/1.1", "s1"),
+            None
+        );
+        assert_eq!(parse_callback("GET /favicon.ico HTTP/1.1", "s1"), None);
+    }
+
+    #[test]
+    fn reads_email_from_id_token() {
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"email":"dev@example.com"}"#);
+        assert_eq!(
+            email_from_id_token(&format!("h.{payload}.sig")).as_deref(),
+            Some("dev@example.com")
+        );
+        assert_eq!(email_from_id_token("garbage"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn adc_file_is_owner_only_even_when_replacing_a_readable_one() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("pv-adc-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("adc.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let credentials = GoogleCredentials {
+            client_id: "id".to_string(),
+            client_secret: "secret".to_string(),
+            refresh_token: "refresh".to_string(),
+            email: None,
+            project_id: "project".to_string(),
+            location: "us-central1".to_string(),
+        };
+        write_adc_file(&path, &credentials).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("refresh"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
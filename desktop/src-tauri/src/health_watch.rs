@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::sleep;
+
+use crate::commands::AppState;
+use crate::events;
+use crate::redact;
+use crate::supervisor;
+use crate::tray;
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+// Health-checks the default backend in the background and pushes `backend-status` whenever it
+// starts, stops, exits, or flips between healthy and unhealthy, so the UI need not poll.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(WATCH_INTERVAL).await;
+            if let Err(err) = watch(&app).await {
+                redact::log("[health-watch]", err);
+            }
+        }
+    });
+}
+
+async fn watch(app: &AppHandle) -> Result<(), String> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| "App state is not available".to_string())?;
+    let mut backend = state.backend.lock().await;
+    let (status, exited) = backend.status().await?;
+    if exited {
+        supervisor::report_exit(app, &backend, &status);
+    }
+    if backend.observe_health((&status).into()) {
+        tray::show_backend_status(app, &status);
+        app.emit(&events::name(events::BACKEND_STATUS), &status)
+            .map_err(|e| format!("failed to emit backend-status: {e}"))?;
+    }
+    Ok(())
+}
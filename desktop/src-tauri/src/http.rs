@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use tokio::process::Command;
+use tokio_tungstenite::Connector;
+
+use crate::backend_auth::{matches_base, normalize_base_url};
+use crate::settings;
+
+const LOOPBACK_NO_PROXY: &str = "localhost,127.0.0.1,::1";
+const CA_CERT_ENV: &str = "PV_DESKTOP_BACKEND_CA_CERT";
+const INSECURE_TLS_ENV: &str = "PV_DESKTOP_BACKEND_INSECURE_TLS";
+const CHILD_PROXY_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyMode {
+    System,
+    Manual,
+    Off,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: String,
+}
+
+impl ProxyConfig {
+    pub fn from_env() -> Self {
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self::resolve(
+            read("PV_DESKTOP_PROXY_MODE").as_deref(),
+            read("PV_DESKTOP_HTTP_PROXY"),
+            read("PV_DESKTOP_HTTPS_PROXY"),
+            read("PV_DESKTOP_NO_PROXY").as_deref(),
+        )
+    }
+
+    fn resolve(
+        mode: Option<&str>,
+        http: Option<String>,
+        https: Option<String>,
+        extra_no_proxy: Option<&str>,
+    ) -> Self {
+        let mode = match mode.map(str::to_ascii_lowercase).as_deref() {
+            Some("off" | "none" | "direct") => ProxyMode::Off,
+            Some("system") => ProxyMode::System,
+            _ if http.is_some() || https.is_some() => ProxyMode::Manual,
+            _ => ProxyMode::System,
+        };
+
+        let mut no_proxy = LOOPBACK_NO_PROXY.to_string();
+        if let Some(extra) = extra_no_proxy {
+            no_proxy.push(',');
+            no_proxy.push_str(extra);
+        }
+
+        Self {
+            mode,
+            http,
+            https,
+            no_proxy,
+        }
+    }
+
+    fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        match self.mode {
+            // reqwest would read the proxy variables itself, but without the loopback exclusions, so
+            // they are installed here. With none set it falls back to the OS proxy settings.
+            ProxyMode::System => {
+                let no_proxy = self.system_no_proxy(env_value("NO_PROXY").as_deref());
+                let mut builder = builder;
+                if let Some(url) = env_value("HTTP_PROXY") {
+                    builder = with_proxy(builder, &url, Proxy::http(&url), &no_proxy);
+                }
+                if let Some(url) = env_value("HTTPS_PROXY") {
+                    builder = with_proxy(builder, &url, Proxy::https(&url), &no_proxy);
+                }
+                if let Some(url) = env_value("ALL_PROXY") {
+                    builder = with_proxy(builder, &url, Proxy::all(&url), &no_proxy);
+                }
+                builder
+            }
+            ProxyMode::Off => builder.no_proxy(),
+            ProxyMode::Manual => {
+                let mut builder = builder;
+                if let Some(url) = &self.http {
+                    builder = with_proxy(builder, url, Proxy::http(url), &self.no_proxy);
+                }
+                if let Some(url) = &self.https {
+                    builder = with_proxy(builder, url, Proxy::https(url), &self.no_proxy);
+                }
+                builder
+            }
+        }
+    }
+
+    // The inherited NO_PROXY, with loopback always excluded.
+    fn system_no_proxy(&self, inherited: Option<&str>) -> String {
+        match inherited {
+            Some(inherited) => format!("{},{inherited}", self.no_proxy),
+            None => self.no_proxy.clone(),
+        }
+    }
+
+    pub fn apply_to_child(&self, cmd: &mut Command) {
+        match self.mode {
+            ProxyMode::System => {
+                let no_proxy = self.system_no_proxy(env_value("NO_PROXY").as_deref());
+                cmd.env("NO_PROXY", &no_proxy).env("no_proxy", &no_proxy);
+            }
+            ProxyMode::Off => {
+                for name in CHILD_PROXY_VARS {
+                    cmd.env_remove(name);
+                }
+            }
+            ProxyMode::Manual => {
+                if let Some(http) = &self.http {
+                    cmd.env("HTTP_PROXY", http).env("http_proxy", http);
+                }
+                if let Some(https) = self.https.as_ref().or(self.http.as_ref()) {
+                    cmd.env("HTTPS_PROXY", https).env("https_proxy", https);
+                }
+                cmd.env("NO_PROXY", &self.no_proxy)
+                    .env("no_proxy", &self.no_proxy);
+            }
+        }
+    }
+}
+
+fn with_proxy(
+    builder: ClientBuilder,
+    url: &str,
+    proxy: reqwest::Result<Proxy>,
+    no_proxy: &str,
+) -> ClientBuilder {
+    match proxy {
+        Ok(proxy) => builder.proxy(proxy.no_proxy(NoProxy::from_string(no_proxy))),
+        Err(err) => {
+            eprintln!("[http] ignoring invalid proxy '{url}': {err}");
+            builder
+        }
+    }
+}
+
+// Proxy variables are conventionally accepted in either case.
+fn env_value(name: &str) -> Option<String> {
+    [name.to_string(), name.to_ascii_lowercase()]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+}
+
+pub fn client_builder() -> ClientBuilder {
+    ProxyConfig::from_env().apply(Client::builder())
+}
+
+// TLS trust for connections to the ADK backend only; third-party APIs keep the default roots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BackendTls {
+    pub ca_cert_path: Option<PathBuf>,
+    pub accept_invalid_certs: bool,
+}
+
+static TLS_OVERRIDE: Mutex<Option<BackendTls>> = Mutex::new(None);
+
+impl BackendTls {
+    pub fn configured() -> Self {
+        Self {
+            ca_cert_path: std::env::var(CA_CERT_ENV)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from),
+            accept_invalid_certs: settings::flag(
+                INSECURE_TLS_ENV,
+                settings::current().backend_insecure_tls,
+            ),
+        }
+    }
+
+    pub fn current() -> Self {
+        TLS_OVERRIDE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_else(Self::configured)
+    }
+
+    // Loads the CA bundle up front so a bad path is reported when the setting is changed.
+    pub fn set(tls: Self) -> Result<(), String> {
+        tls.ca_certs()?;
+        *TLS_OVERRIDE.lock().unwrap_or_else(|e| e.into_inner()) = Some(tls);
+        Ok(())
+    }
+
+    fn ca_certs(&self) -> Result<Vec<CertificateDer<'static>>, String> {
+        let Some(path) = &self.ca_cert_path else {
+            return Ok(Vec::new());
+        };
+        let pem = std::fs::read(path)
+            .map_err(|e| format!("Failed to read CA certificate {}: {e}", path.display()))?;
+        let certs = CertificateDer::pem_slice_iter(&pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid PEM in CA certificate {}: {e}", path.display()))?;
+        if certs.is_empty() {
+            return Err(format!("No certificates found in {}.", path.display()));
+        }
+        Ok(certs)
+    }
+
+    fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        let mut builder = builder.danger_accept_invalid_certs(self.accept_invalid_certs);
+        match self.ca_certs() {
+            Ok(certs) => {
+                for cert in certs {
+                    match Certificate::from_der(&cert) {
+                        Ok(cert) => builder = builder.add_root_certificate(cert),
+                        Err(err) => eprintln!("[http] ignoring CA certificate: {err}"),
+                    }
+                }
+            }
+            Err(err) => eprintln!("[http] {err}"),
+        }
+        builder
+    }
+
+    // `None` keeps tokio-tungstenite's default webpki roots.
+    pub fn ws_connector(&self) -> Result<Option<Connector>, String> {
+        if *self == Self::default() {
+            return Ok(None);
+        }
+        let provider = Arc::new(crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| format!("Failed to configure TLS: {e}"))?;
+        let config = if self.accept_invalid_certs {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+                .with_no_client_auth()
+        } else {
+            let mut roots = RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            let (_, ignored) = roots.add_parsable_certificates(self.ca_certs()?);
+            if ignored > 0 {
+                eprintln!("[http] ignored {ignored} unparsable CA certificate(s)");
+            }
+            builder.with_root_certificates(roots).with_no_client_auth()
+        };
+        Ok(Some(Connector::Rustls(Arc::new(config))))
+    }
+}
+
+fn unix_sockets() -> MutexGuard<'static, HashMap<String, PathBuf>> {
+    static SOCKETS: OnceLock<Mutex<HashMap<String, PathBuf>>> = OnceLock::new();
+    SOCKETS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+// Routes requests for `base_url` through a unix socket instead of TCP; `None` removes the route.
+pub fn set_unix_socket(base_url: &str, path: Option<PathBuf>) {
+    let key = normalize_base_url(base_url);
+    match path {
+        Some(path) => unix_sockets().insert(key, path),
+        None => unix_sockets().remove(&key),
+    };
+}
+
+pub fn unix_socket_for(url: &str) -> Option<PathBuf> {
+    let url = url.to_ascii_lowercase();
+    unix_sockets()
+        .iter()
+        .find(|(base, _)| matches_base(&url, base))
+        .map(|(_, path)| path.clone())
+}
+
+// Client for requests to the ADK backend at `url`: backend TLS trust plus unix socket routing.
+pub fn backend_client_builder(url: &str) -> ClientBuilder {
+    let builder = BackendTls::current().apply(client_builder());
+    #[cfg(unix)]
+    if let Some(path) = unix_socket_for(url) {
+        return builder.unix_socket(path);
+    }
+    #[cfg(not(unix))]
+    let _ = url;
+    builder
+}
+
+// Signatures are still checked; only the certificate chain and host name are trusted blindly.
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_unix_socket, unix_socket_for, BackendTls, ProxyConfig, ProxyMode};
+    use std::path::PathBuf;
+
+    #[test]
+    fn unix_socket_routes_match_their_base_url_only() {
+        let path = PathBuf::from("/tmp/pv-backend-test.sock");
+        set_unix_socket("http://pv-backend-test.localhost", Some(path.clone()));
+        assert_eq!(
+            unix_socket_for("http://pv-backend-test.localhost/run_sse"),
+            Some(path)
+        );
+        assert_eq!(
+            unix_socket_for("http://pv-backend-test.localhost.evil/run"),
+            None
+        );
+        set_unix_socket("http://pv-backend-test.localhost", None);
+        assert_eq!(
+            unix_socket_for("http://pv-backend-test.localhost/run_sse"),
+            None
+        );
+    }
+
+    #[test]
+    fn backend_tls_rejects_unreadable_or_empty_ca_bundles() {
+        let missing = BackendTls {
+            ca_cert_path: Some(PathBuf::from("/nonexistent/pv-ca.pem")),
+            accept_invalid_certs: false,
+        };
+        assert!(missing.ca_certs().unwrap_err().contains("Failed to read"));
+
+        let empty = std::env::temp_dir().join(format!("pv-ca-{}.pem", std::process::id()));
+        std::fs::write(&empty, "not a certificate\n").unwrap();
+        let tls = BackendTls {
+            ca_cert_path: Some(empty.clone()),
+            accept_invalid_certs: false,
+        };
+        assert!(tls.ca_certs().unwrap_err().contains("No certificates"));
+        std::fs::remove_file(empty).unwrap();
+
+        assert!(BackendTls::default().ws_connector().unwrap().is_none());
+        let insecure = BackendTls {
+            ca_cert_path: None,
+            accept_invalid_certs: true,
+        };
+        assert!(insecure.ws_connector().unwrap().is_some());
+    }
+
+    #[test]
+    fn resolves_proxy_mode_from_settings() {
+        let config = ProxyConfig::resolve(None, None, None, None);
+        assert_eq!(config.mode, ProxyMode::System);
+
+        let config = ProxyConfig::resolve(
+            None,
+            Some("http://proxy.corp:3128".to_string()),
+            None,
+            Some("intranet.corp"),
+        );
+        assert_eq!(config.mode, ProxyMode::Manual);
+        assert_eq!(config.no_proxy, "localhost,127.0.0.1,::1,intranet.corp");
+
+        let config = ProxyConfig::resolve(
+            Some("OFF"),
+            Some("http://proxy.corp:3128".to_string()),
+            None,
+            None,
+        );
+        assert_eq!(config.mode, ProxyMode::Off);
+    }
+
+    #[test]
+    fn system_proxy_mode_still_excludes_loopback() {
+        let config = ProxyConfig::resolve(Some("system"), None, None, Some("intranet.corp"));
+        assert_eq!(
+            config.system_no_proxy(None),
+            "localhost,127.0.0.1,::1,intranet.corp"
+        );
+        assert_eq!(
+            config.system_no_proxy(Some(".corp.example")),
+            "localhost,127.0.0.1,::1,intranet.corp,.corp.example"
+        );
+
+        let mut cmd = tokio::process::Command::new("true");
+        config.apply_to_child(&mut cmd);
+        let no_proxy = cmd
+            .as_std()
+            .get_envs()
+            .find(|(name, _)| *name == "NO_PROXY")
+            .and_then(|(_, value)| value)
+            .and_then(|value| value.to_str())
+            .expect("NO_PROXY set for the backend");
+        assert!(no_proxy.starts_with("localhost,127.0.0.1,::1"));
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use tokio::time::sleep;
+
+use crate::commands::AppState;
+use crate::error::AppError;
+use crate::memory::keywords;
+use crate::redact;
+use crate::session_store::SessionStore;
+use crate::types::{IndexingPending, IndexingState, IndexingStatus};
+
+const IDLE_INTERVAL: Duration = Duration::from_secs(30);
+const BATCH_PAUSE: Duration = Duration::from_millis(250);
+const BATCH_SIZE: usize = 25;
+const EMBEDDING_DIMS: usize = 256;
+const SUMMARY_MAX_CHARS: usize = 280;
+const THUMBNAIL_SIZE: u32 = 256;
+const THUMBNAIL_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+#[derive(Default)]
+pub struct Indexer {
+    inner: Mutex<IndexerInner>,
+}
+
+#[derive(Default)]
+struct IndexerInner {
+    state: IndexingState,
+    indexed_total: u64,
+    last_pass_at_ms: Option<i64>,
+    last_error: Option<String>,
+    failed_thumbnails: HashSet<PathBuf>,
+}
+
+impl Indexer {
+    fn set_state(&self, state: IndexingState) {
+        self.lock().state = state;
+    }
+
+    fn record_batch(&self, count: usize) {
+        self.lock().indexed_total += count as u64;
+    }
+
+    fn finish_pass(&self, result: Result<(), String>) {
+        let mut inner = self.lock();
+        inner.state = IndexingState::Idle;
+        inner.last_pass_at_ms = Some(now_ms());
+        inner.last_error = result.err();
+    }
+
+    fn skip_thumbnail(&self, source: &Path) -> bool {
+        self.lock().failed_thumbnails.contains(source)
+    }
+
+    fn mark_thumbnail_failed(&self, source: PathBuf) {
+        self.lock().failed_thumbnails.insert(source);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, IndexerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Some(state) = app.try_state::<AppState>() {
+                let indexer = state.indexer.clone();
+                match run_pass(&app, &indexer).await {
+                    Ok(true) => indexer.finish_pass(Ok(())),
+                    Ok(false) => indexer.set_state(IndexingState::Paused),
+                    Err(err) => {
+                        redact::log("[indexer] pass failed:", &err);
+                        indexer.finish_pass(Err(err));
+                    }
+                }
+            }
+            sleep(IDLE_INTERVAL).await;
+        }
+    });
+}
+
+pub fn status(app: &AppHandle, indexer: &Indexer) -> Result<IndexingStatus, AppError> {
+    let pending = SessionStore::from_app(app)?.index_pending()?;
+    let thumbnails = pending_thumbnails(
+        &artifacts_dir(app).map_err(AppError::Storage)?,
+        &thumbnails_dir(app).map_err(AppError::Storage)?,
+    )
+    .into_iter()
+    .filter(|(source, _)| !indexer.skip_thumbnail(source))
+    .count() as u64;
+    let inner = indexer.lock();
+    Ok(IndexingStatus {
+        state: inner.state,
+        pending: IndexingPending {
+            fts: pending.fts,
+            embeddings: pending.embeddings,
+            summaries: pending.summaries,
+            thumbnails,
+        },
+        indexed_total: inner.indexed_total,
+        last_pass_at_ms: inner.last_pass_at_ms,
+        last_error: inner.last_error.clone(),
+    })
+}
+
+// Returns false when the pass stopped early because a run started.
+async fn run_pass(app: &AppHandle, indexer: &Arc<Indexer>) -> Result<bool, String> {
+    loop {
+        if runs_active(app).await {
+            return Ok(false);
+        }
+        indexer.set_state(IndexingState::Indexing);
+
+        let step_app = app.clone();
+        let step_indexer = indexer.clone();
+        let indexed = tokio::task::spawn_blocking(move || index_step(&step_app, &step_indexer))
+            .await
+            .map_err(|e| format!("Indexer task failed: {e}"))??;
+        if indexed == 0 {
+            return Ok(true);
+        }
+        indexer.record_batch(indexed);
+        sleep(BATCH_PAUSE).await;
+    }
+}
+
+async fn runs_active(app: &AppHandle) -> bool {
+    match app.try_state::<AppState>() {
+        Some(state) => !state.stream_tokens.lock().await.is_empty(),
+        None => true,
+    }
+}
+
+// Store errors only reach the indexer's `last_error`, so they are kept as text.
+fn index_step(app: &AppHandle, indexer: &Indexer) -> Result<usize, String> {
+    let store = SessionStore::from_app(app).map_err(|e| e.to_string())?;
+    let indexed = store
+        .fts_index_batch(BATCH_SIZE)
+        .map_err(|e| e.to_string())?;
+    if indexed > 0 {
+        return Ok(indexed);
+    }
+    let indexed = store
+        .embedding_batch(BATCH_SIZE, |text| embedding_bytes(&embed(text)))
+        .map_err(|e| e.to_string())?;
+    if indexed > 0 {
+        return Ok(indexed);
+    }
+    let indexed = store
+        .summary_batch(BATCH_SIZE, summarize)
+        .map_err(|e| e.to_string())?;
+    if indexed > 0 {
+        return Ok(indexed);
+    }
+
+    let pending: Vec<(PathBuf, PathBuf)> =
+        pending_thumbnails(&artifacts_dir(app)?, &thumbnails_dir(app)?)
+            .into_iter()
+            .filter(|(source, _)| !indexer.skip_thumbnail(source))
+            .take(BATCH_SIZE)
+            .collect();
+    for (source, target) in &pending {
+        if let Err(err) = make_thumbnail(source, target) {
+            redact::log("[indexer]", err);
+            indexer.mark_thumbnail_failed(source.clone());
+        }
+    }
+    Ok(pending.len())
+}
+
+// Local hashed bag-of-words vector; cheap enough to compute for every message without a model call.
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; EMBEDDING_DIMS];
+    for word in keywords(text) {
+        let hash = fnv1a(word.as_bytes());
+        let sign = if hash & 1 == 0 { 1.0 } else { -1.0 };
+        vector[(hash >> 1) as usize % EMBEDDING_DIMS] += sign;
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+pub fn embedding_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn summarize(report: &str) -> String {
+    let prose = report
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('|'))
+        .map(|line| line.trim_start_matches(['-', '*', '>', ' ']))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['*', '`'], "");
+    let prose = prose.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut summary = String::new();
+    for sentence in prose.split_inclusive(['.', '!', '?']) {
+        if !summary.is_empty()
+            && summary.chars().count() + sentence.chars().count() > SUMMARY_MAX_CHARS
+        {
+            break;
+        }
+        summary.push_str(sentence);
+    }
+    let summary = summary.trim();
+    if summary.chars().count() <= SUMMARY_MAX_CHARS {
+        return summary.to_string();
+    }
+    let cut: String = summary.chars().take(SUMMARY_MAX_CHARS).collect();
+    format!("{}...", cut.trim_end())
+}
+
+fn artifacts_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("artifacts"))
+}
+
+fn thumbnails_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("thumbnails"))
+}
+
+pub fn pending_thumbnails(artifacts_dir: &Path, thumbnails_dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let Ok(runs) = fs::read_dir(artifacts_dir) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for run in runs.flatten() {
+        let Ok(files) = fs::read_dir(run.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let source = file.path();
+            let is_image = source
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    THUMBNAIL_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                });
+            if !is_image {
+                continue;
+            }
+            let target = thumbnails_dir
+                .join(run.file_name())
+                .join(file.file_name())
+                .with_extension("png");
+            if !target.exists() {
+                out.push((source, target));
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+fn make_thumbnail(source: &Path, target: &Path) -> Result<(), String> {
+    let image = image::open(source)
+        .map_err(|e| format!("Failed to decode artifact image {:?}: {e}", source))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create thumbnail dir {:?}: {e}", parent))?;
+    }
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .save_with_format(target, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write thumbnail {:?}: {e}", target))
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{embed, pending_thumbnails, summarize};
+
+    #[test]
+    fn derives_embeddings_summaries_and_thumbnail_targets() {
+        let a = embed("Meal kit delivery for busy parents");
+        let b = embed("Busy parents want meal kit delivery");
+        let c = embed("Industrial welding robots");
+        let dot = |x: &[f32], y: &[f32]| x.iter().zip(y).map(|(p, q)| p * q).sum::<f32>();
+        assert!((dot(&a, &a) - 1.0).abs() < 1e-4);
+        assert!(dot(&a, &b) > dot(&a, &c));
+
+        let summary = summarize(
+            "# Verdict\n\n**Promising** niche with strong demand. Competitors are small.\n\n## Market\n- TAM is large.",
+        );
+        assert_eq!(
+            summary,
+            "Promising niche with strong demand. Competitors are small. TAM is large."
+        );
+        assert!(summarize(&"word ".repeat(200)).ends_with("..."));
+
+        let root = std::env::temp_dir().join(format!("pv-index-{}", uuid::Uuid::new_v4()));
+        let artifacts = root.join("artifacts");
+        std::fs::create_dir_all(artifacts.join("req-1")).unwrap();
+        std::fs::write(artifacts.join("req-1").join("chart.JPG"), b"x").unwrap();
+        std::fs::write(artifacts.join("req-1").join("notes.csv"), b"x").unwrap();
+        let pending = pending_thumbnails(&artifacts, &root.join("thumbnails"));
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].1.ends_with("req-1/chart.png"));
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::backend;
+use crate::error::AppError;
+use crate::events;
+use crate::redact;
+use crate::types::{BackendInstallProgress, BackendInstallResult};
+
+const INSTALLER_ENV: &str = "PV_DESKTOP_BACKEND_INSTALLER";
+const TAIL_LINES: usize = 20;
+
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+// `PV_DESKTOP_BACKEND_INSTALLER` wins (split on whitespace), then `uv sync`, then pip into the repo's .venv.
+pub fn plan(
+    repo_root: &Path,
+    configured: Option<&str>,
+    find: impl Fn(&str) -> Option<PathBuf>,
+) -> Result<Vec<String>, String> {
+    if let Some(raw) = configured.map(str::trim).filter(|raw| !raw.is_empty()) {
+        return Ok(raw.split_whitespace().map(str::to_string).collect());
+    }
+    if let Some(uv) = find("uv") {
+        return Ok(vec![uv.display().to_string(), "sync".to_string()]);
+    }
+    let venv_python = backend::venv_python(repo_root);
+    if venv_python.is_file() {
+        return Ok(vec![
+            venv_python.display().to_string(),
+            "-m".to_string(),
+            "pip".to_string(),
+            "install".to_string(),
+            "-e".to_string(),
+            ".".to_string(),
+        ]);
+    }
+    Err(format!(
+        "No installer found: install uv or create a .venv in {}, or set {INSTALLER_ENV}.",
+        repo_root.display()
+    ))
+}
+
+pub fn configured_installer() -> Option<String> {
+    std::env::var(INSTALLER_ENV).ok()
+}
+
+// Held while an install or update touches the repo root, so the two never run concurrently.
+pub struct RepoJobGuard;
+
+impl RepoJobGuard {
+    pub fn acquire() -> Result<Self, AppError> {
+        INSTALLING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map(|_| Self)
+            .map_err(|_| {
+                AppError::Conflict("A backend install or update is already running.".to_string())
+            })
+    }
+}
+
+impl Drop for RepoJobGuard {
+    fn drop(&mut self) {
+        INSTALLING.store(false, Ordering::SeqCst);
+    }
+}
+
+// Runs the installer in the repo root, emitting every output line as a `backend-install` event.
+pub async fn run(
+    app: &AppHandle,
+    repo_root: &Path,
+    argv: Vec<String>,
+) -> Result<BackendInstallResult, AppError> {
+    let _guard = RepoJobGuard::acquire()?;
+    run_streamed(app, events::BACKEND_INSTALL, repo_root, argv).await
+}
+
+// Runs `argv` in `cwd`, emitting progress under the app-level `event` name.
+pub async fn run_streamed(
+    app: &AppHandle,
+    event: &'static str,
+    cwd: &Path,
+    argv: Vec<String>,
+) -> Result<BackendInstallResult, AppError> {
+    let command_line = argv.join(" ");
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| AppError::InvalidInput("Command is empty.".to_string()))?;
+
+    let started = Instant::now();
+    emit(
+        app,
+        event,
+        "started",
+        None,
+        Some(command_line.clone()),
+        None,
+    );
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::Internal(format!("Failed to run `{command_line}`: {e}")))?;
+
+    let tail = Arc::new(Mutex::new(Vec::new()));
+    let readers = [
+        child
+            .stdout
+            .take()
+            .map(|out| forward_lines(app.clone(), event, out, "stdout", tail.clone())),
+        child
+            .stderr
+            .take()
+            .map(|err| forward_lines(app.clone(), event, err, "stderr", tail.clone())),
+    ];
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to wait for `{command_line}`: {e}")))?;
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.await;
+    }
+    let exit_code = status.code();
+    emit(app, event, "finished", None, None, exit_code);
+
+    let tail = std::mem::take(&mut *tail.lock().unwrap_or_else(|e| e.into_inner()));
+    Ok(BackendInstallResult {
+        ok: status.success(),
+        command: command_line,
+        exit_code,
+        duration_ms: started.elapsed().as_millis() as u64,
+        tail,
+    })
+}
+
+fn forward_lines<R>(
+    app: AppHandle,
+    event: &'static str,
+    reader: R,
+    stream: &'static str,
+    tail: Arc<Mutex<Vec<String>>>,
+) -> tokio::task::JoinHandle<()>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let line = redact::scrub(&line);
+            {
+                let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                if tail.len() >= TAIL_LINES {
+                    tail.remove(0);
+                }
+                tail.push(format!("[{stream}] {line}"));
+            }
+            emit(&app, event, "output", Some(stream), Some(line), None);
+        }
+    })
+}
+
+fn emit(
+    app: &AppHandle,
+    event: &str,
+    phase: &str,
+    stream: Option<&str>,
+    line: Option<String>,
+    exit_code: Option<i32>,
+) {
+    let _ = app.emit(
+        &events::name(event),
+        BackendInstallProgress {
+            phase: phase.to_string(),
+            stream: stream.map(str::to_string),
+            line,
+            exit_code,
+            timestamp_ms: now_ms(),
+        },
+    );
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan;
+    use std::path::PathBuf;
+
+    #[test]
+    fn prefers_configured_installer_then_uv_then_venv() {
+        let root = std::env::temp_dir().join(format!("pv-install-{}", std::process::id()));
+        let uv = |name: &str| (name == "uv").then(|| PathBuf::from("/opt/bin/uv"));
+
+        assert_eq!(
+            plan(&root, Some(" poetry install "), uv).unwrap(),
+            vec!["poetry", "install"]
+        );
+        assert_eq!(
+            plan(&root, Some("  "), uv).unwrap(),
+            vec!["/opt/bin/uv", "sync"]
+        );
+        assert!(plan(&root, None, |_| None)
+            .unwrap_err()
+            .contains("No installer found"));
+
+        let venv_python = crate::backend::venv_python(&root);
+        std::fs::create_dir_all(venv_python.parent().unwrap()).unwrap();
+        std::fs::write(&venv_python, "").unwrap();
+        let pip = plan(&root, None, |_| None).unwrap();
+        assert_eq!(pip[1..], ["-m", "pip", "install", "-e", "."]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Mutex;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+// Persisted next to the keys it protects. Only a verifier is stored for the passphrase; the
+// biometric path is a keychain item that macOS releases only after Touch ID (or the login
+// password) confirms the user.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockConfig {
+    pub passphrase_hash: Option<String>,
+    pub biometric: bool,
+}
+
+// Lock state for stored keys. Starts locked whenever a lock is configured, so a fresh launch
+// cannot read keys (or start the backend) until the user unlocks.
+#[derive(Debug, Default)]
+pub struct KeyLock {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    // None until loaded from storage on first use.
+    config: Option<Option<LockConfig>>,
+    unlocked: bool,
+}
+
+impl KeyLock {
+    pub fn config(
+        &self,
+        load: impl FnOnce() -> Result<Option<LockConfig>, String>,
+    ) -> Result<Option<LockConfig>, String> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.config.is_none() {
+            inner.config = Some(load()?);
+        }
+        Ok(inner.config.clone().flatten())
+    }
+
+    pub fn is_locked(
+        &self,
+        load: impl FnOnce() -> Result<Option<LockConfig>, String>,
+    ) -> Result<bool, String> {
+        let enabled = self.config(load)?.is_some();
+        Ok(enabled
+            && !self
+                .inner
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .unlocked)
+    }
+
+    // The user just proved access by configuring the lock, so the session stays unlocked.
+    pub fn replace_config(&self, config: Option<LockConfig>) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.config = Some(config);
+        inner.unlocked = true;
+    }
+
+    pub fn unlock(&self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unlocked = true;
+    }
+
+    pub fn lock(&self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .unlocked = false;
+    }
+}
+
+pub fn hash_passphrase(passphrase: &str) -> Result<String, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {MIN_PASSPHRASE_LEN} characters."
+        ));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash passphrase: {e}"))
+}
+
+pub fn verify_passphrase(hash: &str, passphrase: &str) -> Result<bool, String> {
+    let parsed = PasswordHash::new(hash)
+        .map_err(|e| format!("Stored passphrase verifier is corrupt: {e}"))?;
+    Ok(Argon2::default()
+        .verify_password(passphrase.as_bytes(), &parsed)
+        .is_ok())
+}
+
+pub fn biometric_available() -> bool {
+    cfg!(target_os = "macos")
+}
+
+#[cfg(target_os = "macos")]
+mod biometric {
+    use security_framework::passwords::{
+        delete_generic_password, generic_password, set_generic_password_options,
+        AccessControlOptions, PasswordOptions,
+    };
+
+    const ACCOUNT: &str = "key_lock_presence";
+    // errSecItemNotFound
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn enroll(service: &str) -> Result<(), String> {
+        remove(service)?;
+        let mut options = PasswordOptions::new_generic_password(service, ACCOUNT);
+        options.set_access_control_options(AccessControlOptions::USER_PRESENCE);
+        let token = uuid::Uuid::new_v4().to_string();
+        set_generic_password_options(token.as_bytes(), options)
+            .map_err(|e| format!("Failed to enable Touch ID unlock: {e}"))
+    }
+
+    // Reading the item makes macOS prompt for Touch ID; success is the confirmation.
+    pub fn confirm(service: &str) -> Result<(), String> {
+        generic_password(PasswordOptions::new_generic_password(service, ACCOUNT))
+            .map(|_| ())
+            .map_err(|e| format!("Touch ID confirmation failed: {e}"))
+    }
+
+    pub fn remove(service: &str) -> Result<(), String> {
+        match delete_generic_password(service, ACCOUNT) {
+            Ok(()) => Ok(()),
+            Err(err) if err.code() == ITEM_NOT_FOUND => Ok(()),
+            Err(err) => Err(format!("Failed to remove Touch ID unlock item: {err}")),
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod biometric {
+    const UNAVAILABLE: &str = "Touch ID unlock is only available on macOS.";
+
+    pub fn enroll(_service: &str) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn confirm(_service: &str) -> Result<(), String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub fn remove(_service: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+pub use biometric::{
+    confirm as biometric_confirm, enroll as biometric_enroll, remove as biometric_remove,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_passphrase, verify_passphrase, KeyLock, LockConfig};
+
+    #[test]
+    fn passphrase_verifier_round_trips() {
+        assert!(hash_passphrase("short").is_err());
+        let hash = hash_passphrase("open sesame").unwrap();
+        assert!(!hash.contains("open sesame"));
+        assert!(verify_passphrase(&hash, "open sesame").unwrap());
+        assert!(!verify_passphrase(&hash, "open sesame!").unwrap());
+    }
+
+    #[test]
+    fn configured_lock_starts_locked_until_unlocked() {
+        let configured = || {
+            Ok(Some(LockConfig {
+                passphrase_hash: None,
+                biometric: true,
+            }))
+        };
+        let lock = KeyLock::default();
+        assert!(lock.is_locked(configured).unwrap());
+        lock.unlock();
+        assert!(!lock.is_locked(|| unreachable!()).unwrap());
+        lock.lock();
+        assert!(lock.is_locked(|| unreachable!()).unwrap());
+
+        let open = KeyLock::default();
+        assert!(!open.is_locked(|| Ok(None)).unwrap());
+        lock.replace_config(None);
+        lock.lock();
+        assert!(!lock.is_locked(|| unreachable!()).unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use reqwest::{Client, RequestBuilder};
+use serde_json::json;
+
+use crate::translate::GEMINI_MODELS_URL;
+use crate::types::{KeyValidation, KeyValidity, KeysInput};
+
+const VALIDATE_TIMEOUT: Duration = Duration::from_secs(10);
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const TODOIST_PROJECTS_URL: &str = "https://api.todoist.com/rest/v2/projects";
+const LINEAR_GRAPHQL_URL: &str = "https://api.linear.app/graphql";
+
+type ProbeRequest = fn(&Client, &str) -> RequestBuilder;
+
+// Makes one cheap authenticated call per key that is set; unset keys are reported as `missing`
+// without touching the network. Brave has no free endpoint, so its check costs one search.
+pub async fn validate(keys: &KeysInput) -> Result<Vec<KeyValidation>, String> {
+    let client = crate::http::client_builder()
+        .timeout(VALIDATE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let checks: [(&str, &Option<String>, ProbeRequest); 5] = [
+        ("googleApiKey", &keys.google_api_key, gemini_request),
+        ("geminiApiKey", &keys.gemini_api_key, gemini_request),
+        ("braveApiKey", &keys.brave_api_key, brave_request),
+        ("todoistApiToken", &keys.todoist_api_token, todoist_request),
+        ("linearApiKey", &keys.linear_api_key, linear_request),
+    ];
+    let mut out = Vec::with_capacity(checks.len());
+    for (key, value, request) in checks {
+        let Some(value) = value.as_deref().map(str::trim).filter(|v| !v.is_empty()) else {
+            out.push(KeyValidation {
+                key: key.to_string(),
+                validity: KeyValidity::Missing,
+                detail: None,
+            });
+            continue;
+        };
+        let (validity, detail) = match request(&client, value).send().await {
+            Ok(response) => classify(response.status().as_u16()),
+            Err(err) => (
+                KeyValidity::Unreachable,
+                Some(format!("Could not reach the provider: {err}")),
+            ),
+        };
+        out.push(KeyValidation {
+            key: key.to_string(),
+            validity,
+            detail,
+        });
+    }
+    Ok(out)
+}
+
+fn gemini_request(client: &Client, key: &str) -> RequestBuilder {
+    client
+        .get(GEMINI_MODELS_URL)
+        .header("x-goog-api-key", key)
+        .query(&[("pageSize", "1")])
+}
+
+fn brave_request(client: &Client, key: &str) -> RequestBuilder {
+    client
+        .get(BRAVE_SEARCH_URL)
+        .header("X-Subscription-Token", key)
+        .header("Accept", "application/json")
+        .query(&[("q", "test"), ("count", "1")])
+}
+
+fn todoist_request(client: &Client, key: &str) -> RequestBuilder {
+    client.get(TODOIST_PROJECTS_URL).bearer_auth(key)
+}
+
+fn linear_request(client: &Client, key: &str) -> RequestBuilder {
+    client
+        .post(LINEAR_GRAPHQL_URL)
+        .header("Authorization", key)
+        .json(&json!({ "query": "{ viewer { id } }" }))
+}
+
+// A rate-limited response still proves the key authenticated.
+fn classify(status: u16) -> (KeyValidity, Option<String>) {
+    match status {
+        200..=299 => (KeyValidity::Valid, None),
+        429 => (
+            KeyValidity::Valid,
+            Some("The provider is rate limiting this key, but accepted it.".to_string()),
+        ),
+        400 | 401 | 403 | 422 => (
+            KeyValidity::Invalid,
+            Some(format!("The provider rejected the key (HTTP {status}).")),
+        ),
+        _ => (
+            KeyValidity::Unreachable,
+            Some(format!(
+                "The provider returned HTTP {status}; try again later."
+            )),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::classify;
+    use crate::types::KeyValidity;
+
+    #[test]
+    fn classifies_provider_responses() {
+        assert_eq!(classify(200).0, KeyValidity::Valid);
+        assert_eq!(classify(429).0, KeyValidity::Valid);
+        assert_eq!(classify(400).0, KeyValidity::Invalid);
+        assert_eq!(classify(401).0, KeyValidity::Invalid);
+        assert_eq!(classify(422).0, KeyValidity::Invalid);
+        assert_eq!(classify(503).0, KeyValidity::Unreachable);
+    }
+}
@@ -1,24 +1,102 @@
 #[cfg(not(target_os = "macos"))]
 use keyring::{Entry, Error as KeyringError};
 #[cfg(target_os = "macos")]
+use security_framework::passwords::{
+    delete_generic_password, get_generic_password, set_generic_password,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
 use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager};
 
-use crate::types::{KeyPresence, KeysInput};
+use crate::encrypted_store::EncryptedFileStore;
+use crate::env_export;
+use crate::error::AppError;
+use crate::google_oauth::GoogleCredentials;
+use crate::key_lock::{self, KeyLock, LockConfig};
+use crate::session_store::SessionStore;
+use crate::types::{
+    EnvKeyCandidate, ExternalKey, GoogleOAuthStatus, KeyLockStatus, KeyOrigin, KeyPresence,
+    KeyProfileSummary, KeyStorageKind, KeysInput, SecretSummary,
+};
 
 const SERVICE: &str = "project-validator-search";
+// Service names earlier builds stored keys under, newest first. When SERVICE is renamed, add the
+// old name here so existing keys are copied forward on the next launch.
+const LEGACY_SERVICES: &[&str] = &[];
 const GOOGLE_ACCOUNT: &str = "google_api_key";
 const BRAVE_ACCOUNT: &str = "brave_search_api_key";
 const GEMINI_ACCOUNT: &str = "gemini_api_key";
+const TODOIST_ACCOUNT: &str = "todoist_api_token";
+const LINEAR_ACCOUNT: &str = "linear_api_key";
+// Key profiles only carry what the backend process reads.
+const PROFILE_ACCOUNTS: &[&str] = &[GOOGLE_ACCOUNT, BRAVE_ACCOUNT, GEMINI_ACCOUNT];
+// Marks that entries written by the `security` CLI were moved to app-owned items.
+#[cfg(target_os = "macos")]
+const STORAGE_VERSION_ACCOUNT: &str = "storage_version";
+#[cfg(target_os = "macos")]
+const STORAGE_VERSION: &str = "2";
+// Holds a key between removing its CLI-owned item and writing the app-owned one.
+#[cfg(target_os = "macos")]
+const MIGRATION_STAGING_SUFFIX: &str = ".migrating";
+// errSecItemNotFound, and the exit code the `security` CLI reports for it.
+#[cfg(target_os = "macos")]
+const ITEM_NOT_FOUND: i32 = -25300;
+#[cfg(target_os = "macos")]
+const CLI_ITEM_NOT_FOUND: i32 = 44;
+const PROBE_ACCOUNT: &str = "storage_probe";
+const LOCK_ACCOUNT: &str = "key_lock";
+const GOOGLE_OAUTH_ACCOUNT: &str = "google_oauth";
+// Records which legacy service names have already been copied forward.
+const SERVICE_MIGRATION_ACCOUNT: &str = "migrated_services";
+const KEY_FILE: &str = "keys.enc";
+const STORE_ENV: &str = "PV_DESKTOP_KEY_STORE";
+const PASSPHRASE_ENV: &str = "PV_DESKTOP_KEYSTORE_PASSPHRASE";
+
+// (field name, keychain account, environment variables checked in order)
+const ENV_KEYS: &[(&str, &str, &[&str])] = &[
+    ("googleApiKey", GOOGLE_ACCOUNT, &["GOOGLE_API_KEY"]),
+    (
+        "braveApiKey",
+        BRAVE_ACCOUNT,
+        &["BRAVE_SEARCH_API_KEY", "BRAVE_API_KEY"],
+    ),
+    ("geminiApiKey", GEMINI_ACCOUNT, &["GEMINI_API_KEY"]),
+    ("todoistApiToken", TODOIST_ACCOUNT, &["TODOIST_API_TOKEN"]),
+    ("linearApiKey", LINEAR_ACCOUNT, &["LINEAR_API_KEY"]),
+];
 
 #[derive(Debug, Clone)]
 pub struct KeyEnv {
     pub google_api_key: Option<String>,
     pub brave_api_key: Option<String>,
     pub gemini_api_key: Option<String>,
+    // User-configured variables from settings, applied to the backend before the keys above.
+    pub extra_env: BTreeMap<String, String>,
+    // The key profile the values above came from; None is the default set.
+    pub profile: Option<String>,
+    // Named secrets plus Google sign-in credentials (ADC file location and Vertex AI settings),
+    // applied last.
+    pub credential_env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedEnvKey {
+    pub name: &'static str,
+    pub account: &'static str,
+    pub env_var: &'static str,
+    pub value: String,
 }
 
+// Keys imported from the environment "for this session only" live here and are never written to
+// the keychain; keychain values always win.
 #[derive(Debug, Clone, Default)]
-pub struct KeyStore;
+pub struct KeyStore {
+    session: Arc<Mutex<HashMap<&'static str, String>>>,
+    lock: Arc<KeyLock>,
+}
 
 impl KeyStore {
     pub fn set_keys(&self, keys: KeysInput) -> Result<(), String> {
@@ -47,6 +125,12 @@ impl KeyStore {
         if let Some(value) = keys.gemini_api_key {
             set_value(GEMINI_ACCOUNT, &value)?;
         }
+        if let Some(value) = keys.todoist_api_token {
+            set_value(TODOIST_ACCOUNT, &value)?;
+        }
+        if let Some(value) = keys.linear_api_key {
+            set_value(LINEAR_ACCOUNT, &value)?;
+        }
 
         let presence = self.key_presence()?;
         eprintln!(
@@ -85,132 +169,724 @@ impl KeyStore {
         delete_value(GOOGLE_ACCOUNT)?;
         delete_value(BRAVE_ACCOUNT)?;
         delete_value(GEMINI_ACCOUNT)?;
+        delete_value(TODOIST_ACCOUNT)?;
+        delete_value(LINEAR_ACCOUNT)?;
+        self.session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
         Ok(())
     }
 
-    pub fn read_env_values(&self) -> Result<KeyEnv, String> {
+    pub fn env_candidates(&self) -> Result<Vec<EnvKeyCandidate>, String> {
+        let session = self
+            .session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        detect_env_keys(|name| std::env::var(name).ok())
+            .into_iter()
+            .map(|key| {
+                let stored = get_value(key.account)?;
+                Ok(EnvKeyCandidate {
+                    name: key.name.to_string(),
+                    env_var: key.env_var.to_string(),
+                    masked: mask_secret(&key.value),
+                    stored_in_keychain: stored.is_some(),
+                    matches_keychain: stored.as_deref() == Some(key.value.as_str()),
+                    active_for_session: session.get(key.account) == Some(&key.value),
+                })
+            })
+            .collect()
+    }
+
+    pub fn import_env_keys(&self, names: &[String], persist: bool) -> Result<Vec<String>, String> {
+        let selected: Vec<DetectedEnvKey> = detect_env_keys(|name| std::env::var(name).ok())
+            .into_iter()
+            .filter(|key| names.is_empty() || names.iter().any(|n| n == key.name))
+            .collect();
+        if selected.is_empty() {
+            return Err("No matching API keys found in the app environment".to_string());
+        }
+
+        let mut session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+        for key in &selected {
+            if persist {
+                set_value(key.account, &key.value)?;
+                if get_value(key.account)?.as_deref() != Some(key.value.as_str()) {
+                    return Err(format!(
+                        "{} was imported but could not be verified from OS keychain.",
+                        key.env_var
+                    ));
+                }
+                session.remove(key.account);
+            } else {
+                session.insert(key.account, key.value.clone());
+            }
+        }
+        Ok(selected.iter().map(|key| key.name.to_string()).collect())
+    }
+
+    fn value(&self, account: &str) -> Result<Option<String>, String> {
+        if let Some(value) = get_value(account)? {
+            return Ok(Some(value));
+        }
+        Ok(self
+            .session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(account)
+            .cloned())
+    }
+
+    pub fn todoist_api_token(&self) -> Result<Option<String>, AppError> {
+        self.ensure_unlocked()?;
+        Ok(self.value(TODOIST_ACCOUNT)?)
+    }
+
+    pub fn linear_api_key(&self) -> Result<Option<String>, AppError> {
+        self.ensure_unlocked()?;
+        Ok(self.value(LINEAR_ACCOUNT)?)
+    }
+
+    // Every stored key under the primary environment variable name the backend reads.
+    pub fn env_exports(&self) -> Result<Vec<(&'static str, String)>, AppError> {
+        self.ensure_unlocked()?;
+        let mut out = Vec::new();
+        for (_, account, vars) in ENV_KEYS {
+            if let Some(value) = self.value(account)? {
+                out.push((vars[0], value));
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn read_env_values(&self) -> Result<KeyEnv, AppError> {
+        self.ensure_unlocked()?;
         Ok(KeyEnv {
-            google_api_key: get_value(GOOGLE_ACCOUNT)?,
-            brave_api_key: get_value(BRAVE_ACCOUNT)?,
-            gemini_api_key: get_value(GEMINI_ACCOUNT)?,
+            google_api_key: self.value(GOOGLE_ACCOUNT)?,
+            brave_api_key: self.value(BRAVE_ACCOUNT)?,
+            gemini_api_key: self.value(GEMINI_ACCOUNT)?,
+            extra_env: BTreeMap::new(),
+            profile: None,
+            credential_env: BTreeMap::new(),
         })
     }
 
+    pub fn set_profile_keys(&self, profile: &str, keys: &KeysInput) -> Result<(), String> {
+        for (account, value) in [
+            (GOOGLE_ACCOUNT, &keys.google_api_key),
+            (BRAVE_ACCOUNT, &keys.brave_api_key),
+            (GEMINI_ACCOUNT, &keys.gemini_api_key),
+        ] {
+            if let Some(value) = value {
+                set_value(&profile_account(profile, account), value)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear_profile(&self, profile: &str) -> Result<(), String> {
+        for account in PROFILE_ACCOUNTS {
+            delete_value(&profile_account(profile, account))?;
+        }
+        Ok(())
+    }
+
+    pub fn profile_summary(&self, profile: &str) -> Result<KeyProfileSummary, String> {
+        let set = |account| get_value(&profile_account(profile, account)).map(|v| v.is_some());
+        Ok(KeyProfileSummary {
+            name: profile.to_string(),
+            google_api_key_set: set(GOOGLE_ACCOUNT)?,
+            brave_api_key_set: set(BRAVE_ACCOUNT)?,
+            gemini_api_key_set: set(GEMINI_ACCOUNT)?,
+        })
+    }
+
+    // Keys the profile sets win; anything it leaves unset falls back to the default keys.
+    pub fn profile_env_values(&self, profile: &str) -> Result<KeyEnv, AppError> {
+        let mut env = self.read_env_values()?;
+        for (account, slot) in [
+            (GOOGLE_ACCOUNT, &mut env.google_api_key),
+            (BRAVE_ACCOUNT, &mut env.brave_api_key),
+            (GEMINI_ACCOUNT, &mut env.gemini_api_key),
+        ] {
+            if let Some(value) = get_value(&profile_account(profile, account))? {
+                *slot = Some(value);
+            }
+        }
+        env.profile = Some(profile.to_string());
+        Ok(env)
+    }
+
+    // Presence plus the backend keys the environment or `<repo_root>/.env` would supply.
+    pub fn key_presence_with_external(&self, repo_root: &Path) -> Result<KeyPresence, String> {
+        let mut presence = self.key_presence()?;
+        let dotenv_path = env_export::path(repo_root);
+        let dotenv = env_export::read(repo_root);
+        for (key, origin) in external_backend_keys(|name| std::env::var(name).ok(), &dotenv) {
+            presence.external_keys.push(ExternalKey {
+                name: key.name.to_string(),
+                env_var: key.env_var.to_string(),
+                origin,
+                source: match origin {
+                    KeyOrigin::Environment => "App environment".to_string(),
+                    KeyOrigin::DotEnv => dotenv_path.display().to_string(),
+                },
+                masked: mask_secret(&key.value),
+                in_use: self.value(key.account)?.is_none(),
+            });
+        }
+        Ok(presence)
+    }
+
     pub fn key_presence(&self) -> Result<KeyPresence, String> {
-        let google = get_value(GOOGLE_ACCOUNT)?;
-        let brave = get_value(BRAVE_ACCOUNT)?;
-        let gemini = get_value(GEMINI_ACCOUNT)?;
+        let locked = self.is_locked()?;
+        // While locked, report which keys exist but not even their masked tails.
+        let mask = |value: Option<String>| value.filter(|_| !locked).as_deref().map(mask_secret);
+        let google = self.value(GOOGLE_ACCOUNT)?;
+        let brave = self.value(BRAVE_ACCOUNT)?;
+        let gemini = self.value(GEMINI_ACCOUNT)?;
+        let todoist = self.value(TODOIST_ACCOUNT)?;
+        let linear = self.value(LINEAR_ACCOUNT)?;
+        let session_keys = {
+            let session = self.session.lock().unwrap_or_else(|e| e.into_inner());
+            ENV_KEYS
+                .iter()
+                .filter(|(_, account, _)| session.contains_key(account))
+                .map(|(name, _, _)| name.to_string())
+                .collect()
+        };
 
         Ok(KeyPresence {
             google_api_key_set: google.is_some(),
+            google_oauth_set: get_value(GOOGLE_OAUTH_ACCOUNT)?.is_some(),
             brave_api_key_set: brave.is_some(),
             gemini_api_key_set: gemini.is_some(),
-            google_api_key_masked: google.as_deref().map(mask_secret),
-            brave_api_key_masked: brave.as_deref().map(mask_secret),
-            gemini_api_key_masked: gemini.as_deref().map(mask_secret),
+            todoist_api_token_set: todoist.is_some(),
+            linear_api_key_set: linear.is_some(),
+            google_api_key_masked: mask(google),
+            brave_api_key_masked: mask(brave),
+            gemini_api_key_masked: mask(gemini),
+            todoist_api_token_masked: mask(todoist),
+            linear_api_key_masked: mask(linear),
+            session_keys,
+            storage: storage().kind(),
+            storage_detail: storage().detail.clone(),
+            locked,
+            external_keys: Vec::new(),
+        })
+    }
+
+    // Refresh-token credentials from Google sign-in; gated by the key lock like the API keys.
+    pub fn google_credentials(&self) -> Result<Option<GoogleCredentials>, AppError> {
+        self.ensure_unlocked()?;
+        Ok(self.google_credentials_unchecked()?)
+    }
+
+    pub fn google_oauth_status(&self) -> Result<GoogleOAuthStatus, String> {
+        let credentials = self.google_credentials_unchecked()?;
+        Ok(GoogleOAuthStatus {
+            signed_in: credentials.is_some(),
+            email: credentials.as_ref().and_then(|c| c.email.clone()),
+            project_id: credentials.as_ref().map(|c| c.project_id.clone()),
+            location: credentials.map(|c| c.location),
+        })
+    }
+
+    fn google_credentials_unchecked(&self) -> Result<Option<GoogleCredentials>, String> {
+        match get_value(GOOGLE_OAUTH_ACCOUNT)? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map(Some)
+                .map_err(|e| format!("Stored Google sign-in is unreadable: {e}")),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_google_credentials(
+        &self,
+        credentials: Option<&GoogleCredentials>,
+    ) -> Result<(), String> {
+        match credentials {
+            Some(credentials) => {
+                let raw = serde_json::to_string(credentials)
+                    .map_err(|e| format!("Failed to encode Google sign-in: {e}"))?;
+                set_value(GOOGLE_OAUTH_ACCOUNT, &raw)
+            }
+            None => delete_value(GOOGLE_OAUTH_ACCOUNT),
+        }
+    }
+
+    pub fn set_secret(&self, name: &str, value: &str) -> Result<(), String> {
+        if value.trim().is_empty() {
+            return Err(format!("Secret '{name}' cannot be empty."));
+        }
+        set_value(&secret_account(name), value)
+    }
+
+    pub fn secret(&self, name: &str) -> Result<Option<String>, AppError> {
+        self.ensure_unlocked()?;
+        Ok(get_value(&secret_account(name))?)
+    }
+
+    pub fn secret_summary(&self, name: &str) -> Result<SecretSummary, String> {
+        let value = get_value(&secret_account(name))?;
+        let locked = self.is_locked()?;
+        Ok(SecretSummary {
+            name: name.to_string(),
+            set: value.is_some(),
+            masked: value.filter(|_| !locked).as_deref().map(mask_secret),
+        })
+    }
+
+    pub fn delete_secret(&self, name: &str) -> Result<(), String> {
+        delete_value(&secret_account(name))
+    }
+
+    fn lock_config(&self) -> Result<Option<LockConfig>, String> {
+        self.lock.config(|| match get_value(LOCK_ACCOUNT)? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map(Some)
+                .map_err(|e| format!("Stored key lock settings are corrupt: {e}")),
+            None => Ok(None),
+        })
+    }
+
+    pub fn is_locked(&self) -> Result<bool, String> {
+        let config = self.lock_config()?;
+        self.lock.is_locked(|| Ok(config))
+    }
+
+    pub fn ensure_unlocked(&self) -> Result<(), AppError> {
+        if self.is_locked()? {
+            return Err(AppError::KeysLocked(
+                "Stored API keys are locked.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn lock_status(&self) -> Result<KeyLockStatus, String> {
+        let config = self.lock_config()?;
+        Ok(KeyLockStatus {
+            enabled: config.is_some(),
+            locked: self.is_locked()?,
+            passphrase: config.as_ref().is_some_and(|c| c.passphrase_hash.is_some()),
+            biometric: config.as_ref().is_some_and(|c| c.biometric),
+            biometric_available: key_lock::biometric_available(),
         })
     }
+
+    // Replaces the lock settings; None removes the lock. Callers must check the store is
+    // unlocked first so a locked session cannot simply reconfigure its way in.
+    pub fn set_lock_config(&self, config: Option<LockConfig>) -> Result<(), String> {
+        let biometric = config.as_ref().is_some_and(|c| c.biometric);
+        if biometric {
+            key_lock::biometric_enroll(SERVICE)?;
+        } else {
+            key_lock::biometric_remove(SERVICE)?;
+        }
+        match &config {
+            Some(config) => {
+                let raw = serde_json::to_string(config)
+                    .map_err(|e| format!("Failed to encode key lock settings: {e}"))?;
+                set_value(LOCK_ACCOUNT, &raw)?;
+            }
+            None => delete_value(LOCK_ACCOUNT)?,
+        }
+        self.lock.replace_config(config);
+        Ok(())
+    }
+
+    // With a passphrase, checks it against the stored verifier; without one, asks for Touch ID.
+    pub fn unlock(&self, passphrase: Option<&str>) -> Result<(), AppError> {
+        let Some(config) = self.lock_config()? else {
+            return Ok(());
+        };
+        match (passphrase, &config.passphrase_hash) {
+            (Some(passphrase), Some(hash)) => {
+                if !key_lock::verify_passphrase(hash, passphrase)? {
+                    return Err(AppError::InvalidInput("Incorrect passphrase.".to_string()));
+                }
+            }
+            (Some(_), None) => {
+                return Err(AppError::InvalidInput(
+                    "No passphrase is set; unlock with Touch ID.".to_string(),
+                ))
+            }
+            (None, _) if config.biometric => {
+                key_lock::biometric_confirm(SERVICE).map_err(AppError::KeysLocked)?
+            }
+            (None, _) => {
+                return Err(AppError::InvalidInput(
+                    "Enter the passphrase to unlock keys.".to_string(),
+                ))
+            }
+        }
+        self.lock.unlock();
+        Ok(())
+    }
+
+    pub fn lock_keys(&self) {
+        self.lock.lock();
+    }
 }
 
-#[cfg(not(target_os = "macos"))]
-fn entry(account: &str) -> Result<Entry, String> {
-    Entry::new(SERVICE, account).map_err(|e| e.to_string())
+// Where keys persist. The keychain is preferred; when it is missing or does not round-trip a
+// value (headless Linux without a secret service, locked-down machines), keys go to an encrypted
+// file in the app data directory instead.
+#[derive(Debug)]
+enum Backing {
+    Keychain,
+    File(EncryptedFileStore),
 }
 
-#[cfg(target_os = "macos")]
-fn command_output(cmd: &mut Command) -> Result<std::process::Output, String> {
-    cmd.output()
-        .map_err(|e| format!("failed to invoke macOS `security` CLI: {e}"))
+#[derive(Debug)]
+struct Storage {
+    backing: Backing,
+    detail: Option<String>,
+    // The key file is sealed with the machine-bound passphrase, which hides keys but does not
+    // protect them.
+    obfuscated: bool,
+    // Chosen after a failed keychain probe; not kept, so the next call probes again.
+    provisional: bool,
 }
 
-#[cfg(target_os = "macos")]
-fn set_value(account: &str, value: &str) -> Result<(), String> {
-    if value.trim().is_empty() {
-        return Ok(());
+impl Storage {
+    fn keychain(detail: Option<String>) -> Self {
+        Self {
+            backing: Backing::Keychain,
+            detail,
+            obfuscated: false,
+            provisional: false,
+        }
     }
 
-    let mut cmd = Command::new("security");
-    cmd.args([
-        "add-generic-password",
-        "-U",
-        "-s",
-        SERVICE,
-        "-a",
-        account,
-        "-w",
-        value,
-    ]);
+    fn kind(&self) -> KeyStorageKind {
+        match self.backing {
+            Backing::Keychain => KeyStorageKind::Keychain,
+            Backing::File(_) if self.obfuscated => KeyStorageKind::ObfuscatedFile,
+            Backing::File(_) => KeyStorageKind::EncryptedFile,
+        }
+    }
+}
 
-    let output = command_output(&mut cmd)?;
-    if output.status.success() {
-        return Ok(());
+fn key_file_path() -> &'static OnceLock<PathBuf> {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    &PATH
+}
+
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = key_file_path().set(dir.join(KEY_FILE));
+        }
+        Err(err) => eprintln!("[keyring] encrypted key file fallback disabled: {err}"),
     }
+    if !LEGACY_SERVICES.is_empty() {
+        let store = SessionStore::from_app(app);
+        let profiles = store
+            .as_ref()
+            .ok()
+            .and_then(|store| store.key_profiles_get().ok())
+            .unwrap_or_default();
+        let secrets = store
+            .ok()
+            .and_then(|store| store.secret_names_get().ok())
+            .unwrap_or_default();
+        migrate_legacy_services(LEGACY_SERVICES, &profiles, &secrets);
+    }
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    Err(format!(
-        "failed to store key '{}' via macOS keychain (exit={}): {}",
-        account,
-        output
-            .status
-            .code()
-            .map(|code| code.to_string())
-            .unwrap_or_else(|| "signal".to_string()),
-        if stderr.is_empty() {
-            "unknown error".to_string()
-        } else {
-            stderr
+// Copies keys stored under earlier service names into the current store so renaming the app
+// does not orphan them. Values already present under SERVICE win, and the old entries are left
+// in place so an older build keeps working. Runs until one pass completes without errors.
+fn migrate_legacy_services(legacy: &[&str], profiles: &[String], secrets: &[String]) {
+    let marker = legacy.join(",");
+    if matches!(get_value(SERVICE_MIGRATION_ACCOUNT), Ok(Some(done)) if done == marker) {
+        return;
+    }
+    let mut accounts: Vec<String> = ENV_KEYS
+        .iter()
+        .map(|(_, account, _)| account.to_string())
+        .collect();
+    accounts.push(LOCK_ACCOUNT.to_string());
+    accounts.push(GOOGLE_OAUTH_ACCOUNT.to_string());
+    for profile in profiles {
+        accounts.extend(
+            PROFILE_ACCOUNTS
+                .iter()
+                .map(|account| profile_account(profile, account)),
+        );
+    }
+    accounts.extend(secrets.iter().map(|name| secret_account(name)));
+    let (copied, complete) = copy_forward(legacy, &accounts, read_item, get_value, set_value);
+    if !copied.is_empty() {
+        eprintln!(
+            "[keyring] copied {} key(s) from legacy service names: {}",
+            copied.len(),
+            copied.join(", ")
+        );
+    }
+    if complete {
+        if let Err(err) = set_value(SERVICE_MIGRATION_ACCOUNT, &marker) {
+            eprintln!("[keyring] failed to record service migration: {err}");
         }
-    ))
+    }
+}
+
+// Returns the accounts copied and whether every lookup succeeded.
+fn copy_forward(
+    legacy: &[&str],
+    accounts: &[String],
+    read_legacy: impl Fn(&str, &str) -> Result<Option<String>, String>,
+    read_current: impl Fn(&str) -> Result<Option<String>, String>,
+    mut write: impl FnMut(&str, &str) -> Result<(), String>,
+) -> (Vec<String>, bool) {
+    let mut copied = Vec::new();
+    let mut complete = true;
+    for account in accounts {
+        match read_current(account) {
+            Ok(Some(_)) => continue,
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("[keyring] {err}");
+                complete = false;
+                continue;
+            }
+        }
+        for service in legacy {
+            match read_legacy(service, account) {
+                Ok(Some(value)) => {
+                    match write(account, &value) {
+                        Ok(()) => copied.push(account.clone()),
+                        Err(err) => {
+                            eprintln!("[keyring] {err}");
+                            complete = false;
+                        }
+                    }
+                    break;
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    eprintln!("[keyring] {err}");
+                    complete = false;
+                }
+            }
+        }
+    }
+    (copied, complete)
 }
 
+fn storage() -> Arc<Storage> {
+    static STORAGE: Mutex<Option<Arc<Storage>>> = Mutex::new(None);
+    let mut selected = STORAGE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(storage) = selected.as_ref() {
+        return storage.clone();
+    }
+    let storage = Arc::new(select_storage(std::env::var(STORE_ENV).ok().as_deref()));
+    eprintln!(
+        "[keyring] using {:?} storage{}",
+        storage.kind(),
+        storage
+            .detail
+            .as_deref()
+            .map(|d| format!(": {d}"))
+            .unwrap_or_default()
+    );
+    if !storage.provisional {
+        *selected = Some(storage.clone());
+    }
+    storage
+}
+
+// Keys move to the file store only when this system has no keychain at all. A keychain that is
+// there but fails the probe (locked, access denied) stays selected, so its errors surface rather
+// than keys quietly landing in a file.
+fn select_storage(forced: Option<&str>) -> Storage {
+    let reason = match forced.map(str::trim) {
+        Some("keychain") => return Storage::keychain(Some(format!("Forced by {STORE_ENV}."))),
+        Some("file") => format!("Forced by {STORE_ENV}."),
+        _ => match keychain_absent() {
+            Some(why) => format!("No OS keychain ({why})."),
+            None => {
+                return match probe_keychain() {
+                    Ok(()) => Storage::keychain(None),
+                    Err(err) => Storage {
+                        provisional: true,
+                        ..Storage::keychain(Some(format!(
+                            "OS keychain check failed ({err}); it is checked again on next use."
+                        )))
+                    },
+                }
+            }
+        },
+    };
+    let Some(path) = key_file_path().get() else {
+        return Storage::keychain(Some(format!(
+            "{reason} No app data directory for a key file."
+        )));
+    };
+    let (passphrase, obfuscated, detail) = match std::env::var(PASSPHRASE_ENV) {
+        Ok(value) if !value.is_empty() => (
+            value,
+            false,
+            format!(
+                "Keys are stored encrypted in {} (passphrase from {PASSPHRASE_ENV}).",
+                path.display()
+            ),
+        ),
+        _ => (
+            machine_passphrase(),
+            true,
+            format!(
+                "Keys are obfuscated, not encrypted, in {}: the machine-bound passphrase can be \
+                 rebuilt by anyone using this account. Set {PASSPHRASE_ENV} to encrypt them.",
+                path.display()
+            ),
+        ),
+    };
+    Storage {
+        backing: Backing::File(EncryptedFileStore::new(path.clone(), passphrase)),
+        detail: Some(format!("{reason} {detail}")),
+        obfuscated,
+        provisional: false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_absent() -> Option<String> {
+    None
+}
+
+// The mock store is what keyring falls back to when built without a platform backend, and
+// NoStorageAccess means the platform store (e.g. Secret Service) is not running.
 #[cfg(not(target_os = "macos"))]
+fn keychain_absent() -> Option<String> {
+    let entry = match Entry::new(SERVICE, PROBE_ACCOUNT) {
+        Ok(entry) => entry,
+        Err(err @ KeyringError::NoStorageAccess(_)) => return Some(err.to_string()),
+        Err(_) => return None,
+    };
+    if entry.get_credential().is::<keyring::mock::MockCredential>() {
+        return Some("this build has no OS keychain support".to_string());
+    }
+    match entry.get_password() {
+        Err(err @ KeyringError::NoStorageAccess(_)) => Some(err.to_string()),
+        _ => None,
+    }
+}
+
+// Writes a throwaway value and reads it back through a fresh entry, which catches both a backend
+// that errors and one that silently keeps values only in memory.
+fn probe_keychain() -> Result<(), String> {
+    probe_round_trip(keychain_set, keychain_get, keychain_delete)
+}
+
+fn probe_round_trip(
+    set: impl Fn(&str, &str) -> Result<(), String>,
+    get: impl Fn(&str) -> Result<Option<String>, String>,
+    delete: impl Fn(&str) -> Result<(), String>,
+) -> Result<(), String> {
+    let value = uuid::Uuid::new_v4().to_string();
+    set(PROBE_ACCOUNT, &value)?;
+    let read = get(PROBE_ACCOUNT);
+    let _ = delete(PROBE_ACCOUNT);
+    match read? {
+        Some(stored) if stored == value => Ok(()),
+        _ => Err("stored values do not persist".to_string()),
+    }
+}
+
+// Without an explicit passphrase the file is bound to this machine and user: it keeps keys out
+// of plain text and useless if copied elsewhere, but does not protect against the same user.
+fn machine_passphrase() -> String {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .unwrap_or_default();
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    format!("{SERVICE}:{machine_id}:{user}")
+}
+
 fn set_value(account: &str, value: &str) -> Result<(), String> {
+    match &storage().backing {
+        Backing::Keychain => keychain_set(account, value),
+        Backing::File(_) if value.trim().is_empty() => Ok(()),
+        Backing::File(file) => file.set(account, value),
+    }
+}
+
+fn get_value(account: &str) -> Result<Option<String>, String> {
+    match &storage().backing {
+        Backing::Keychain => keychain_get(account),
+        Backing::File(file) => file.get(account),
+    }
+}
+
+fn delete_value(account: &str) -> Result<(), String> {
+    match &storage().backing {
+        Backing::Keychain => keychain_delete(account),
+        Backing::File(file) => file.delete(account),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn entry(service: &str, account: &str) -> Result<Entry, String> {
+    Entry::new(service, account).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_set(account: &str, value: &str) -> Result<(), String> {
     if value.trim().is_empty() {
         return Ok(());
     }
-    entry(account)?
+    migrate_cli_items();
+    set_generic_password(SERVICE, account, value.as_bytes())
+        .map_err(|e| format!("failed to store key '{account}' in the macOS keychain: {e}"))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn keychain_set(account: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Ok(());
+    }
+    entry(SERVICE, account)?
         .set_password(value)
         .map_err(|e| format!("failed to store key '{}': {}", account, e))
 }
 
 #[cfg(target_os = "macos")]
-fn get_value(account: &str) -> Result<Option<String>, String> {
-    let mut cmd = Command::new("security");
-    cmd.args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"]);
+fn keychain_get(account: &str) -> Result<Option<String>, String> {
+    migrate_cli_items();
+    read_item(SERVICE, account)
+}
 
-    let output = command_output(&mut cmd)?;
-    if output.status.success() {
-        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if value.is_empty() {
-            return Ok(None);
+#[cfg(target_os = "macos")]
+fn read_item(service: &str, account: &str) -> Result<Option<String>, String> {
+    match get_generic_password(service, account) {
+        Ok(bytes) => {
+            let value = String::from_utf8_lossy(&bytes).trim().to_string();
+            Ok((!value.is_empty()).then_some(value))
         }
-        return Ok(Some(value));
-    }
-
-    if matches!(output.status.code(), Some(44)) {
-        return Ok(None);
+        Err(err) if err.code() == ITEM_NOT_FOUND => Ok(None),
+        Err(err) => Err(format!(
+            "failed to read key '{account}' from the macOS keychain: {err}"
+        )),
     }
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    Err(format!(
-        "failed to read key '{}' via macOS keychain (exit={}): {}",
-        account,
-        output
-            .status
-            .code()
-            .map(|code| code.to_string())
-            .unwrap_or_else(|| "signal".to_string()),
-        if stderr.is_empty() {
-            "unknown error".to_string()
-        } else {
-            stderr
-        }
-    ))
+#[cfg(not(target_os = "macos"))]
+fn keychain_get(account: &str) -> Result<Option<String>, String> {
+    read_item(SERVICE, account)
 }
 
 #[cfg(not(target_os = "macos"))]
-fn get_value(account: &str) -> Result<Option<String>, String> {
-    match entry(account)?.get_password() {
+fn read_item(service: &str, account: &str) -> Result<Option<String>, String> {
+    match entry(service, account)?.get_password() {
         Ok(value) if !value.trim().is_empty() => Ok(Some(value)),
         Ok(_) => Ok(None),
         Err(KeyringError::NoEntry) => Ok(None),
@@ -219,40 +895,290 @@ fn get_value(account: &str) -> Result<Option<String>, String> {
 }
 
 #[cfg(target_os = "macos")]
-fn delete_value(account: &str) -> Result<(), String> {
-    let mut cmd = Command::new("security");
-    cmd.args(["delete-generic-password", "-s", SERVICE, "-a", account]);
+fn keychain_delete(account: &str) -> Result<(), String> {
+    migrate_cli_items();
+    match delete_generic_password(SERVICE, account) {
+        Ok(()) => Ok(()),
+        Err(err) if err.code() == ITEM_NOT_FOUND => Ok(()),
+        Err(err) => Err(format!(
+            "failed to clear key '{account}' from the macOS keychain: {err}"
+        )),
+    }
+}
 
-    let output = command_output(&mut cmd)?;
-    if output.status.success() || matches!(output.status.code(), Some(44)) {
-        return Ok(());
+// Earlier versions wrote entries through the `security` CLI, so their access list trusts only
+// that tool and reading them from the app would prompt. Once per install, read each one through
+// the CLI (the secret comes back on stdout, never in argv), replace it with an app-owned item,
+// and record a marker so the CLI is not spawned again.
+#[cfg(target_os = "macos")]
+fn migrate_cli_items() {
+    static MIGRATION: std::sync::Once = std::sync::Once::new();
+    MIGRATION.call_once(|| {
+        if matches!(read_item(SERVICE, STORAGE_VERSION_ACCOUNT), Ok(Some(version)) if version == STORAGE_VERSION)
+        {
+            return;
+        }
+        let mut complete = true;
+        for (_, account, _) in ENV_KEYS {
+            if let Err(err) = migrate_cli_item(account) {
+                eprintln!("[keychain] {err}");
+                complete = false;
+            }
+        }
+        if !complete {
+            return;
+        }
+        if let Err(err) =
+            set_generic_password(SERVICE, STORAGE_VERSION_ACCOUNT, STORAGE_VERSION.as_bytes())
+        {
+            eprintln!("[keychain] failed to record migration: {err}");
+        }
+    });
+}
+
+// The value is first copied to an app-owned staging item and read back, and only then is the CLI
+// item removed, so at every point at least one item holds the key. A failure before the delete
+// leaves the CLI item untouched; a crash after it is finished from the staging item next launch.
+#[cfg(target_os = "macos")]
+fn migrate_cli_item(account: &str) -> Result<(), String> {
+    let staging = format!("{account}{MIGRATION_STAGING_SUFFIX}");
+    let value = match cli_read(account)? {
+        Some(value) => value,
+        // The CLI item is gone; finish an interrupted migration if the key was not written yet.
+        None => match read_item(SERVICE, &staging)? {
+            Some(value) if read_item(SERVICE, account)?.is_none() => value,
+            Some(_) => return delete_staged(account, &staging),
+            None => return Ok(()),
+        },
+    };
+
+    set_generic_password(SERVICE, &staging, value.as_bytes())
+        .map_err(|e| format!("failed to stage key '{account}' for migration: {e}"))?;
+    if read_item(SERVICE, &staging)?.as_deref() != Some(value.as_str()) {
+        return Err(format!(
+            "staged copy of key '{account}' did not read back; leaving it in place"
+        ));
     }
+    cli_delete(account)?;
+    set_generic_password(SERVICE, account, value.as_bytes())
+        .map_err(|e| format!("failed to migrate key '{account}': {e}"))?;
+    delete_staged(account, &staging)
+}
+
+#[cfg(target_os = "macos")]
+fn delete_staged(account: &str, staging: &str) -> Result<(), String> {
+    match delete_generic_password(SERVICE, staging) {
+        Ok(()) => Ok(()),
+        Err(err) if err.code() == ITEM_NOT_FOUND => Ok(()),
+        Err(err) => Err(format!(
+            "failed to remove staged copy of key '{account}': {err}"
+        )),
+    }
+}
 
-    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+#[cfg(target_os = "macos")]
+fn cli_read(account: &str) -> Result<Option<String>, String> {
+    let output = Command::new("security")
+        .args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"])
+        .output()
+        .map_err(|e| format!("failed to invoke macOS `security` CLI: {e}"))?;
+    if output.status.success() {
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        return Ok((!value.is_empty()).then_some(value));
+    }
+    if output.status.code() == Some(CLI_ITEM_NOT_FOUND) {
+        return Ok(None);
+    }
     Err(format!(
-        "failed to clear key '{}' via macOS keychain (exit={}): {}",
-        account,
-        output
-            .status
-            .code()
-            .map(|code| code.to_string())
-            .unwrap_or_else(|| "signal".to_string()),
-        if stderr.is_empty() {
-            "unknown error".to_string()
-        } else {
-            stderr
-        }
+        "failed to read legacy key '{account}': {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn cli_delete(account: &str) -> Result<(), String> {
+    let output = Command::new("security")
+        .args(["delete-generic-password", "-s", SERVICE, "-a", account])
+        .output()
+        .map_err(|e| format!("failed to invoke macOS `security` CLI: {e}"))?;
+    if output.status.success() || output.status.code() == Some(CLI_ITEM_NOT_FOUND) {
+        return Ok(());
+    }
+    Err(format!(
+        "failed to remove legacy key '{account}': {}",
+        String::from_utf8_lossy(&output.stderr).trim()
     ))
 }
 
 #[cfg(not(target_os = "macos"))]
-fn delete_value(account: &str) -> Result<(), String> {
-    match entry(account)?.delete_credential() {
+fn keychain_delete(account: &str) -> Result<(), String> {
+    match entry(SERVICE, account)?.delete_credential() {
         Ok(_) | Err(KeyringError::NoEntry) => Ok(()),
         Err(err) => Err(format!("failed to clear key '{}': {}", account, err)),
     }
 }
 
+pub fn detect_env_keys(lookup: impl Fn(&str) -> Option<String>) -> Vec<DetectedEnvKey> {
+    ENV_KEYS
+        .iter()
+        .filter_map(|(name, account, vars)| {
+            vars.iter().find_map(|var| {
+                let value = lookup(var)?.trim().to_string();
+                (!value.is_empty()).then_some(DetectedEnvKey {
+                    name,
+                    account,
+                    env_var: var,
+                    value,
+                })
+            })
+        })
+        .collect()
+}
+
+// Backend keys visible in the app's own environment or the repo's .env, environment first (it
+// is what a terminal `adk web` would also see over .env).
+pub fn external_backend_keys(
+    env: impl Fn(&str) -> Option<String>,
+    dotenv: &BTreeMap<String, String>,
+) -> Vec<(DetectedEnvKey, KeyOrigin)> {
+    let from_env = detect_env_keys(env);
+    let from_dotenv = detect_env_keys(|name| dotenv.get(name).cloned());
+    PROFILE_ACCOUNTS
+        .iter()
+        .filter_map(|account| {
+            let find =
+                |keys: &[DetectedEnvKey]| keys.iter().find(|k| k.account == *account).cloned();
+            find(&from_env)
+                .map(|key| (key, KeyOrigin::Environment))
+                .or_else(|| find(&from_dotenv).map(|key| (key, KeyOrigin::DotEnv)))
+        })
+        .collect()
+}
+
+// Primary variable names and values for backend keys the store does not have but the
+// environment or .env does, so the spawned backend sees them under the name it reads.
+pub fn external_fallback_env(keys: &KeyEnv, repo_root: &Path) -> Vec<(&'static str, String)> {
+    let dotenv = env_export::read(repo_root);
+    external_backend_keys(|name| std::env::var(name).ok(), &dotenv)
+        .into_iter()
+        .filter(|(key, _)| match key.account {
+            GOOGLE_ACCOUNT => keys.google_api_key.is_none(),
+            BRAVE_ACCOUNT => keys.brave_api_key.is_none(),
+            GEMINI_ACCOUNT => keys.gemini_api_key.is_none(),
+            _ => false,
+        })
+        .filter_map(|(key, _)| {
+            let (_, _, vars) = ENV_KEYS.iter().find(|(_, a, _)| *a == key.account)?;
+            Some((vars[0], key.value))
+        })
+        .collect()
+}
+
+pub fn validate_profile_name(raw: &str) -> Result<String, String> {
+    let name = raw.trim();
+    if name.is_empty() || name.len() > 40 {
+        return Err("Key profile names must be 1-40 characters.".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Key profile name '{name}' may only contain letters, digits, '-' and '_'."
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn profile_account(profile: &str, account: &str) -> String {
+    format!("profile:{profile}:{account}")
+}
+
+// Named secrets are handed to the backend as environment variables of the same name, so names
+// follow env-var rules and may not shadow the keys this module already manages.
+pub fn validate_secret_name(raw: &str) -> Result<String, String> {
+    let name = raw.trim();
+    if name.is_empty() || name.len() > 64 {
+        return Err("Secret names must be 1-64 characters.".to_string());
+    }
+    if !name.starts_with(|c: char| c.is_ascii_uppercase())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(format!(
+            "Secret name '{name}' must be an environment variable name like SERPAPI_API_KEY."
+        ));
+    }
+    if name.starts_with("PV_DESKTOP_") || ENV_KEYS.iter().any(|(_, _, vars)| vars.contains(&name)) {
+        return Err(format!(
+            "{name} is managed by the desktop app; set it in the API keys section instead."
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn secret_account(name: &str) -> String {
+    format!("secret:{name}")
+}
+
+// Raw facts for `keys_doctor`; the doctor module turns them into checks and hints.
+#[derive(Debug)]
+pub struct KeyringDiagnostics {
+    pub storage: KeyStorageKind,
+    pub storage_detail: Option<String>,
+    pub platform_backend: String,
+    pub keychain_round_trip: Result<(), String>,
+    // Only when keys live in the encrypted file.
+    pub file_round_trip: Option<Result<(), String>>,
+    pub locked: bool,
+    // macOS only: whether the `security` tool used to migrate older entries is present.
+    pub security_cli: Option<bool>,
+    // Linux only: whether a D-Bus session bus (needed by Secret Service) is advertised.
+    pub session_bus: Option<bool>,
+}
+
+// Runs fresh round-trips rather than reusing the launch-time probe, so the report reflects the
+// keychain as it is now (e.g. locked since launch).
+pub fn diagnose(store: &KeyStore) -> Result<KeyringDiagnostics, String> {
+    let storage = storage();
+    Ok(KeyringDiagnostics {
+        storage: storage.kind(),
+        storage_detail: storage.detail.clone(),
+        platform_backend: platform_backend(),
+        keychain_round_trip: probe_keychain(),
+        file_round_trip: match &storage.backing {
+            Backing::File(file) => Some(probe_round_trip(
+                |account, value| file.set(account, value),
+                |account| file.get(account),
+                |account| file.delete(account),
+            )),
+            Backing::Keychain => None,
+        },
+        locked: store.is_locked()?,
+        security_cli: cfg!(target_os = "macos")
+            .then(|| std::path::Path::new("/usr/bin/security").exists()),
+        session_bus: cfg!(target_os = "linux")
+            .then(|| std::env::var("DBUS_SESSION_BUS_ADDRESS").is_ok_and(|v| !v.trim().is_empty())),
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_backend() -> String {
+    "macOS Keychain (Security framework)".to_string()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn platform_backend() -> String {
+    match entry(SERVICE, PROBE_ACCOUNT) {
+        Ok(entry) if entry.get_credential().is::<keyring::mock::MockCredential>() => {
+            "In-memory mock (this build has no OS keychain support)".to_string()
+        }
+        Ok(_) => "OS credential store".to_string(),
+        Err(err) => format!("Unavailable ({err})"),
+    }
+}
+
 pub fn mask_secret(secret: &str) -> String {
     let suffix_len = 4usize.min(secret.len());
     let suffix = &secret[secret.len().saturating_sub(suffix_len)..];
@@ -261,11 +1187,141 @@ pub fn mask_secret(secret: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::mask_secret;
+    use super::{
+        copy_forward, detect_env_keys, external_backend_keys, mask_secret, profile_account,
+        secret_account, validate_profile_name, validate_secret_name,
+    };
+    use crate::types::KeyOrigin;
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn copies_legacy_entries_without_overwriting_current_ones() {
+        let legacy = HashMap::from([
+            (("newer", "google_api_key"), "g-new"),
+            (("older", "google_api_key"), "g-old"),
+            (("older", "brave_search_api_key"), "b-old"),
+            (("older", "gemini_api_key"), "m-old"),
+        ]);
+        let current = RefCell::new(HashMap::from([(
+            "gemini_api_key".to_string(),
+            "m-current".to_string(),
+        )]));
+        let accounts = [
+            "google_api_key",
+            "brave_search_api_key",
+            "gemini_api_key",
+            "linear_api_key",
+        ]
+        .map(String::from);
+
+        let (copied, complete) = copy_forward(
+            &["newer", "older"],
+            &accounts,
+            |service, account| Ok(legacy.get(&(service, account)).map(|v| v.to_string())),
+            |account| Ok(current.borrow().get(account).cloned()),
+            |account, value| {
+                current
+                    .borrow_mut()
+                    .insert(account.to_string(), value.to_string());
+                Ok(())
+            },
+        );
+
+        assert!(complete);
+        assert_eq!(copied, ["google_api_key", "brave_search_api_key"]);
+        let current = current.into_inner();
+        assert_eq!(current["google_api_key"], "g-new");
+        assert_eq!(current["brave_search_api_key"], "b-old");
+        assert_eq!(current["gemini_api_key"], "m-current");
+        assert!(!current.contains_key("linear_api_key"));
+
+        let (copied, complete) = copy_forward(
+            &["older"],
+            &accounts[..1],
+            |_, _| Err("locked".to_string()),
+            |_| Ok(None),
+            |_, _| Ok(()),
+        );
+        assert!(copied.is_empty());
+        assert!(!complete);
+    }
+
+    #[test]
+    fn profile_names_are_validated_and_namespaced() {
+        assert_eq!(
+            validate_profile_name(" acme-client ").unwrap(),
+            "acme-client"
+        );
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("a:b").is_err());
+        assert!(validate_profile_name(&"x".repeat(41)).is_err());
+        assert_eq!(
+            profile_account("acme", "google_api_key"),
+            "profile:acme:google_api_key"
+        );
+    }
+
+    #[test]
+    fn secret_names_are_env_vars_that_do_not_shadow_managed_keys() {
+        assert_eq!(
+            validate_secret_name(" SERPAPI_API_KEY ").unwrap(),
+            "SERPAPI_API_KEY"
+        );
+        assert_eq!(secret_account("REDDIT_TOKEN"), "secret:REDDIT_TOKEN");
+        assert!(validate_secret_name("reddit_token").is_err());
+        assert!(validate_secret_name("1TOKEN").is_err());
+        assert!(validate_secret_name("BRAVE_API_KEY").is_err());
+        assert!(validate_secret_name("PV_DESKTOP_TOKEN").is_err());
+    }
+
+    #[test]
+    fn external_keys_prefer_the_environment_over_dotenv() {
+        let dotenv = BTreeMap::from([
+            ("GOOGLE_API_KEY".to_string(), "g-dotenv".to_string()),
+            ("BRAVE_API_KEY".to_string(), "b-dotenv".to_string()),
+            (
+                "LINEAR_API_KEY".to_string(),
+                "not-a-backend-key".to_string(),
+            ),
+        ]);
+        let found = external_backend_keys(
+            |name| (name == "GOOGLE_API_KEY").then(|| "g-env".to_string()),
+            &dotenv,
+        );
+        let summary: Vec<_> = found
+            .iter()
+            .map(|(key, origin)| (key.env_var, key.value.as_str(), *origin))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("GOOGLE_API_KEY", "g-env", KeyOrigin::Environment),
+                ("BRAVE_API_KEY", "b-dotenv", KeyOrigin::DotEnv),
+            ]
+        );
+    }
 
     #[test]
     fn mask_secret_keeps_last_four() {
         assert_eq!(mask_secret("abcdef1234"), "***1234");
         assert_eq!(mask_secret("abc"), "***abc");
     }
+
+    #[test]
+    fn detects_env_keys_with_fallback_names() {
+        let env = |name: &str| match name {
+            "GOOGLE_API_KEY" => Some(" g-123 ".to_string()),
+            "BRAVE_API_KEY" => Some("b-456".to_string()),
+            "GEMINI_API_KEY" => Some("  ".to_string()),
+            _ => None,
+        };
+        let detected = detect_env_keys(env);
+        assert_eq!(detected.len(), 2);
+        assert_eq!(
+            (detected[0].name, detected[0].value.as_str()),
+            ("googleApiKey", "g-123")
+        );
+        assert_eq!(detected[1].env_var, "BRAVE_API_KEY");
+    }
 }
@@ -0,0 +1,180 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "backend.log";
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const KEEP_ROTATED: usize = 3;
+
+// Size-capped log that rolls `backend.log` over to `backend.log.1`, `.2`, ... keeping the newest few.
+#[derive(Debug)]
+pub struct RotatingLog {
+    dir: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl RotatingLog {
+    pub fn new(dir: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            keep,
+            file: None,
+            size: 0,
+        }
+    }
+
+    pub fn current_path(&self) -> PathBuf {
+        self.dir.join(LOG_FILE)
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        self.dir.join(format!("{LOG_FILE}.{n}"))
+    }
+
+    pub fn append(&mut self, line: &str) -> Result<(), String> {
+        let bytes = line.len() as u64 + 1;
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size + bytes > self.max_bytes {
+            self.rotate()?;
+            self.open()?;
+        }
+        if let Some(file) = self.file.as_mut() {
+            writeln!(file, "{line}").map_err(|e| format!("Failed to write backend log: {e}"))?;
+            self.size += bytes;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create log dir {:?}: {e}", self.dir))?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.current_path())
+            .map_err(|e| format!("Failed to open backend log file: {e}"))?;
+        self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        self.file = None;
+        self.size = 0;
+        let _ = fs::remove_file(self.rotated_path(self.keep));
+        for n in (1..self.keep).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                let _ = fs::rename(&from, self.rotated_path(n + 1));
+            }
+        }
+        fs::rename(self.current_path(), self.rotated_path(1))
+            .map_err(|e| format!("Failed to rotate backend log: {e}"))
+    }
+
+    pub fn files_oldest_first(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = (1..=self.keep)
+            .rev()
+            .map(|n| self.rotated_path(n))
+            .collect();
+        files.push(self.current_path());
+        files.into_iter().filter(|path| path.is_file()).collect()
+    }
+}
+
+fn log() -> &'static OnceLock<Mutex<RotatingLog>> {
+    static LOG: OnceLock<Mutex<RotatingLog>> = OnceLock::new();
+    &LOG
+}
+
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = log().set(Mutex::new(RotatingLog::new(
+                dir.join(LOG_DIR),
+                MAX_FILE_BYTES,
+                KEEP_ROTATED,
+            )));
+        }
+        Err(err) => eprintln!("[backend-log] file logging disabled: {err}"),
+    }
+}
+
+pub fn append(line: &str) {
+    if let Some(log) = log().get() {
+        if let Err(err) = log.lock().unwrap_or_else(|e| e.into_inner()).append(line) {
+            eprintln!("[backend-log] {err}");
+        }
+    }
+}
+
+// Concatenates the rotated files and the current one into a single export, oldest lines first.
+pub fn export(app: &AppHandle) -> Result<(PathBuf, PathBuf, u64), String> {
+    let log = log()
+        .get()
+        .ok_or_else(|| "Backend file logging is not enabled.".to_string())?;
+    let (files, current) = {
+        let log = log.lock().unwrap_or_else(|e| e.into_inner());
+        (log.files_oldest_first(), log.current_path())
+    };
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join(LOG_DIR)
+        .join("exports");
+    let path = dir.join(format!("backend-{stamp}.log"));
+    let size = concat_files(&files, &path)?;
+    Ok((path, current, size))
+}
+
+fn concat_files(files: &[PathBuf], out: &Path) -> Result<u64, String> {
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create export dir: {e}"))?;
+    }
+    let mut body = Vec::new();
+    for file in files {
+        body.extend(fs::read(file).map_err(|e| format!("Failed to read {file:?}: {e}"))?);
+    }
+    fs::write(out, &body).map_err(|e| format!("Failed to write log export: {e}"))?;
+    Ok(body.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{concat_files, RotatingLog};
+
+    #[test]
+    fn rotates_when_size_cap_is_reached() {
+        let dir = std::env::temp_dir().join(format!("pv-logs-{}", uuid::Uuid::new_v4()));
+        let mut log = RotatingLog::new(dir.clone(), 20, 2);
+        for n in 0..7 {
+            log.append(&format!("line {n} ..")).unwrap();
+        }
+
+        let files = log.files_oldest_first();
+        assert_eq!(files.len(), 3);
+        assert!(files[2].ends_with("backend.log"));
+        let out = dir.join("export.log");
+        concat_files(&files, &out).unwrap();
+        let exported = std::fs::read_to_string(&out).unwrap();
+        assert!(exported.ends_with("line 6 ..\n"));
+        assert!(!exported.contains("line 0"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
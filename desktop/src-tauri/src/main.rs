@@ -1,45 +1,208 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod adk_gc;
+mod analytics;
+mod artifacts;
+mod attachments;
 mod backend;
+mod backend_auth;
+mod backend_version;
+mod browser;
 mod commands;
+mod debug_bundle;
+mod debug_events;
+mod deep_link;
+mod degradation;
+mod doctor;
+mod encrypted_store;
+mod env_export;
+mod error;
+mod event_buffer;
+mod events;
+mod google_oauth;
+mod health_watch;
+mod http;
+mod indexer;
+mod installer;
+mod key_lock;
+mod key_validation;
 mod keyring_store;
+mod log_files;
+mod memory;
+mod models;
+mod multi_run;
+mod orphans;
+mod pdf;
+mod plan_detect;
+mod prompt_estimate;
+mod quota;
+mod redact;
+mod repo_update;
+mod report_blocks;
+mod report_export;
+mod report_sections;
+mod resources;
+mod revalidation;
+mod run_admission;
+mod run_budget;
+mod run_env;
+mod scheduler;
 mod session_store;
+mod settings;
+mod shortcuts;
+mod startup_failure;
 mod stream;
+mod supervisor;
+mod tasks;
+mod text_stats;
+mod transcript;
+mod translate;
+mod tray;
 mod types;
+mod windows;
 
 use commands::AppState;
-use tauri::{Manager, RunEvent};
+use tauri::{Manager, RunEvent, WindowEvent};
 
 fn main() {
     tauri::Builder::default()
+        // Must come first: a second launch hands its deep link to this instance and exits.
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            tray::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(shortcuts::plugin())
         .manage(AppState::new())
+        .setup(|app| {
+            backend_auth::load(app.handle());
+            backend::attach_log_emitter(app.handle().clone());
+            log_files::init(app.handle());
+            orphans::init(app.handle());
+            keyring_store::init(app.handle());
+            settings::init(app.handle());
+            if let Err(err) = tray::init(app.handle()) {
+                eprintln!("[tray] system tray unavailable: {err}");
+            }
+            if let Err(err) = deep_link::init(app.handle()) {
+                eprintln!("[deep-link] {err}");
+            }
+            revalidation::spawn_scheduler(app.handle().clone());
+            indexer::spawn(app.handle().clone());
+            adk_gc::spawn(app.handle().clone());
+            supervisor::spawn(app.handle().clone());
+            resources::spawn(app.handle().clone());
+            health_watch::spawn(app.handle().clone());
+            commands::detect_interrupted_runs(app.handle());
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { api, .. }
+                if tray::should_hide_on_close(window.app_handle(), window.label()) =>
+            {
+                api.prevent_close();
+                let _ = window.hide();
+            }
+            WindowEvent::Destroyed => windows::forget(window.label()),
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             commands::backend_start,
             commands::backend_stop,
+            commands::backend_instance_start,
+            commands::backend_instance_stop,
+            commands::backend_instances,
             commands::backend_status,
             commands::backend_list_apps,
+            commands::backend_logs,
+            commands::backend_log_export,
+            commands::backend_env_get,
+            commands::backend_env_set,
+            commands::backend_doctor,
+            commands::backend_validate_repo,
+            commands::backend_install,
+            commands::backend_update,
+            commands::backend_auth_get,
+            commands::backend_auth_set,
             commands::session_create,
             commands::session_list,
             commands::session_delete,
             commands::session_messages_get,
             commands::session_messages_append,
+            commands::session_debug_bundle,
+            commands::run_transcript_export,
             commands::session_phase_get,
             commands::session_phase_set,
+            commands::revalidation_schedule,
+            commands::revalidation_get,
+            commands::revalidation_cancel,
+            commands::deep_link_take,
+            commands::session_open_window,
+            commands::settings_get,
+            commands::settings_set,
+            commands::memory_settings_get,
+            commands::memory_settings_set,
+            commands::memory_list,
+            commands::memory_delete,
+            commands::memory_clear,
+            commands::session_tasks_get,
+            commands::session_task_update,
+            commands::session_tasks_export,
+            commands::report_translate,
+            commands::report_translations_get,
+            commands::report_blocks_get,
+            commands::report_export,
+            commands::report_open_in_browser,
+            commands::models_list,
+            commands::tool_quota_get,
+            commands::indexing_status,
+            commands::adk_sessions_gc,
+            commands::analytics_funnel_get,
+            commands::events_handshake,
+            commands::prompt_estimate,
             commands::stream_run,
+            commands::stream_run_multi,
             commands::stream_cancel,
+            commands::stream_attach,
+            commands::stream_debug_enable,
+            commands::stream_status,
+            commands::runs_interrupted,
+            commands::run_resume,
+            commands::stream_pause,
+            commands::stream_resume,
             commands::keys_set,
+            commands::keys_validate,
             commands::keys_get_masked,
+            commands::keys_export_env,
+            commands::key_profiles_list,
+            commands::key_profile_set,
+            commands::key_profile_delete,
             commands::keys_clear,
+            commands::secrets_list,
+            commands::secret_set,
+            commands::secret_get_masked,
+            commands::secret_delete,
+            commands::keys_doctor,
+            commands::google_oauth_status,
+            commands::google_oauth_sign_in,
+            commands::google_oauth_sign_out,
+            commands::key_lock_status,
+            commands::key_lock_configure,
+            commands::key_lock_unlock,
+            commands::key_lock_lock,
+            commands::keys_env_detect,
+            commands::keys_env_import,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
             if matches!(event, RunEvent::Exit | RunEvent::ExitRequested { .. }) {
                 let state = app_handle.state::<AppState>();
-                let backend = state.backend.clone();
+                let mut backends = vec![state.backend.clone()];
+                backends.extend(state.backend_instances.all().into_iter().map(|(_, m)| m));
                 tauri::async_runtime::block_on(async move {
-                    let mut manager = backend.lock().await;
-                    let _ = manager.stop().await;
+                    for backend in backends {
+                        let _ = backend.lock().await.stop().await;
+                    }
                 });
             }
         });
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use crate::session_store::ReplayMessage;
+use crate::types::MemoryEntry;
+
+const MAX_FINDINGS_PER_REPORT: usize = 20;
+const MAX_INJECTED_FINDINGS: usize = 8;
+const MIN_SHARED_KEYWORDS: usize = 2;
+const MIN_FINDING_CHARS: usize = 30;
+const MAX_FINDING_CHARS: usize = 320;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "been", "being", "between", "could", "from", "have", "into", "like",
+    "more", "most", "much", "only", "other", "over", "should", "some", "such", "than", "that",
+    "their", "them", "then", "there", "these", "they", "this", "those", "very", "were", "what",
+    "when", "which", "while", "will", "with", "would", "your", "idea", "product", "market",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: &'static str,
+    pub text: String,
+}
+
+pub fn extract_findings(report: &str) -> Vec<Finding> {
+    let mut out = Vec::new();
+    let mut in_competitor_section = false;
+
+    for raw_line in report.lines() {
+        let line = raw_line.trim();
+        if line.starts_with('#') {
+            let heading = line.trim_start_matches('#').to_ascii_lowercase();
+            in_competitor_section = heading.contains("competit") || heading.contains("alternative");
+            continue;
+        }
+
+        let text = line
+            .trim_start_matches(['-', '*', '+', '>'])
+            .trim()
+            .trim_matches('|')
+            .trim()
+            .replace("**", "");
+        let length = text.chars().count();
+        if !(MIN_FINDING_CHARS..=MAX_FINDING_CHARS).contains(&length) {
+            continue;
+        }
+
+        let lower = text.to_ascii_lowercase();
+        let kind = if in_competitor_section || lower.contains("competitor") {
+            "competitor"
+        } else if looks_like_market_stat(&lower) {
+            "market_stat"
+        } else {
+            continue;
+        };
+
+        if out.iter().any(|f: &Finding| f.text == text) {
+            continue;
+        }
+        out.push(Finding { kind, text });
+        if out.len() >= MAX_FINDINGS_PER_REPORT {
+            break;
+        }
+    }
+
+    out
+}
+
+fn looks_like_market_stat(lower: &str) -> bool {
+    lower.chars().any(|c| c.is_ascii_digit())
+        && [
+            "%",
+            "$",
+            "€",
+            "million",
+            "billion",
+            "cagr",
+            "market size",
+            "users",
+            "revenue",
+        ]
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+pub fn keywords(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 4 && !STOPWORDS.contains(&word.as_str()))
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+pub fn related_entries<'a>(idea: &str, entries: &'a [MemoryEntry]) -> Vec<&'a MemoryEntry> {
+    let idea_keywords: HashSet<String> = keywords(idea).into_iter().collect();
+    let mut scored: Vec<(usize, &MemoryEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let shared = entry
+                .keywords
+                .split_whitespace()
+                .filter(|k| idea_keywords.contains(*k))
+                .count();
+            (shared >= MIN_SHARED_KEYWORDS).then_some((shared, entry))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(b.1.created_at_ms.cmp(&a.1.created_at_ms))
+    });
+    scored
+        .into_iter()
+        .take(MAX_INJECTED_FINDINGS)
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+pub fn context_message(entries: &[&MemoryEntry]) -> Option<ReplayMessage> {
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut text =
+        "Findings saved from earlier validations of related ideas (verify before relying on them):"
+            .to_string();
+    for entry in entries {
+        text.push_str(&format!("\n- [{}] {}", entry.kind, entry.text));
+    }
+
+    Some(ReplayMessage {
+        role: "system".to_string(),
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_findings, related_entries};
+    use crate::types::MemoryEntry;
+
+    #[test]
+    fn extracts_stats_and_competitor_facts_from_report() {
+        let report = "# Market\n- The pet care market is worth $261 billion globally in 2024.\n- Owners love dogs.\n\n## Competitors\n- Rover connects owners with sitters in 10 countries.\n";
+        let findings = extract_findings(report);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].kind, "market_stat");
+        assert_eq!(findings[1].kind, "competitor");
+        assert!(findings[1].text.starts_with("Rover"));
+    }
+
+    #[test]
+    fn related_entries_require_shared_keywords() {
+        let entry = |id: &str, keywords: &str| MemoryEntry {
+            id: id.to_string(),
+            source_session_id: None,
+            kind: "market_stat".to_string(),
+            text: "fact".to_string(),
+            keywords: keywords.to_string(),
+            created_at_ms: 0,
+        };
+        let entries = vec![
+            entry("a", "sitting marketplace dogs"),
+            entry("b", "invoice accounting freelancers"),
+        ];
+        let related = related_entries("A marketplace for dog sitting", &entries);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].id, "a");
+    }
+}
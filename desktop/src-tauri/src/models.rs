@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Client;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::keyring_store::KeyEnv;
+use crate::translate::GEMINI_MODELS_URL;
+use crate::types::{ModelCatalog, ModelInfo};
+
+const CATALOG_TTL_MS: i64 = 10 * 60 * 1000;
+const GEMINI_MAX_PAGES: usize = 10;
+
+pub type ModelCatalogCache = Arc<Mutex<Option<ModelCatalog>>>;
+
+pub async fn cached_catalog(
+    cache: &ModelCatalogCache,
+    keys: &KeyEnv,
+    refresh: bool,
+) -> Result<ModelCatalog, String> {
+    let mut cached = cache.lock().await;
+    if let Some(catalog) = cached.as_ref() {
+        if !refresh && now_ms() - catalog.fetched_at_ms < CATALOG_TTL_MS {
+            return Ok(catalog.clone());
+        }
+    }
+
+    let catalog = fetch_catalog(keys).await?;
+    *cached = Some(catalog.clone());
+    Ok(catalog)
+}
+
+async fn fetch_catalog(keys: &KeyEnv) -> Result<ModelCatalog, String> {
+    let client = crate::http::client_builder()
+        .timeout(Duration::from_secs(20))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let mut models = Vec::new();
+    let mut errors = Vec::new();
+
+    match keys
+        .gemini_api_key
+        .as_ref()
+        .or(keys.google_api_key.as_ref())
+    {
+        Some(api_key) => match fetch_gemini_models(&client, api_key).await {
+            Ok(found) => models.extend(found),
+            Err(err) => errors.push(format!("gemini: {err}")),
+        },
+        None => errors.push("gemini: no Gemini or Google API key configured".to_string()),
+    }
+
+    if let Some(base_url) = litellm_base_url() {
+        match fetch_litellm_models(&client, &base_url).await {
+            Ok(found) => models.extend(found),
+            Err(err) => errors.push(format!("litellm: {err}")),
+        }
+    }
+
+    Ok(ModelCatalog {
+        models,
+        errors,
+        fetched_at_ms: now_ms(),
+    })
+}
+
+fn litellm_base_url() -> Option<String> {
+    std::env::var("PV_DESKTOP_LITELLM_BASE_URL")
+        .ok()
+        .map(|v| v.trim().trim_end_matches('/').to_string())
+        .filter(|v| !v.is_empty())
+}
+
+async fn fetch_gemini_models(client: &Client, api_key: &str) -> Result<Vec<ModelInfo>, String> {
+    let mut out = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    for _ in 0..GEMINI_MAX_PAGES {
+        let mut request = client
+            .get(GEMINI_MODELS_URL)
+            .header("x-goog-api-key", api_key)
+            .query(&[("pageSize", "1000")]);
+        if let Some(token) = page_token.as_deref() {
+            request = request.query(&[("pageToken", token)]);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("models endpoint returned {}", response.status()));
+        }
+        let payload = response
+            .json::<Value>()
+            .await
+            .map_err(|e| format!("invalid response: {e}"))?;
+
+        let (models, next) = parse_gemini_models(&payload);
+        out.extend(models);
+        match next {
+            Some(token) => page_token = Some(token),
+            None => break,
+        }
+    }
+
+    Ok(out)
+}
+
+async fn fetch_litellm_models(client: &Client, base_url: &str) -> Result<Vec<ModelInfo>, String> {
+    let mut request = client.get(format!("{base_url}/v1/models"));
+    if let Ok(api_key) = std::env::var("PV_DESKTOP_LITELLM_API_KEY") {
+        if !api_key.trim().is_empty() {
+            request = request.bearer_auth(api_key.trim());
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("request failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("models endpoint returned {}", response.status()));
+    }
+    let payload = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("invalid response: {e}"))?;
+    Ok(parse_openai_models(&payload, "litellm"))
+}
+
+fn parse_gemini_models(payload: &Value) -> (Vec<ModelInfo>, Option<String>) {
+    let models = payload
+        .get("models")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| {
+                    item.get("supportedGenerationMethods")
+                        .and_then(Value::as_array)
+                        .is_some_and(|methods| {
+                            methods
+                                .iter()
+                                .any(|m| m.as_str() == Some("generateContent"))
+                        })
+                })
+                .filter_map(|item| {
+                    let name = item.get("name")?.as_str()?;
+                    Some(ModelInfo {
+                        provider: "gemini".to_string(),
+                        id: name.trim_start_matches("models/").to_string(),
+                        display_name: item
+                            .get("displayName")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        input_token_limit: item.get("inputTokenLimit").and_then(Value::as_u64),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let next = payload
+        .get("nextPageToken")
+        .and_then(Value::as_str)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string);
+    (models, next)
+}
+
+fn parse_openai_models(payload: &Value, provider: &str) -> Vec<ModelInfo> {
+    payload
+        .get("data")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("id")?.as_str())
+                .map(|id| ModelInfo {
+                    provider: provider.to_string(),
+                    id: id.to_string(),
+                    display_name: None,
+                    input_token_limit: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse_gemini_models, parse_openai_models};
+
+    #[test]
+    fn keeps_only_generate_content_gemini_models() {
+        let payload = json!({
+            "models": [
+                {
+                    "name": "models/gemini-3-flash-preview",
+                    "displayName": "Gemini 3 Flash Preview",
+                    "inputTokenLimit": 1048576,
+                    "supportedGenerationMethods": ["generateContent", "countTokens"]
+                },
+                {
+                    "name": "models/text-embedding-004",
+                    "supportedGenerationMethods": ["embedContent"]
+                }
+            ],
+            "nextPageToken": "abc"
+        });
+        let (models, next) = parse_gemini_models(&payload);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "gemini-3-flash-preview");
+        assert_eq!(models[0].input_token_limit, Some(1048576));
+        assert_eq!(next.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn parses_openai_compatible_model_list() {
+        let payload = json!({ "data": [{ "id": "gpt-4o" }, { "id": "claude-sonnet" }] });
+        let models = parse_openai_models(&payload, "litellm");
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[1].id, "claude-sonnet");
+        assert!(models.iter().all(|m| m.provider == "litellm"));
+    }
+}
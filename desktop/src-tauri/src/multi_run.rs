@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunTag {
+    pub parent_request_id: String,
+    pub app_name: String,
+}
+
+#[derive(Default)]
+pub struct MultiRunTags {
+    runs: Mutex<HashMap<String, RunTag>>,
+}
+
+impl MultiRunTags {
+    pub fn register(&self, parent_request_id: &str, app_name: &str) -> String {
+        let request_id = child_request_id(parent_request_id, app_name);
+        self.lock().insert(
+            request_id.clone(),
+            RunTag {
+                parent_request_id: parent_request_id.to_string(),
+                app_name: app_name.to_string(),
+            },
+        );
+        request_id
+    }
+
+    pub fn tag(&self, request_id: &str) -> Option<RunTag> {
+        self.lock().get(request_id).cloned()
+    }
+
+    pub fn children(&self, parent_request_id: &str) -> Vec<String> {
+        self.lock()
+            .iter()
+            .filter(|(_, tag)| tag.parent_request_id == parent_request_id)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    pub fn release(&self, request_id: &str) {
+        self.lock().remove(request_id);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, RunTag>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub fn child_request_id(parent_request_id: &str, app_name: &str) -> String {
+    format!("{parent_request_id}:{app_name}")
+}
+
+pub fn apply_tag(payload: &mut Value, tag: &RunTag) {
+    if let Value::Object(map) = payload {
+        map.insert("appName".to_string(), Value::from(tag.app_name.clone()));
+        map.insert(
+            "parentRequestId".to_string(),
+            Value::from(tag.parent_request_id.clone()),
+        );
+    }
+}
+
+pub fn unique_app_names(app_names: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for name in app_names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        if !out.iter().any(|existing| existing == name) {
+            out.push(name.to_string());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{apply_tag, unique_app_names, MultiRunTags};
+
+    #[test]
+    fn tags_child_runs_with_their_app() {
+        let tags = MultiRunTags::default();
+        let first = tags.register("req-1", "product_validator_search");
+        let second = tags.register("req-1", "lean_validator");
+        assert_eq!(first, "req-1:product_validator_search");
+
+        let mut payload = json!({ "kind": "stream_delta", "requestId": second });
+        apply_tag(&mut payload, &tags.tag(&second).unwrap());
+        assert_eq!(payload["appName"], "lean_validator");
+        assert_eq!(payload["parentRequestId"], "req-1");
+
+        let mut children = tags.children("req-1");
+        children.sort();
+        assert_eq!(children, vec![second.clone(), first.clone()]);
+        tags.release(&first);
+        assert_eq!(tags.children("req-1"), vec![second]);
+
+        assert_eq!(
+            unique_app_names(&[" a ".to_string(), "b".to_string(), "a".to_string()]),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+}
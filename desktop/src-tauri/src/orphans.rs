@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager};
+
+use crate::resources;
+
+const MARKER_DIR: &str = "backend-pids";
+
+// One marker per spawned backend, removed on a clean stop. A marker that outlives the desktop
+// process that wrote it means the app crashed and the backend may still hold its port.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Marker {
+    pid: u32,
+    start_time: u64,
+    owner_pid: u32,
+    owner_start_time: u64,
+    base_url: String,
+}
+
+fn marker_dir() -> &'static OnceLock<PathBuf> {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    &DIR
+}
+
+pub fn init(app: &AppHandle) {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = marker_dir().set(dir.join(MARKER_DIR));
+        }
+        Err(err) => eprintln!("[orphans] backend pid markers disabled: {err}"),
+    }
+}
+
+fn marker_path(dir: &Path, pid: u32) -> PathBuf {
+    dir.join(format!("backend-{pid}.json"))
+}
+
+pub fn record(pid: u32, base_url: &str) {
+    let Some(dir) = marker_dir().get() else {
+        return;
+    };
+    let mut system = System::new();
+    let own_pid = std::process::id();
+    system.refresh_processes(
+        ProcessesToUpdate::Some(&[Pid::from_u32(pid), Pid::from_u32(own_pid)]),
+        true,
+    );
+    let start_time = |pid: u32| {
+        system
+            .process(Pid::from_u32(pid))
+            .map(|process| process.start_time())
+            .unwrap_or_default()
+    };
+    let marker = Marker {
+        pid,
+        start_time: start_time(pid),
+        owner_pid: own_pid,
+        owner_start_time: start_time(own_pid),
+        base_url: base_url.to_string(),
+    };
+    let written = fs::create_dir_all(dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_vec(&marker).map_err(|e| e.to_string()))
+        .and_then(|body| fs::write(marker_path(dir, pid), body).map_err(|e| e.to_string()));
+    if let Err(err) = written {
+        eprintln!("[orphans] failed to record backend pid {pid}: {err}");
+    }
+}
+
+pub fn forget(pid: u32) {
+    if let Some(dir) = marker_dir().get() {
+        let _ = fs::remove_file(marker_path(dir, pid));
+    }
+}
+
+// Kills backends left behind by a crashed session, once per app launch. Returns a note per
+// process tree that was stopped.
+pub fn reap_once() -> Vec<String> {
+    static REAPED: AtomicBool = AtomicBool::new(false);
+    if REAPED.swap(true, Ordering::SeqCst) {
+        return Vec::new();
+    }
+    match marker_dir().get() {
+        Some(dir) => reap(dir),
+        None => Vec::new(),
+    }
+}
+
+fn reap(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let started = |pid: u32| {
+        system
+            .process(Pid::from_u32(pid))
+            .map(|process| process.start_time())
+    };
+
+    let mut notes = Vec::new();
+    let mut orphans = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(marker) = fs::read(&path)
+            .ok()
+            .and_then(|body| serde_json::from_slice::<Marker>(&body).ok())
+        else {
+            let _ = fs::remove_file(&path);
+            continue;
+        };
+        match classify(&marker, std::process::id(), started) {
+            Verdict::Live => {}
+            Verdict::Gone => {
+                let _ = fs::remove_file(&path);
+            }
+            Verdict::Orphan => {
+                orphans.push(marker);
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+    if orphans.is_empty() {
+        return notes;
+    }
+
+    let parents: HashMap<u32, u32> = system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| Some((pid.as_u32(), process.parent()?.as_u32())))
+        .collect();
+    for marker in orphans {
+        // Children first so `uv run` cannot respawn or outlive the server it forked.
+        let tree = resources::process_tree(&parents, marker.pid);
+        let killed = tree
+            .iter()
+            .rev()
+            .filter_map(|pid| system.process(Pid::from_u32(*pid)))
+            .filter(|process| process.kill())
+            .count();
+        notes.push(format!(
+            "Stopped orphaned backend (pid {}, {} process{}) left by a previous session at {}.",
+            marker.pid,
+            killed,
+            if killed == 1 { "" } else { "es" },
+            marker.base_url
+        ));
+    }
+    notes
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    // Still owned by a running desktop app (this one or another instance).
+    Live,
+    // The backend is no longer running, or its pid now belongs to an unrelated process.
+    Gone,
+    Orphan,
+}
+
+// Start times guard against pid reuse on both the backend and the desktop app that owned it.
+fn classify(marker: &Marker, own_pid: u32, started: impl Fn(u32) -> Option<u64>) -> Verdict {
+    if started(marker.pid) != Some(marker.start_time) {
+        return Verdict::Gone;
+    }
+    let owner_alive = started(marker.owner_pid) == Some(marker.owner_start_time);
+    if marker.owner_pid == own_pid || owner_alive {
+        return Verdict::Live;
+    }
+    Verdict::Orphan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, Marker, Verdict};
+    use std::collections::HashMap;
+
+    #[test]
+    fn only_backends_of_dead_owners_are_orphans() {
+        let marker = Marker {
+            pid: 200,
+            start_time: 1_000,
+            owner_pid: 100,
+            owner_start_time: 900,
+            base_url: "http://127.0.0.1:8000".to_string(),
+        };
+        let running = |table: HashMap<u32, u64>| move |pid: u32| table.get(&pid).copied();
+
+        let owner_crashed = running(HashMap::from([(200, 1_000)]));
+        assert_eq!(classify(&marker, 1, &owner_crashed), Verdict::Orphan);
+
+        let owner_alive = running(HashMap::from([(200, 1_000), (100, 900)]));
+        assert_eq!(classify(&marker, 1, &owner_alive), Verdict::Live);
+        assert_eq!(classify(&marker, 100, &owner_crashed), Verdict::Live);
+
+        // The owner's pid was reused by something else; the backend is still an orphan.
+        let owner_reused = running(HashMap::from([(200, 1_000), (100, 5_000)]));
+        assert_eq!(classify(&marker, 1, &owner_reused), Verdict::Orphan);
+
+        let backend_reused = running(HashMap::from([(200, 7_000)]));
+        assert_eq!(classify(&marker, 1, &backend_reused), Verdict::Gone);
+        assert_eq!(classify(&marker, 1, |_| None), Verdict::Gone);
+    }
+}
@@ -0,0 +1,206 @@
+// Just enough PDF to carry a text report: the standard Helvetica fonts on US Letter pages, so
+// nothing has to be embedded. Characters outside WinAnsi are approximated or replaced.
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+// Helvetica averages roughly half an em per character; wrapping on that keeps lines inside the
+// margins without font metrics.
+const AVG_CHAR_WIDTH_EM: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfLine {
+    Heading(String),
+    Text(String),
+    Blank,
+}
+
+struct Placed {
+    bold: bool,
+    size: f32,
+    text: String,
+}
+
+pub fn render(lines: &[PdfLine]) -> Vec<u8> {
+    let placed = layout(lines);
+    let pages = paginate(&placed);
+
+    // Objects 1-4 are fixed; each page then takes a page object and its content stream.
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|i| format!("{} 0 R", 5 + i * 2))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (i, page) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            6 + i * 2
+        ));
+        let content = page_content(page);
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{content}\nendstream",
+            content.len()
+        ));
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend(format!("{} 0 obj\n{object}\nendobj\n", i + 1).into_bytes());
+    }
+    let xref_at = out.len();
+    out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        out.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    out.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_at}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+    out
+}
+
+fn layout(lines: &[PdfLine]) -> Vec<Placed> {
+    let mut placed = Vec::new();
+    for line in lines {
+        let (bold, size, text) = match line {
+            PdfLine::Heading(text) => (true, 13.0, text.as_str()),
+            PdfLine::Text(text) => (false, 10.0, text.as_str()),
+            PdfLine::Blank => (false, 10.0, ""),
+        };
+        let columns = ((PAGE_WIDTH - 2.0 * MARGIN) / (size * AVG_CHAR_WIDTH_EM)) as usize;
+        for text in wrap(text, columns) {
+            placed.push(Placed { bold, size, text });
+        }
+    }
+    placed
+}
+
+fn paginate(placed: &[Placed]) -> Vec<Vec<&Placed>> {
+    let mut pages = vec![Vec::new()];
+    let mut used = 0.0;
+    for line in placed {
+        let height = line.size * 1.4;
+        if used + height > PAGE_HEIGHT - 2.0 * MARGIN {
+            pages.push(Vec::new());
+            used = 0.0;
+        }
+        used += height;
+        if let Some(page) = pages.last_mut() {
+            page.push(line);
+        }
+    }
+    pages
+}
+
+fn page_content(lines: &[&Placed]) -> String {
+    let mut out = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in lines {
+        y -= line.size * 1.4;
+        if line.text.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "BT /{} {} Tf {MARGIN} {y:.1} Td ({}) Tj ET\n",
+            if line.bold { "F2" } else { "F1" },
+            line.size,
+            encode(&line.text)
+        ));
+    }
+    out
+}
+
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        // Words longer than a line (usually URLs) are hard-split.
+        while word.chars().count() > columns {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let split = word
+                .char_indices()
+                .nth(columns)
+                .map_or(word.len(), |(i, _)| i);
+            lines.push(word[..split].to_string());
+            word = word[split..].to_string();
+        }
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > columns {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// A PDF literal string in WinAnsi, which matches Latin-1 for the characters kept as-is.
+fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => out.push_str(&format!("\\{:03o}", c as u32)),
+            '\u{2018}' | '\u{2019}' => out.push('\''),
+            '\u{201c}' | '\u{201d}' => out.push('"'),
+            '\u{2013}' | '\u{2014}' => out.push('-'),
+            '\u{2022}' => out.push_str("\\225"),
+            '\u{2026}' => out.push_str("..."),
+            '\t' => out.push(' '),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode, render, wrap, PdfLine};
+
+    #[test]
+    fn renders_a_well_formed_document() {
+        let mut lines = vec![PdfLine::Heading("Report (draft)".to_string())];
+        lines.extend((0..120).map(|i| PdfLine::Text(format!("Line {i} café — “quoted”"))));
+        let pdf = String::from_utf8_lossy(&render(&lines)).to_string();
+
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.trim_end().ends_with("%%EOF"));
+        assert!(pdf.contains("/Count 3"));
+        assert!(pdf.contains("(Report \\(draft\\)) Tj"));
+        assert_eq!(encode("café — “x”"), "caf\\351 - \"x\"");
+    }
+
+    #[test]
+    fn wraps_on_words_and_splits_long_ones() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+}
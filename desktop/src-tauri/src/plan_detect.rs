@@ -0,0 +1,228 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::report_sections::split_sections;
+
+const PLAN_STATE_KEY: &str = "research_plan";
+const PLAN_TOOL: &str = "plan_generator";
+// Headings the root agent is instructed to present a plan under; two or more means a plan.
+const PLAN_MARKERS: &[&str] = &[
+    "how we will validate",
+    "how we will try to invalidate",
+    "what would kill this idea",
+    "how we will verify supporting evidence",
+    "how we will verify contradiction evidence",
+];
+const MIN_MARKERS: usize = 2;
+const SCHEMA_LISTS: &[(&str, &str)] = &[
+    ("selected_sources", "Sources"),
+    ("validation_keywords", "Validation keywords"),
+    ("invalidation_keywords", "Invalidation keywords"),
+    ("falsification_criteria", "Falsification criteria"),
+    ("deep_dive_hypotheses", "Deep-dive hypotheses"),
+    ("evidence_validation_rules", "Evidence validation rules"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanItem {
+    pub section: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedPlan {
+    pub source: &'static str,
+    pub product_idea: Option<String>,
+    pub items: Vec<PlanItem>,
+}
+
+// Prefers the structured `ResearchPlan` the planner writes to session state (or returns from the
+// `plan_generator` tool) over parsing the rendered text.
+pub fn from_event(event: &Value) -> Option<DetectedPlan> {
+    let actions = event.get("actions");
+    let state_plan = actions
+        .and_then(|a| a.get("stateDelta").or_else(|| a.get("state_delta")))
+        .and_then(|delta| delta.get(PLAN_STATE_KEY));
+    let tool_plan = || {
+        event
+            .get("content")?
+            .get("parts")?
+            .as_array()?
+            .iter()
+            .filter_map(|part| {
+                part.get("functionResponse")
+                    .or_else(|| part.get("function_response"))
+            })
+            .filter(|response| response.get("name").and_then(Value::as_str) == Some(PLAN_TOOL))
+            .find_map(|response| {
+                let body = response.get("response")?;
+                Some(body.get("result").unwrap_or(body).clone())
+            })
+    };
+    let raw = state_plan.cloned().or_else(tool_plan)?;
+    from_schema(&raw)
+}
+
+pub fn from_schema(raw: &Value) -> Option<DetectedPlan> {
+    let parsed;
+    let plan = match raw {
+        Value::String(text) => {
+            parsed = serde_json::from_str::<Value>(text).ok()?;
+            &parsed
+        }
+        other => other,
+    };
+    let object = plan.as_object()?;
+    if !object.contains_key("selected_sources") && !object.contains_key("search_keywords") {
+        return None;
+    }
+
+    let mut items = Vec::new();
+    for (key, section) in SCHEMA_LISTS {
+        for entry in object
+            .get(*key)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(text) = entry.as_str().map(str::trim).filter(|t| !t.is_empty()) {
+                items.push(PlanItem {
+                    section: section.to_string(),
+                    text: text.to_string(),
+                });
+            }
+        }
+    }
+    Some(DetectedPlan {
+        source: "schema",
+        product_idea: object
+            .get("product_idea")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        items,
+    })
+}
+
+pub fn from_text(text: &str) -> Option<DetectedPlan> {
+    let lowered = text.to_lowercase();
+    let markers = PLAN_MARKERS
+        .iter()
+        .filter(|marker| lowered.contains(*marker))
+        .count();
+    if markers < MIN_MARKERS {
+        return None;
+    }
+
+    let mut items = Vec::new();
+    for section in split_sections(text) {
+        for line in section.body.lines() {
+            if let Some(item) = bullet(line) {
+                items.push(PlanItem {
+                    section: section.title.clone(),
+                    text: item.to_string(),
+                });
+            }
+        }
+    }
+    // Plans are often rendered with bold labels instead of headings.
+    if items.is_empty() {
+        let mut section = String::new();
+        for line in text.lines() {
+            if let Some(label) = bold_label(line) {
+                section = label;
+            } else if let Some(item) = bullet(line) {
+                items.push(PlanItem {
+                    section: section.clone(),
+                    text: item.to_string(),
+                });
+            }
+        }
+    }
+    Some(DetectedPlan {
+        source: "markers",
+        product_idea: None,
+        items,
+    })
+}
+
+fn bullet(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let rest = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+        .or_else(|| {
+            let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+            (digits > 0)
+                .then(|| trimmed[digits..].strip_prefix(". "))
+                .flatten()
+        })?;
+    Some(rest.trim()).filter(|r| !r.is_empty())
+}
+
+fn bold_label(line: &str) -> Option<String> {
+    let text = bullet(line).unwrap_or(line.trim());
+    let label = text
+        .strip_prefix("**")?
+        .trim_end_matches(':')
+        .strip_suffix("**")?;
+    let label = label.trim_end_matches(':').trim();
+    PLAN_MARKERS
+        .contains(&label.to_lowercase().as_str())
+        .then(|| label.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{from_event, from_text};
+
+    #[test]
+    fn detects_plan_from_state_delta_and_tool_response() {
+        let plan = json!({
+            "product_idea": "Pet sitting marketplace",
+            "selected_sources": ["reddit", "competitors"],
+            "search_keywords": ["pet sitter app"],
+            "falsification_criteria": ["No willingness to pay"]
+        });
+        let state_event = json!({"actions": {"stateDelta": {"research_plan": plan}}});
+        let detected = from_event(&state_event).unwrap();
+        assert_eq!(detected.source, "schema");
+        assert_eq!(
+            detected.product_idea.as_deref(),
+            Some("Pet sitting marketplace")
+        );
+        assert_eq!(detected.items.len(), 3);
+        assert_eq!(detected.items[2].section, "Falsification criteria");
+
+        let tool_event = json!({"content": {"parts": [{"functionResponse": {
+            "name": "plan_generator",
+            "response": {"result": plan.to_string()}
+        }}]}});
+        assert_eq!(from_event(&tool_event), Some(detected));
+        assert!(
+            from_event(&json!({"actions": {"stateDelta": {"final_validation": "x"}}})).is_none()
+        );
+    }
+
+    #[test]
+    fn detects_plan_markers_in_text_but_not_reports() {
+        let plan = "Here is the plan.\n\n**How we will validate**\n- Reddit pain points\n- Competitor pricing\n\n**What would kill this idea**\n1. No one pays\n";
+        let detected = from_text(plan).unwrap();
+        assert_eq!(detected.items.len(), 3);
+        assert_eq!(detected.items[0].section, "How we will validate");
+        assert_eq!(detected.items[2].text, "No one pays");
+
+        let headed =
+            "## How we will validate\n- Reddit\n## How we will try to invalidate\n- Reviews\n";
+        assert_eq!(
+            from_text(headed).unwrap().items[1].section,
+            "How we will try to invalidate"
+        );
+
+        assert!(from_text("# Verdict\nGO. How we will validate was covered earlier.").is_none());
+    }
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::AppError;
+use crate::session_store::ReplayMessage;
+use crate::stream::{self, replay_text};
+use crate::types::PromptEstimate;
+
+const ASCII_CHARS_PER_TOKEN: u64 = 4;
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+const DEFAULT_CONTEXT_WINDOW_TOKENS: u64 = 1_048_576;
+const WARN_RATIO: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptEstimateEvent<'a> {
+    kind: &'static str,
+    request_id: &'a str,
+    #[serde(flatten)]
+    estimate: &'a PromptEstimate,
+}
+
+// Rough tokenizer-free estimate: ~4 ASCII chars per token, one token per non-ASCII char (CJK, emoji).
+pub fn estimate_tokens(text: &str) -> u64 {
+    let (ascii, other) = text.chars().fold((0u64, 0u64), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(ASCII_CHARS_PER_TOKEN) + other + MESSAGE_OVERHEAD_TOKENS
+}
+
+pub fn context_window(requested: Option<u64>, env_tokens: Option<String>) -> u64 {
+    requested
+        .or_else(|| env_tokens?.trim().parse::<u64>().ok())
+        .filter(|tokens| *tokens > 0)
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW_TOKENS)
+}
+
+pub fn estimate(text: &str, replay: &[ReplayMessage], context_window: u64) -> PromptEstimate {
+    let idea_tokens = estimate_tokens(text);
+    let replay_tokens: u64 = replay
+        .iter()
+        .map(|message| estimate_tokens(&replay_text(message)))
+        .sum();
+    let total_tokens = idea_tokens + replay_tokens;
+    let usage_ratio = total_tokens as f64 / context_window.max(1) as f64;
+    let warning = (usage_ratio >= WARN_RATIO).then(|| {
+        format!(
+            "Replayed context is about {total_tokens} tokens, {:.0}% of the {context_window}-token window. Older messages may be truncated; consider starting a new session.",
+            usage_ratio * 100.0
+        )
+    });
+
+    PromptEstimate {
+        idea_tokens,
+        replay_tokens,
+        replay_messages: replay.len() as u32,
+        total_tokens,
+        context_window,
+        usage_ratio,
+        warning,
+    }
+}
+
+pub fn emit(app: &AppHandle, request_id: &str, estimate: &PromptEstimate) -> Result<(), AppError> {
+    stream::emit(
+        app,
+        request_id,
+        PromptEstimateEvent {
+            kind: "prompt_estimate",
+            request_id,
+            estimate,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{context_window, estimate, estimate_tokens};
+    use crate::session_store::ReplayMessage;
+
+    #[test]
+    fn estimates_idea_and_replay_against_window() {
+        assert_eq!(estimate_tokens("abcdefgh"), 2 + 4);
+        assert_eq!(estimate_tokens("市場"), 2 + 4);
+        assert_eq!(context_window(None, Some("32000".to_string())), 32_000);
+        assert_eq!(
+            context_window(Some(8_000), Some("32000".to_string())),
+            8_000
+        );
+        assert_eq!(context_window(None, Some("0".to_string())), 1_048_576);
+
+        let replay = vec![ReplayMessage {
+            role: "assistant".to_string(),
+            text: "x".repeat(400),
+        }];
+        let small = estimate("pet sitting", &replay, 1_000_000);
+        assert_eq!(small.replay_messages, 1);
+        assert!(small.replay_tokens > 100);
+        assert!(small.warning.is_none());
+
+        let tight = estimate("pet sitting", &replay, 120);
+        assert!(tight.usage_ratio >= 0.8);
+        assert!(tight.warning.is_some());
+    }
+}
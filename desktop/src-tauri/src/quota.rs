@@ -0,0 +1,86 @@
+use crate::session_store::{SessionStore, StoreError, ToolUsage};
+use crate::types::ToolQuota;
+
+pub const BRAVE_TOOL: &str = "brave_search";
+const DEFAULT_BRAVE_MONTHLY_QUOTA: u64 = 2_000;
+const DEFAULT_BRAVE_CALLS_PER_RUN: u64 = 30;
+
+pub fn brave_quota(store: &SessionStore) -> Result<ToolQuota, StoreError> {
+    let usage = store.tool_usage_totals(BRAVE_TOOL)?;
+    Ok(build_quota(
+        BRAVE_TOOL,
+        &usage,
+        read_quota("PV_DESKTOP_BRAVE_MONTHLY_QUOTA").unwrap_or(DEFAULT_BRAVE_MONTHLY_QUOTA),
+        read_quota("PV_DESKTOP_BRAVE_DAILY_QUOTA"),
+    ))
+}
+
+pub fn run_warning(quota: &ToolQuota) -> Option<String> {
+    if !quota.likely_exceeded {
+        return None;
+    }
+    Some(format!(
+        "Brave Search quota is nearly used up: {} of {} monthly calls remain and a run uses about {}. Searches may fail once the quota is exhausted.",
+        quota.remaining_month, quota.monthly_quota, quota.estimated_calls_per_run
+    ))
+}
+
+fn read_quota(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|v| *v > 0)
+}
+
+fn build_quota(
+    tool: &str,
+    usage: &ToolUsage,
+    monthly_quota: u64,
+    daily_quota: Option<u64>,
+) -> ToolQuota {
+    let estimated_calls_per_run = if usage.month_runs > 0 {
+        usage.month_calls.div_ceil(usage.month_runs)
+    } else {
+        DEFAULT_BRAVE_CALLS_PER_RUN
+    };
+    let remaining_month = monthly_quota.saturating_sub(usage.month_calls);
+    let remaining_today = daily_quota.map(|quota| quota.saturating_sub(usage.day_calls));
+    let likely_exceeded = remaining_month < estimated_calls_per_run
+        || remaining_today.is_some_and(|remaining| remaining < estimated_calls_per_run);
+
+    ToolQuota {
+        tool: tool.to_string(),
+        day_calls: usage.day_calls,
+        month_calls: usage.month_calls,
+        daily_quota,
+        monthly_quota,
+        remaining_today,
+        remaining_month,
+        estimated_calls_per_run,
+        likely_exceeded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_quota, run_warning};
+    use crate::session_store::ToolUsage;
+
+    #[test]
+    fn estimates_from_history_and_flags_likely_overrun() {
+        let usage = ToolUsage {
+            day_calls: 40,
+            month_calls: 1_980,
+            month_runs: 66,
+        };
+        let quota = build_quota("brave_search", &usage, 2_000, None);
+        assert_eq!(quota.estimated_calls_per_run, 30);
+        assert_eq!(quota.remaining_month, 20);
+        assert!(quota.likely_exceeded);
+        assert!(run_warning(&quota).is_some());
+
+        let quota = build_quota("brave_search", &usage, 5_000, Some(100));
+        assert_eq!(quota.remaining_today, Some(60));
+        assert!(!quota.likely_exceeded);
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use crate::settings;
+
+pub fn privacy_mode_enabled() -> bool {
+    settings::flag("PV_DESKTOP_PRIVACY_MODE", settings::current().privacy_mode)
+}
+
+pub fn fingerprint(text: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    format!(
+        "[redacted len={} hash={:016x}]",
+        text.chars().count(),
+        hasher.finish()
+    )
+}
+
+// Every sink for free text (backend and installer output, log files, error causes, degradation
+// details) routes it through here. In privacy mode nothing but the fingerprint leaves, whatever
+// the text contains.
+pub fn scrub(text: &str) -> String {
+    scrub_with(text, privacy_mode_enabled())
+}
+
+fn scrub_with(text: &str, private: bool) -> String {
+    if private {
+        fingerprint(text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Diagnostics carrying runtime text go to stderr through here: the static context is kept, the
+// detail is scrubbed.
+pub fn log(context: &str, detail: impl Display) {
+    eprintln!("{context} {}", scrub(&detail.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fingerprint, scrub_with};
+
+    #[test]
+    fn fingerprint_exposes_only_length_and_hash() {
+        let marked = fingerprint("a delivery app for dog owners");
+        assert!(marked.starts_with("[redacted len=29 hash="));
+        assert!(!marked.contains("dog"));
+        assert_eq!(marked, fingerprint("a delivery app for dog owners"));
+    }
+
+    #[test]
+    fn privacy_mode_reduces_any_text_to_its_fingerprint() {
+        let line = "[stdout] INFO prompt=\"a delivery app for dog owners\" tokens=12";
+        assert_eq!(scrub_with(line, true), fingerprint(line));
+        assert_eq!(scrub_with(line, false), line);
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+use tokio::process::Command;
+
+use crate::error::AppError;
+use crate::events;
+use crate::installer::{self, RepoJobGuard};
+use crate::types::BackendUpdateResult;
+
+const DIRTY_PATHS_SHOWN: usize = 5;
+
+// Fast-forwards the backend checkout. Refuses to touch a tree with uncommitted changes to tracked
+// files; untracked files (reports, .venv) are left alone by git anyway.
+pub async fn update(app: &AppHandle, repo_root: &Path) -> Result<BackendUpdateResult, AppError> {
+    let _guard = RepoJobGuard::acquire()?;
+    let head_before = git_capture(repo_root, &["rev-parse", "HEAD"]).await?;
+    let porcelain = git_capture(
+        repo_root,
+        &["status", "--porcelain", "--untracked-files=no"],
+    )
+    .await?;
+    let dirty = dirty_paths(&porcelain);
+    if !dirty.is_empty() {
+        return Err(AppError::Conflict(dirty_message(&dirty)));
+    }
+
+    let mut tail = Vec::new();
+    let mut ok = true;
+    for args in [&["fetch", "--prune"][..], &["pull", "--ff-only"][..]] {
+        let argv = std::iter::once("git")
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect();
+        let step = installer::run_streamed(app, events::BACKEND_UPDATE, repo_root, argv).await?;
+        tail.extend(step.tail);
+        if !step.ok {
+            ok = false;
+            break;
+        }
+    }
+
+    let head_after = git_capture(repo_root, &["rev-parse", "HEAD"]).await?;
+    Ok(BackendUpdateResult {
+        ok,
+        updated: head_before != head_after,
+        head_before,
+        head_after,
+        restarted: false,
+        message: None,
+        tail,
+        backend: None,
+    })
+}
+
+async fn git_capture(repo_root: &Path, args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to run git: {e}")))?;
+    if !output.status.success() {
+        return Err(AppError::InvalidInput(format!(
+            "git {} failed in {}: {}",
+            args.join(" "),
+            repo_root.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn dirty_paths(porcelain: &str) -> Vec<String> {
+    porcelain
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+fn dirty_message(dirty: &[String]) -> String {
+    let shown = dirty
+        .iter()
+        .take(DIRTY_PATHS_SHOWN)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    let more = dirty.len().saturating_sub(DIRTY_PATHS_SHOWN);
+    let suffix = if more > 0 {
+        format!(" and {more} more")
+    } else {
+        String::new()
+    };
+    format!(
+        "Backend repo has local changes ({shown}{suffix}); commit or stash them before updating."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dirty_message, dirty_paths};
+
+    #[test]
+    fn lists_modified_tracked_files() {
+        let porcelain =
+            " M product_validator_search/agent.py\nM  pyproject.toml\nR  a.py -> b.py\n";
+        let dirty = dirty_paths(porcelain);
+        assert_eq!(
+            dirty,
+            vec![
+                "product_validator_search/agent.py",
+                "pyproject.toml",
+                "a.py -> b.py"
+            ]
+        );
+        assert!(dirty_paths("").is_empty());
+
+        let many: Vec<String> = (0..7).map(|i| format!("f{i}.py")).collect();
+        assert_eq!(
+            dirty_message(&many),
+            "Backend repo has local changes (f0.py, f1.py, f2.py, f3.py, f4.py and 2 more); commit or stash them before updating."
+        );
+    }
+}
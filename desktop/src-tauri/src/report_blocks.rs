@@ -0,0 +1,328 @@
+use pulldown_cmark::{BlockQuoteKind, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+use crate::types::{ReportBlock, ReportListItem};
+
+const CALLOUT_PREFIXES: &[(&str, &str)] = &[
+    ("note", "note"),
+    ("tip", "tip"),
+    ("important", "important"),
+    ("warning", "warning"),
+    ("caution", "caution"),
+    ("risk", "warning"),
+];
+
+enum Frame {
+    Root(Vec<ReportBlock>),
+    Heading(u8, String),
+    Paragraph(String),
+    List {
+        ordered: bool,
+        items: Vec<ReportListItem>,
+    },
+    Item(ReportListItem),
+    Quote {
+        kind: Option<BlockQuoteKind>,
+        children: Vec<ReportBlock>,
+    },
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+        row: Vec<String>,
+        cell: String,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+pub fn markdown_to_blocks(markdown: &str) -> Vec<ReportBlock> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_GFM;
+    let mut stack = vec![Frame::Root(Vec::new())];
+    // Open links as (destination, text so far). Blocks hold plain text, so the destination is
+    // written out after the link text; exports then link it like any bare URL.
+    let mut links: Vec<(String, String)> = Vec::new();
+
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Heading { level, .. } => {
+                    stack.push(Frame::Heading(level as u8, String::new()))
+                }
+                Tag::Paragraph => stack.push(Frame::Paragraph(String::new())),
+                Tag::List(start) => stack.push(Frame::List {
+                    ordered: start.is_some(),
+                    items: Vec::new(),
+                }),
+                Tag::Item => stack.push(Frame::Item(ReportListItem {
+                    text: String::new(),
+                    checked: None,
+                    children: Vec::new(),
+                })),
+                Tag::BlockQuote(kind) => stack.push(Frame::Quote {
+                    kind,
+                    children: Vec::new(),
+                }),
+                Tag::Table(_) => stack.push(Frame::Table {
+                    headers: Vec::new(),
+                    rows: Vec::new(),
+                    row: Vec::new(),
+                    cell: String::new(),
+                }),
+                Tag::CodeBlock(kind) => stack.push(Frame::Code {
+                    language: match kind {
+                        CodeBlockKind::Fenced(lang) if !lang.trim().is_empty() => {
+                            Some(lang.trim().to_string())
+                        }
+                        _ => None,
+                    },
+                    code: String::new(),
+                }),
+                Tag::Link { dest_url, .. } => links.push((dest_url.to_string(), String::new())),
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Heading(_)
+                | TagEnd::Paragraph
+                | TagEnd::List(_)
+                | TagEnd::Item
+                | TagEnd::BlockQuote(_)
+                | TagEnd::Table
+                | TagEnd::CodeBlock => close_frame(&mut stack),
+                TagEnd::TableCell => {
+                    if let Some(Frame::Table { row, cell, .. }) = stack.last_mut() {
+                        row.push(std::mem::take(cell).trim().to_string());
+                    }
+                }
+                TagEnd::TableHead => {
+                    if let Some(Frame::Table { headers, row, .. }) = stack.last_mut() {
+                        *headers = std::mem::take(row);
+                    }
+                }
+                TagEnd::TableRow => {
+                    if let Some(Frame::Table { rows, row, .. }) = stack.last_mut() {
+                        rows.push(std::mem::take(row));
+                    }
+                }
+                TagEnd::Link => {
+                    if let Some((dest, text)) = links.pop() {
+                        if !dest.is_empty() && !dest.starts_with('#') && text.trim() != dest {
+                            push_text(&mut stack, &format!(" ({dest})"));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, link_text)) = links.last_mut() {
+                    link_text.push_str(&text);
+                }
+                push_text(&mut stack, &text);
+            }
+            Event::SoftBreak => push_text(&mut stack, " "),
+            Event::HardBreak => push_text(&mut stack, "\n"),
+            Event::TaskListMarker(checked) => {
+                if let Some(Frame::Item(item)) = stack.last_mut() {
+                    item.checked = Some(checked);
+                }
+            }
+            Event::Rule => push_block(&mut stack, ReportBlock::Rule),
+            _ => {}
+        }
+    }
+
+    while stack.len() > 1 {
+        close_frame(&mut stack);
+    }
+    match stack.pop() {
+        Some(Frame::Root(blocks)) => blocks,
+        _ => Vec::new(),
+    }
+}
+
+fn push_text(stack: &mut [Frame], text: &str) {
+    match stack.last_mut() {
+        Some(Frame::Heading(_, out)) | Some(Frame::Paragraph(out)) => out.push_str(text),
+        Some(Frame::Item(item)) => item.text.push_str(text),
+        Some(Frame::Table { cell, .. }) => cell.push_str(text),
+        Some(Frame::Code { code, .. }) => code.push_str(text),
+        _ => {}
+    }
+}
+
+fn push_block(stack: &mut [Frame], block: ReportBlock) {
+    match stack.last_mut() {
+        Some(Frame::Root(blocks))
+        | Some(Frame::Quote {
+            children: blocks, ..
+        }) => blocks.push(block),
+        Some(Frame::Item(item)) => {
+            // Loose list items wrap their text in a paragraph; fold the first one into the item.
+            if let ReportBlock::Paragraph { text } = &block {
+                if item.text.trim().is_empty() && item.children.is_empty() {
+                    item.text = text.clone();
+                    return;
+                }
+            }
+            item.children.push(block);
+        }
+        _ => {}
+    }
+}
+
+fn close_frame(stack: &mut Vec<Frame>) {
+    let Some(frame) = stack.pop() else {
+        return;
+    };
+
+    let block = match frame {
+        Frame::Root(blocks) => {
+            stack.push(Frame::Root(blocks));
+            return;
+        }
+        Frame::Heading(level, text) => ReportBlock::Heading {
+            level,
+            text: text.trim().to_string(),
+        },
+        Frame::Paragraph(text) => ReportBlock::Paragraph {
+            text: text.trim().to_string(),
+        },
+        Frame::List { ordered, items } => ReportBlock::List { ordered, items },
+        Frame::Item(mut item) => {
+            item.text = item.text.trim().to_string();
+            if let Some(Frame::List { items, .. }) = stack.last_mut() {
+                items.push(item);
+            }
+            return;
+        }
+        Frame::Quote { kind, children } => quote_block(kind, children),
+        Frame::Table { headers, rows, .. } => ReportBlock::Table { headers, rows },
+        Frame::Code { language, code } => ReportBlock::Code { language, code },
+    };
+    push_block(stack, block);
+}
+
+fn quote_block(kind: Option<BlockQuoteKind>, mut children: Vec<ReportBlock>) -> ReportBlock {
+    if let Some(kind) = kind {
+        let tone = match kind {
+            BlockQuoteKind::Note => "note",
+            BlockQuoteKind::Tip => "tip",
+            BlockQuoteKind::Important => "important",
+            BlockQuoteKind::Warning => "warning",
+            BlockQuoteKind::Caution => "caution",
+        };
+        return ReportBlock::Callout {
+            tone: tone.to_string(),
+            title: None,
+            children,
+        };
+    }
+
+    if let Some(ReportBlock::Paragraph { text }) = children.first_mut() {
+        let lower = text.to_ascii_lowercase();
+        for (prefix, tone) in CALLOUT_PREFIXES {
+            if lower.starts_with(prefix) && text[prefix.len()..].starts_with(':') {
+                let title = text[..prefix.len()].to_string();
+                *text = text[prefix.len() + 1..].trim().to_string();
+                if text.is_empty() {
+                    children.remove(0);
+                }
+                return ReportBlock::Callout {
+                    tone: tone.to_string(),
+                    title: Some(title),
+                    children,
+                };
+            }
+        }
+    }
+
+    ReportBlock::Quote { children }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown_to_blocks;
+    use crate::types::ReportBlock;
+
+    #[test]
+    fn converts_report_markdown_into_typed_blocks() {
+        let report = "# Verdict\n\nPromising **overall**.\n\n- [x] Demand\n- [ ] Pricing\n\n| Competitor | Price |\n| --- | --- |\n| Rover | $20 |\n\n> [!WARNING]\n> Small sample.\n\n> Risk: regulation may change.\n";
+        let blocks = markdown_to_blocks(report);
+
+        assert!(matches!(&blocks[0], ReportBlock::Heading { level: 1, text } if text == "Verdict"));
+        assert!(
+            matches!(&blocks[1], ReportBlock::Paragraph { text } if text == "Promising overall.")
+        );
+        match &blocks[2] {
+            ReportBlock::List { ordered, items } => {
+                assert!(!ordered);
+                assert_eq!(items[0].text, "Demand");
+                assert_eq!(items[0].checked, Some(true));
+                assert_eq!(items[1].checked, Some(false));
+            }
+            other => panic!("expected list, got {other:?}"),
+        }
+        match &blocks[3] {
+            ReportBlock::Table { headers, rows } => {
+                assert_eq!(
+                    headers,
+                    &vec!["Competitor".to_string(), "Price".to_string()]
+                );
+                assert_eq!(rows, &vec![vec!["Rover".to_string(), "$20".to_string()]]);
+            }
+            other => panic!("expected table, got {other:?}"),
+        }
+        assert!(matches!(&blocks[4], ReportBlock::Callout { tone, .. } if tone == "warning"));
+        match &blocks[5] {
+            ReportBlock::Callout {
+                tone,
+                title,
+                children,
+            } => {
+                assert_eq!(tone, "warning");
+                assert_eq!(title.as_deref(), Some("Risk"));
+                assert!(
+                    matches!(&children[0], ReportBlock::Paragraph { text } if text == "regulation may change.")
+                );
+            }
+            other => panic!("expected callout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn keeps_link_destinations_as_citations() {
+        let blocks = markdown_to_blocks(
+            "Rover charges more ([pricing](https://rover.com/pricing)); see <https://a.io>.\n\n\
+             - [Wag](https://wag.com) is cheaper\n\n[top](#verdict)\n",
+        );
+        assert!(matches!(
+            &blocks[0],
+            ReportBlock::Paragraph { text }
+                if text == "Rover charges more (pricing (https://rover.com/pricing)); see https://a.io."
+        ));
+        match &blocks[1] {
+            ReportBlock::List { items, .. } => {
+                assert_eq!(items[0].text, "Wag (https://wag.com) is cheaper")
+            }
+            other => panic!("expected list, got {other:?}"),
+        }
+        assert!(matches!(&blocks[2], ReportBlock::Paragraph { text } if text == "top"));
+    }
+
+    #[test]
+    fn folds_loose_list_paragraphs_into_items() {
+        let blocks = markdown_to_blocks("1. First\n\n2. Second\n   - nested\n");
+        match &blocks[0] {
+            ReportBlock::List { ordered, items } => {
+                assert!(ordered);
+                assert_eq!(items[0].text, "First");
+                assert_eq!(items[1].text, "Second");
+                assert!(matches!(&items[1].children[0], ReportBlock::List { .. }));
+            }
+            other => panic!("expected list, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::debug_bundle::entry_stem;
+use crate::deep_link;
+use crate::pdf::{self, PdfLine};
+use crate::report_blocks;
+use crate::session_store::{RunTotals, SessionStore, StoreError};
+use crate::text_stats;
+use crate::transcript;
+use crate::types::{ReportBlock, ReportFormat, TextStats};
+
+const MAX_FILE_STEM_CHARS: usize = 60;
+const HTML_STYLE: &str = "body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;\
+max-width:46rem;margin:2rem auto;padding:0 1rem;line-height:1.55;color:#1d1d1f}\
+table{border-collapse:collapse}th,td{border:1px solid #ccc;padding:.3rem .6rem}\
+pre{background:#f5f5f7;padding:.8rem;overflow-x:auto}blockquote{color:#555;margin-left:0;\
+padding-left:1rem;border-left:3px solid #ddd}\
+@media print{body{max-width:none;margin:0}a{color:inherit}}";
+
+pub struct Report {
+    pub session_id: String,
+    pub title: String,
+    pub markdown: String,
+    pub citations: Vec<String>,
+    pub stats: TextStats,
+    pub runs: RunTotals,
+}
+
+// The latest completed assistant message is the report, as in the report view.
+pub fn build(store: &SessionStore, session_id: &str) -> Result<Option<Report>, StoreError> {
+    let title = store.session_title(session_id)?;
+    let Some(message) = store.latest_report(session_id)? else {
+        return Ok(None);
+    };
+    let markdown = message.text.trim().to_string();
+    let mut citations = Vec::new();
+    for url in transcript::extract_urls(&markdown) {
+        if !citations.contains(&url) {
+            citations.push(url);
+        }
+    }
+    Ok(Some(Report {
+        session_id: session_id.to_string(),
+        title,
+        stats: text_stats::measure(&markdown),
+        runs: store.run_totals(session_id)?,
+        citations,
+        markdown,
+    }))
+}
+
+pub fn render(report: &Report, format: ReportFormat) -> Vec<u8> {
+    match format {
+        ReportFormat::Markdown => compose(report, true).into_bytes(),
+        ReportFormat::Html => {
+            let mut body = String::new();
+            html_blocks(
+                &report_blocks::markdown_to_blocks(&compose(report, false)),
+                &mut body,
+            );
+            format!(
+                "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n{body}\
+                 <p><a href=\"{link}\">Open this session in Product Validator</a></p>\n\
+                 </body>\n</html>\n",
+                escape_html(report.title.trim()),
+                link = escape_html(&deep_link::session_url(&report.session_id)),
+            )
+            .into_bytes()
+        }
+        ReportFormat::Pdf => {
+            let mut lines = Vec::new();
+            pdf_lines(
+                &report_blocks::markdown_to_blocks(&compose(report, false)),
+                &mut lines,
+            );
+            pdf::render(&lines)
+        }
+    }
+}
+
+// A directory gets a file named after the session; a path without an extension gets the
+// format's one.
+pub fn destination(
+    default_dir: &Path,
+    chosen: Option<&str>,
+    report: &Report,
+    format: ReportFormat,
+) -> PathBuf {
+    let extension = format.extension();
+    let file_name = format!("{}.{extension}", file_stem(report));
+    match chosen.map(str::trim).filter(|p| !p.is_empty()) {
+        None => default_dir.join(file_name),
+        Some(raw) => {
+            let path = PathBuf::from(raw);
+            if path.is_dir() || raw.ends_with(['/', '\\']) {
+                path.join(file_name)
+            } else if path.extension().is_none() {
+                path.with_extension(extension)
+            } else {
+                path
+            }
+        }
+    }
+}
+
+pub fn write(path: &Path, body: &[u8]) -> Result<u64, String> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create export dir {dir:?}: {e}"))?;
+    }
+    fs::write(path, body).map_err(|e| format!("Failed to write report to {path:?}: {e}"))?;
+    Ok(body.len() as u64)
+}
+
+fn file_stem(report: &Report) -> String {
+    let stem: String = entry_stem(report.title.trim())
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .chars()
+        .take(MAX_FILE_STEM_CHARS)
+        .collect();
+    if stem.is_empty() {
+        format!("report-{}", entry_stem(&report.session_id))
+    } else {
+        stem
+    }
+}
+
+fn compose(report: &Report, app_link: bool) -> String {
+    let mut out = format!("# {}\n\n{}\n", report.title.trim(), report.markdown);
+    if !report.citations.is_empty() {
+        out.push_str("\n## Citations\n\n");
+        for (i, url) in report.citations.iter().enumerate() {
+            out.push_str(&format!("{}. <{url}>\n", i + 1));
+        }
+    }
+    out.push_str("\n## Usage\n\n");
+    let runs = &report.runs;
+    out.push_str(&format!(
+        "- Runs: {} ({} completed)\n",
+        runs.runs, runs.completed
+    ));
+    out.push_str(&format!(
+        "- Total run time: {:.1}s\n",
+        runs.duration_ms as f64 / 1000.0
+    ));
+    out.push_str(&format!("- Stream events: {}\n", runs.event_count));
+    out.push_str(&format!(
+        "- Report length: {} words, about {} min read\n",
+        report.stats.word_count, report.stats.reading_minutes
+    ));
+    if app_link {
+        out.push_str(&format!(
+            "\n[Open this session in Product Validator]({})\n",
+            deep_link::session_url(&report.session_id)
+        ));
+    }
+    out
+}
+
+// Built from the same blocks as the report view, so raw HTML in the model's markdown never
+// becomes live markup in the exported page.
+fn html_blocks(blocks: &[ReportBlock], out: &mut String) {
+    for block in blocks {
+        match block {
+            ReportBlock::Heading { level, text } => {
+                let level = (*level).clamp(1, 6);
+                out.push_str(&format!("<h{level}>{}</h{level}>\n", inline_html(text)));
+            }
+            ReportBlock::Paragraph { text } => {
+                out.push_str(&format!("<p>{}</p>\n", inline_html(text)));
+            }
+            ReportBlock::List { ordered, items } => {
+                let tag = if *ordered { "ol" } else { "ul" };
+                out.push_str(&format!("<{tag}>\n"));
+                for item in items {
+                    let checkbox = match item.checked {
+                        Some(true) => "&#9745; ",
+                        Some(false) => "&#9744; ",
+                        None => "",
+                    };
+                    out.push_str(&format!("<li>{checkbox}{}", inline_html(&item.text)));
+                    html_blocks(&item.children, out);
+                    out.push_str("</li>\n");
+                }
+                out.push_str(&format!("</{tag}>\n"));
+            }
+            ReportBlock::Table { headers, rows } => {
+                out.push_str("<table>\n<tr>");
+                for header in headers {
+                    out.push_str(&format!("<th>{}</th>", inline_html(header)));
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", inline_html(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+            ReportBlock::Callout {
+                title, children, ..
+            } => {
+                out.push_str("<blockquote>\n");
+                if let Some(title) = title {
+                    out.push_str(&format!("<p><strong>{}</strong></p>\n", inline_html(title)));
+                }
+                html_blocks(children, out);
+                out.push_str("</blockquote>\n");
+            }
+            ReportBlock::Quote { children } => {
+                out.push_str("<blockquote>\n");
+                html_blocks(children, out);
+                out.push_str("</blockquote>\n");
+            }
+            ReportBlock::Code { code, .. } => {
+                out.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(code)));
+            }
+            ReportBlock::Rule => out.push_str("<hr>\n"),
+        }
+    }
+}
+
+// Escapes the text and turns bare URLs into links. One pass over the URL positions, so a URL that
+// is a prefix of another one is only linked where it actually occurs.
+fn inline_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for span in transcript::url_spans(text) {
+        out.push_str(&escape_html(&text[last..span.start]));
+        let url = escape_html(&text[span.clone()]);
+        out.push_str(&format!("<a href=\"{url}\">{url}</a>"));
+        last = span.end;
+    }
+    out.push_str(&escape_html(&text[last..]));
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn pdf_lines(blocks: &[ReportBlock], out: &mut Vec<PdfLine>) {
+    for block in blocks {
+        match block {
+            ReportBlock::Heading { text, .. } => {
+                out.push(PdfLine::Blank);
+                out.push(PdfLine::Heading(text.clone()));
+            }
+            ReportBlock::Paragraph { text } => {
+                out.push(PdfLine::Text(text.clone()));
+                out.push(PdfLine::Blank);
+            }
+            ReportBlock::List { ordered, items } => {
+                for (i, item) in items.iter().enumerate() {
+                    let marker = match (item.checked, ordered) {
+                        (Some(true), _) => "[x]".to_string(),
+                        (Some(false), _) => "[ ]".to_string(),
+                        (None, true) => format!("{}.", i + 1),
+                        (None, false) => "-".to_string(),
+                    };
+                    out.push(PdfLine::Text(format!("{marker} {}", item.text)));
+                    pdf_lines(&item.children, out);
+                }
+                out.push(PdfLine::Blank);
+            }
+            ReportBlock::Table { headers, rows } => {
+                out.push(PdfLine::Text(headers.join(" | ")));
+                for row in rows {
+                    out.push(PdfLine::Text(row.join(" | ")));
+                }
+                out.push(PdfLine::Blank);
+            }
+            ReportBlock::Callout {
+                title, children, ..
+            } => {
+                if let Some(title) = title {
+                    out.push(PdfLine::Text(title.clone()));
+                }
+                pdf_lines(children, out);
+            }
+            ReportBlock::Quote { children } => pdf_lines(children, out),
+            ReportBlock::Code { code, .. } => {
+                out.extend(code.lines().map(|line| PdfLine::Text(line.to_string())));
+                out.push(PdfLine::Blank);
+            }
+            ReportBlock::Rule => out.push(PdfLine::Blank),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::session_store::RunTotals;
+    use crate::text_stats;
+    use crate::types::ReportFormat;
+
+    use super::{destination, inline_html, render, Report};
+
+    fn report() -> Report {
+        let markdown =
+            "Verdict: **promising**.\n\n- Demand <b>is</b> real, see https://example.com/a.\n";
+        Report {
+            session_id: "s1".to_string(),
+            title: "Pet food subscription".to_string(),
+            markdown: markdown.to_string(),
+            citations: vec!["https://example.com/a".to_string()],
+            stats: text_stats::measure(markdown),
+            runs: RunTotals {
+                runs: 2,
+                completed: 1,
+                duration_ms: 42_500,
+                event_count: 310,
+            },
+        }
+    }
+
+    #[test]
+    fn renders_each_format_with_citations_and_usage() {
+        let report = report();
+        let markdown = String::from_utf8(render(&report, ReportFormat::Markdown)).unwrap();
+        assert!(markdown.starts_with("# Pet food subscription\n"));
+        assert!(markdown.contains("1. <https://example.com/a>"));
+        assert!(markdown.contains("- Runs: 2 (1 completed)"));
+        assert!(markdown.contains("- Total run time: 42.5s"));
+        assert!(markdown.contains("idea-validator://session/s1"));
+
+        let html = String::from_utf8(render(&report, ReportFormat::Html)).unwrap();
+        assert!(html.contains("<title>Pet food subscription</title>"));
+        assert!(html.contains("<p>Verdict: promising.</p>"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("<a href=\"https://example.com/a\">https://example.com/a</a>"));
+
+        let pdf = render(&report, ReportFormat::Pdf);
+        assert!(pdf.starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn links_overlapping_urls_where_they_occur() {
+        assert_eq!(
+            inline_html("See https://a.com and https://a.com/x?a=1&b=2."),
+            "See <a href=\"https://a.com\">https://a.com</a> and \
+             <a href=\"https://a.com/x?a=1&amp;b=2\">https://a.com/x?a=1&amp;b=2</a>."
+        );
+        assert_eq!(
+            inline_html("https://a.com/x then https://a.com"),
+            "<a href=\"https://a.com/x\">https://a.com/x</a> then \
+             <a href=\"https://a.com\">https://a.com</a>"
+        );
+    }
+
+    #[test]
+    fn destination_names_files_after_the_session() {
+        let report = report();
+        let dir = Path::new("/exports");
+        assert_eq!(
+            destination(dir, None, &report, ReportFormat::Pdf),
+            dir.join("Pet-food-subscription.pdf")
+        );
+        assert_eq!(
+            destination(dir, Some("/tmp/out/"), &report, ReportFormat::Html),
+            Path::new("/tmp/out/").join("Pet-food-subscription.html")
+        );
+        assert_eq!(
+            destination(dir, Some("/tmp/summary"), &report, ReportFormat::Markdown),
+            Path::new("/tmp/summary.md")
+        );
+    }
+}
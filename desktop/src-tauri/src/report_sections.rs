@@ -0,0 +1,82 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportSection {
+    pub level: u8,
+    pub title: String,
+    pub body: String,
+}
+
+// Line-based so it can run on every streamed chunk; text before the first heading is not a section.
+pub fn split_sections(markdown: &str) -> Vec<ReportSection> {
+    let mut sections: Vec<ReportSection> = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if let Some((level, title)) = parse_heading(line) {
+            sections.push(ReportSection {
+                level,
+                title,
+                body: String::new(),
+            });
+            continue;
+        }
+
+        if let Some(section) = sections.last_mut() {
+            section.body.push_str(line);
+            section.body.push('\n');
+        }
+    }
+
+    for section in &mut sections {
+        section.body = section.body.trim().to_string();
+    }
+    sections
+}
+
+fn parse_heading(line: &str) -> Option<(u8, String)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let level = rest.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let after = &rest[level..];
+    if !after.is_empty() && !after.starts_with([' ', '\t']) {
+        return None;
+    }
+    let title = after.trim().trim_end_matches('#').trim_end();
+    if title.is_empty() {
+        return None;
+    }
+    Some((level as u8, title.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_sections;
+
+    #[test]
+    fn splits_on_headings_outside_code_fences() {
+        let sections = split_sections(
+            "Intro text\n# Verdict #\nGO with caveats.\n\n## Market\n```md\n# not a heading\n```\nTAM is large.\n#hashtag stays body\n",
+        );
+        assert_eq!(sections.len(), 2);
+        assert_eq!(
+            (sections[0].level, sections[0].title.as_str()),
+            (1, "Verdict")
+        );
+        assert_eq!(sections[0].body, "GO with caveats.");
+        assert_eq!(sections[1].title, "Market");
+        assert!(sections[1].body.contains("# not a heading"));
+        assert!(sections[1].body.ends_with("#hashtag stays body"));
+    }
+}
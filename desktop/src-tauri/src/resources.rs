@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::sleep;
+
+use crate::commands::AppState;
+use crate::events;
+use crate::types::BackendResources;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Samples CPU and memory of the spawned backend and everything it launched (`uv run` forks the
+// actual server), storing the result for backend_status and emitting `backend-metrics`.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut system = System::new();
+        loop {
+            sleep(SAMPLE_INTERVAL).await;
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            let Some(pid) = state.backend.lock().await.child_pid() else {
+                continue;
+            };
+            let resources = sample(&mut system, pid);
+            state
+                .backend
+                .lock()
+                .await
+                .set_resources(pid, resources.clone());
+            if let Some(resources) = resources {
+                let _ = app.emit(&events::name(events::BACKEND_METRICS), &resources);
+            }
+        }
+    });
+}
+
+fn sample(system: &mut System, root: u32) -> Option<BackendResources> {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+    let parents: HashMap<u32, u32> = system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| Some((pid.as_u32(), process.parent()?.as_u32())))
+        .collect();
+    system.process(Pid::from_u32(root))?;
+
+    let tree = process_tree(&parents, root);
+    let (cpu_percent, memory_bytes) = tree
+        .iter()
+        .filter_map(|pid| system.process(Pid::from_u32(*pid)))
+        .fold((0.0_f32, 0_u64), |(cpu, memory), process| {
+            (cpu + process.cpu_usage(), memory + process.memory())
+        });
+    Some(BackendResources {
+        pid: root,
+        cpu_percent,
+        memory_bytes,
+        process_count: tree.len() as u32,
+        sampled_at_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64,
+    })
+}
+
+// `root` plus every process whose parent chain leads back to it.
+pub fn process_tree(parents: &HashMap<u32, u32>, root: u32) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut index = 0;
+    while index < tree.len() {
+        let parent = tree[index];
+        let mut children: Vec<u32> = parents
+            .iter()
+            .filter(|(pid, ppid)| **ppid == parent && !tree.contains(pid))
+            .map(|(pid, _)| *pid)
+            .collect();
+        children.sort_unstable();
+        tree.extend(children);
+        index += 1;
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::process_tree;
+    use std::collections::HashMap;
+
+    #[test]
+    fn collects_descendants_of_the_backend_process() {
+        let parents = HashMap::from([(10, 1), (11, 10), (12, 11), (13, 10), (20, 1), (21, 20)]);
+        assert_eq!(process_tree(&parents, 10), vec![10, 11, 13, 12]);
+        assert_eq!(process_tree(&parents, 99), vec![99]);
+    }
+}
@@ -0,0 +1,65 @@
+use tauri::{AppHandle, Emitter};
+use tokio::time::{sleep, Duration};
+
+use crate::events;
+use crate::redact;
+use crate::session_store::SessionStore;
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const VERDICT_EXCERPT_CHARS: usize = 4_000;
+
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match SessionStore::from_app(&app).and_then(|store| store.run_due_revalidations()) {
+                Ok(created) => {
+                    for follow_up in created {
+                        let _ = app.emit(&events::name(events::REVALIDATION_DUE), &follow_up);
+                    }
+                }
+                Err(err) => redact::log("[revalidation] scheduler pass failed:", err),
+            }
+            sleep(SCHEDULER_INTERVAL).await;
+        }
+    });
+}
+
+pub fn follow_up_context(idea: &str, prior_verdict: Option<&str>, validated_at_ms: i64) -> String {
+    let mut out = format!(
+        "This is a scheduled re-validation of an idea first validated {}.\n\nOriginal idea:\n{}\n",
+        describe_age(validated_at_ms),
+        idea.trim()
+    );
+
+    match prior_verdict.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(verdict) => {
+            let excerpt: String = verdict.chars().take(VERDICT_EXCERPT_CHARS).collect();
+            out.push_str("\nPrior verdict:\n");
+            out.push_str(&excerpt);
+            if verdict.chars().count() > VERDICT_EXCERPT_CHARS {
+                out.push_str("...");
+            }
+            out.push('\n');
+        }
+        None => out.push_str("\nNo prior verdict was recorded.\n"),
+    }
+
+    out.push_str(
+        "\nFocus on what has changed in the market since then: new or exited competitors, shifts in demand and search interest, pricing changes, and whether the prior verdict still holds.",
+    );
+    out
+}
+
+fn describe_age(validated_at_ms: i64) -> String {
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    let days = (now_ms - validated_at_ms).max(0) / (24 * 60 * 60 * 1000);
+    match days {
+        0 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        d if d < 60 => format!("{d} days ago"),
+        d => format!("about {} months ago", d / 30),
+    }
+}
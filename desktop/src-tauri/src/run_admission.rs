@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use crate::backend::{self, BackendHandle, BackendManager};
+use crate::commands::{backend_keys, AppState};
+use crate::error::AppError;
+use crate::events;
+use crate::keyring_store::KeyEnv;
+use crate::tray;
+use crate::types::{BackendRecoveryProgress, BackendStartConfig};
+
+const RECOVERY_ATTEMPTS: u32 = 3;
+const RECOVERY_BACKOFF: Duration = Duration::from_secs(2);
+const RETRY_AFTER: Duration = Duration::from_secs(5);
+
+// Instances with a recovery in flight, so concurrent admissions share one restart loop.
+#[derive(Debug, Default)]
+pub struct BackendRecovery {
+    active: StdMutex<HashSet<String>>,
+}
+
+impl BackendRecovery {
+    fn begin(&self, instance: &str) -> bool {
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance.to_string())
+    }
+
+    fn finish(&self, instance: &str) {
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance);
+    }
+
+    fn is_active(&self, instance: &str) -> bool {
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(instance)
+    }
+}
+
+// Gates a run on backend health. A healthy backend is admitted immediately; an unhealthy one is
+// recovered in the background while the run is rejected with `backend_recovering`, so first-token
+// latency never includes a restart. A changed run environment or key profile still restarts
+// inline because the run needs the new process.
+pub async fn admit(
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+    instance: Option<&str>,
+    env: &BTreeMap<String, String>,
+    key_profile: Option<&str>,
+) -> Result<BackendHandle, AppError> {
+    let instance_name = instance
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(backend::DEFAULT_INSTANCE)
+        .to_string();
+    if state.backend_recovery.is_active(&instance_name) {
+        return Err(recovering());
+    }
+
+    let manager = state.backend_for(instance)?;
+    let mut backend = manager.lock().await;
+    let (status, _) = backend.status().await?;
+    if !status.running {
+        return Err(AppError::BackendUnavailable(
+            "Backend is not running. Start backend before streaming.".to_string(),
+        ));
+    }
+
+    let env_changed = backend.run_env() != env;
+    if env_changed && backend.is_remote() {
+        return Err(AppError::InvalidInput(
+            "Run environment overrides need a locally spawned backend; they cannot be applied to a remote one."
+                .to_string(),
+        ));
+    }
+    if env_changed && !state.stream_tokens.lock().await.is_empty() {
+        return Err(AppError::Conflict(
+            "Run environment overrides need a backend restart; wait for active runs to finish first."
+                .to_string(),
+        ));
+    }
+    let profile_changed = backend.key_profile() != key_profile;
+    if profile_changed && backend.is_remote() {
+        return Err(AppError::InvalidInput(
+            "Key profiles need a locally spawned backend; a remote backend uses its own keys."
+                .to_string(),
+        ));
+    }
+    if profile_changed && !state.stream_tokens.lock().await.is_empty() {
+        return Err(AppError::Conflict(
+            "Switching key profiles needs a backend restart; wait for active runs to finish first."
+                .to_string(),
+        ));
+    }
+
+    if env_changed || profile_changed {
+        let keys = backend_keys(app, state, key_profile)?;
+        backend.set_run_env(env.clone());
+        let restarted = backend.start(restart_config(), &keys).await?;
+        if !restarted.health {
+            return Err(AppError::BackendStartup(
+                "Backend did not come back healthy after applying the run environment or key profile."
+                    .to_string(),
+            ));
+        }
+        return Ok(backend.handle());
+    }
+    if status.health {
+        return Ok(backend.handle());
+    }
+    let current_profile = backend.key_profile().map(str::to_string);
+    drop(backend);
+
+    if state.backend_recovery.begin(&instance_name) {
+        let keys = backend_keys(app, state, current_profile.as_deref())?;
+        let recovery = state.backend_recovery.clone();
+        let app = app.clone();
+        let name = instance_name.clone();
+        tauri::async_runtime::spawn(async move {
+            recover(&app, &manager, &name, &keys).await;
+            recovery.finish(&name);
+        });
+    }
+    Err(recovering())
+}
+
+fn recovering() -> AppError {
+    AppError::BackendRecovering {
+        message: "The backend is unhealthy and is being restarted; the run was not started."
+            .to_string(),
+        retry_after_ms: Some(RETRY_AFTER.as_millis() as u64),
+    }
+}
+
+fn restart_config() -> Option<BackendStartConfig> {
+    Some(BackendStartConfig {
+        force_restart: Some(true),
+        ..BackendStartConfig::default()
+    })
+}
+
+// Re-checks health first (a slow response may have been transient), then restarts, backing off
+// between attempts. Progress streams as `backend-recovery` events.
+async fn recover(
+    app: &AppHandle,
+    manager: &Arc<Mutex<BackendManager>>,
+    instance: &str,
+    keys: &KeyEnv,
+) {
+    for attempt in 1..=RECOVERY_ATTEMPTS {
+        emit(app, instance, attempt, "checking", None);
+        {
+            let mut backend = manager.lock().await;
+            match backend.status().await {
+                Ok((status, _)) if status.health => {
+                    emit(app, instance, attempt, "recovered", None);
+                    return;
+                }
+                Ok((status, _)) if !status.running => {
+                    emit(
+                        app,
+                        instance,
+                        attempt,
+                        "failed",
+                        Some("The backend was stopped during recovery.".to_string()),
+                    );
+                    return;
+                }
+                _ => {}
+            }
+
+            emit(app, instance, attempt, "restarting", None);
+            match backend.start(restart_config(), keys).await {
+                Ok(status) if status.health => {
+                    emit(app, instance, attempt, "recovered", None);
+                    if instance == backend::DEFAULT_INSTANCE {
+                        tray::show_backend_status(app, &status);
+                        let _ = app.emit(&events::name(events::BACKEND_STATUS), &status);
+                    }
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => emit(app, instance, attempt, "restarting", Some(err.to_string())),
+            }
+        }
+        if attempt < RECOVERY_ATTEMPTS {
+            sleep(RECOVERY_BACKOFF * attempt).await;
+        }
+    }
+    emit(
+        app,
+        instance,
+        RECOVERY_ATTEMPTS,
+        "failed",
+        Some(format!(
+            "The backend did not recover after {RECOVERY_ATTEMPTS} attempts; restart it from Settings."
+        )),
+    );
+}
+
+fn emit(app: &AppHandle, instance: &str, attempt: u32, phase: &str, message: Option<String>) {
+    let _ = app.emit(
+        &events::name(events::BACKEND_RECOVERY),
+        BackendRecoveryProgress {
+            instance: instance.to_string(),
+            attempt,
+            max_attempts: RECOVERY_ATTEMPTS,
+            phase: phase.to_string(),
+            message,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recovering, BackendRecovery};
+
+    #[test]
+    fn one_recovery_per_instance_at_a_time() {
+        let recovery = BackendRecovery::default();
+        assert!(recovery.begin("default"));
+        assert!(!recovery.begin("default"));
+        assert!(recovery.begin("staging"));
+        assert!(recovery.is_active("default"));
+        recovery.finish("default");
+        assert!(!recovery.is_active("default"));
+        assert!(recovery.begin("default"));
+
+        let err = recovering();
+        assert_eq!(err.kind(), "backend_recovering");
+        assert!(err.retryable());
+        assert_eq!(
+            err.hint().as_deref(),
+            Some("The backend is recovering; retry in 5s.")
+        );
+    }
+}
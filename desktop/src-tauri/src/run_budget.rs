@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::commands::AppState;
+use crate::degradation;
+
+pub const TIMEOUT_CATEGORY: &str = "timeout";
+pub const TIMED_OUT_STATUS: &str = "timed_out";
+
+#[derive(Default)]
+pub struct RunDeadlines {
+    expired: Mutex<HashSet<String>>,
+}
+
+impl RunDeadlines {
+    pub fn mark_expired(&self, request_id: &str) {
+        self.lock().insert(request_id.to_string());
+    }
+
+    pub fn is_expired(&self, request_id: &str) -> bool {
+        self.lock().contains(request_id)
+    }
+
+    pub fn take(&self, request_id: &str) -> bool {
+        self.lock().remove(request_id)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashSet<String>> {
+        self.expired.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// A per-run override wins over PV_DESKTOP_MAX_RUN_MINUTES; zero from either source disables the budget.
+pub fn max_run_duration(
+    requested_secs: Option<u64>,
+    env_minutes: Option<String>,
+) -> Option<Duration> {
+    let secs = match requested_secs {
+        Some(secs) => secs,
+        None => env_minutes?.trim().parse::<u64>().ok()?.saturating_mul(60),
+    };
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+pub fn is_expired(app: &AppHandle, request_id: &str) -> bool {
+    app.try_state::<AppState>()
+        .is_some_and(|state| state.run_deadlines.is_expired(request_id))
+}
+
+pub async fn enforce(
+    app: AppHandle,
+    request_id: String,
+    limit: Duration,
+    cancel: CancellationToken,
+) {
+    tokio::select! {
+        _ = cancel.cancelled() => {}
+        _ = tokio::time::sleep(limit) => {
+            if let Some(state) = app.try_state::<AppState>() {
+                state.run_deadlines.mark_expired(&request_id);
+            }
+            degradation::note(
+                &app,
+                &request_id,
+                "run_timeout",
+                format!("Run stopped after its {}-minute budget", limit.as_secs().div_ceil(60)),
+            );
+            cancel.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{max_run_duration, RunDeadlines};
+
+    #[test]
+    fn resolves_budget_from_request_or_env() {
+        assert_eq!(max_run_duration(None, None), None);
+        assert_eq!(
+            max_run_duration(None, Some("20".to_string())),
+            Some(Duration::from_secs(1_200))
+        );
+        assert_eq!(
+            max_run_duration(Some(90), Some("20".to_string())),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(max_run_duration(Some(0), Some("20".to_string())), None);
+        assert_eq!(max_run_duration(None, Some("soon".to_string())), None);
+
+        let deadlines = RunDeadlines::default();
+        deadlines.mark_expired("req");
+        assert!(deadlines.is_expired("req"));
+        assert!(deadlines.take("req"));
+        assert!(!deadlines.is_expired("req"));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+const STATE_DELTA_KEY: &str = "run_config";
+const PROTECTED_ENV: &[&str] = &["GOOGLE_API_KEY", "BRAVE_SEARCH_API_KEY", "GEMINI_API_KEY"];
+const PROTECTED_PREFIX: &str = "PV_DESKTOP_";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunEnvPlan {
+    pub process_env: BTreeMap<String, String>,
+    pub run_config: Map<String, Value>,
+}
+
+// UPPER_SNAKE_CASE keys are read by the agents at import time, so they are applied to the backend
+// process (restarting it when they change); any other key is passed to the run as session state.
+pub fn plan(overrides: Option<&BTreeMap<String, String>>) -> Result<RunEnvPlan, String> {
+    let mut plan = RunEnvPlan::default();
+    for (key, value) in overrides.into_iter().flatten() {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err("Run environment override keys must not be empty".to_string());
+        }
+        if !is_env_name(key) {
+            plan.run_config
+                .insert(key.to_string(), Value::String(value.clone()));
+            continue;
+        }
+        if PROTECTED_ENV.contains(&key) || key.starts_with(PROTECTED_PREFIX) {
+            return Err(format!(
+                "{key} cannot be overridden per run; manage it in desktop settings instead"
+            ));
+        }
+        plan.process_env.insert(key.to_string(), value.clone());
+    }
+    Ok(plan)
+}
+
+// Settings-level variables for the backend process. Lowercase names are allowed here since tools
+// like proxies read `https_proxy` and friends; API keys stay in the keychain.
+pub fn backend_env(env: &BTreeMap<String, String>) -> Result<BTreeMap<String, String>, String> {
+    let mut out = BTreeMap::new();
+    for (key, value) in env {
+        let key = key.trim();
+        let valid = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("'{key}' is not a valid environment variable name"));
+        }
+        if PROTECTED_ENV.contains(&key) || key.starts_with(PROTECTED_PREFIX) {
+            return Err(format!(
+                "{key} is managed by the desktop app and cannot be set here"
+            ));
+        }
+        out.insert(key.to_string(), value.clone());
+    }
+    Ok(out)
+}
+
+pub fn state_delta(plan: &RunEnvPlan) -> Option<Value> {
+    if plan.run_config.is_empty() {
+        return None;
+    }
+    let mut delta = Map::new();
+    delta.insert(
+        STATE_DELTA_KEY.to_string(),
+        Value::Object(plan.run_config.clone()),
+    );
+    Some(Value::Object(delta))
+}
+
+fn is_env_name(key: &str) -> bool {
+    key.chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::{backend_env, plan, state_delta};
+
+    #[test]
+    fn splits_process_env_from_run_config() {
+        let overrides = BTreeMap::from([
+            ("WORKER_MODEL".to_string(), "gemini-flash-lite".to_string()),
+            ("disabled_tools".to_string(), "reddit".to_string()),
+        ]);
+        let result = plan(Some(&overrides)).unwrap();
+        assert_eq!(
+            result.process_env.get("WORKER_MODEL").map(String::as_str),
+            Some("gemini-flash-lite")
+        );
+        assert_eq!(
+            state_delta(&result),
+            Some(json!({"run_config": {"disabled_tools": "reddit"}}))
+        );
+        assert_eq!(state_delta(&plan(None).unwrap()), None);
+
+        let secret = BTreeMap::from([("GOOGLE_API_KEY".to_string(), "x".to_string())]);
+        assert!(plan(Some(&secret)).is_err());
+        let desktop = BTreeMap::from([("PV_DESKTOP_EVENT_SCHEME".to_string(), "x".to_string())]);
+        assert!(plan(Some(&desktop)).is_err());
+    }
+
+    #[test]
+    fn validates_backend_env_names() {
+        let env = BTreeMap::from([
+            (" https_proxy ".to_string(), "http://proxy:3128".to_string()),
+            (
+                "OTEL_EXPORTER_OTLP_ENDPOINT".to_string(),
+                "http://otel:4317".to_string(),
+            ),
+        ]);
+        let cleaned = backend_env(&env).unwrap();
+        assert_eq!(
+            cleaned.keys().collect::<Vec<_>>(),
+            vec!["OTEL_EXPORTER_OTLP_ENDPOINT", "https_proxy"]
+        );
+        for bad in [
+            "1PROXY",
+            "MY-VAR",
+            "",
+            "BRAVE_SEARCH_API_KEY",
+            "PV_DESKTOP_X",
+        ] {
+            let env = BTreeMap::from([(bad.to_string(), "x".to_string())]);
+            assert!(backend_env(&env).is_err(), "{bad} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::{oneshot, watch};
+
+const DEFAULT_MAX_CONCURRENT_RUNS: usize = 2;
+pub const MAX_CONCURRENT_RUNS_LIMIT: usize = 16;
+
+pub struct RunScheduler {
+    limit: AtomicUsize,
+    inner: Mutex<SchedulerInner>,
+    queue_changed: watch::Sender<u64>,
+}
+
+#[derive(Default)]
+struct SchedulerInner {
+    active: usize,
+    waiting: VecDeque<Waiter>,
+}
+
+struct Waiter {
+    request_id: String,
+    ready: oneshot::Sender<RunSlot>,
+}
+
+pub struct RunSlot {
+    scheduler: Arc<RunScheduler>,
+}
+
+impl Drop for RunSlot {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
+
+pub enum Admission {
+    Started(RunSlot),
+    Queued {
+        position: usize,
+        ready: oneshot::Receiver<RunSlot>,
+        queue_changed: watch::Receiver<u64>,
+    },
+}
+
+impl RunScheduler {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: AtomicUsize::new(limit.max(1)),
+            inner: Mutex::new(SchedulerInner::default()),
+            queue_changed: watch::channel(0).0,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(Self::configured_limit(None))
+    }
+
+    pub fn configured_limit(setting: Option<usize>) -> usize {
+        std::env::var("PV_DESKTOP_MAX_CONCURRENT_RUNS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .or(setting)
+            .map(|v| v.clamp(1, MAX_CONCURRENT_RUNS_LIMIT))
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_RUNS)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    // Raising the limit admits waiting runs right away; lowering it lets active runs finish and
+    // holds back the queue until they drop below the new limit.
+    pub fn set_limit(self: &Arc<Self>, limit: usize) {
+        self.limit.store(limit.max(1), Ordering::Relaxed);
+        let admitted = {
+            let mut inner = self.lock();
+            let mut admitted = Vec::new();
+            while inner.active < self.limit() {
+                let Some(waiter) = inner.waiting.pop_front() else {
+                    break;
+                };
+                inner.active += 1;
+                admitted.push(waiter);
+            }
+            admitted
+        };
+        if admitted.is_empty() {
+            return;
+        }
+        self.notify_queue_changed();
+        for waiter in admitted {
+            let slot = RunSlot {
+                scheduler: self.clone(),
+            };
+            if let Err(slot) = waiter.ready.send(slot) {
+                drop(slot);
+            }
+        }
+    }
+
+    pub fn admit(self: &Arc<Self>, request_id: &str) -> Admission {
+        let mut inner = self.lock();
+        if inner.active < self.limit() && inner.waiting.is_empty() {
+            inner.active += 1;
+            return Admission::Started(RunSlot {
+                scheduler: self.clone(),
+            });
+        }
+
+        let (ready_tx, ready_rx) = oneshot::channel();
+        inner.waiting.push_back(Waiter {
+            request_id: request_id.to_string(),
+            ready: ready_tx,
+        });
+        Admission::Queued {
+            position: inner.waiting.len(),
+            ready: ready_rx,
+            queue_changed: self.queue_changed.subscribe(),
+        }
+    }
+
+    pub fn position(&self, request_id: &str) -> Option<usize> {
+        self.lock()
+            .waiting
+            .iter()
+            .position(|w| w.request_id == request_id)
+            .map(|index| index + 1)
+    }
+
+    pub fn withdraw(&self, request_id: &str) {
+        let removed = {
+            let mut inner = self.lock();
+            let before = inner.waiting.len();
+            inner.waiting.retain(|w| w.request_id != request_id);
+            inner.waiting.len() != before
+        };
+        if removed {
+            self.notify_queue_changed();
+        }
+    }
+
+    fn release(self: &Arc<Self>) {
+        let next = {
+            let mut inner = self.lock();
+            let next = if inner.active > self.limit() {
+                None
+            } else {
+                inner.waiting.pop_front()
+            };
+            match next {
+                Some(waiter) => waiter,
+                None => {
+                    inner.active = inner.active.saturating_sub(1);
+                    return;
+                }
+            }
+        };
+
+        self.notify_queue_changed();
+        let slot = RunSlot {
+            scheduler: self.clone(),
+        };
+        // If the waiter already went away, dropping the returned slot hands it to the next one.
+        if let Err(slot) = next.ready.send(slot) {
+            drop(slot);
+        }
+    }
+
+    fn notify_queue_changed(&self) {
+        self.queue_changed.send_modify(|version| *version += 1);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SchedulerInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Admission, RunScheduler};
+
+    #[test]
+    fn queues_runs_beyond_limit_and_hands_off_slots_in_order() {
+        let scheduler = Arc::new(RunScheduler::new(1));
+        let Admission::Started(first) = scheduler.admit("a") else {
+            panic!("first run should start immediately");
+        };
+        let Admission::Queued {
+            position,
+            ready: mut second_ready,
+            ..
+        } = scheduler.admit("b")
+        else {
+            panic!("second run should queue");
+        };
+        assert_eq!(position, 1);
+        let Admission::Queued {
+            position,
+            ready: mut third_ready,
+            ..
+        } = scheduler.admit("c")
+        else {
+            panic!("third run should queue");
+        };
+        assert_eq!(position, 2);
+
+        drop(first);
+        let second = second_ready
+            .try_recv()
+            .expect("second run should be admitted");
+        assert!(third_ready.try_recv().is_err());
+        assert_eq!(scheduler.position("c"), Some(1));
+
+        scheduler.withdraw("c");
+        drop(second);
+        assert!(matches!(scheduler.admit("d"), Admission::Started(_)));
+    }
+
+    #[test]
+    fn changing_the_limit_admits_or_holds_back_waiting_runs() {
+        let scheduler = Arc::new(RunScheduler::new(1));
+        let Admission::Started(first) = scheduler.admit("a") else {
+            panic!("first run should start immediately");
+        };
+        let Admission::Queued {
+            ready: mut second_ready,
+            ..
+        } = scheduler.admit("b")
+        else {
+            panic!("second run should queue");
+        };
+
+        scheduler.set_limit(2);
+        let second = second_ready
+            .try_recv()
+            .expect("raising the limit should admit the waiting run");
+
+        scheduler.set_limit(1);
+        let Admission::Queued {
+            ready: mut third_ready,
+            ..
+        } = scheduler.admit("c")
+        else {
+            panic!("third run should queue");
+        };
+        drop(first);
+        assert!(third_ready.try_recv().is_err());
+        drop(second);
+        assert!(third_ready.try_recv().is_ok());
+    }
+}
@@ -1,17 +1,88 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
 use tauri::{AppHandle, Manager};
+use thiserror::Error;
 use uuid::Uuid;
 
+use crate::analytics::{AnalyticsSession, PhaseEntry};
+use crate::backend_auth;
+use crate::memory::{keywords, Finding};
+use crate::revalidation::follow_up_context;
+use crate::text_stats;
 use crate::types::{
-    RunMode, SessionCreateInput, SessionListInput, SessionMessage, SessionMessageAppendInput,
-    SessionMeta, SessionPhase, SessionPhaseState,
+    BackendAuth, InterruptedRun, MemoryEntry, ReportBlock, ReportTranslation, RevalidationFollowUp,
+    RevalidationSchedule, RunMetrics, RunMode, SessionCreateInput, SessionListInput,
+    SessionMessage, SessionMessageAppendInput, SessionMeta, SessionPhase, SessionPhaseState,
+    SessionTask, Settings, StoredReportBlocks, StreamRunInput, TextStats,
 };
 
 const DEFAULT_DB_NAME: &str = "desktop_sessions.sqlite3";
+// Store calls run on async command threads, so a contended write gives up after well under a
+// second (3 x 200ms busy waits plus 75ms backoff) and surfaces as a retryable StorageBusy.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(200);
+const WRITE_RETRY_ATTEMPTS: u32 = 3;
+const WRITE_RETRY_BASE_DELAY: Duration = Duration::from_millis(25);
+const REVALIDATION_MONTH_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+const SETTING_KEY_PREFIX: &str = "setting:";
+const BACKEND_ENV_KEY: &str = "backend_env";
+const KEY_PROFILES_KEY: &str = "key_profiles";
+const SECRET_NAMES_KEY: &str = "secret_names";
+const BACKEND_AUTH_KEY_PREFIX: &str = "backend_auth:";
+
+const FTS_PENDING_SQL: &str = "SELECT m.id, m.session_id, m.text
+     FROM messages m
+     LEFT JOIN message_index i ON i.message_id = m.id
+     WHERE i.fts_indexed_at_ms IS NULL AND trim(m.text) != ''
+     ORDER BY m.created_at_ms ASC
+     LIMIT ?1";
+const EMBEDDING_PENDING_SQL: &str = "SELECT m.id, m.session_id, m.text
+     FROM messages m
+     LEFT JOIN message_index i ON i.message_id = m.id
+     WHERE i.embedded_at_ms IS NULL AND trim(m.text) != ''
+     ORDER BY m.created_at_ms ASC
+     LIMIT ?1";
+const SUMMARY_PENDING_SQL: &str = "SELECT m.id, m.session_id, m.text
+     FROM messages m
+     LEFT JOIN session_summaries s ON s.session_id = m.session_id
+     WHERE lower(trim(m.status)) = 'done'
+       AND lower(trim(m.role)) IN ('assistant', 'model')
+       AND trim(m.text) != ''
+       AND m.created_at_ms = (
+            SELECT MAX(x.created_at_ms) FROM messages x
+            WHERE x.session_id = m.session_id
+              AND lower(trim(x.status)) = 'done'
+              AND lower(trim(x.role)) IN ('assistant', 'model')
+              AND trim(x.text) != ''
+       )
+       AND (s.source_message_id IS NULL OR s.source_message_id != m.id)
+     ORDER BY m.created_at_ms ASC
+     LIMIT ?1";
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("Local session DB stayed locked after {attempts} attempts to {action}; another write is still in progress.")]
+    Busy { action: &'static str, attempts: u32 },
+    #[error("Failed to {action}: {source}")]
+    Sqlite {
+        action: &'static str,
+        #[source]
+        source: rusqlite::Error,
+    },
+    #[error("{0}")]
+    Other(String),
+}
+
+// Failures without a more specific variant, with their message already formatted.
+impl From<String> for StoreError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ReplayMessage {
@@ -19,13 +90,51 @@ pub struct ReplayMessage {
     pub text: String,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunTotals {
+    pub runs: u64,
+    pub completed: u64,
+    pub duration_ms: u64,
+    pub event_count: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ToolUsage {
+    pub day_calls: u64,
+    pub month_calls: u64,
+    pub month_runs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingRun {
+    pub request_id: String,
+    pub adk_session_id: String,
+    pub input: StreamRunInput,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdkSessionRef {
+    pub id: String,
+    pub request_id: String,
+    pub app_name: String,
+    pub user_id: String,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IndexPending {
+    pub fts: u64,
+    pub embeddings: u64,
+    pub summaries: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionStore {
     db_path: PathBuf,
 }
 
 impl SessionStore {
-    pub fn from_app(app: &AppHandle) -> Result<Self, String> {
+    pub fn from_app(app: &AppHandle) -> Result<Self, StoreError> {
         let app_data_dir = app
             .path()
             .app_data_dir()
@@ -45,7 +154,7 @@ impl SessionStore {
         self.db_path.clone()
     }
 
-    pub fn create_session(&self, input: &SessionCreateInput) -> Result<SessionMeta, String> {
+    pub fn create_session(&self, input: &SessionCreateInput) -> Result<SessionMeta, StoreError> {
         let id = input
             .session_id
             .clone()
@@ -53,24 +162,16 @@ impl SessionStore {
         let now = now_ms();
 
         let conn = self.open_conn()?;
-        conn.execute(
-            "INSERT OR IGNORE INTO sessions (id, title, app_name, user_id, phase, read_only, created_at_ms, updated_at_ms)
-             VALUES (?1, '', ?2, ?3, ?4, 0, ?5, ?5)",
-            params![
-                id,
-                input.app_name,
-                input.user_id,
-                SessionPhase::IdeaInput.as_str(),
-                now
-            ],
-        )
-        .map_err(|e| format!("Failed to create session in local DB: {e}"))?;
+        write_with_retry("create session in local DB", || {
+            insert_session(&conn, &id, &input.app_name, &input.user_id, now)
+        })?;
 
-        self.get_session(&conn, &id)?
-            .ok_or_else(|| "Created session could not be loaded from local DB.".to_string())
+        self.get_session(&conn, &id)?.ok_or_else(|| {
+            StoreError::Other("Created session could not be loaded from local DB.".to_string())
+        })
     }
 
-    pub fn list_sessions(&self, input: &SessionListInput) -> Result<Vec<SessionMeta>, String> {
+    pub fn list_sessions(&self, input: &SessionListInput) -> Result<Vec<SessionMeta>, StoreError> {
         let conn = self.open_conn()?;
         let mut stmt = conn
             .prepare(
@@ -103,22 +204,24 @@ impl SessionStore {
         Ok(out)
     }
 
-    pub fn delete_session(&self, session_id: &str) -> Result<bool, String> {
+    pub fn delete_session(&self, session_id: &str) -> Result<bool, StoreError> {
         let conn = self.open_conn()?;
-        let deleted = conn
-            .execute("DELETE FROM sessions WHERE id = ?1", params![session_id])
-            .map_err(|e| format!("Failed to delete session '{}': {e}", session_id))?;
+        let deleted = write_with_retry("delete session", || {
+            conn.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])
+        })?;
         Ok(deleted > 0)
     }
 
-    pub fn messages_get(&self, session_id: &str) -> Result<Vec<SessionMessage>, String> {
+    pub fn messages_get(&self, session_id: &str) -> Result<Vec<SessionMessage>, StoreError> {
         let conn = self.open_conn()?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, session_id, role, text, status, created_at_ms
-                 FROM messages
-                 WHERE session_id = ?1
-                 ORDER BY created_at_ms ASC, rowid ASC",
+                "SELECT m.id, m.session_id, m.role, m.text, m.status, m.created_at_ms,
+                        s.word_count, s.char_count, s.reading_minutes
+                 FROM messages m
+                 LEFT JOIN message_stats s ON s.message_id = m.id
+                 WHERE m.session_id = ?1
+                 ORDER BY m.created_at_ms ASC, m.rowid ASC",
             )
             .map_err(|e| format!("Failed to prepare messages query: {e}"))?;
 
@@ -131,6 +234,7 @@ impl SessionStore {
                     text: row.get(3)?,
                     status: row.get(4)?,
                     created_at_ms: row.get(5)?,
+                    stats: stats_from_row(row, 6)?,
                 })
             })
             .map_err(|e| format!("Failed to query messages: {e}"))?;
@@ -145,8 +249,8 @@ impl SessionStore {
     pub fn message_append(
         &self,
         input: &SessionMessageAppendInput,
-    ) -> Result<SessionMessage, String> {
-        let conn = self.open_conn()?;
+    ) -> Result<SessionMessage, StoreError> {
+        let mut conn = self.open_conn()?;
         let session_exists: Option<String> = conn
             .query_row(
                 "SELECT id FROM sessions WHERE id = ?1",
@@ -157,51 +261,23 @@ impl SessionStore {
             .map_err(|e| format!("Failed to verify session before appending message: {e}"))?;
 
         if session_exists.is_none() {
-            return Err(format!(
+            return Err(StoreError::Other(format!(
                 "Cannot append message: session '{}' does not exist in local DB.",
                 input.session_id
-            ));
+            )));
         }
 
-        let message = SessionMessage {
-            id: format!("msg-{}", Uuid::new_v4()),
-            session_id: input.session_id.clone(),
-            role: input.role.clone(),
-            text: input.text.clone(),
-            status: input.status.clone(),
-            created_at_ms: input.created_at_ms.unwrap_or_else(now_ms),
-        };
-
-        conn.execute(
-            "INSERT INTO messages (id, session_id, role, text, status, created_at_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                message.id,
-                message.session_id,
-                message.role,
-                message.text,
-                message.status,
-                message.created_at_ms
-            ],
-        )
-        .map_err(|e| format!("Failed to append message: {e}"))?;
-
-        let title_candidate = infer_title_from_message(&message.role, &message.text);
-        let update_time = now_ms();
-
-        conn.execute(
-            "UPDATE sessions
-             SET title = CASE WHEN TRIM(title) = '' AND ?1 IS NOT NULL THEN ?1 ELSE title END,
-                 updated_at_ms = ?2
-             WHERE id = ?3",
-            params![title_candidate, update_time, input.session_id],
-        )
-        .map_err(|e| format!("Failed to update session metadata after message append: {e}"))?;
+        let message = new_message(input);
+        write_with_retry("append message", || {
+            let tx = conn.transaction()?;
+            insert_message(&tx, &message)?;
+            tx.commit()
+        })?;
 
         Ok(message)
     }
 
-    pub fn phase_get(&self, session_id: &str) -> Result<SessionPhaseState, String> {
+    pub fn phase_get(&self, session_id: &str) -> Result<SessionPhaseState, StoreError> {
         let conn = self.open_conn()?;
         let row: Option<(String, i64)> = conn
             .query_row(
@@ -213,7 +289,10 @@ impl SessionStore {
             .map_err(|e| format!("Failed to read session phase: {e}"))?;
 
         let Some((phase_raw, read_only_raw)) = row else {
-            return Err(format!("Session '{}' was not found.", session_id));
+            return Err(StoreError::Other(format!(
+                "Session '{}' was not found.",
+                session_id
+            )));
         };
 
         Ok(SessionPhaseState {
@@ -227,24 +306,32 @@ impl SessionStore {
         session_id: &str,
         phase: SessionPhase,
         read_only: bool,
-    ) -> Result<SessionPhaseState, String> {
+    ) -> Result<SessionPhaseState, StoreError> {
         let conn = self.open_conn()?;
-        let updated = conn
-            .execute(
+        let now = now_ms();
+        let updated = write_with_retry("update session phase", || {
+            let updated = conn.execute(
                 "UPDATE sessions
                  SET phase = ?1, read_only = ?2, updated_at_ms = ?3
                  WHERE id = ?4",
                 params![
                     phase.as_str(),
                     if read_only { 1 } else { 0 },
-                    now_ms(),
+                    now,
                     session_id
                 ],
-            )
-            .map_err(|e| format!("Failed to update session phase: {e}"))?;
+            )?;
+            if updated > 0 {
+                record_phase(&conn, session_id, phase, now)?;
+            }
+            Ok(updated)
+        })?;
 
         if updated == 0 {
-            return Err(format!("Session '{}' was not found.", session_id));
+            return Err(StoreError::Other(format!(
+                "Session '{}' was not found.",
+                session_id
+            )));
         }
 
         Ok(SessionPhaseState { phase, read_only })
@@ -254,21 +341,21 @@ impl SessionStore {
         &self,
         session_id: &str,
         run_mode: RunMode,
-    ) -> Result<SessionPhaseState, String> {
+    ) -> Result<SessionPhaseState, StoreError> {
         let state = self.phase_get(session_id)?;
         if state.read_only {
-            return Err(format!(
+            return Err(StoreError::Other(format!(
                 "Session is read-only in phase '{}'.",
                 state.phase.as_str()
-            ));
+            )));
         }
 
         if !is_run_mode_allowed(state.phase, run_mode) {
-            return Err(format!(
+            return Err(StoreError::Other(format!(
                 "Run mode '{}' is not allowed while phase is '{}'.",
                 run_mode_as_str(run_mode),
                 state.phase.as_str()
-            ));
+            )));
         }
 
         Ok(state)
@@ -279,7 +366,7 @@ impl SessionStore {
         session_id: &str,
         current_text: &str,
         max_messages: usize,
-    ) -> Result<Vec<ReplayMessage>, String> {
+    ) -> Result<Vec<ReplayMessage>, StoreError> {
         let mut messages: Vec<ReplayMessage> = self
             .messages_get(session_id)?
             .into_iter()
@@ -297,27 +384,1171 @@ impl SessionStore {
                     text: trimmed,
                 })
             })
-            .collect();
-
-        if let Some(last) = messages.last() {
-            if normalize_text(&last.text) == normalize_text(current_text)
-                && normalize_text(&last.role) == "user"
-            {
-                messages.pop();
-            }
+            .collect();
+
+        if let Some(last) = messages.last() {
+            if normalize_text(&last.text) == normalize_text(current_text)
+                && normalize_text(&last.role) == "user"
+            {
+                messages.pop();
+            }
+        }
+
+        if messages.len() > max_messages {
+            let start = messages.len() - max_messages;
+            messages = messages[start..].to_vec();
+        }
+
+        Ok(messages)
+    }
+
+    pub fn revalidation_schedule(
+        &self,
+        session_id: &str,
+        months: u32,
+    ) -> Result<RevalidationSchedule, StoreError> {
+        if !(1..=24).contains(&months) {
+            return Err(StoreError::Other(
+                "Re-validation interval must be between 1 and 24 months.".to_string(),
+            ));
+        }
+
+        let conn = self.open_conn()?;
+        if self.get_session(&conn, session_id)?.is_none() {
+            return Err(StoreError::Other(format!(
+                "Session '{}' was not found.",
+                session_id
+            )));
+        }
+
+        let now = now_ms();
+        let schedule = RevalidationSchedule {
+            session_id: session_id.to_string(),
+            months,
+            due_at_ms: now + i64::from(months) * REVALIDATION_MONTH_MS,
+            follow_up_session_id: None,
+            created_at_ms: now,
+        };
+
+        write_with_retry("schedule re-validation", || {
+            conn.execute(
+                "INSERT OR REPLACE INTO revalidations (session_id, months, due_at_ms, follow_up_session_id, created_at_ms)
+                 VALUES (?1, ?2, ?3, NULL, ?4)",
+                params![
+                    schedule.session_id,
+                    schedule.months,
+                    schedule.due_at_ms,
+                    schedule.created_at_ms
+                ],
+            )
+        })?;
+
+        Ok(schedule)
+    }
+
+    pub fn revalidation_get(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<RevalidationSchedule>, StoreError> {
+        let conn = self.open_conn()?;
+        conn.query_row(
+            "SELECT session_id, months, due_at_ms, follow_up_session_id, created_at_ms
+             FROM revalidations
+             WHERE session_id = ?1",
+            params![session_id],
+            map_revalidation_row,
+        )
+        .optional()
+        .map_err(|e| StoreError::Other(format!("Failed to read re-validation schedule: {e}")))
+    }
+
+    pub fn revalidation_cancel(&self, session_id: &str) -> Result<bool, StoreError> {
+        let conn = self.open_conn()?;
+        let deleted = write_with_retry("cancel re-validation", || {
+            conn.execute(
+                "DELETE FROM revalidations WHERE session_id = ?1 AND follow_up_session_id IS NULL",
+                params![session_id],
+            )
+        })?;
+        Ok(deleted > 0)
+    }
+
+    pub fn run_due_revalidations(&self) -> Result<Vec<RevalidationFollowUp>, StoreError> {
+        let due = {
+            let conn = self.open_conn()?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT session_id, months, due_at_ms, follow_up_session_id, created_at_ms
+                     FROM revalidations
+                     WHERE follow_up_session_id IS NULL AND due_at_ms <= ?1
+                     ORDER BY due_at_ms ASC",
+                )
+                .map_err(|e| format!("Failed to prepare due re-validation query: {e}"))?;
+            let rows = stmt
+                .query_map(params![now_ms()], map_revalidation_row)
+                .map_err(|e| format!("Failed to query due re-validations: {e}"))?;
+
+            let mut out = Vec::new();
+            for row in rows {
+                out.push(row.map_err(|e| format!("Failed to parse re-validation row: {e}"))?);
+            }
+            out
+        };
+
+        let mut created = Vec::with_capacity(due.len());
+        for schedule in due {
+            created.push(self.create_revalidation_follow_up(schedule)?);
+        }
+        Ok(created)
+    }
+
+    fn create_revalidation_follow_up(
+        &self,
+        mut schedule: RevalidationSchedule,
+    ) -> Result<RevalidationFollowUp, StoreError> {
+        let mut conn = self.open_conn()?;
+        let original = self
+            .get_session(&conn, &schedule.session_id)?
+            .ok_or_else(|| format!("Session '{}' was not found.", schedule.session_id))?;
+
+        let done_messages: Vec<SessionMessage> = self
+            .messages_get(&original.id)?
+            .into_iter()
+            .filter(|m| m.status.trim().eq_ignore_ascii_case("done"))
+            .collect();
+        let idea = done_messages
+            .iter()
+            .find(|m| normalize_text(&m.role) == "user")
+            .map(|m| m.text.trim().to_string())
+            .unwrap_or_else(|| original.title.clone());
+        let verdict = done_messages
+            .iter()
+            .rev()
+            .find(|m| matches!(normalize_text(&m.role).as_str(), "assistant" | "model"))
+            .map(|m| m.text.trim().to_string());
+
+        // The session, its prompt and both links are written together, so a failure part-way
+        // leaves the schedule due instead of pointing at a half-built follow-up.
+        let follow_up_id = format!("desktop-{}", Uuid::new_v4());
+        let prompt = new_message(&SessionMessageAppendInput {
+            session_id: follow_up_id.clone(),
+            role: "system".to_string(),
+            text: follow_up_context(&idea, verdict.as_deref(), original.created_at_ms),
+            status: "done".to_string(),
+            created_at_ms: None,
+        });
+        let title = format!("Re-validation: {}", original.title.trim());
+        write_with_retry("create re-validation follow-up", || {
+            let tx = conn.transaction()?;
+            insert_session(
+                &tx,
+                &follow_up_id,
+                &original.app_name,
+                &original.user_id,
+                now_ms(),
+            )?;
+            insert_message(&tx, &prompt)?;
+            tx.execute(
+                "UPDATE sessions SET title = ?1 WHERE id = ?2",
+                params![title, follow_up_id],
+            )?;
+            tx.execute(
+                "UPDATE revalidations SET follow_up_session_id = ?1 WHERE session_id = ?2",
+                params![follow_up_id, schedule.session_id],
+            )?;
+            tx.commit()
+        })?;
+        schedule.follow_up_session_id = Some(follow_up_id.clone());
+
+        let session = self
+            .get_session(&conn, &follow_up_id)?
+            .ok_or_else(|| "Follow-up session could not be loaded from local DB.".to_string())?;
+
+        Ok(RevalidationFollowUp {
+            schedule,
+            session,
+            prefill_text: idea,
+        })
+    }
+
+    pub fn backend_env_get(&self) -> Result<BTreeMap<String, String>, StoreError> {
+        match self.preference_get(BACKEND_ENV_KEY)? {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                StoreError::Other(format!("Stored backend environment is unreadable: {e}"))
+            }),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    pub fn backend_env_set(&self, env: &BTreeMap<String, String>) -> Result<(), StoreError> {
+        let raw = serde_json::to_string(env)
+            .map_err(|e| format!("Failed to serialize backend environment: {e}"))?;
+        self.preference_set(BACKEND_ENV_KEY, &raw)
+    }
+
+    // Names only; the keys themselves live in the OS keychain.
+    pub fn key_profiles_get(&self) -> Result<Vec<String>, StoreError> {
+        match self.preference_get(KEY_PROFILES_KEY)? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map_err(|e| StoreError::Other(format!("Stored key profiles are unreadable: {e}"))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn key_profiles_set(&self, names: &[String]) -> Result<(), StoreError> {
+        let raw = serde_json::to_string(names)
+            .map_err(|e| format!("Failed to serialize key profiles: {e}"))?;
+        self.preference_set(KEY_PROFILES_KEY, &raw)
+    }
+
+    // Names only, like key profiles; values live in the key store.
+    pub fn secret_names_get(&self) -> Result<Vec<String>, StoreError> {
+        match self.preference_get(SECRET_NAMES_KEY)? {
+            Some(raw) => serde_json::from_str(&raw)
+                .map_err(|e| StoreError::Other(format!("Stored secret names are unreadable: {e}"))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn secret_names_set(&self, names: &[String]) -> Result<(), StoreError> {
+        let raw = serde_json::to_string(names)
+            .map_err(|e| format!("Failed to serialize secret names: {e}"))?;
+        self.preference_set(SECRET_NAMES_KEY, &raw)
+    }
+
+    // One preference per field holding its JSON value, so adding a field needs no migration.
+    pub fn settings_get(&self) -> Result<Settings, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare("SELECT key, value FROM preferences WHERE key LIKE ?1")
+            .map_err(|e| format!("Failed to prepare settings query: {e}"))?;
+        let rows = stmt
+            .query_map(params![format!("{SETTING_KEY_PREFIX}%")], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| format!("Failed to query settings: {e}"))?;
+
+        let mut fields = serde_json::Map::new();
+        for row in rows {
+            let (key, value) = row.map_err(|e| format!("Failed to read settings row: {e}"))?;
+            let key = key.trim_start_matches(SETTING_KEY_PREFIX).to_string();
+            let value = serde_json::from_str(&value)
+                .map_err(|e| format!("Stored setting '{key}' is unreadable: {e}"))?;
+            fields.insert(key, value);
+        }
+        serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(|e| StoreError::Other(format!("Stored settings are unreadable: {e}")))
+    }
+
+    pub fn settings_set(&self, settings: &Settings) -> Result<(), StoreError> {
+        let serde_json::Value::Object(fields) = serde_json::to_value(settings)
+            .map_err(|e| format!("Failed to serialize settings: {e}"))?
+        else {
+            return Err(StoreError::Other(
+                "Settings did not serialize to an object.".to_string(),
+            ));
+        };
+        let mut conn = self.open_conn()?;
+        write_with_retry("save settings", || {
+            let tx = conn.transaction()?;
+            tx.execute(
+                "DELETE FROM preferences WHERE key LIKE ?1",
+                params![format!("{SETTING_KEY_PREFIX}%")],
+            )?;
+            for (key, value) in fields.iter().filter(|(_, v)| !v.is_null()) {
+                tx.execute(
+                    "INSERT INTO preferences (key, value) VALUES (?1, ?2)",
+                    params![format!("{SETTING_KEY_PREFIX}{key}"), value.to_string()],
+                )?;
+            }
+            tx.commit()
+        })?;
+        Ok(())
+    }
+
+    pub fn backend_auth_get(&self, base_url: &str) -> Result<Option<BackendAuth>, StoreError> {
+        let key = format!(
+            "{BACKEND_AUTH_KEY_PREFIX}{}",
+            backend_auth::normalize_base_url(base_url)
+        );
+        self.preference_get(&key)?
+            .map(|json| {
+                serde_json::from_str(&json).map_err(|e| {
+                    StoreError::Other(format!("Failed to parse backend auth settings: {e}"))
+                })
+            })
+            .transpose()
+    }
+
+    pub fn backend_auth_all(&self) -> Result<Vec<BackendAuth>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare("SELECT value FROM preferences WHERE key LIKE ?1")
+            .map_err(|e| format!("Failed to prepare backend auth query: {e}"))?;
+        let rows = stmt
+            .query_map(params![format!("{BACKEND_AUTH_KEY_PREFIX}%")], |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(|e| format!("Failed to query backend auth settings: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            let json = row.map_err(|e| format!("Failed to read backend auth row: {e}"))?;
+            out.push(
+                serde_json::from_str(&json)
+                    .map_err(|e| format!("Failed to parse backend auth settings: {e}"))?,
+            );
+        }
+        Ok(out)
+    }
+
+    pub fn backend_auth_set(&self, auth: &BackendAuth) -> Result<(), StoreError> {
+        let key = format!(
+            "{BACKEND_AUTH_KEY_PREFIX}{}",
+            backend_auth::normalize_base_url(&auth.base_url)
+        );
+        let empty = auth
+            .bearer_token
+            .as_deref()
+            .is_none_or(|t| t.trim().is_empty())
+            && auth.headers.is_empty();
+        if empty {
+            let conn = self.open_conn()?;
+            write_with_retry("clear backend auth", || {
+                conn.execute("DELETE FROM preferences WHERE key = ?1", params![key])
+            })?;
+            return Ok(());
+        }
+
+        let json = serde_json::to_string(auth)
+            .map_err(|e| format!("Failed to serialize backend auth settings: {e}"))?;
+        self.preference_set(&key, &json)
+    }
+
+    pub fn memory_list(
+        &self,
+        app_name: &str,
+        user_id: &str,
+    ) -> Result<Vec<MemoryEntry>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, source_session_id, kind, text, keywords, created_at_ms
+                 FROM memory_entries
+                 WHERE app_name = ?1 AND user_id = ?2
+                 ORDER BY created_at_ms DESC, rowid DESC",
+            )
+            .map_err(|e| format!("Failed to prepare memory query: {e}"))?;
+
+        let rows = stmt
+            .query_map(params![app_name, user_id], |row| {
+                Ok(MemoryEntry {
+                    id: row.get(0)?,
+                    source_session_id: row.get(1)?,
+                    kind: row.get(2)?,
+                    text: row.get(3)?,
+                    keywords: row.get(4)?,
+                    created_at_ms: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query memory entries: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(|e| format!("Failed to parse memory row: {e}"))?);
+        }
+        Ok(out)
+    }
+
+    pub fn memory_save_findings(
+        &self,
+        session_id: &str,
+        idea: &str,
+        findings: &[Finding],
+    ) -> Result<usize, StoreError> {
+        let conn = self.open_conn()?;
+        let session = self
+            .get_session(&conn, session_id)?
+            .ok_or_else(|| format!("Session '{}' was not found.", session_id))?;
+        let now = now_ms();
+
+        write_with_retry("clear previous memory for session", || {
+            conn.execute(
+                "DELETE FROM memory_entries WHERE source_session_id = ?1",
+                params![session_id],
+            )
+        })?;
+
+        for finding in findings {
+            let entry_keywords = keywords(&format!("{idea} {}", finding.text)).join(" ");
+            write_with_retry("save memory entry", || {
+                conn.execute(
+                    "INSERT INTO memory_entries (id, source_session_id, app_name, user_id, kind, text, keywords, created_at_ms)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        format!("mem-{}", Uuid::new_v4()),
+                        session_id,
+                        session.app_name,
+                        session.user_id,
+                        finding.kind,
+                        finding.text,
+                        entry_keywords,
+                        now
+                    ],
+                )
+            })?;
+        }
+
+        Ok(findings.len())
+    }
+
+    pub fn memory_delete(&self, entry_id: &str) -> Result<bool, StoreError> {
+        let conn = self.open_conn()?;
+        let deleted = write_with_retry("delete memory entry", || {
+            conn.execute(
+                "DELETE FROM memory_entries WHERE id = ?1",
+                params![entry_id],
+            )
+        })?;
+        Ok(deleted > 0)
+    }
+
+    pub fn memory_clear(&self, app_name: &str, user_id: &str) -> Result<usize, StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("clear memory entries", || {
+            conn.execute(
+                "DELETE FROM memory_entries WHERE app_name = ?1 AND user_id = ?2",
+                params![app_name, user_id],
+            )
+        })
+    }
+
+    pub fn first_user_text(&self, session_id: &str) -> Result<Option<String>, StoreError> {
+        Ok(self
+            .messages_get(session_id)?
+            .into_iter()
+            .find(|m| normalize_text(&m.role) == "user" && !m.text.trim().is_empty())
+            .map(|m| m.text.trim().to_string()))
+    }
+
+    pub fn latest_report(&self, session_id: &str) -> Result<Option<SessionMessage>, StoreError> {
+        Ok(self.messages_get(session_id)?.into_iter().rev().find(|m| {
+            m.status.trim().eq_ignore_ascii_case("done")
+                && matches!(normalize_text(&m.role).as_str(), "assistant" | "model")
+                && !m.text.trim().is_empty()
+        }))
+    }
+
+    pub fn translation_save(
+        &self,
+        report: &SessionMessage,
+        lang: &str,
+        model: &str,
+        text: &str,
+    ) -> Result<ReportTranslation, StoreError> {
+        let conn = self.open_conn()?;
+        let translation = ReportTranslation {
+            session_id: report.session_id.clone(),
+            source_message_id: report.id.clone(),
+            lang: lang.to_string(),
+            model: model.to_string(),
+            text: text.to_string(),
+            created_at_ms: now_ms(),
+        };
+
+        write_with_retry("save report translation", || {
+            conn.execute(
+                "INSERT INTO report_translations (session_id, lang, source_message_id, model, text, created_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(session_id, lang) DO UPDATE SET
+                    source_message_id = excluded.source_message_id,
+                    model = excluded.model,
+                    text = excluded.text,
+                    created_at_ms = excluded.created_at_ms",
+                params![
+                    translation.session_id,
+                    translation.lang,
+                    translation.source_message_id,
+                    translation.model,
+                    translation.text,
+                    translation.created_at_ms
+                ],
+            )
+        })?;
+        Ok(translation)
+    }
+
+    pub fn translations_get(&self, session_id: &str) -> Result<Vec<ReportTranslation>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT session_id, source_message_id, lang, model, text, created_at_ms
+                 FROM report_translations
+                 WHERE session_id = ?1
+                 ORDER BY lang ASC",
+            )
+            .map_err(|e| format!("Failed to prepare translations query: {e}"))?;
+
+        let rows = stmt
+            .query_map(params![session_id], |row| {
+                Ok(ReportTranslation {
+                    session_id: row.get(0)?,
+                    source_message_id: row.get(1)?,
+                    lang: row.get(2)?,
+                    model: row.get(3)?,
+                    text: row.get(4)?,
+                    created_at_ms: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query translations: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(|e| format!("Failed to parse translation row: {e}"))?);
+        }
+        Ok(out)
+    }
+
+    pub fn tool_usage_record(&self, tool: &str, calls: u32) -> Result<(), StoreError> {
+        if calls == 0 {
+            return Ok(());
+        }
+        let conn = self.open_conn()?;
+        write_with_retry("record tool usage", || {
+            conn.execute(
+                "INSERT INTO tool_usage (day, tool, calls, runs)
+                 VALUES (strftime('%Y-%m-%d', ?1 / 1000, 'unixepoch'), ?2, ?3, 1)
+                 ON CONFLICT(day, tool) DO UPDATE SET
+                    calls = calls + excluded.calls,
+                    runs = runs + 1",
+                params![now_ms(), tool, calls],
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn tool_usage_totals(&self, tool: &str) -> Result<ToolUsage, StoreError> {
+        let conn = self.open_conn()?;
+        conn.query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN day = strftime('%Y-%m-%d', ?1 / 1000, 'unixepoch') THEN calls ELSE 0 END), 0),
+                COALESCE(SUM(calls), 0),
+                COALESCE(SUM(runs), 0)
+             FROM tool_usage
+             WHERE tool = ?2 AND substr(day, 1, 7) = strftime('%Y-%m', ?1 / 1000, 'unixepoch')",
+            params![now_ms(), tool],
+            |row| {
+                Ok(ToolUsage {
+                    day_calls: row.get::<_, i64>(0)?.max(0) as u64,
+                    month_calls: row.get::<_, i64>(1)?.max(0) as u64,
+                    month_runs: row.get::<_, i64>(2)?.max(0) as u64,
+                })
+            },
+        )
+        .map_err(|e| StoreError::Other(format!("Failed to query tool usage: {e}")))
+    }
+
+    pub fn report_blocks_save(
+        &self,
+        session_id: &str,
+        report: &str,
+        blocks: &[ReportBlock],
+    ) -> Result<TextStats, StoreError> {
+        let conn = self.open_conn()?;
+        let json = serde_json::to_string(blocks)
+            .map_err(|e| format!("Failed to serialize report blocks: {e}"))?;
+        let stats = text_stats::measure(report);
+        write_with_retry("save report blocks", || {
+            conn.execute(
+                "INSERT INTO report_blocks (session_id, blocks_json, word_count, char_count, reading_minutes, created_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(session_id) DO UPDATE SET
+                    blocks_json = excluded.blocks_json,
+                    word_count = excluded.word_count,
+                    char_count = excluded.char_count,
+                    reading_minutes = excluded.reading_minutes,
+                    created_at_ms = excluded.created_at_ms",
+                params![
+                    session_id,
+                    json,
+                    stats.word_count,
+                    stats.char_count,
+                    stats.reading_minutes,
+                    now_ms()
+                ],
+            )
+        })?;
+        Ok(stats)
+    }
+
+    pub fn report_blocks_get(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<StoredReportBlocks>, StoreError> {
+        let conn = self.open_conn()?;
+        let row = conn
+            .query_row(
+                "SELECT blocks_json, created_at_ms, word_count, char_count, reading_minutes
+                 FROM report_blocks
+                 WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        stats_from_row(row, 2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| format!("Failed to query report blocks: {e}"))?;
+
+        let Some((json, created_at_ms, stats)) = row else {
+            return Ok(None);
+        };
+        let blocks = serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse stored report blocks: {e}"))?;
+        Ok(Some(StoredReportBlocks {
+            session_id: session_id.to_string(),
+            blocks,
+            stats,
+            created_at_ms,
+        }))
+    }
+
+    pub fn tasks_replace(
+        &self,
+        session_id: &str,
+        titles: &[String],
+    ) -> Result<Vec<SessionTask>, StoreError> {
+        let conn = self.open_conn()?;
+        let now = now_ms();
+        let tasks: Vec<SessionTask> = titles
+            .iter()
+            .enumerate()
+            .map(|(position, title)| SessionTask {
+                id: format!("task-{}", Uuid::new_v4()),
+                session_id: session_id.to_string(),
+                position: position as i64,
+                title: title.clone(),
+                done: false,
+                created_at_ms: now,
+            })
+            .collect();
+
+        write_with_retry("replace session tasks", || {
+            conn.execute(
+                "DELETE FROM session_tasks WHERE session_id = ?1",
+                params![session_id],
+            )?;
+            for task in &tasks {
+                conn.execute(
+                    "INSERT INTO session_tasks (id, session_id, position, title, done, created_at_ms)
+                     VALUES (?1, ?2, ?3, ?4, 0, ?5)",
+                    params![
+                        task.id,
+                        task.session_id,
+                        task.position,
+                        task.title,
+                        task.created_at_ms
+                    ],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(tasks)
+    }
+
+    pub fn tasks_get(&self, session_id: &str) -> Result<Vec<SessionTask>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, session_id, position, title, done, created_at_ms
+                 FROM session_tasks
+                 WHERE session_id = ?1
+                 ORDER BY position ASC",
+            )
+            .map_err(|e| format!("Failed to prepare tasks query: {e}"))?;
+
+        let rows = stmt
+            .query_map(params![session_id], |row| {
+                Ok(SessionTask {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    position: row.get(2)?,
+                    title: row.get(3)?,
+                    done: row.get::<_, i64>(4)? != 0,
+                    created_at_ms: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query tasks: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(|e| format!("Failed to parse task row: {e}"))?);
+        }
+        Ok(out)
+    }
+
+    pub fn task_set_done(&self, task_id: &str, done: bool) -> Result<bool, StoreError> {
+        let conn = self.open_conn()?;
+        let updated = write_with_retry("update task", || {
+            conn.execute(
+                "UPDATE session_tasks SET done = ?1 WHERE id = ?2",
+                params![if done { 1 } else { 0 }, task_id],
+            )
+        })?;
+        Ok(updated > 0)
+    }
+
+    pub fn run_record(
+        &self,
+        request_id: &str,
+        session_id: &str,
+        run_mode: RunMode,
+        outcome: &str,
+        metrics: Option<&RunMetrics>,
+    ) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        let num = |value: Option<u64>| value.map(|v| v as i64);
+        write_with_retry("record run metrics", || {
+            conn.execute(
+                "INSERT OR REPLACE INTO runs
+                   (request_id, session_id, run_mode, outcome, ttfb_ms, first_text_ms, duration_ms,
+                    bytes_received, event_count, finished_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    request_id,
+                    session_id,
+                    run_mode.as_str(),
+                    outcome,
+                    num(metrics.and_then(|m| m.time_to_first_byte_ms)),
+                    num(metrics.and_then(|m| m.time_to_first_text_ms)),
+                    num(metrics.map(|m| m.duration_ms)),
+                    num(metrics.map(|m| m.bytes_received)),
+                    num(metrics.map(|m| m.event_count)),
+                    now_ms()
+                ],
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn run_totals(&self, session_id: &str) -> Result<RunTotals, StoreError> {
+        let conn = self.open_conn()?;
+        conn.query_row(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(outcome = 'completed'), 0),
+                COALESCE(SUM(duration_ms), 0),
+                COALESCE(SUM(event_count), 0)
+             FROM runs
+             WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok(RunTotals {
+                    runs: row.get::<_, i64>(0)?.max(0) as u64,
+                    completed: row.get::<_, i64>(1)?.max(0) as u64,
+                    duration_ms: row.get::<_, i64>(2)?.max(0) as u64,
+                    event_count: row.get::<_, i64>(3)?.max(0) as u64,
+                })
+            },
+        )
+        .map_err(|e| StoreError::Other(format!("Failed to query run totals: {e}")))
+    }
+
+    // Remembers the approve run a session is waiting on so it can be re-issued after a restart.
+    pub fn pending_run_begin(
+        &self,
+        session_id: &str,
+        adk_session_id: &str,
+        input: &StreamRunInput,
+    ) -> Result<(), StoreError> {
+        let input_json = serde_json::to_string(input)
+            .map_err(|e| format!("Failed to serialize run input: {e}"))?;
+        let conn = self.open_conn()?;
+        write_with_retry("record pending run", || {
+            conn.execute(
+                "INSERT OR REPLACE INTO pending_runs
+                   (session_id, request_id, adk_session_id, input_json, started_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    session_id,
+                    input.request_id,
+                    adk_session_id,
+                    input_json,
+                    now_ms()
+                ],
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn pending_run_end(&self, session_id: &str, request_id: &str) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("clear pending run", || {
+            conn.execute(
+                "DELETE FROM pending_runs WHERE session_id = ?1 AND request_id = ?2",
+                params![session_id, request_id],
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn pending_run_get(&self, session_id: &str) -> Result<Option<PendingRun>, StoreError> {
+        let conn = self.open_conn()?;
+        let row = conn
+            .query_row(
+                "SELECT request_id, adk_session_id, input_json FROM pending_runs
+                 WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| format!("Failed to load pending run: {e}"))?;
+        let Some((request_id, adk_session_id, input_json)) = row else {
+            return Ok(None);
+        };
+        let input = serde_json::from_str(&input_json)
+            .map_err(|e| format!("Stored run input is unreadable: {e}"))?;
+        Ok(Some(PendingRun {
+            request_id,
+            adk_session_id,
+            input,
+        }))
+    }
+
+    // Sessions still marked running; only meaningful before any run has started in this process.
+    pub fn interrupted_runs(&self) -> Result<Vec<InterruptedRun>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.id, s.title, p.request_id, p.started_at_ms
+                 FROM sessions s
+                 LEFT JOIN pending_runs p ON p.session_id = s.id
+                 WHERE s.phase = 'running'
+                 ORDER BY s.updated_at_ms DESC",
+            )
+            .map_err(|e| format!("Failed to prepare interrupted run query: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let request_id: Option<String> = row.get(2)?;
+                Ok(InterruptedRun {
+                    session_id: row.get(0)?,
+                    title: row.get(1)?,
+                    resumable: request_id.is_some(),
+                    request_id,
+                    started_at_ms: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query interrupted runs: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(|e| format!("Failed to parse interrupted run row: {e}"))?);
+        }
+        Ok(out)
+    }
+
+    pub fn adk_session_record(
+        &self,
+        id: &str,
+        request_id: &str,
+        app_name: &str,
+        user_id: &str,
+    ) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("record ADK session", || {
+            conn.execute(
+                "INSERT OR IGNORE INTO adk_sessions (id, request_id, app_name, user_id, created_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, request_id, app_name, user_id, now_ms()],
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn adk_sessions_finish(&self, request_id: &str) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("finish ADK sessions", || {
+            conn.execute(
+                "UPDATE adk_sessions SET finished_at_ms = ?2
+                 WHERE request_id = ?1 AND finished_at_ms IS NULL",
+                params![request_id, now_ms()],
+            )
+        })?;
+        Ok(())
+    }
+
+    // Finished sessions plus any created before `created_before_ms`, oldest first.
+    pub fn adk_sessions_due(
+        &self,
+        created_before_ms: i64,
+    ) -> Result<Vec<AdkSessionRef>, StoreError> {
+        let conn = self.open_conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, request_id, app_name, user_id, finished_at_ms IS NOT NULL
+                 FROM adk_sessions
+                 WHERE finished_at_ms IS NOT NULL OR created_at_ms < ?1
+                 ORDER BY created_at_ms ASC",
+            )
+            .map_err(|e| format!("Failed to prepare ADK session query: {e}"))?;
+        let rows = stmt
+            .query_map(params![created_before_ms], |row| {
+                Ok(AdkSessionRef {
+                    id: row.get(0)?,
+                    request_id: row.get(1)?,
+                    app_name: row.get(2)?,
+                    user_id: row.get(3)?,
+                    finished: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query ADK sessions: {e}"))?;
+
+        let mut out = Vec::new();
+        for row in rows {
+            out.push(row.map_err(|e| format!("Failed to parse ADK session row: {e}"))?);
+        }
+        Ok(out)
+    }
+
+    pub fn adk_session_forget(&self, id: &str) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("forget ADK session", || {
+            conn.execute("DELETE FROM adk_sessions WHERE id = ?1", params![id])
+        })?;
+        Ok(())
+    }
+
+    pub fn adk_sessions_tracked(&self) -> Result<u64, StoreError> {
+        let conn = self.open_conn()?;
+        conn.query_row("SELECT COUNT(*) FROM adk_sessions", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|n| n.max(0) as u64)
+        .map_err(|e| StoreError::Other(format!("Failed to count ADK sessions: {e}")))
+    }
+
+    pub fn index_pending(&self) -> Result<IndexPending, StoreError> {
+        let conn = self.open_conn()?;
+        let count = |sql: &str| -> Result<u64, StoreError> {
+            conn.query_row(
+                &format!("SELECT COUNT(*) FROM ({sql})"),
+                params![i64::MAX],
+                |row| row.get::<_, i64>(0),
+            )
+            .map(|n| n.max(0) as u64)
+            .map_err(|e| StoreError::Other(format!("Failed to count pending index work: {e}")))
+        };
+        Ok(IndexPending {
+            fts: count(FTS_PENDING_SQL)?,
+            embeddings: count(EMBEDDING_PENDING_SQL)?,
+            summaries: count(SUMMARY_PENDING_SQL)?,
+        })
+    }
+
+    pub fn fts_index_batch(&self, limit: usize) -> Result<usize, StoreError> {
+        let conn = self.open_conn()?;
+        let pending = pending_rows(&conn, FTS_PENDING_SQL, limit, "full-text index")?;
+        let now = now_ms();
+        write_with_retry("update full-text index", || {
+            conn.execute(
+                "DELETE FROM message_fts WHERE message_id NOT IN (SELECT id FROM messages)",
+                [],
+            )?;
+            for (message_id, session_id, text) in &pending {
+                conn.execute(
+                    "DELETE FROM message_fts WHERE message_id = ?1",
+                    params![message_id],
+                )?;
+                conn.execute(
+                    "INSERT INTO message_fts (text, message_id, session_id) VALUES (?1, ?2, ?3)",
+                    params![text, message_id, session_id],
+                )?;
+                conn.execute(
+                    "INSERT INTO message_index (message_id, fts_indexed_at_ms)
+                     VALUES (?1, ?2)
+                     ON CONFLICT(message_id) DO UPDATE SET fts_indexed_at_ms = excluded.fts_indexed_at_ms",
+                    params![message_id, now],
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(pending.len())
+    }
+
+    pub fn embedding_batch(
+        &self,
+        limit: usize,
+        embed: impl Fn(&str) -> Vec<u8>,
+    ) -> Result<usize, StoreError> {
+        let conn = self.open_conn()?;
+        let pending = pending_rows(&conn, EMBEDDING_PENDING_SQL, limit, "embeddings")?;
+        let vectors: Vec<(String, Vec<u8>)> = pending
+            .into_iter()
+            .map(|(message_id, _, text)| (message_id, embed(&text)))
+            .collect();
+        let now = now_ms();
+        write_with_retry("save message embeddings", || {
+            for (message_id, vector) in &vectors {
+                conn.execute(
+                    "INSERT INTO message_index (message_id, embedding, embedded_at_ms)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(message_id) DO UPDATE SET
+                        embedding = excluded.embedding,
+                        embedded_at_ms = excluded.embedded_at_ms",
+                    params![message_id, vector, now],
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(vectors.len())
+    }
+
+    pub fn summary_batch(
+        &self,
+        limit: usize,
+        summarize: impl Fn(&str) -> String,
+    ) -> Result<usize, StoreError> {
+        let conn = self.open_conn()?;
+        let pending = pending_rows(&conn, SUMMARY_PENDING_SQL, limit, "session summaries")?;
+        let summaries: Vec<(String, String, String)> = pending
+            .into_iter()
+            .map(|(message_id, session_id, text)| (session_id, message_id, summarize(&text)))
+            .collect();
+        let now = now_ms();
+        write_with_retry("save session summaries", || {
+            for (session_id, message_id, summary) in &summaries {
+                conn.execute(
+                    "INSERT INTO session_summaries (session_id, source_message_id, summary, created_at_ms)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(session_id) DO UPDATE SET
+                        source_message_id = excluded.source_message_id,
+                        summary = excluded.summary,
+                        created_at_ms = excluded.created_at_ms",
+                    params![session_id, message_id, summary, now],
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(summaries.len())
+    }
+
+    // Sessions created before phase history existed get their creation and current phase backfilled.
+    pub fn analytics_rows(&self) -> Result<(Vec<AnalyticsSession>, Vec<PhaseEntry>), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("backfill phase history", || {
+            conn.execute(
+                "INSERT INTO phase_history (session_id, phase, entered_at_ms)
+                 SELECT s.id, ?1, s.created_at_ms FROM sessions s
+                 WHERE NOT EXISTS (SELECT 1 FROM phase_history h WHERE h.session_id = s.id)
+                 UNION ALL
+                 SELECT s.id, s.phase, s.updated_at_ms FROM sessions s
+                 WHERE s.phase != ?1
+                   AND NOT EXISTS (SELECT 1 FROM phase_history h WHERE h.session_id = s.id)",
+                params![SessionPhase::IdeaInput.as_str()],
+            )
+        })?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT
+                    s.id,
+                    strftime('%Y-%m', s.created_at_ms / 1000, 'unixepoch'),
+                    EXISTS(SELECT 1 FROM messages m WHERE m.session_id = s.id AND lower(trim(m.role)) = 'user'),
+                    EXISTS(SELECT 1 FROM session_tasks t WHERE t.session_id = s.id AND t.done = 1)
+                 FROM sessions s",
+            )
+            .map_err(|e| format!("Failed to prepare analytics sessions query: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(AnalyticsSession {
+                    id: row.get(0)?,
+                    created_month: row.get(1)?,
+                    has_idea: row.get(2)?,
+                    pursued: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query analytics sessions: {e}"))?;
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(row.map_err(|e| format!("Failed to parse analytics session row: {e}"))?);
         }
 
-        if messages.len() > max_messages {
-            let start = messages.len() - max_messages;
-            messages = messages[start..].to_vec();
+        let mut stmt = conn
+            .prepare(
+                "SELECT session_id, phase, entered_at_ms, strftime('%Y-%m', entered_at_ms / 1000, 'unixepoch')
+                 FROM phase_history
+                 ORDER BY session_id ASC, entered_at_ms ASC, rowid ASC",
+            )
+            .map_err(|e| format!("Failed to prepare phase history query: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|e| format!("Failed to query phase history: {e}"))?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (session_id, phase, entered_at_ms, month) =
+                row.map_err(|e| format!("Failed to parse phase history row: {e}"))?;
+            entries.push(PhaseEntry {
+                session_id,
+                phase: parse_phase(&phase)?,
+                entered_at_ms,
+                month,
+            });
         }
+        Ok((sessions, entries))
+    }
 
-        Ok(messages)
+    pub fn session_title(&self, session_id: &str) -> Result<String, StoreError> {
+        let conn = self.open_conn()?;
+        self.get_session(&conn, session_id)?
+            .map(|session| session.title)
+            .ok_or_else(|| StoreError::Other(format!("Session '{}' was not found.", session_id)))
+    }
+
+    fn preference_get(&self, key: &str) -> Result<Option<String>, StoreError> {
+        let conn = self.open_conn()?;
+        conn.query_row(
+            "SELECT value FROM preferences WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| StoreError::Other(format!("Failed to read preference '{}': {e}", key)))
+    }
+
+    fn preference_set(&self, key: &str, value: &str) -> Result<(), StoreError> {
+        let conn = self.open_conn()?;
+        write_with_retry("save preference", || {
+            conn.execute(
+                "INSERT INTO preferences (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )
+        })?;
+        Ok(())
     }
 
-    fn open_conn(&self) -> Result<Connection, String> {
+    fn open_conn(&self) -> Result<Connection, StoreError> {
         let conn = Connection::open(&self.db_path)
             .map_err(|e| format!("Failed to open local session DB {:?}: {e}", self.db_path))?;
+        conn.busy_timeout(BUSY_TIMEOUT)
+            .map_err(|e| format!("Failed to set busy timeout on local session DB: {e}"))?;
         conn.execute("PRAGMA foreign_keys = ON", [])
             .map_err(|e| format!("Failed to enable foreign keys on local session DB: {e}"))?;
         conn.pragma_update(None, "journal_mode", "WAL")
@@ -326,7 +1557,7 @@ impl SessionStore {
         Ok(conn)
     }
 
-    fn init_schema(&self, conn: &Connection) -> Result<(), String> {
+    fn init_schema(&self, conn: &Connection) -> Result<(), StoreError> {
         conn.execute_batch(
             "
             CREATE TABLE IF NOT EXISTS sessions (
@@ -355,6 +1586,150 @@ impl SessionStore {
 
             CREATE INDEX IF NOT EXISTS idx_messages_session_created
                 ON messages(session_id, created_at_ms ASC);
+
+            CREATE TABLE IF NOT EXISTS phase_history (
+                session_id TEXT NOT NULL,
+                phase TEXT NOT NULL,
+                entered_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_phase_history_session
+                ON phase_history(session_id, entered_at_ms ASC);
+
+            CREATE TABLE IF NOT EXISTS message_stats (
+                message_id TEXT PRIMARY KEY,
+                word_count INTEGER NOT NULL,
+                char_count INTEGER NOT NULL,
+                reading_minutes INTEGER NOT NULL,
+                FOREIGN KEY(message_id) REFERENCES messages(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS revalidations (
+                session_id TEXT PRIMARY KEY,
+                months INTEGER NOT NULL,
+                due_at_ms INTEGER NOT NULL,
+                follow_up_session_id TEXT,
+                created_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_revalidations_due
+                ON revalidations(due_at_ms ASC);
+
+            CREATE TABLE IF NOT EXISTS session_tasks (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                title TEXT NOT NULL,
+                done INTEGER NOT NULL DEFAULT 0,
+                created_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_session_tasks_session
+                ON session_tasks(session_id, position ASC);
+
+            CREATE TABLE IF NOT EXISTS report_translations (
+                session_id TEXT NOT NULL,
+                lang TEXT NOT NULL,
+                source_message_id TEXT NOT NULL,
+                model TEXT NOT NULL,
+                text TEXT NOT NULL,
+                created_at_ms INTEGER NOT NULL,
+                PRIMARY KEY(session_id, lang),
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS tool_usage (
+                day TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                calls INTEGER NOT NULL,
+                runs INTEGER NOT NULL,
+                PRIMARY KEY(day, tool)
+            );
+
+            CREATE TABLE IF NOT EXISTS report_blocks (
+                session_id TEXT PRIMARY KEY,
+                blocks_json TEXT NOT NULL,
+                word_count INTEGER,
+                char_count INTEGER,
+                reading_minutes INTEGER,
+                created_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS preferences (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS memory_entries (
+                id TEXT PRIMARY KEY,
+                source_session_id TEXT,
+                app_name TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                text TEXT NOT NULL,
+                keywords TEXT NOT NULL,
+                created_at_ms INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memory_owner
+                ON memory_entries(app_name, user_id, created_at_ms DESC);
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS message_fts USING fts5(
+                text,
+                message_id UNINDEXED,
+                session_id UNINDEXED
+            );
+
+            CREATE TABLE IF NOT EXISTS message_index (
+                message_id TEXT PRIMARY KEY,
+                fts_indexed_at_ms INTEGER,
+                embedding BLOB,
+                embedded_at_ms INTEGER,
+                FOREIGN KEY(message_id) REFERENCES messages(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS session_summaries (
+                session_id TEXT PRIMARY KEY,
+                source_message_id TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                created_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS runs (
+                request_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                run_mode TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                ttfb_ms INTEGER,
+                first_text_ms INTEGER,
+                duration_ms INTEGER,
+                bytes_received INTEGER,
+                event_count INTEGER,
+                finished_at_ms INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS adk_sessions (
+                id TEXT PRIMARY KEY,
+                request_id TEXT NOT NULL,
+                app_name TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                created_at_ms INTEGER NOT NULL,
+                finished_at_ms INTEGER
+            );
+
+            CREATE TABLE IF NOT EXISTS pending_runs (
+                session_id TEXT PRIMARY KEY,
+                request_id TEXT NOT NULL,
+                adk_session_id TEXT NOT NULL,
+                input_json TEXT NOT NULL,
+                started_at_ms INTEGER NOT NULL,
+                FOREIGN KEY(session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
             ",
         )
         .map_err(|e| format!("Failed to initialize local session DB schema: {e}"))?;
@@ -365,7 +1740,7 @@ impl SessionStore {
         &self,
         conn: &Connection,
         session_id: &str,
-    ) -> Result<Option<SessionMeta>, String> {
+    ) -> Result<Option<SessionMeta>, StoreError> {
         conn.query_row(
             "SELECT id, title, app_name, user_id, phase, read_only, created_at_ms, updated_at_ms
              FROM sessions
@@ -385,10 +1760,172 @@ impl SessionStore {
             },
         )
         .optional()
-        .map_err(|e| format!("Failed to load session '{}': {e}", session_id))
+        .map_err(|e| StoreError::Other(format!("Failed to load session '{}': {e}", session_id)))
+    }
+}
+
+// Repeated writes of the same phase (e.g. read-only toggles) do not start a new history entry.
+fn insert_session(
+    conn: &Connection,
+    id: &str,
+    app_name: &str,
+    user_id: &str,
+    now: i64,
+) -> rusqlite::Result<()> {
+    let inserted = conn.execute(
+        "INSERT OR IGNORE INTO sessions (id, title, app_name, user_id, phase, read_only, created_at_ms, updated_at_ms)
+         VALUES (?1, '', ?2, ?3, ?4, 0, ?5, ?5)",
+        params![
+            id,
+            app_name,
+            user_id,
+            SessionPhase::IdeaInput.as_str(),
+            now
+        ],
+    )?;
+    if inserted > 0 {
+        record_phase(conn, id, SessionPhase::IdeaInput, now)?;
+    }
+    Ok(())
+}
+
+fn new_message(input: &SessionMessageAppendInput) -> SessionMessage {
+    SessionMessage {
+        id: format!("msg-{}", Uuid::new_v4()),
+        session_id: input.session_id.clone(),
+        role: input.role.clone(),
+        text: input.text.clone(),
+        status: input.status.clone(),
+        created_at_ms: input.created_at_ms.unwrap_or_else(now_ms),
+        stats: text_stats::applies_to_role(&input.role).then(|| text_stats::measure(&input.text)),
+    }
+}
+
+// Writes the message with its stats and bumps the session's title and update time.
+fn insert_message(conn: &Connection, message: &SessionMessage) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO messages (id, session_id, role, text, status, created_at_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            message.id,
+            message.session_id,
+            message.role,
+            message.text,
+            message.status,
+            message.created_at_ms
+        ],
+    )?;
+    if let Some(stats) = &message.stats {
+        conn.execute(
+            "INSERT OR REPLACE INTO message_stats (message_id, word_count, char_count, reading_minutes)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                message.id,
+                stats.word_count,
+                stats.char_count,
+                stats.reading_minutes
+            ],
+        )?;
+    }
+    conn.execute(
+        "UPDATE sessions
+         SET title = CASE WHEN TRIM(title) = '' AND ?1 IS NOT NULL THEN ?1 ELSE title END,
+             updated_at_ms = ?2
+         WHERE id = ?3",
+        params![
+            infer_title_from_message(&message.role, &message.text),
+            now_ms(),
+            message.session_id
+        ],
+    )?;
+    Ok(())
+}
+
+fn record_phase(
+    conn: &Connection,
+    session_id: &str,
+    phase: SessionPhase,
+    at_ms: i64,
+) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO phase_history (session_id, phase, entered_at_ms)
+         SELECT ?1, ?2, ?3
+         WHERE COALESCE((
+            SELECT phase FROM phase_history
+            WHERE session_id = ?1
+            ORDER BY entered_at_ms DESC, rowid DESC
+            LIMIT 1
+         ), '') != ?2",
+        params![session_id, phase.as_str(), at_ms],
+    )
+}
+
+fn pending_rows(
+    conn: &Connection,
+    sql: &str,
+    limit: usize,
+    label: &str,
+) -> Result<Vec<(String, String, String)>, StoreError> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("Failed to prepare pending {label} query: {e}"))?;
+    let rows = stmt
+        .query_map(params![limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| format!("Failed to query pending {label}: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| format!("Failed to parse pending {label} row: {e}"))?);
+    }
+    Ok(out)
+}
+
+fn stats_from_row(row: &rusqlite::Row<'_>, start: usize) -> rusqlite::Result<Option<TextStats>> {
+    let word_count: Option<u32> = row.get(start)?;
+    let char_count: Option<u32> = row.get(start + 1)?;
+    let reading_minutes: Option<u32> = row.get(start + 2)?;
+    Ok(match (word_count, char_count, reading_minutes) {
+        (Some(word_count), Some(char_count), Some(reading_minutes)) => Some(TextStats {
+            word_count,
+            char_count,
+            reading_minutes,
+        }),
+        _ => None,
+    })
+}
+
+fn write_with_retry<T>(
+    action: &'static str,
+    mut op: impl FnMut() -> rusqlite::Result<T>,
+) -> Result<T, StoreError> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if is_busy_error(&err) => {
+                if attempt >= WRITE_RETRY_ATTEMPTS {
+                    return Err(StoreError::Busy {
+                        action,
+                        attempts: attempt,
+                    });
+                }
+                thread::sleep(WRITE_RETRY_BASE_DELAY * 2u32.pow(attempt - 1));
+                attempt += 1;
+            }
+            Err(source) => return Err(StoreError::Sqlite { action, source }),
+        }
     }
 }
 
+fn is_busy_error(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
 pub fn is_run_mode_allowed(phase: SessionPhase, run_mode: RunMode) -> bool {
     matches!(
         (phase, run_mode),
@@ -398,14 +1935,20 @@ pub fn is_run_mode_allowed(phase: SessionPhase, run_mode: RunMode) -> bool {
     )
 }
 
-pub fn phase_after_run(run_mode: RunMode, succeeded: bool) -> (SessionPhase, bool) {
-    if succeeded {
-        match run_mode {
-            RunMode::Idea | RunMode::EditPlan => (SessionPhase::AwaitingApproval, false),
-            RunMode::Approve => (SessionPhase::Completed, true),
-        }
-    } else {
-        (SessionPhase::Failed, true)
+// An idea run only awaits approval once the agent actually produced a plan; a clarifying question
+// leaves the session in idea input. A plan edit that returns no new plan keeps the previous one.
+pub fn phase_after_run(
+    run_mode: RunMode,
+    succeeded: bool,
+    plan_detected: bool,
+) -> (SessionPhase, bool) {
+    if !succeeded {
+        return (SessionPhase::Failed, true);
+    }
+    match run_mode {
+        RunMode::Idea if !plan_detected => (SessionPhase::IdeaInput, false),
+        RunMode::Idea | RunMode::EditPlan => (SessionPhase::AwaitingApproval, false),
+        RunMode::Approve => (SessionPhase::Completed, true),
     }
 }
 
@@ -439,14 +1982,27 @@ fn map_session_row(
     })
 }
 
-fn parse_phase(raw: &str) -> Result<SessionPhase, String> {
+fn map_revalidation_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RevalidationSchedule> {
+    Ok(RevalidationSchedule {
+        session_id: row.get(0)?,
+        months: row.get(1)?,
+        due_at_ms: row.get(2)?,
+        follow_up_session_id: row.get(3)?,
+        created_at_ms: row.get(4)?,
+    })
+}
+
+fn parse_phase(raw: &str) -> Result<SessionPhase, StoreError> {
     match raw.trim().to_ascii_lowercase().as_str() {
         "idea_input" => Ok(SessionPhase::IdeaInput),
         "awaiting_approval" => Ok(SessionPhase::AwaitingApproval),
         "running" => Ok(SessionPhase::Running),
         "completed" => Ok(SessionPhase::Completed),
         "failed" => Ok(SessionPhase::Failed),
-        other => Err(format!("Unknown session phase '{}'.", other)),
+        other => Err(StoreError::Other(format!(
+            "Unknown session phase '{}'.",
+            other
+        ))),
     }
 }
 
@@ -500,9 +2056,13 @@ mod tests {
 
     use crate::types::{
         RunMode, SessionCreateInput, SessionListInput, SessionMessageAppendInput, SessionPhase,
+        Settings, StreamRunInput,
     };
 
-    use super::{is_run_mode_allowed, phase_after_run, SessionStore};
+    use super::{
+        is_run_mode_allowed, phase_after_run, write_with_retry, SessionStore, StoreError,
+        WRITE_RETRY_ATTEMPTS,
+    };
 
     fn test_db_path(name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
@@ -513,6 +2073,31 @@ mod tests {
         path
     }
 
+    #[test]
+    fn settings_round_trip_and_clear_unset_fields() {
+        let store = SessionStore::from_path(test_db_path("settings"));
+        assert_eq!(
+            store.settings_get().expect("empty settings"),
+            Settings::default()
+        );
+
+        let settings = Settings {
+            backend_port: Some(8123),
+            replay_depth: Some(40),
+            privacy_mode: Some(true),
+            ..Settings::default()
+        };
+        store.settings_set(&settings).expect("save settings");
+        assert_eq!(store.settings_get().expect("load settings"), settings);
+
+        let cleared = Settings {
+            replay_depth: Some(10),
+            ..Settings::default()
+        };
+        store.settings_set(&cleared).expect("save settings");
+        assert_eq!(store.settings_get().expect("load settings"), cleared);
+    }
+
     #[test]
     fn session_crud_and_message_ordering() {
         let store = SessionStore::from_path(test_db_path("crud"));
@@ -580,19 +2165,23 @@ mod tests {
     #[test]
     fn phase_transition_defaults_follow_plan() {
         assert_eq!(
-            phase_after_run(RunMode::Idea, true),
+            phase_after_run(RunMode::Idea, true, true),
             (SessionPhase::AwaitingApproval, false)
         );
         assert_eq!(
-            phase_after_run(RunMode::EditPlan, true),
+            phase_after_run(RunMode::Idea, true, false),
+            (SessionPhase::IdeaInput, false)
+        );
+        assert_eq!(
+            phase_after_run(RunMode::EditPlan, true, false),
             (SessionPhase::AwaitingApproval, false)
         );
         assert_eq!(
-            phase_after_run(RunMode::Approve, true),
+            phase_after_run(RunMode::Approve, true, false),
             (SessionPhase::Completed, true)
         );
         assert_eq!(
-            phase_after_run(RunMode::Approve, false),
+            phase_after_run(RunMode::Approve, false, false),
             (SessionPhase::Failed, true)
         );
     }
@@ -655,4 +2244,259 @@ mod tests {
         let messages = store.messages_get(&session.id).expect("messages read");
         assert!(messages.is_empty());
     }
+
+    #[test]
+    fn write_retry_recovers_from_transient_lock_and_types_persistent_contention() {
+        let busy = || {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            )
+        };
+
+        let mut calls = 0;
+        let recovered = write_with_retry("append message", || {
+            calls += 1;
+            if calls < 3 {
+                Err(busy())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(recovered.expect("third attempt should succeed"), 3);
+
+        let persistent = write_with_retry::<usize>("append message", || Err(busy()));
+        assert!(matches!(
+            persistent,
+            Err(StoreError::Busy { attempts, .. }) if attempts == WRITE_RETRY_ATTEMPTS
+        ));
+    }
+
+    #[test]
+    fn due_revalidation_creates_linked_follow_up_with_prior_context() {
+        let store = SessionStore::from_path(test_db_path("revalidate"));
+        let session = store
+            .create_session(&SessionCreateInput {
+                app_name: "product_validator_search".to_string(),
+                user_id: "u1".to_string(),
+                session_id: None,
+            })
+            .expect("session create");
+        for (role, text) in [
+            ("user", "pet sitting marketplace"),
+            ("assistant", "Verdict: GO"),
+        ] {
+            store
+                .message_append(&SessionMessageAppendInput {
+                    session_id: session.id.clone(),
+                    role: role.to_string(),
+                    text: text.to_string(),
+                    status: "done".to_string(),
+                    created_at_ms: None,
+                })
+                .expect("append");
+        }
+
+        store
+            .revalidation_schedule(&session.id, 3)
+            .expect("schedule");
+        assert!(store.run_due_revalidations().expect("not due").is_empty());
+
+        let conn = store.open_conn().expect("conn");
+        conn.execute("UPDATE revalidations SET due_at_ms = 0", [])
+            .expect("force due");
+
+        let created = store.run_due_revalidations().expect("due pass");
+        assert_eq!(created.len(), 1);
+        let follow_up = &created[0];
+        assert_eq!(follow_up.prefill_text, "pet sitting marketplace");
+        assert_eq!(
+            follow_up.schedule.follow_up_session_id.as_deref(),
+            Some(follow_up.session.id.as_str())
+        );
+        assert!(follow_up.session.title.starts_with("Re-validation: "));
+
+        let seeded = store.messages_get(&follow_up.session.id).expect("messages");
+        assert_eq!(seeded.len(), 1);
+        assert_eq!(seeded[0].role, "system");
+        assert!(seeded[0].text.contains("Verdict: GO"));
+        assert!(store
+            .run_due_revalidations()
+            .expect("second pass")
+            .is_empty());
+    }
+
+    #[test]
+    fn index_batches_cover_pending_messages_once() {
+        let store = SessionStore::from_path(test_db_path("index"));
+        let session = store
+            .create_session(&SessionCreateInput {
+                app_name: "product_validator_search".to_string(),
+                user_id: "u1".to_string(),
+                session_id: None,
+            })
+            .expect("session create");
+        for (role, text, at) in [
+            ("user", "pet sitting marketplace", 10),
+            ("assistant", "Verdict: GO", 20),
+        ] {
+            store
+                .message_append(&SessionMessageAppendInput {
+                    session_id: session.id.clone(),
+                    role: role.to_string(),
+                    text: text.to_string(),
+                    status: "done".to_string(),
+                    created_at_ms: Some(at),
+                })
+                .expect("append");
+        }
+
+        let pending = store.index_pending().expect("pending");
+        assert_eq!(
+            (pending.fts, pending.embeddings, pending.summaries),
+            (2, 2, 1)
+        );
+        assert_eq!(store.fts_index_batch(10).expect("fts"), 2);
+        assert_eq!(
+            store.embedding_batch(10, |_| vec![0u8; 4]).expect("embed"),
+            2
+        );
+        assert_eq!(
+            store
+                .summary_batch(10, |text| text.to_uppercase())
+                .expect("summaries"),
+            1
+        );
+
+        let pending = store.index_pending().expect("pending after");
+        assert_eq!(
+            (pending.fts, pending.embeddings, pending.summaries),
+            (0, 0, 0)
+        );
+        let conn = store.open_conn().expect("conn");
+        let hit: String = conn
+            .query_row(
+                "SELECT message_id FROM message_fts WHERE message_fts MATCH 'marketplace'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("fts match");
+        assert_eq!(
+            store.messages_get(&session.id).expect("messages")[0].id,
+            hit
+        );
+    }
+
+    #[test]
+    fn phase_history_records_transitions_once() {
+        let store = SessionStore::from_path(test_db_path("phase-history"));
+        let session = store
+            .create_session(&SessionCreateInput {
+                app_name: "product_validator_search".to_string(),
+                user_id: "u1".to_string(),
+                session_id: None,
+            })
+            .expect("session create");
+        for (phase, read_only) in [
+            (SessionPhase::Running, true),
+            (SessionPhase::Running, false),
+            (SessionPhase::Completed, true),
+        ] {
+            store
+                .phase_set(&session.id, phase, read_only)
+                .expect("phase set");
+        }
+
+        let (sessions, entries) = store.analytics_rows().expect("analytics rows");
+        assert_eq!(sessions.len(), 1);
+        assert!(!sessions[0].has_idea);
+        let phases: Vec<SessionPhase> = entries.iter().map(|e| e.phase).collect();
+        assert_eq!(
+            phases,
+            vec![
+                SessionPhase::IdeaInput,
+                SessionPhase::Running,
+                SessionPhase::Completed
+            ]
+        );
+    }
+
+    #[test]
+    fn adk_sessions_become_due_when_finished_or_expired() {
+        let store = SessionStore::from_path(test_db_path("adk-gc"));
+        store
+            .adk_session_record("adk-1", "r1", "app", "u1")
+            .unwrap();
+        store
+            .adk_session_record("adk-2", "r2", "app", "u1")
+            .unwrap();
+        store
+            .adk_session_record("adk-1", "r9", "app", "u1")
+            .unwrap();
+
+        assert!(store.adk_sessions_due(0).unwrap().is_empty());
+        store.adk_sessions_finish("r1").unwrap();
+        let due = store.adk_sessions_due(0).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!((due[0].id.as_str(), due[0].finished), ("adk-1", true));
+
+        let expired = store.adk_sessions_due(i64::MAX).unwrap();
+        assert_eq!(expired.len(), 2);
+        assert!(!expired[1].finished);
+
+        store.adk_session_forget("adk-1").unwrap();
+        assert_eq!(store.adk_sessions_tracked().unwrap(), 1);
+    }
+
+    #[test]
+    fn running_sessions_surface_as_interrupted_runs() {
+        let store = SessionStore::from_path(test_db_path("pending-runs"));
+        let create = || {
+            store
+                .create_session(&SessionCreateInput {
+                    app_name: "app".to_string(),
+                    user_id: "u1".to_string(),
+                    session_id: None,
+                })
+                .expect("session")
+        };
+        let (with_input, without_input, idle) = (create(), create(), create());
+        for session in [&with_input, &without_input] {
+            store
+                .phase_set(&session.id, SessionPhase::Running, true)
+                .unwrap();
+        }
+        let input: StreamRunInput = serde_json::from_value(serde_json::json!({
+            "requestId": "r1",
+            "appName": "app",
+            "userId": "u1",
+            "sessionId": with_input.id,
+            "text": "Approve",
+            "runMode": "approve"
+        }))
+        .unwrap();
+        store
+            .pending_run_begin(&with_input.id, "adk-1", &input)
+            .unwrap();
+
+        let runs = store.interrupted_runs().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert!(runs.iter().all(|run| run.session_id != idle.id));
+        let resumable = runs.iter().find(|run| run.resumable).expect("resumable");
+        assert_eq!(
+            (
+                resumable.session_id.as_str(),
+                resumable.request_id.as_deref()
+            ),
+            (with_input.id.as_str(), Some("r1"))
+        );
+
+        let pending = store.pending_run_get(&with_input.id).unwrap().unwrap();
+        assert_eq!(pending.adk_session_id, "adk-1");
+        assert_eq!(pending.input.text, "Approve");
+        store.pending_run_end(&with_input.id, "other").unwrap();
+        assert!(store.pending_run_get(&with_input.id).unwrap().is_some());
+        store.pending_run_end(&with_input.id, "r1").unwrap();
+        assert!(store.pending_run_get(&with_input.id).unwrap().is_none());
+    }
 }
@@ -0,0 +1,201 @@
+use std::sync::{OnceLock, RwLock};
+
+use tauri::{AppHandle, Manager};
+
+use crate::backend::{STARTUP_TIMEOUT_ENV, SUPERVISE_ENV, UNIX_SOCKET_ENV};
+use crate::commands::AppState;
+use crate::debug_events::DEBUG_EVENTS_ENV;
+use crate::scheduler::{RunScheduler, MAX_CONCURRENT_RUNS_LIMIT};
+use crate::session_store::SessionStore;
+use crate::shortcuts;
+use crate::types::{BackendStartConfig, Settings};
+
+const MAX_REPLAY_DEPTH: usize = 200;
+
+fn cell() -> &'static RwLock<Settings> {
+    static SETTINGS: OnceLock<RwLock<Settings>> = OnceLock::new();
+    SETTINGS.get_or_init(|| RwLock::new(Settings::default()))
+}
+
+pub fn init(app: &AppHandle) {
+    match SessionStore::from_app(app).and_then(|store| store.settings_get()) {
+        Ok(settings) => apply(app, settings),
+        Err(err) => eprintln!("[settings] using defaults: {err}"),
+    }
+}
+
+pub fn current() -> Settings {
+    cell().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// Most settings are read through current() where they are used; only state built once at
+// startup has to be pushed here.
+pub fn apply(app: &AppHandle, settings: Settings) {
+    let state = app.state::<AppState>();
+    state
+        .run_scheduler
+        .set_limit(RunScheduler::configured_limit(settings.max_concurrent_runs));
+    state
+        .debug_events
+        .set_global(flag(DEBUG_EVENTS_ENV, settings.debug_events));
+    if let Err(err) = shortcuts::apply(app, settings.new_idea_shortcut.as_deref()) {
+        eprintln!("[settings] {err}");
+    }
+    *cell().write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+pub fn validate(mut settings: Settings) -> Result<Settings, String> {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    settings.backend_host = trimmed(settings.backend_host);
+    settings.backend_repo_root = trimmed(settings.backend_repo_root);
+    settings.new_idea_shortcut = trimmed(settings.new_idea_shortcut);
+
+    if settings.backend_port == Some(0) {
+        return Err("Backend port must be between 1 and 65535.".to_string());
+    }
+    if settings.backend_startup_timeout_secs == Some(0) {
+        return Err("Backend startup timeout must be at least one second.".to_string());
+    }
+    if settings
+        .retry_max_attempts
+        .is_some_and(|n| !(1..=10).contains(&n))
+    {
+        return Err("Retry attempts must be between 1 and 10.".to_string());
+    }
+    if settings
+        .replay_depth
+        .is_some_and(|n| !(1..=MAX_REPLAY_DEPTH).contains(&n))
+    {
+        return Err(format!(
+            "Replay depth must be between 1 and {MAX_REPLAY_DEPTH} messages."
+        ));
+    }
+    if settings
+        .max_concurrent_runs
+        .is_some_and(|n| !(1..=MAX_CONCURRENT_RUNS_LIMIT).contains(&n))
+    {
+        return Err(format!(
+            "Concurrent runs must be between 1 and {MAX_CONCURRENT_RUNS_LIMIT}."
+        ));
+    }
+    if let Some(shortcut) = settings.new_idea_shortcut.as_deref() {
+        shortcuts::validate(shortcut)?;
+    }
+    Ok(settings)
+}
+
+// Environment variables still win, so launch scripts keep working; a stored setting only fills
+// in when the variable is unset.
+pub fn env_or<T: ToString>(name: &str, setting: Option<T>) -> Option<String> {
+    env_set(name)
+        .then(|| std::env::var(name).ok())
+        .flatten()
+        .or_else(|| setting.map(|v| v.to_string()))
+}
+
+pub fn flag(name: &str, setting: Option<bool>) -> bool {
+    if env_set(name) {
+        env_flag_enabled(name)
+    } else {
+        setting.unwrap_or(false)
+    }
+}
+
+pub fn env_flag_enabled(name: &str) -> bool {
+    matches!(
+        std::env::var(name)
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+fn env_set(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| !v.trim().is_empty())
+}
+
+// Fills what the caller left out of a local backend start from the stored backend defaults.
+pub fn backend_defaults(
+    config: Option<BackendStartConfig>,
+    settings: &Settings,
+) -> Option<BackendStartConfig> {
+    let has_defaults = settings.backend_host.is_some()
+        || settings.backend_port.is_some()
+        || settings.backend_repo_root.is_some()
+        || settings.backend_supervise.is_some()
+        || settings.backend_unix_socket.is_some()
+        || settings.backend_startup_timeout_secs.is_some();
+    if !has_defaults {
+        return config;
+    }
+
+    let mut cfg = config.unwrap_or_default();
+    if cfg.remote_url.is_none() {
+        cfg.host = cfg.host.or_else(|| settings.backend_host.clone());
+        cfg.port = cfg.port.or(settings.backend_port);
+    }
+    cfg.repo_root = cfg.repo_root.or_else(|| settings.backend_repo_root.clone());
+    if !env_set(SUPERVISE_ENV) {
+        cfg.supervise = cfg.supervise.or(settings.backend_supervise);
+    }
+    if !env_set(UNIX_SOCKET_ENV) {
+        cfg.unix_socket = cfg.unix_socket.or(settings.backend_unix_socket);
+    }
+    if !env_set(STARTUP_TIMEOUT_ENV) {
+        cfg.startup_timeout_secs = cfg
+            .startup_timeout_secs
+            .or(settings.backend_startup_timeout_secs);
+    }
+    Some(cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{BackendStartConfig, Settings};
+
+    use super::{backend_defaults, env_or, flag, validate};
+
+    #[test]
+    fn environment_wins_over_stored_settings() {
+        let name = "PV_DESKTOP_SETTINGS_TEST_VALUE";
+        std::env::remove_var(name);
+        assert_eq!(env_or(name, Some(30)), Some("30".to_string()));
+        assert!(flag(name, Some(true)));
+
+        std::env::set_var(name, "0");
+        assert_eq!(env_or(name, Some(30)), Some("0".to_string()));
+        assert!(!flag(name, Some(true)));
+        std::env::remove_var(name);
+    }
+
+    #[test]
+    fn validation_and_backend_defaults() {
+        let settings = validate(Settings {
+            backend_host: Some("  ".to_string()),
+            backend_port: Some(9000),
+            ..Settings::default()
+        })
+        .expect("valid settings");
+        assert_eq!(settings.backend_host, None);
+        assert!(validate(Settings {
+            replay_depth: Some(0),
+            ..Settings::default()
+        })
+        .is_err());
+
+        assert!(backend_defaults(None, &Settings::default()).is_none());
+        let cfg = backend_defaults(None, &settings).expect("defaults applied");
+        assert_eq!(cfg.port, Some(9000));
+        let explicit = BackendStartConfig {
+            port: Some(8001),
+            ..BackendStartConfig::default()
+        };
+        let cfg = backend_defaults(Some(explicit), &settings).expect("config kept");
+        assert_eq!(cfg.port, Some(8001));
+    }
+}
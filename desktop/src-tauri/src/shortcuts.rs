@@ -0,0 +1,74 @@
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::settings;
+use crate::tray;
+
+pub const DEFAULT_NEW_IDEA_SHORTCUT: &str = "CommandOrControl+Shift+I";
+const NEW_IDEA_SHORTCUT_ENV: &str = "PV_DESKTOP_NEW_IDEA_SHORTCUT";
+const DISABLED: &str = "off";
+
+// Only the new-idea shortcut is ever registered, so any press means "new idea".
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                tray::new_idea(app);
+            }
+        })
+        .build()
+}
+
+pub fn apply(app: &AppHandle, setting: Option<&str>) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("failed to clear global shortcuts: {e}"))?;
+    let Some(raw) = configured(settings::env_or(NEW_IDEA_SHORTCUT_ENV, setting)) else {
+        return Ok(());
+    };
+    shortcuts
+        .register(parse(&raw)?)
+        .map_err(|e| format!("failed to register {raw} (another app may own it): {e}"))
+}
+
+pub fn validate(raw: &str) -> Result<(), String> {
+    if raw.eq_ignore_ascii_case(DISABLED) {
+        return Ok(());
+    }
+    parse(raw).map(|_| ())
+}
+
+fn parse(raw: &str) -> Result<Shortcut, String> {
+    raw.parse::<Shortcut>()
+        .map_err(|e| format!("'{raw}' is not a valid shortcut: {e}"))
+}
+
+// Unset means the default; "off" turns the shortcut off.
+fn configured(raw: Option<String>) -> Option<String> {
+    let raw = raw
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_NEW_IDEA_SHORTCUT.to_string());
+    (!raw.eq_ignore_ascii_case(DISABLED)).then_some(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{configured, DEFAULT_NEW_IDEA_SHORTCUT};
+
+    #[test]
+    fn unset_uses_the_default_and_off_disables() {
+        assert_eq!(configured(None).as_deref(), Some(DEFAULT_NEW_IDEA_SHORTCUT));
+        assert_eq!(
+            configured(Some(" ".to_string())).as_deref(),
+            Some(DEFAULT_NEW_IDEA_SHORTCUT)
+        );
+        assert_eq!(
+            configured(Some("Alt+Space".to_string())).as_deref(),
+            Some("Alt+Space")
+        );
+        assert_eq!(configured(Some("OFF".to_string())), None);
+    }
+}
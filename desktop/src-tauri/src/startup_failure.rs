@@ -0,0 +1,152 @@
+use crate::types::{StartupFailure, StartupFailureKind};
+
+// Matches known failure signatures in the captured backend output. `fallback` describes what the
+// launcher saw (exited vs. never became healthy) when nothing in the output is recognized.
+pub fn analyze(lines: &[String], fallback: StartupFailureKind, log_tail: String) -> StartupFailure {
+    let (kind, hint) = classify(lines).unwrap_or_else(|| (fallback, fallback_hint(fallback)));
+    StartupFailure {
+        kind,
+        hint,
+        log_tail,
+    }
+}
+
+fn classify(lines: &[String]) -> Option<(StartupFailureKind, String)> {
+    let lowered: Vec<String> = lines.iter().map(|line| line.to_lowercase()).collect();
+    let find = |needles: &[&str]| {
+        lowered
+            .iter()
+            .position(|line| needles.iter().any(|needle| line.contains(needle)))
+    };
+
+    if find(&[
+        "requires-python",
+        "no interpreter found for python",
+        "requires a different python",
+        "unsupported python version",
+    ])
+    .is_some()
+    {
+        return Some((
+            StartupFailureKind::PythonVersion,
+            "The backend needs a newer Python. Install the version from pyproject.toml's requires-python, or let uv manage it.".to_string(),
+        ));
+    }
+    if let Some(index) = find(&["modulenotfounderror", "no module named"]) {
+        let hint = match module_name(&lines[index]) {
+            Some(module) => format!(
+                "Python module '{module}' is not installed. Run Install backend to sync dependencies."
+            ),
+            None => "A Python module is missing. Run Install backend to sync dependencies."
+                .to_string(),
+        };
+        return Some((StartupFailureKind::ModuleNotFound, hint));
+    }
+    if find(&[
+        "missing key inputs argument",
+        "api key not valid",
+        "api_key_invalid",
+        "no api key",
+        "google_api_key is not set",
+        "gemini_api_key is not set",
+        "brave_search_api_key",
+    ])
+    .is_some()
+    {
+        return Some((
+            StartupFailureKind::MissingApiKey,
+            "An API key is missing or invalid. Check the Google/Gemini and Brave Search keys in Settings.".to_string(),
+        ));
+    }
+    if find(&[
+        "address already in use",
+        "error while attempting to bind",
+        "only one usage of each socket address",
+        "errno 98",
+        "errno 48",
+    ])
+    .is_some()
+    {
+        return Some((
+            StartupFailureKind::AddressInUse,
+            "The backend port is taken by another process. Stop it or choose a port range in the backend settings.".to_string(),
+        ));
+    }
+    None
+}
+
+// `No module named 'google.adk'` -> `google.adk`.
+fn module_name(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("No module named ")?;
+    let name = rest.trim().trim_matches(|c| c == '\'' || c == '"');
+    let name = name.split(['\'', '"']).next()?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn fallback_hint(kind: StartupFailureKind) -> String {
+    match kind {
+        StartupFailureKind::Timeout => {
+            "The backend started but never answered its health check. Raise the startup timeout or check the health path."
+        }
+        StartupFailureKind::Exited => {
+            "The backend process exited during startup; the log tail shows its last output."
+        }
+        _ => "Check that uv is installed and the backend repo path is correct.",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::analyze;
+    use crate::types::StartupFailureKind;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn recognizes_known_startup_signatures() {
+        let missing = analyze(
+            &lines(&[
+                "[stderr] Traceback (most recent call last):",
+                "[stderr] ModuleNotFoundError: No module named 'google.adk'",
+            ]),
+            StartupFailureKind::Exited,
+            "tail".to_string(),
+        );
+        assert_eq!(missing.kind, StartupFailureKind::ModuleNotFound);
+        assert!(missing.hint.contains("'google.adk'"));
+        assert_eq!(missing.log_tail, "tail");
+
+        let busy = analyze(
+            &lines(&["[stderr] ERROR:    [Errno 98] error while attempting to bind on address ('127.0.0.1', 8000): address already in use"]),
+            StartupFailureKind::Exited,
+            String::new(),
+        );
+        assert_eq!(busy.kind, StartupFailureKind::AddressInUse);
+
+        let key = analyze(
+            &lines(&["[stderr] ValueError: Missing key inputs argument! To use the Google AI API, provide (`api_key`) arguments."]),
+            StartupFailureKind::Exited,
+            String::new(),
+        );
+        assert_eq!(key.kind, StartupFailureKind::MissingApiKey);
+
+        let python = analyze(
+            &lines(&[
+                "[stderr] error: No interpreter found for Python >=3.10 in managed installations",
+            ]),
+            StartupFailureKind::Exited,
+            String::new(),
+        );
+        assert_eq!(python.kind, StartupFailureKind::PythonVersion);
+
+        let quiet = analyze(
+            &lines(&["[stdout] INFO: Started server process"]),
+            StartupFailureKind::Timeout,
+            String::new(),
+        );
+        assert_eq!(quiet.kind, StartupFailureKind::Timeout);
+    }
+}
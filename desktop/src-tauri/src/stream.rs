@@ -1,15 +1,52 @@
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use futures_util::StreamExt;
+use futures_util::{SinkExt, StreamExt};
 use reqwest::{Client, StatusCode};
 use serde::Serialize;
 use serde_json::{json, Value};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::net::TcpStream;
+use tokio::sync::{watch, Mutex};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{connect_async_tls_with_config, MaybeTlsStream, WebSocketStream};
 use tokio_util::sync::CancellationToken;
 
-use crate::backend::{run_fallback_url, run_sse_url};
-use crate::session_store::ReplayMessage;
-use crate::types::StreamRunInput;
+use crate::artifacts::{
+    self, extract_artifact_parts, extract_artifact_refs, ArtifactPart, ArtifactRef,
+};
+use crate::attachments;
+use crate::backend::{run_fallback_url, run_live_url, run_sse_url, BackendHandle};
+use crate::backend_auth;
+use crate::commands::AppState;
+use crate::debug_events;
+use crate::degradation;
+use crate::error::{AppError, ErrorCode};
+use crate::events;
+use crate::multi_run;
+use crate::plan_detect::{self, DetectedPlan};
+use crate::redact;
+use crate::report_sections::{split_sections, ReportSection};
+use crate::run_budget;
+use crate::run_env;
+use crate::session_store::{ReplayMessage, SessionStore};
+use crate::settings;
+use crate::types::{
+    Degradation, ReplayMode, RunMetrics, RunMode, RunState, RunStatus, StreamRunInput,
+};
+use crate::windows;
+
+const RESUME_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RESUME_IDLE_LIMIT: Duration = Duration::from_secs(90);
+const DEFAULT_SSE_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+const EMPTY_RESPONSE_NUDGE: &str =
+    "Your previous turn ended without any visible reply. Please answer the request above in full.";
+
+pub type LiveSessions = Arc<Mutex<HashMap<String, LiveSession>>>;
+pub type StreamPauses = Arc<Mutex<HashMap<String, watch::Sender<bool>>>>;
+type LiveSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +55,15 @@ struct StreamOpen {
     request_id: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamThought {
+    kind: &'static str,
+    request_id: String,
+    text: String,
+    source: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct StreamMessage {
@@ -25,6 +71,43 @@ struct StreamMessage {
     request_id: String,
     text: String,
     source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sse_event: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamDelta {
+    kind: &'static str,
+    request_id: String,
+    text: String,
+    reset: bool,
+    source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sse_event: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamArtifact {
+    kind: &'static str,
+    request_id: String,
+    name: String,
+    mime_type: String,
+    path: Option<String>,
+    uri: Option<String>,
+    size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamSection {
+    kind: &'static str,
+    request_id: String,
+    index: usize,
+    level: u8,
+    title: String,
+    body: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -33,6 +116,38 @@ struct StreamRaw {
     kind: &'static str,
     request_id: String,
     event: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sse_event: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct SseFrame {
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseFrame {
+    // Applies one line per the SSE spec; returns true on the blank line that dispatches the frame.
+    fn push_line(&mut self, line: &str) -> bool {
+        if line.is_empty() {
+            return true;
+        }
+        if line.starts_with(':') {
+            return false;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => self.data.push(value.to_string()),
+            "event" => self.event = Some(value.to_string()).filter(|e| !e.is_empty()),
+            "id" if !value.contains('\0') => self.id = Some(value.to_string()),
+            _ => {}
+        }
+        false
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -42,6 +157,83 @@ struct StreamError {
     request_id: String,
     message: String,
     retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after_ms: Option<u64>,
+    #[serde(flatten)]
+    code: ErrorCode,
+    // The raw cause behind `message`, for logs and the debug view.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl StreamError {
+    fn failed(request_id: &str, code: ErrorCode, message: String, detail: String) -> Self {
+        Self {
+            kind: "stream_error",
+            request_id: request_id.to_string(),
+            message,
+            retryable: true,
+            category: None,
+            retry_after_ms: None,
+            code,
+            detail: Some(detail),
+        }
+    }
+
+    fn cancelled(app: &AppHandle, request_id: &str) -> Self {
+        let timed_out = run_budget::is_expired(app, request_id);
+        Self {
+            kind: "stream_error",
+            request_id: request_id.to_string(),
+            message: if timed_out {
+                "Run exceeded its time budget; partial results were kept.".to_string()
+            } else {
+                "Run cancelled.".to_string()
+            },
+            retryable: false,
+            category: Some(if timed_out {
+                run_budget::TIMEOUT_CATEGORY
+            } else {
+                "cancelled"
+            }),
+            retry_after_ms: None,
+            code: if timed_out {
+                ErrorCode::RunTimedOut
+            } else {
+                ErrorCode::RunCancelled
+            },
+            detail: None,
+        }
+    }
+
+    fn empty_response(request_id: &str) -> Self {
+        Self {
+            kind: "stream_error",
+            request_id: request_id.to_string(),
+            message: "The agent finished without producing a response.".to_string(),
+            retryable: true,
+            category: Some("empty_response"),
+            retry_after_ms: None,
+            code: ErrorCode::EmptyResponse,
+            detail: None,
+        }
+    }
+
+    fn rate_limited(request_id: &str, message: String, retry_after: Option<Duration>) -> Self {
+        let retry_after_ms = retry_after.map(|delay| delay.as_millis() as u64);
+        Self {
+            category: Some(RATE_LIMIT_CATEGORY),
+            retry_after_ms,
+            ..Self::failed(
+                request_id,
+                ErrorCode::RateLimited { retry_after_ms },
+                message.clone(),
+                message,
+            )
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -50,6 +242,8 @@ struct StreamDone {
     kind: &'static str,
     request_id: String,
     usage: Option<Value>,
+    degradations: Vec<Degradation>,
+    metrics: Option<RunMetrics>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -71,6 +265,70 @@ struct StreamProgress {
     tools_total: usize,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamFinish {
+    kind: &'static str,
+    request_id: String,
+    reason: String,
+    category: &'static str,
+    message: String,
+    source: Option<String>,
+    safety: Vec<SafetyFlag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SafetyFlag {
+    category: String,
+    probability: Option<String>,
+    blocked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FinishSignal {
+    reason: String,
+    category: &'static str,
+    message: String,
+    safety: Vec<SafetyFlag>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamPlan<'a> {
+    kind: &'static str,
+    request_id: &'a str,
+    #[serde(flatten)]
+    plan: &'a DetectedPlan,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamAgentProgress {
+    kind: &'static str,
+    request_id: String,
+    active_agent: Option<String>,
+    agents: Vec<AgentLaneProgress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentLaneProgress {
+    author: String,
+    status: &'static str,
+    tools_completed: usize,
+    tools_total: usize,
+    saw_text: bool,
+}
+
+#[derive(Debug, Default)]
+struct AgentLane {
+    author: String,
+    tools_started: usize,
+    tools_completed: usize,
+    saw_text: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct StreamTool {
@@ -88,11 +346,20 @@ struct ToolSignal {
     name: String,
     query: Option<String>,
     detail: Option<String>,
+    brave_calls: u32,
+    problem: Option<(&'static str, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BackendProgress {
+    stage: Option<String>,
+    fraction: Option<f64>,
 }
 
 #[derive(Debug, Default)]
 struct StreamState {
     last_model_text: String,
+    streamed_text: String,
     saw_model_text: bool,
     saw_error: bool,
     tools_started: usize,
@@ -100,85 +367,455 @@ struct StreamState {
     last_progress_percent: Option<u8>,
     last_progress_stage: Option<String>,
     last_invocation_id: Option<String>,
+    seen_event_ids: HashSet<String>,
+    usage: Option<Value>,
+    brave_calls: u32,
+    seen_artifacts: HashSet<String>,
+    pending_artifacts: Vec<ArtifactRef>,
+    emitted_sections: Vec<ReportSection>,
+    agents: Vec<AgentLane>,
+    active_agent: Option<String>,
+    last_agent_lanes: Option<Vec<AgentLaneProgress>>,
+    backend_progress: Option<BackendProgress>,
+    plan: Option<DetectedPlan>,
+    sse_event: Option<String>,
+    last_sse_id: Option<String>,
+    streamed_thoughts: String,
+    started_at: Option<Instant>,
+    first_byte_ms: Option<u64>,
+    first_text_ms: Option<u64>,
+    bytes_received: u64,
+    event_count: u64,
+}
+
+impl StreamState {
+    // Timings are measured from when the request was sent, not from when the stream opened.
+    fn started(at: Instant) -> Self {
+        Self {
+            started_at: Some(at),
+            ..Self::default()
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started_at
+            .map(|at| at.elapsed().as_millis() as u64)
+            .unwrap_or_default()
+    }
+
+    fn note_bytes(&mut self, bytes: usize) {
+        if self.first_byte_ms.is_none() {
+            self.first_byte_ms = Some(self.elapsed_ms());
+        }
+        self.bytes_received += bytes as u64;
+    }
+
+    fn metrics(&self) -> RunMetrics {
+        RunMetrics {
+            time_to_first_byte_ms: self.first_byte_ms,
+            time_to_first_text_ms: self.first_text_ms,
+            duration_ms: self.elapsed_ms(),
+            bytes_received: self.bytes_received,
+            event_count: self.event_count,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct SseFailure {
     status: Option<u16>,
+    retryable: bool,
+    retry_after: Option<Duration>,
     message: String,
 }
 
+// Local failures (emitting, persisting artifacts) end the attempt without a retry.
+impl From<AppError> for SseFailure {
+    fn from(err: AppError) -> Self {
+        Self {
+            status: None,
+            retryable: false,
+            retry_after: None,
+            message: err.to_string(),
+        }
+    }
+}
+
+const RATE_LIMIT_CATEGORY: &str = "rate_limited";
+const MAX_RATE_LIMIT_RESUBMITS: u32 = 3;
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+// Accepts both forms allowed by RFC 9110: delta-seconds and an IMF-fixdate.
+fn parse_retry_after(raw: &str, now: std::time::SystemTime) -> Option<Duration> {
+    let raw = raw.trim();
+    if let Ok(secs) = raw.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let parts: Vec<&str> = raw.split_whitespace().collect();
+    let [_, day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|name| name == month)? as i64
+        + 1;
+    let (day, year) = (day.parse::<i64>().ok()?, year.parse::<i64>().ok()?);
+    let clock: Vec<i64> = time
+        .split(':')
+        .map(|part| part.parse::<i64>().ok())
+        .collect::<Option<_>>()?;
+    let [hours, minutes, seconds] = clock.as_slice() else {
+        return None;
+    };
+    // Days since the Unix epoch for a proleptic Gregorian date (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    let target = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    let now = now
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    Some(Duration::from_secs((target - now).max(0) as u64))
+}
+
+// Upstream model quota errors come through as event errors such as
+// `429 RESOURCE_EXHAUSTED ... 'retryDelay': '27s'`.
+fn upstream_rate_limit(message: &str) -> Option<Option<Duration>> {
+    if !message.contains("429") && !message.contains("RESOURCE_EXHAUSTED") {
+        return None;
+    }
+    let retry_after = message.split("retryDelay").nth(1).and_then(|rest| {
+        let digits: String = rest
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse::<u64>().ok().map(Duration::from_secs)
+    });
+    Some(retry_after)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1_000),
+            max_delay: Duration::from_secs(15),
+            jitter: 0.25,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+
+        Self {
+            max_attempts: read("PV_DESKTOP_RETRY_MAX_ATTEMPTS")
+                .or_else(|| settings::current().retry_max_attempts.map(u64::from))
+                .map(|v| v.clamp(1, 10) as u32)
+                .unwrap_or(defaults.max_attempts),
+            base_delay: read("PV_DESKTOP_RETRY_BASE_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            max_delay: read("PV_DESKTOP_RETRY_MAX_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+            jitter: read("PV_DESKTOP_RETRY_JITTER_PCT")
+                .map(|v| v.min(100) as f64 / 100.0)
+                .unwrap_or(defaults.jitter),
+        }
+    }
+
+    // `attempt` is the 1-based attempt that just failed; `noise` is in [0, 1).
+    pub fn delay_for(&self, attempt: u32, noise: f64) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let factor = 1.0 + self.jitter * (noise * 2.0 - 1.0);
+        exponential.mul_f64(factor.max(0.0))
+    }
+}
+
+fn jitter_noise() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    f64::from(nanos % 1_000) / 1_000.0
+}
+
+// An open /run_live socket bound to one ADK session, reused across plan-editing turns.
+pub struct LiveSession {
+    adk_session_id: String,
+    socket: LiveSocket,
+}
+
+enum LiveFailure {
+    Unsupported(String),
+    Failed(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamOutcome {
     Completed,
     Failed,
+    Empty,
+}
+
+impl StreamOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Empty => "empty",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamSummary {
+    pub outcome: StreamOutcome,
+    pub final_text: String,
+    pub brave_calls: u32,
+    pub plan_detected: bool,
+    pub metrics: Option<RunMetrics>,
+}
+
+impl StreamSummary {
+    pub fn failed() -> Self {
+        Self {
+            outcome: StreamOutcome::Failed,
+            final_text: String::new(),
+            brave_calls: 0,
+            plan_detected: false,
+            metrics: None,
+        }
+    }
+
+    fn from_state(state: &StreamState, failed: bool) -> Self {
+        Self {
+            outcome: if failed {
+                StreamOutcome::Failed
+            } else {
+                StreamOutcome::Completed
+            },
+            final_text: state.last_model_text.clone(),
+            brave_calls: state.brave_calls,
+            plan_detected: state.plan.is_some(),
+            metrics: Some(state.metrics()),
+        }
+    }
 }
 
 pub async fn run_stream_task(
     app: AppHandle,
-    base_url: String,
+    backend: BackendHandle,
     input: StreamRunInput,
     replay_messages: Vec<ReplayMessage>,
     cancel: CancellationToken,
-) -> Result<StreamOutcome, String> {
-    ensure_adk_session(&base_url, &input).await?;
-
-    emit(
-        &app,
-        &input.request_id,
-        StreamOpen {
-            kind: "stream_open",
-            request_id: input.request_id.clone(),
-        },
-    )?;
-    emit(
-        &app,
-        &input.request_id,
-        StreamProgress {
-            kind: "stream_progress",
-            request_id: input.request_id.clone(),
-            percent: 5,
-            stage: "Rehydrating context".to_string(),
-            tools_completed: 0,
-            tools_total: 0,
-        },
-    )?;
+    pause: watch::Receiver<bool>,
+) -> Result<StreamSummary, AppError> {
+    ensure_adk_session(&app, &backend.base_url(), &input).await?;
+    stream_in_session(app, backend, input, replay_messages, cancel, pause).await
+}
 
-    if !replay_messages.is_empty() {
-        if let Err(err) = replay_history(&base_url, &input, &replay_messages, &cancel).await {
-            emit(
-                &app,
-                &input.request_id,
-                StreamTool {
-                    kind: "stream_tool",
-                    request_id: input.request_id.clone(),
-                    phase: "info",
-                    name: "context_replay".to_string(),
-                    query: None,
-                    detail: Some(format!("Replay degraded: {}", truncate(&err, 240))),
-                },
-            )?;
+// Runs the turn over /run_sse in an ADK session that already exists.
+async fn stream_in_session(
+    app: AppHandle,
+    backend: BackendHandle,
+    input: StreamRunInput,
+    replay_messages: Vec<ReplayMessage>,
+    cancel: CancellationToken,
+    pause: watch::Receiver<bool>,
+) -> Result<StreamSummary, AppError> {
+    let mut base_url = backend.base_url();
+    let mut generation = backend.generation();
+    open_run(&app, &base_url, &input, &replay_messages, &cancel).await?;
+
+    let policy = RetryPolicy::from_env();
+    let mut attempt = 1;
+    let mut turn = input.clone();
+    let mut retry_empty = input.run_mode == RunMode::Idea;
+    let mut carried_brave_calls = 0;
+    let auto_resubmit = input.auto_resubmit.unwrap_or_else(|| {
+        settings::flag(
+            "PV_DESKTOP_RATE_LIMIT_AUTO_RESUBMIT",
+            settings::current().rate_limit_auto_resubmit,
+        )
+    });
+    let mut resubmits = 0;
+    let result = loop {
+        if backend.generation() != generation {
+            base_url = backend.base_url();
+            generation = backend.generation();
+            retarget_run(&app, &base_url, &input, &replay_messages, &cancel).await?;
         }
-    }
+        let outcome = run_sse_stream(
+            &app,
+            &base_url,
+            &turn,
+            cancel.clone(),
+            pause.clone(),
+            retry_empty,
+        )
+        .await;
+        match outcome {
+            Ok(summary) if summary.outcome == StreamOutcome::Empty => {
+                retry_empty = false;
+                carried_brave_calls += summary.brave_calls;
+                turn.text = EMPTY_RESPONSE_NUDGE.to_string();
+                degradation::note(
+                    &app,
+                    &input.request_id,
+                    "empty_response_retry",
+                    "Agent returned no text; retried once with a nudge",
+                );
+                emit(
+                    &app,
+                    &input.request_id,
+                    StreamTool {
+                        kind: "stream_tool",
+                        request_id: input.request_id.clone(),
+                        phase: "info",
+                        name: "empty_response_retry".to_string(),
+                        query: None,
+                        detail: Some(
+                            "The agent returned no text; asking it once more.".to_string(),
+                        ),
+                    },
+                )?;
+            }
+            Err(failure)
+                if failure.status == Some(429)
+                    && auto_resubmit
+                    && resubmits < MAX_RATE_LIMIT_RESUBMITS =>
+            {
+                let delay = failure
+                    .retry_after
+                    .unwrap_or_else(|| policy.delay_for(resubmits + 1, jitter_noise()))
+                    .min(MAX_RETRY_AFTER);
+                degradation::note(
+                    &app,
+                    &input.request_id,
+                    RATE_LIMIT_CATEGORY,
+                    format!("Rate limited; resubmitted after {}s", delay.as_secs()),
+                );
+                emit(
+                    &app,
+                    &input.request_id,
+                    StreamTool {
+                        kind: "stream_tool",
+                        request_id: input.request_id.clone(),
+                        phase: "info",
+                        name: "rate_limit_wait".to_string(),
+                        query: None,
+                        detail: Some(format!(
+                            "The backend is rate limiting requests; resubmitting in {}s.",
+                            delay.as_secs()
+                        )),
+                    },
+                )?;
+                tokio::select! {
+                    _ = cancel.cancelled() => break Err(failure),
+                    _ = tokio::time::sleep(delay) => {}
+                }
+                resubmits += 1;
+            }
+            Err(failure) if failure.retryable && attempt < policy.max_attempts => {
+                let delay = policy.delay_for(attempt, jitter_noise());
+                degradation::note(
+                    &app,
+                    &input.request_id,
+                    "transport_retry",
+                    format!("Retried after: {}", truncate(&failure.message, 160)),
+                );
+                emit(
+                    &app,
+                    &input.request_id,
+                    StreamTool {
+                        kind: "stream_tool",
+                        request_id: input.request_id.clone(),
+                        phase: "info",
+                        name: "stream_retry".to_string(),
+                        query: None,
+                        detail: Some(format!(
+                            "{}; retrying in {:.1}s (attempt {} of {}).",
+                            truncate(&failure.message, 160),
+                            delay.as_secs_f32(),
+                            attempt + 1,
+                            policy.max_attempts
+                        )),
+                    },
+                )?;
+                tokio::select! {
+                    _ = cancel.cancelled() => break Err(failure),
+                    _ = tokio::time::sleep(delay) => {}
+                }
+                attempt += 1;
+            }
+            other => break other,
+        }
+    };
 
-    match run_sse_stream(&app, &base_url, &input, cancel).await {
-        Ok(outcome) => Ok(outcome),
+    match result {
+        Ok(mut summary) => {
+            summary.brave_calls += carried_brave_calls;
+            Ok(summary)
+        }
         Err(failure) => {
             // Fall back to /run only when /run_sse is clearly unsupported by this backend.
             let fallback_allowed = matches!(failure.status, Some(404 | 405 | 501));
             if fallback_allowed {
-                run_non_streaming_fallback(app, &base_url, &input, failure.status).await
-            } else {
-                emit(
+                degradation::note(
                     &app,
                     &input.request_id,
-                    StreamError {
-                        kind: "stream_error",
-                        request_id: input.request_id.clone(),
-                        message: format!("SSE stream failed: {}", failure.message),
-                        retryable: true,
-                    },
-                )?;
+                    "fallback_transport",
+                    "/run_sse is unavailable; used non-streaming /run",
+                );
+                Ok(run_non_streaming_fallback(app, &base_url, &input, failure.status).await?)
+            } else {
+                let error = if failure.status == Some(429) {
+                    StreamError::rate_limited(
+                        &input.request_id,
+                        format!(
+                            "The backend is rate limiting requests{}.",
+                            failure
+                                .retry_after
+                                .map(|delay| format!("; retry in {}s", delay.as_secs()))
+                                .unwrap_or_default()
+                        ),
+                        failure.retry_after,
+                    )
+                } else {
+                    StreamError::failed(
+                        &input.request_id,
+                        ErrorCode::StreamFailed,
+                        format!("SSE stream failed: {}", failure.message),
+                        failure.message,
+                    )
+                };
+                emit(&app, &input.request_id, error)?;
                 emit(
                     &app,
                     &input.request_id,
@@ -186,26 +823,305 @@ pub async fn run_stream_task(
                         kind: "stream_done",
                         request_id: input.request_id.clone(),
                         usage: None,
+                        degradations: degradation::take(&app, &input.request_id),
+                        metrics: None,
                     },
                 )?;
-                Ok(StreamOutcome::Failed)
+                Ok(StreamSummary::failed())
             }
         }
     }
 }
 
-async fn run_sse_stream(
+// The backend restarted mid-run (possibly on another port), so the execution session and its
+// replayed history have to be recreated before retrying.
+async fn retarget_run(
     app: &AppHandle,
     base_url: &str,
     input: &StreamRunInput,
-    cancel: CancellationToken,
-) -> Result<StreamOutcome, SseFailure> {
-    let response = send_run_sse_request(base_url, input).await?;
-    let status = response.status();
-    if !status.is_success() {
-        let body = response.text().await.unwrap_or_default();
-        return Err(SseFailure {
+    replay_messages: &[ReplayMessage],
+    cancel: &CancellationToken,
+) -> Result<(), AppError> {
+    degradation::note(
+        app,
+        &input.request_id,
+        "backend_restarted",
+        format!("Backend restarted during the run; continued against {base_url}"),
+    );
+    ensure_adk_session(app, base_url, input).await?;
+    if !replay_messages.is_empty() {
+        replay_history(app, base_url, input, replay_messages, cancel).await?;
+    }
+    Ok(())
+}
+
+async fn open_run(
+    app: &AppHandle,
+    base_url: &str,
+    input: &StreamRunInput,
+    replay_messages: &[ReplayMessage],
+    cancel: &CancellationToken,
+) -> Result<(), AppError> {
+    emit(
+        app,
+        &input.request_id,
+        StreamOpen {
+            kind: "stream_open",
+            request_id: input.request_id.clone(),
+        },
+    )?;
+    emit(
+        app,
+        &input.request_id,
+        StreamProgress {
+            kind: "stream_progress",
+            request_id: input.request_id.clone(),
+            percent: 5,
+            stage: "Rehydrating context".to_string(),
+            tools_completed: 0,
+            tools_total: 0,
+        },
+    )?;
+
+    if !replay_messages.is_empty() {
+        if let Err(err) = replay_history(app, base_url, input, replay_messages, cancel).await {
+            degradation::note(
+                app,
+                &input.request_id,
+                "replay_failed",
+                format!(
+                    "Earlier conversation could not be replayed: {}",
+                    truncate(&err.to_string(), 200)
+                ),
+            );
+            emit(
+                app,
+                &input.request_id,
+                StreamTool {
+                    kind: "stream_tool",
+                    request_id: input.request_id.clone(),
+                    phase: "info",
+                    name: "context_replay".to_string(),
+                    query: None,
+                    detail: Some(format!(
+                        "Replay degraded: {}",
+                        truncate(&err.to_string(), 240)
+                    )),
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run_live_task(
+    app: AppHandle,
+    backend: BackendHandle,
+    mut input: StreamRunInput,
+    replay_messages: Vec<ReplayMessage>,
+    cancel: CancellationToken,
+    sessions: LiveSessions,
+    desktop_session_id: String,
+    keep_open: bool,
+) -> Result<StreamSummary, AppError> {
+    let base_url = backend.base_url();
+    let existing = sessions.lock().await.remove(&desktop_session_id);
+    let mut live = match existing {
+        Some(live) => {
+            // The ADK session already holds the conversation, so no replay is needed.
+            input.session_id = live.adk_session_id.clone();
+            open_run(&app, &base_url, &input, &[], &cancel).await?;
+            live
+        }
+        None => {
+            ensure_adk_session(&app, &base_url, &input).await?;
+            match connect_live(&base_url, &input).await {
+                Ok(socket) => {
+                    open_run(&app, &base_url, &input, &replay_messages, &cancel).await?;
+                    LiveSession {
+                        adk_session_id: input.session_id.clone(),
+                        socket,
+                    }
+                }
+                Err(LiveFailure::Unsupported(reason)) => {
+                    redact::log("[stream] falling back to /run_sse:", reason);
+                    degradation::note(
+                        &app,
+                        &input.request_id,
+                        "fallback_transport",
+                        "/run_live is unavailable; used /run_sse",
+                    );
+                    // Reuses the ADK session created above instead of orphaning it.
+                    let (_, never_paused) = watch::channel(false);
+                    return stream_in_session(
+                        app,
+                        backend,
+                        input,
+                        replay_messages,
+                        cancel,
+                        never_paused,
+                    )
+                    .await;
+                }
+                Err(LiveFailure::Failed(message)) => return Err(AppError::Network(message)),
+            }
+        }
+    };
+
+    let summary = run_live_turn(&app, &input, &mut live.socket, &cancel).await?;
+    if keep_open && summary.outcome == StreamOutcome::Completed {
+        sessions.lock().await.insert(desktop_session_id, live);
+    } else {
+        let _ = live.socket.close(None).await;
+    }
+
+    Ok(summary)
+}
+
+async fn connect_live(base_url: &str, input: &StreamRunInput) -> Result<LiveSocket, LiveFailure> {
+    if crate::http::unix_socket_for(base_url).is_some() {
+        return Err(LiveFailure::Unsupported(
+            "/run_live is not available over the unix socket transport".to_string(),
+        ));
+    }
+    let url = run_live_url(base_url, &input.app_name, &input.user_id, &input.session_id);
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| LiveFailure::Failed(format!("Invalid /run_live URL: {e}")))?;
+    request
+        .headers_mut()
+        .extend(backend_auth::headers_for(base_url));
+    let connector = crate::http::BackendTls::current()
+        .ws_connector()
+        .map_err(LiveFailure::Failed)?;
+    match connect_async_tls_with_config(request, None, false, connector).await {
+        Ok((socket, _)) => Ok(socket),
+        Err(tungstenite::Error::Http(response)) => Err(LiveFailure::Unsupported(format!(
+            "/run_live handshake returned HTTP {}",
+            response.status()
+        ))),
+        Err(err) => Err(LiveFailure::Failed(format!(
+            "Failed to connect to /run_live: {err}"
+        ))),
+    }
+}
+
+async fn run_live_turn(
+    app: &AppHandle,
+    input: &StreamRunInput,
+    socket: &mut LiveSocket,
+    cancel: &CancellationToken,
+) -> Result<StreamSummary, AppError> {
+    let request = json!({
+        "content": {
+            "role": "user",
+            "parts": attachments::message_parts(&input.text, input.parts.as_deref())
+        }
+    });
+
+    let mut state = StreamState::started(Instant::now());
+    emit_progress_if_changed(app, &input.request_id, &mut state, false)?;
+
+    let mut failure = socket
+        .send(Message::Text(request.to_string().into()))
+        .await
+        .err()
+        .map(|e| {
+            (
+                "Failed to send turn over /run_live".to_string(),
+                e.to_string(),
+            )
+        });
+    let mut cancelled = false;
+
+    while failure.is_none() {
+        let next = tokio::select! {
+            _ = cancel.cancelled() => {
+                cancelled = true;
+                break;
+            }
+            message = socket.next() => message,
+        };
+
+        match next {
+            None | Some(Ok(Message::Close(_))) => break,
+            Some(Err(err)) => {
+                failure = Some(("Lost the /run_live connection".to_string(), err.to_string()))
+            }
+            Some(Ok(Message::Text(text))) => {
+                state.note_bytes(text.len());
+                let Ok(event) = serde_json::from_str::<Value>(text.as_str()) else {
+                    continue;
+                };
+                process_event(app, &input.request_id, &event, &mut state)?;
+                if event
+                    .get("turnComplete")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+                {
+                    break;
+                }
+            }
+            Some(Ok(_)) => {}
+        }
+    }
+
+    if cancelled || failure.is_some() {
+        let error = match failure {
+            Some((message, detail)) => StreamError {
+                retryable: !cancelled,
+                ..StreamError::failed(&input.request_id, ErrorCode::StreamFailed, message, detail)
+            },
+            None => StreamError::cancelled(app, &input.request_id),
+        };
+        emit(app, &input.request_id, error)?;
+        state.saw_error = true;
+    } else {
+        report_empty_response(app, &input.request_id, &mut state)?;
+    }
+    publish_sections(app, &input.request_id, &mut state, true)?;
+    detect_text_plan(app, &input.request_id, &mut state)?;
+
+    emit(
+        app,
+        &input.request_id,
+        StreamDone {
+            kind: "stream_done",
+            request_id: input.request_id.clone(),
+            usage: state.usage.clone(),
+            degradations: degradation::take(app, &input.request_id),
+            metrics: Some(state.metrics()),
+        },
+    )?;
+    emit_progress_if_changed(app, &input.request_id, &mut state, true)?;
+
+    Ok(StreamSummary::from_state(&state, state.saw_error))
+}
+
+async fn run_sse_stream(
+    app: &AppHandle,
+    base_url: &str,
+    input: &StreamRunInput,
+    cancel: CancellationToken,
+    mut pause: watch::Receiver<bool>,
+    retry_empty: bool,
+) -> Result<StreamSummary, SseFailure> {
+    let started = Instant::now();
+    let response = send_run_sse_request(base_url, input).await?;
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after = (status == StatusCode::TOO_MANY_REQUESTS)
+            .then(|| response.headers().get(reqwest::header::RETRY_AFTER))
+            .flatten()
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, std::time::SystemTime::now()));
+        let body = response.text().await.unwrap_or_default();
+        return Err(SseFailure {
             status: Some(status.as_u16()),
+            retryable: matches!(status.as_u16(), 502..=504),
+            retry_after,
             message: format!(
                 "/run_sse returned {}{}",
                 status,
@@ -218,65 +1134,89 @@ async fn run_sse_stream(
         });
     }
 
-    let mut usage = None;
-    let mut state = StreamState::default();
-    emit_progress_if_changed(app, &input.request_id, &mut state, false).map_err(|e| {
-        SseFailure {
-            status: None,
-            message: e,
-        }
-    })?;
+    let mut state = StreamState::started(started);
+    emit_progress_if_changed(app, &input.request_id, &mut state, false)?;
 
     let mut stream = response.bytes_stream();
-    let mut line_buffer = String::new();
-    let mut data_lines: Vec<String> = Vec::new();
+    let mut line_buffer: Vec<u8> = Vec::new();
+    let mut frame = SseFrame::default();
     let mut done = false;
     let mut cancelled = false;
+    let idle_timeout = sse_idle_timeout(settings::env_or(
+        "PV_DESKTOP_SSE_IDLE_TIMEOUT_SECS",
+        settings::current().sse_idle_timeout_secs,
+    ));
 
     while !done {
+        // Not polling the body while paused lets unread events back up in the socket buffers.
+        if *pause.borrow() && !wait_while_paused(&mut pause, &cancel).await {
+            cancelled = true;
+            break;
+        }
+
         let next = tokio::select! {
             _ = cancel.cancelled() => {
                 cancelled = true;
                 break;
             }
             chunk = stream.next() => chunk,
+            _ = idle_sleep(idle_timeout) => {
+                let limit = idle_timeout.unwrap_or_default().as_secs();
+                degradation::note(
+                    app,
+                    &input.request_id,
+                    "stream_stalled",
+                    format!("Backend sent no data for {limit}s"),
+                );
+                return Err(SseFailure {
+                    status: None,
+                    retryable: settings::flag(
+                        "PV_DESKTOP_SSE_STALL_RETRY",
+                        settings::current().sse_stall_retry,
+                    ),
+                    retry_after: None,
+                    message: format!("stream stalled: no data received for {limit}s"),
+                });
+            }
         };
 
         match next {
             None => break,
             Some(Err(err)) => {
-                return Err(SseFailure {
-                    status: None,
-                    message: format!("error reading SSE stream: {err}"),
-                });
+                emit(
+                    app,
+                    &input.request_id,
+                    StreamTool {
+                        kind: "stream_tool",
+                        request_id: input.request_id.clone(),
+                        phase: "info",
+                        name: "stream_resume".to_string(),
+                        query: None,
+                        detail: Some(format!(
+                            "Connection dropped ({}); resuming from session events.",
+                            truncate(&err.to_string(), 160)
+                        )),
+                    },
+                )?;
+                degradation::note(
+                    app,
+                    &input.request_id,
+                    "fallback_transport",
+                    "SSE connection dropped; finished by polling session events",
+                );
+                resume_from_session_events(app, base_url, input, &mut state, &cancel)
+                    .await
+                    .map_err(|e| e.context(&format!("error reading SSE stream: {err}")))?;
+                cancelled = cancel.is_cancelled();
+                done = true;
             }
             Some(Ok(chunk)) => {
-                line_buffer.push_str(&String::from_utf8_lossy(&chunk));
+                state.note_bytes(chunk.len());
+                line_buffer.extend_from_slice(&chunk);
 
-                while let Some(newline_idx) = line_buffer.find('\n') {
-                    let mut line = line_buffer[..newline_idx].to_string();
-                    line_buffer = line_buffer[newline_idx + 1..].to_string();
-                    if line.ends_with('\r') {
-                        line.pop();
-                    }
-
-                    if line.is_empty() {
-                        done = consume_sse_event(
-                            app,
-                            &input.request_id,
-                            &mut state,
-                            &mut usage,
-                            &mut data_lines,
-                        )
-                        .map_err(|e| SseFailure {
-                            status: None,
-                            message: e,
-                        })?;
-                        continue;
-                    }
-
-                    if let Some(data) = line.strip_prefix("data:") {
-                        data_lines.push(data.trim_start().to_string());
+                while let Some(line) = take_sse_line(&mut line_buffer) {
+                    if frame.push_line(&line) {
+                        done = consume_sse_event(app, &input.request_id, &mut state, &mut frame)?;
                     }
                 }
             }
@@ -284,40 +1224,38 @@ async fn run_sse_stream(
     }
 
     if !done {
-        if !line_buffer.trim().is_empty() {
-            if let Some(data) = line_buffer.trim().strip_prefix("data:") {
-                data_lines.push(data.trim_start().to_string());
-            }
+        let rest = String::from_utf8_lossy(&line_buffer);
+        if !rest.trim().is_empty() {
+            frame.push_line(rest.trim());
         }
-        done = consume_sse_event(
-            app,
-            &input.request_id,
-            &mut state,
-            &mut usage,
-            &mut data_lines,
-        )
-        .map_err(|e| SseFailure {
-            status: None,
-            message: e,
-        })?;
+        done = consume_sse_event(app, &input.request_id, &mut state, &mut frame)?;
         let _ = done;
     }
 
+    if !cancelled {
+        flush_artifacts(app, base_url, input, &mut state).await?;
+    }
+
+    publish_sections(app, &input.request_id, &mut state, true)
+        .and_then(|_| detect_text_plan(app, &input.request_id, &mut state))?;
+
     if cancelled {
         emit(
             app,
             &input.request_id,
-            StreamError {
-                kind: "stream_error",
-                request_id: input.request_id.clone(),
-                message: "Run cancelled.".to_string(),
-                retryable: false,
-            },
-        )
-        .map_err(|e| SseFailure {
-            status: None,
-            message: e,
-        })?;
+            StreamError::cancelled(app, &input.request_id),
+        )?;
+    } else if retry_empty && !state.saw_error && !state.saw_model_text {
+        // Leave the run open so the caller can nudge the agent once before reporting.
+        return Ok(StreamSummary {
+            outcome: StreamOutcome::Empty,
+            final_text: String::new(),
+            brave_calls: state.brave_calls,
+            plan_detected: false,
+            metrics: Some(state.metrics()),
+        });
+    } else {
+        report_empty_response(app, &input.request_id, &mut state)?;
     }
 
     emit(
@@ -326,22 +1264,145 @@ async fn run_sse_stream(
         StreamDone {
             kind: "stream_done",
             request_id: input.request_id.clone(),
-            usage,
+            usage: state.usage.clone(),
+            degradations: degradation::take(app, &input.request_id),
+            metrics: Some(state.metrics()),
         },
-    )
-    .map_err(|e| SseFailure {
-        status: None,
-        message: e,
-    })?;
-    emit_progress_if_changed(app, &input.request_id, &mut state, true).map_err(|e| SseFailure {
-        status: None,
-        message: e,
-    })?;
+    )?;
+    emit_progress_if_changed(app, &input.request_id, &mut state, true)?;
 
-    if cancelled || state.saw_error {
-        Ok(StreamOutcome::Failed)
-    } else {
-        Ok(StreamOutcome::Completed)
+    Ok(StreamSummary::from_state(
+        &state,
+        cancelled || state.saw_error,
+    ))
+}
+
+async fn resume_from_session_events(
+    app: &AppHandle,
+    base_url: &str,
+    input: &StreamRunInput,
+    state: &mut StreamState,
+    cancel: &CancellationToken,
+) -> Result<(), AppError> {
+    let url = format!(
+        "{}/apps/{}/users/{}/sessions/{}",
+        base_url, input.app_name, input.user_id, input.session_id
+    );
+    let mut last_progress = Instant::now();
+    let mut pending_final = false;
+
+    loop {
+        if let Ok(events) = fetch_session_events(&url).await {
+            let current = events_for_current_run(events, state.last_invocation_id.as_deref());
+            let unseen: Vec<Value> = events_after_id(current, state.last_sse_id.as_deref())
+                .into_iter()
+                .filter(|event| event_id(event).is_none_or(|id| !state.seen_event_ids.contains(id)))
+                .collect();
+
+            if unseen.is_empty() && pending_final {
+                return Ok(());
+            }
+            if let Some(last) = unseen.last() {
+                pending_final = is_final_response(last);
+                last_progress = Instant::now();
+            }
+            for event in unseen {
+                process_event(app, &input.request_id, &event, state)?;
+            }
+        }
+
+        if last_progress.elapsed() >= RESUME_IDLE_LIMIT {
+            return Err(AppError::Network(
+                "resume gave up: backend produced no new session events.".to_string(),
+            ));
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            _ = tokio::time::sleep(RESUME_POLL_INTERVAL) => {}
+        }
+    }
+}
+
+async fn fetch_session_events(url: &str) -> Result<Vec<Value>, AppError> {
+    let response = http_client(url)
+        .get(url)
+        .headers(backend_auth::headers_for(url))
+        .send()
+        .await
+        .map_err(|e| AppError::from_reqwest(&e, "Failed to fetch session events"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::from_status(
+            status.as_u16(),
+            format!("Session lookup returned HTTP {status}"),
+        ));
+    }
+
+    let session = response
+        .json::<Value>()
+        .await
+        .map_err(|e| AppError::from_reqwest(&e, "Failed to parse session events"))?;
+    Ok(session
+        .get("events")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn events_for_current_run(events: Vec<Value>, invocation_id: Option<&str>) -> Vec<Value> {
+    if let Some(invocation_id) = invocation_id {
+        return events
+            .into_iter()
+            .filter(|event| extract_invocation_id(event).as_deref() == Some(invocation_id))
+            .collect();
+    }
+
+    // Without an invocation id yet, everything after the latest user turn belongs to this run.
+    let start = events
+        .iter()
+        .rposition(|event| event.get("author").and_then(Value::as_str) == Some("user"))
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    events.into_iter().skip(start).collect()
+}
+
+fn event_id(event: &Value) -> Option<&str> {
+    event
+        .get("id")
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty())
+}
+
+fn is_final_response(event: &Value) -> bool {
+    !is_partial_event(event)
+        && extract_model_text(event).is_some()
+        && extract_tool_signals(event).is_empty()
+}
+
+// Splits on raw bytes so a multi-byte character cut across chunks is only decoded once complete;
+// `\n` never occurs inside a UTF-8 sequence.
+fn take_sse_line(buffer: &mut Vec<u8>) -> Option<String> {
+    let newline_idx = buffer.iter().position(|b| *b == b'\n')?;
+    let mut line: Vec<u8> = buffer.drain(..=newline_idx).collect();
+    line.pop();
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Some(String::from_utf8_lossy(&line).into_owned())
+}
+
+// When the backend tags SSE frames with event ids, resume picks up right after the last one seen.
+fn events_after_id(events: Vec<Value>, last_id: Option<&str>) -> Vec<Value> {
+    let Some(last_id) = last_id else {
+        return events;
+    };
+    match events
+        .iter()
+        .position(|event| event_id(event) == Some(last_id))
+    {
+        Some(index) => events.into_iter().skip(index + 1).collect(),
+        None => events,
     }
 }
 
@@ -349,15 +1410,18 @@ fn consume_sse_event(
     app: &AppHandle,
     request_id: &str,
     state: &mut StreamState,
-    usage: &mut Option<Value>,
-    data_lines: &mut Vec<String>,
-) -> Result<bool, String> {
-    if data_lines.is_empty() {
+    frame: &mut SseFrame,
+) -> Result<bool, AppError> {
+    let frame = std::mem::take(frame);
+    // An empty `id:` resets the last event id, per the spec.
+    if let Some(id) = frame.id {
+        state.last_sse_id = Some(id).filter(|id| !id.is_empty());
+    }
+    if frame.data.is_empty() {
         return Ok(false);
     }
 
-    let payload = data_lines.join("\n");
-    data_lines.clear();
+    let payload = frame.data.join("\n");
     let trimmed = payload.trim();
     if trimmed.is_empty() {
         return Ok(false);
@@ -372,11 +1436,12 @@ fn consume_sse_event(
     };
 
     let events = extract_run_events(&parsed).unwrap_or_else(|| vec![parsed]);
-    for event in events {
-        process_event(app, request_id, &event, state, usage)?;
-    }
-
-    Ok(false)
+    state.sse_event = frame.event.filter(|name| name != "message");
+    let result = events
+        .iter()
+        .try_for_each(|event| process_event(app, request_id, event, state));
+    state.sse_event = None;
+    result.map(|_| false)
 }
 
 async fn run_non_streaming_fallback(
@@ -384,7 +1449,8 @@ async fn run_non_streaming_fallback(
     base_url: &str,
     input: &StreamRunInput,
     sse_status: Option<u16>,
-) -> Result<StreamOutcome, String> {
+) -> Result<StreamSummary, AppError> {
+    let started = Instant::now();
     let (status, response_text) = send_run_request(base_url, input).await?;
 
     if !status.is_success() {
@@ -392,10 +1458,13 @@ async fn run_non_streaming_fallback(
         emit(
             &app,
             &input.request_id,
-            StreamError {
-                kind: "stream_error",
-                request_id: input.request_id.clone(),
-                message: format!(
+            StreamError::failed(
+                &input.request_id,
+                ErrorCode::FallbackFailed {
+                    status: status.as_u16(),
+                    sse_status,
+                },
+                format!(
                     "Streaming failed{} and fallback /run returned {}{}",
                     sse_status
                         .map(|s| format!(" with HTTP {s}"))
@@ -407,8 +1476,8 @@ async fn run_non_streaming_fallback(
                         format!(" | backend: {body_excerpt}")
                     }
                 ),
-                retryable: true,
-            },
+                body_excerpt,
+            ),
         )?;
         emit(
             &app,
@@ -417,30 +1486,38 @@ async fn run_non_streaming_fallback(
                 kind: "stream_done",
                 request_id: input.request_id.clone(),
                 usage: None,
+                degradations: degradation::take(&app, &input.request_id),
+                metrics: None,
             },
         )?;
-        return Ok(StreamOutcome::Failed);
+        return Ok(StreamSummary::failed());
     }
 
-    let payload = serde_json::from_str::<Value>(&response_text).map_err(|e| {
-        format!(
+    let payload = serde_json::from_str::<Value>(&response_text).map_err(|e| AppError::Backend {
+        status: status.as_u16(),
+        message: format!(
             "Failed to parse fallback /run response: {e}. Body: {}",
             truncate(&response_text, 500)
-        )
+        ),
     })?;
-    let events = extract_run_events(&payload).ok_or_else(|| {
-        format!(
+    let events = extract_run_events(&payload).ok_or_else(|| AppError::Backend {
+        status: status.as_u16(),
+        message: format!(
             "Unexpected /run response shape: {}",
             summarize_json_shape(&payload)
-        )
+        ),
     })?;
 
-    let mut usage = None;
-    let mut state = StreamState::default();
+    let mut state = StreamState::started(started);
+    state.note_bytes(response_text.len());
     emit_progress_if_changed(&app, &input.request_id, &mut state, false)?;
     for event in events {
-        process_event(&app, &input.request_id, &event, &mut state, &mut usage)?;
+        process_event(&app, &input.request_id, &event, &mut state)?;
     }
+    flush_artifacts(&app, base_url, input, &mut state).await?;
+    publish_sections(&app, &input.request_id, &mut state, true)?;
+    detect_text_plan(&app, &input.request_id, &mut state)?;
+    report_empty_response(&app, &input.request_id, &mut state)?;
 
     emit(
         &app,
@@ -448,91 +1525,126 @@ async fn run_non_streaming_fallback(
         StreamDone {
             kind: "stream_done",
             request_id: input.request_id.clone(),
-            usage,
+            usage: state.usage.clone(),
+            degradations: degradation::take(&app, &input.request_id),
+            metrics: Some(state.metrics()),
         },
     )?;
     emit_progress_if_changed(&app, &input.request_id, &mut state, true)?;
 
-    if state.saw_error {
-        Ok(StreamOutcome::Failed)
-    } else {
-        Ok(StreamOutcome::Completed)
-    }
+    Ok(StreamSummary::from_state(&state, state.saw_error))
 }
 
 async fn send_run_request(
     base_url: &str,
     input: &StreamRunInput,
-) -> Result<(StatusCode, String), String> {
-    let fallback_body = json!({
+) -> Result<(StatusCode, String), AppError> {
+    let mut fallback_body = json!({
         "app_name": input.app_name,
         "user_id": input.user_id,
         "session_id": input.session_id,
         "streaming": false,
         "new_message": {
             "role": "user",
-            "parts": [{"text": input.text}]
+            "parts": attachments::message_parts(&input.text, input.parts.as_deref())
         }
     });
+    apply_run_config(&mut fallback_body, input);
 
-    let response = http_client_long()
+    let response = http_client_long(base_url)
         .post(run_fallback_url(base_url))
+        .headers(backend_auth::headers_for(base_url))
         .json(&fallback_body)
         .send()
         .await
-        .map_err(|e| format!("Fallback /run failed: {e}"))?;
+        .map_err(|e| AppError::from_reqwest(&e, "Fallback /run failed"))?;
 
     let status = response.status();
     let response_text = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read /run response body: {e}"))?;
+        .map_err(|e| AppError::from_reqwest(&e, "Failed to read /run response body"))?;
 
     Ok((status, response_text))
 }
 
-async fn ensure_adk_session(base_url: &str, input: &StreamRunInput) -> Result<(), String> {
+pub async fn live_adk_session_ids(live_sessions: &LiveSessions) -> Vec<String> {
+    live_sessions
+        .lock()
+        .await
+        .values()
+        .map(|live| live.adk_session_id.clone())
+        .collect()
+}
+
+async fn ensure_adk_session(
+    app: &AppHandle,
+    base_url: &str,
+    input: &StreamRunInput,
+) -> Result<(), AppError> {
     let url = format!(
         "{}/apps/{}/users/{}/sessions",
         base_url, input.app_name, input.user_id
     );
     let body = json!({ "sessionId": input.session_id });
 
-    let response = http_client()
+    let response = http_client(base_url)
         .post(url)
+        .headers(backend_auth::headers_for(base_url))
         .json(&body)
         .send()
         .await
-        .map_err(|e| format!("Failed to create ADK execution session: {e}"))?;
+        .map_err(|e| AppError::from_reqwest(&e, "Failed to create ADK execution session"))?;
 
     if response.status().is_success() {
+        if let Err(err) = SessionStore::from_app(app).and_then(|store| {
+            store.adk_session_record(
+                &input.session_id,
+                &input.request_id,
+                &input.app_name,
+                &input.user_id,
+            )
+        }) {
+            redact::log("[adk-gc] failed to track ADK session:", err);
+        }
         return Ok(());
     }
 
     let status = response.status();
     let body_text = response.text().await.unwrap_or_default();
-    Err(format!(
-        "Failed to create ADK execution session (HTTP {status}){}",
-        if body_text.trim().is_empty() {
-            "".to_string()
-        } else {
-            format!(" | backend: {}", truncate(body_text.trim(), 500))
-        }
+    Err(backend_error(
+        status,
+        format!("Failed to create ADK execution session (HTTP {status})"),
+        &body_text,
     ))
 }
 
+// A non-success backend response; the body it sent back is kept as the error's detail.
+fn backend_error(status: StatusCode, message: String, body: &str) -> AppError {
+    let error = AppError::from_status(status.as_u16(), message);
+    match body.trim() {
+        "" => error,
+        body => error.with_detail(truncate(body, 800)),
+    }
+}
+
 async fn replay_history(
+    app: &AppHandle,
     base_url: &str,
     input: &StreamRunInput,
     replay_messages: &[ReplayMessage],
     cancel: &CancellationToken,
-) -> Result<(), String> {
-    for (index, message) in replay_messages.iter().enumerate() {
+) -> Result<(), AppError> {
+    let replay_texts: Vec<String> = match input.replay_mode.unwrap_or_default() {
+        ReplayMode::PerMessage => replay_messages.iter().map(replay_text).collect(),
+        ReplayMode::Collapsed => vec![collapsed_replay_text(replay_messages)],
+    };
+    for (index, replay_text) in replay_texts.iter().enumerate() {
         if cancel.is_cancelled() {
-            return Err("Replay cancelled.".to_string());
+            return Err(AppError::Internal("Replay cancelled.".to_string())
+                .with_code(ErrorCode::RunCancelled));
         }
 
-        let replay_text = replay_text(message);
         let body = json!({
             "app_name": input.app_name,
             "user_id": input.user_id,
@@ -544,40 +1656,78 @@ async fn replay_history(
             }
         });
 
-        let response = http_client_long()
+        let response = http_client_long(base_url)
             .post(run_fallback_url(base_url))
+            .headers(backend_auth::headers_for(base_url))
             .json(&body)
             .send()
             .await
             .map_err(|e| {
-                format!(
-                    "Failed replay request {} of {}: {e}",
-                    index + 1,
-                    replay_messages.len()
+                AppError::from_reqwest(
+                    &e,
+                    &format!(
+                        "Failed replay request {} of {}",
+                        index + 1,
+                        replay_texts.len()
+                    ),
                 )
             })?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body_text = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Replay request {} of {} returned {}{}",
-                index + 1,
-                replay_messages.len(),
+            return Err(backend_error(
                 status,
-                if body_text.trim().is_empty() {
-                    "".to_string()
-                } else {
-                    format!(" | backend: {}", truncate(body_text.trim(), 500))
-                }
+                format!(
+                    "Replay request {} of {} returned {status}",
+                    index + 1,
+                    replay_texts.len()
+                ),
+                &body_text,
             ));
         }
+
+        let (percent, stage) = replay_progress(index + 1, replay_texts.len());
+        emit(
+            app,
+            &input.request_id,
+            StreamProgress {
+                kind: "stream_progress",
+                request_id: input.request_id.clone(),
+                percent,
+                stage,
+                tools_completed: 0,
+                tools_total: 0,
+            },
+        )?;
     }
 
     Ok(())
 }
 
-fn replay_text(message: &ReplayMessage) -> String {
+// Replay fills the gap between the 5% open marker and the 12% heuristic floor.
+fn replay_progress(replayed: usize, total: usize) -> (u8, String) {
+    let total = total.max(1);
+    let replayed = replayed.min(total);
+    let percent = 5 + (replayed * 6 / total) as u8;
+    (
+        percent,
+        format!("Rehydrating context (replayed {replayed} of {total})"),
+    )
+}
+
+// Non-env overrides ride along as session state; launch_run has already rejected invalid maps.
+fn apply_run_config(body: &mut Value, input: &StreamRunInput) {
+    if let Some(delta) = run_env::plan(input.env_overrides.as_ref())
+        .ok()
+        .as_ref()
+        .and_then(run_env::state_delta)
+    {
+        body["state_delta"] = delta;
+    }
+}
+
+pub fn replay_text(message: &ReplayMessage) -> String {
     let role = message.role.trim().to_ascii_lowercase();
     if role == "user" {
         return message.text.clone();
@@ -590,49 +1740,67 @@ fn replay_text(message: &ReplayMessage) -> String {
     format!("Previous context:\n{}", message.text)
 }
 
+pub fn collapsed_replay_text(replay_messages: &[ReplayMessage]) -> String {
+    let mut text = "Previous conversation:".to_string();
+    for message in replay_messages {
+        let role = message.role.trim().to_ascii_lowercase();
+        let speaker = match role.as_str() {
+            "user" => "User",
+            "assistant" | "model" => "Assistant",
+            _ => "Context",
+        };
+        text.push_str(&format!("\n\n{speaker}:\n{}", message.text.trim()));
+    }
+    text
+}
+
 async fn send_run_sse_request(
     base_url: &str,
     input: &StreamRunInput,
 ) -> Result<reqwest::Response, SseFailure> {
-    let body = json!({
+    let mut body = json!({
         "app_name": input.app_name,
         "user_id": input.user_id,
         "session_id": input.session_id,
         "streaming": true,
         "new_message": {
             "role": "user",
-            "parts": [{"text": input.text}]
+            "parts": attachments::message_parts(&input.text, input.parts.as_deref())
         }
     });
+    apply_run_config(&mut body, input);
 
-    http_client_stream()
+    http_client_stream(base_url)
         .post(run_sse_url(base_url))
+        .headers(backend_auth::headers_for(base_url))
         .header("Accept", "text/event-stream")
         .json(&body)
         .send()
         .await
         .map_err(|e| SseFailure {
             status: None,
+            retryable: e.is_timeout() || e.is_connect(),
+            retry_after: None,
             message: format!("error sending request to /run_sse: {e}"),
         })
 }
 
-fn http_client_long() -> Client {
-    Client::builder()
+fn http_client_long(url: &str) -> Client {
+    crate::http::backend_client_builder(url)
         .timeout(std::time::Duration::from_secs(600))
         .build()
         .expect("reqwest client should build")
 }
 
-fn http_client() -> Client {
-    Client::builder()
+fn http_client(url: &str) -> Client {
+    crate::http::backend_client_builder(url)
         .timeout(std::time::Duration::from_secs(120))
         .build()
         .expect("reqwest client should build")
 }
 
-fn http_client_stream() -> Client {
-    Client::builder()
+fn http_client_stream(url: &str) -> Client {
+    crate::http::backend_client_builder(url)
         .connect_timeout(Duration::from_secs(15))
         .timeout(Duration::from_secs(1800))
         .build()
@@ -644,8 +1812,15 @@ fn process_event(
     request_id: &str,
     event: &Value,
     state: &mut StreamState,
-    usage: &mut Option<Value>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    if let Some(id) = event_id(event) {
+        if !state.seen_event_ids.insert(id.to_string()) {
+            return Ok(());
+        }
+    }
+
+    state.event_count += 1;
+
     if let Some(invocation_id) = extract_invocation_id(event) {
         if state.last_invocation_id.as_deref() != Some(invocation_id.as_str()) {
             state.last_invocation_id = Some(invocation_id.clone());
@@ -661,31 +1836,80 @@ fn process_event(
         }
     }
 
-    emit(
-        app,
-        request_id,
-        StreamRaw {
-            kind: "stream_event_raw",
-            request_id: request_id.to_string(),
-            event: event.clone(),
-        },
-    )?;
+    if debug_events::enabled(app, request_id) {
+        emit(
+            app,
+            request_id,
+            StreamRaw {
+                kind: "stream_event_raw",
+                request_id: request_id.to_string(),
+                event: event.clone(),
+                sse_event: state.sse_event.clone(),
+            },
+        )?;
+    }
 
     if let Some(message) = extract_error_message(event) {
         state.saw_error = true;
+        let error = match upstream_rate_limit(&message) {
+            Some(retry_after) => StreamError::rate_limited(request_id, message, retry_after),
+            None => StreamError::failed(
+                request_id,
+                ErrorCode::AgentError,
+                format!("The agent reported an error: {message}"),
+                event["error"].to_string(),
+            ),
+        };
+        emit(app, request_id, error)?;
+    }
+
+    let lane = extract_event_source(event)
+        .filter(|author| author != "user")
+        .map(|author| agent_lane(state, author));
+
+    if let Some(finish) = extract_finish_signal(event).filter(|_| !is_partial_event(event)) {
+        let kind = match finish.category {
+            "truncated" => "truncated_response",
+            "safety" | "blocked" => "safety_block",
+            _ => "abnormal_finish",
+        };
+        degradation::note(app, request_id, kind, finish.message.clone());
         emit(
             app,
             request_id,
-            StreamError {
-                kind: "stream_error",
+            StreamFinish {
+                kind: "stream_finish",
                 request_id: request_id.to_string(),
-                message,
-                retryable: true,
+                reason: finish.reason,
+                category: finish.category,
+                message: finish.message,
+                source: extract_event_source(event),
+                safety: finish.safety,
             },
         )?;
     }
 
     for tool in extract_tool_signals(event) {
+        state.brave_calls += tool.brave_calls;
+        if let Some(index) = lane {
+            let lane = &mut state.agents[index];
+            match tool.phase {
+                "start" => lane.tools_started += 1,
+                "done" => {
+                    lane.tools_completed += 1;
+                    lane.tools_started = lane.tools_started.max(lane.tools_completed);
+                }
+                _ => {}
+            }
+        }
+        if let Some((kind, message)) = &tool.problem {
+            degradation::note(
+                app,
+                request_id,
+                kind,
+                format!("{}: {}", tool.name, truncate(message, 200)),
+            );
+        }
         if tool.phase == "start" {
             state.tools_started += 1;
         } else if tool.phase == "done" {
@@ -709,7 +1933,69 @@ fn process_event(
         )?;
     }
 
-    if let Some(full_text) = extract_model_text(event) {
+    for part in extract_artifact_parts(event) {
+        publish_artifact(app, request_id, state, part)?;
+    }
+    for artifact in extract_artifact_refs(event) {
+        if !state.pending_artifacts.contains(&artifact) {
+            state.pending_artifacts.push(artifact);
+        }
+    }
+
+    if let Some(thought) = extract_thought_text(event) {
+        let full_thought = if is_partial_event(event) {
+            format!("{}{}", state.streamed_thoughts, thought)
+        } else {
+            thought
+        };
+        if full_thought != state.streamed_thoughts {
+            emit(
+                app,
+                request_id,
+                StreamThought {
+                    kind: "stream_thought",
+                    request_id: request_id.to_string(),
+                    text: full_thought.clone(),
+                    source: extract_event_source(event),
+                },
+            )?;
+            state.streamed_thoughts = full_thought;
+        }
+    }
+
+    if let Some(event_text) = extract_model_text(event) {
+        if state.first_text_ms.is_none() {
+            state.first_text_ms = Some(state.elapsed_ms());
+        }
+        if let Some(index) = lane {
+            state.agents[index].saw_text = true;
+        }
+        let full_text = if is_partial_event(event) {
+            format!("{}{}", state.streamed_text, event_text)
+        } else {
+            event_text
+        };
+
+        if let Some((delta, reset)) = compute_delta(&state.streamed_text, &full_text) {
+            if reset {
+                state.emitted_sections.clear();
+            }
+            emit(
+                app,
+                request_id,
+                StreamDelta {
+                    kind: "stream_delta",
+                    request_id: request_id.to_string(),
+                    text: delta,
+                    reset,
+                    source: extract_event_source(event),
+                    sse_event: state.sse_event.clone(),
+                },
+            )?;
+        }
+        state.streamed_text = full_text.clone();
+        publish_sections(app, request_id, state, false)?;
+
         let normalized = full_text.trim().to_string();
         if !normalized.is_empty() && normalized != state.last_model_text {
             emit(
@@ -720,6 +2006,7 @@ fn process_event(
                     request_id: request_id.to_string(),
                     text: normalized.clone(),
                     source: extract_event_source(event),
+                    sse_event: state.sse_event.clone(),
                 },
             )?;
             state.saw_model_text = true;
@@ -727,13 +2014,47 @@ fn process_event(
         }
     }
 
-    if let Some(u) = event.get("usageMetadata") {
-        *usage = Some(u.clone());
+    if let Some(plan) = plan_detect::from_event(event) {
+        if state.plan.as_ref() != Some(&plan) {
+            emit_plan(app, request_id, &plan)?;
+            state.plan = Some(plan);
+        }
+    }
+
+    if let Some(progress) = extract_backend_progress(event) {
+        state.backend_progress = Some(progress);
+    }
+
+    // Partial chunks repeat the usage of the final event for the same call, so only count final events.
+    if let Some(u) = event
+        .get("usageMetadata")
+        .filter(|_| !is_partial_event(event))
+    {
+        accumulate_usage(&mut state.usage, u);
     }
 
     emit_progress_if_changed(app, request_id, state, false)
 }
 
+fn is_partial_event(event: &Value) -> bool {
+    event
+        .get("partial")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+// Returns the text appended since `previous`, or the whole text with `reset` when it diverged.
+fn compute_delta(previous: &str, next: &str) -> Option<(String, bool)> {
+    if next.is_empty() || next == previous {
+        return None;
+    }
+
+    match next.strip_prefix(previous) {
+        Some(appended) => Some((appended.to_string(), false)),
+        None => Some((next.to_string(), true)),
+    }
+}
+
 fn extract_event_source(event: &Value) -> Option<String> {
     event
         .get("author")
@@ -798,6 +2119,15 @@ fn summarize_json_shape(value: &Value) -> String {
 }
 
 fn extract_model_text(event: &Value) -> Option<String> {
+    model_part_text(event, false)
+}
+
+// Gemini marks reasoning parts with `thought: true`; they never belong in the report text.
+fn extract_thought_text(event: &Value) -> Option<String> {
+    model_part_text(event, true)
+}
+
+fn model_part_text(event: &Value, thoughts: bool) -> Option<String> {
     let content = event.get("content")?;
     let role = content.get("role").and_then(Value::as_str);
     let author = event.get("author").and_then(Value::as_str);
@@ -810,6 +2140,10 @@ fn extract_model_text(event: &Value) -> Option<String> {
     let parts = content.get("parts")?.as_array()?;
     let mut out = String::new();
     for part in parts {
+        let is_thought = part.get("thought").and_then(Value::as_bool) == Some(true);
+        if is_thought != thoughts {
+            continue;
+        }
         if let Some(text) = part.get("text").and_then(Value::as_str) {
             out.push_str(text);
         }
@@ -839,6 +2173,80 @@ fn extract_error_message(event: &Value) -> Option<String> {
     None
 }
 
+// Normal stops (`STOP`) are ignored unless a safety rating reports a block.
+fn extract_finish_signal(event: &Value) -> Option<FinishSignal> {
+    let field = |camel: &str, snake: &str| event.get(camel).or_else(|| event.get(snake));
+    let safety: Vec<SafetyFlag> = field("safetyRatings", "safety_ratings")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|rating| {
+            let probability = rating
+                .get("probability")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let blocked = rating
+                .get("blocked")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let flagged = blocked || matches!(probability.as_deref(), Some("MEDIUM" | "HIGH"));
+            flagged.then(|| SafetyFlag {
+                category: rating
+                    .get("category")
+                    .and_then(Value::as_str)
+                    .unwrap_or("UNKNOWN")
+                    .to_string(),
+                probability,
+                blocked,
+            })
+        })
+        .collect();
+
+    let reason = field("finishReason", "finish_reason")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            field("promptFeedback", "prompt_feedback")
+                .and_then(|f| f.get("blockReason").or_else(|| f.get("block_reason")))
+                .and_then(Value::as_str)
+        })
+        .map(|r| r.trim().to_ascii_uppercase())
+        .filter(|r| !r.is_empty())?;
+    let normal = matches!(reason.as_str(), "STOP" | "FINISH_REASON_UNSPECIFIED");
+    if normal && !safety.iter().any(|flag| flag.blocked) {
+        return None;
+    }
+
+    let (category, message) = match reason.as_str() {
+        "MAX_TOKENS" => (
+            "truncated",
+            "The response hit the model's output token limit and was cut off.".to_string(),
+        ),
+        "SAFETY" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => (
+            "safety",
+            format!("The response was blocked by the model's safety filters ({reason})."),
+        ),
+        "RECITATION" => (
+            "blocked",
+            "The response stopped to avoid reciting source material verbatim.".to_string(),
+        ),
+        "MALFORMED_FUNCTION_CALL" => (
+            "tool_call",
+            "The model produced a malformed tool call and stopped.".to_string(),
+        ),
+        _ if normal => (
+            "safety",
+            "Part of the response was blocked by the model's safety filters.".to_string(),
+        ),
+        _ => ("other", format!("The response ended early ({reason}).")),
+    };
+    Some(FinishSignal {
+        reason,
+        category,
+        message,
+        safety,
+    })
+}
+
 fn extract_tool_signals(event: &Value) -> Vec<ToolSignal> {
     let mut out = Vec::new();
     let parts = event
@@ -869,6 +2277,8 @@ fn extract_tool_signals(event: &Value) -> Vec<ToolSignal> {
                 name,
                 query,
                 detail,
+                brave_calls: 0,
+                problem: None,
             });
         }
 
@@ -881,14 +2291,16 @@ fn extract_tool_signals(event: &Value) -> Vec<ToolSignal> {
                 .and_then(Value::as_str)
                 .unwrap_or("tool")
                 .to_string();
-            let detail = function_response
-                .get("response")
-                .and_then(summarize_response_shape);
+            let response = function_response.get("response");
+            let detail = response.and_then(summarize_response_shape);
+            let brave_calls = brave_call_count(&name, response);
             out.push(ToolSignal {
                 phase: "done",
                 name,
                 query: None,
                 detail,
+                brave_calls,
+                problem: response.and_then(degradation::tool_problem),
             });
         }
     }
@@ -896,6 +2308,18 @@ fn extract_tool_signals(event: &Value) -> Vec<ToolSignal> {
     out
 }
 
+fn brave_call_count(tool_name: &str, response: Option<&Value>) -> u32 {
+    match tool_name {
+        "search_brave" => 1,
+        "search_seo_intent" | "search_review_sites" | "search_jobs_signal" => response
+            .and_then(|r| r.get("queries").or_else(|| r.pointer("/result/queries")))
+            .and_then(Value::as_array)
+            .map(|queries| queries.len() as u32)
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
 fn extract_search_query(args: &Value) -> Option<String> {
     let mut queries = Vec::new();
     collect_queries(args, &mut queries, 0);
@@ -997,11 +2421,49 @@ fn truncate(text: &str, max_chars: usize) -> String {
     out
 }
 
+// Agents may report progress in `customMetadata` (or `metadata`), either flat or under `progress`:
+// `{"stage": "Scoring competitors", "fraction": 0.4}`.
+fn extract_backend_progress(event: &Value) -> Option<BackendProgress> {
+    ["customMetadata", "custom_metadata", "metadata"]
+        .iter()
+        .filter_map(|key| event.get(*key))
+        .map(|meta| meta.get("progress").unwrap_or(meta))
+        .find_map(|progress| {
+            let stage = progress
+                .get("stage")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string);
+            let fraction = progress
+                .get("fraction")
+                .and_then(Value::as_f64)
+                .filter(|f| f.is_finite())
+                .map(|f| f.clamp(0.0, 1.0));
+            (stage.is_some() || fraction.is_some()).then_some(BackendProgress { stage, fraction })
+        })
+}
+
 fn progress_snapshot(state: &StreamState, done: bool) -> (u8, String) {
     if done {
         return (100, "Complete".to_string());
     }
 
+    let (percent, stage) = heuristic_progress(state);
+    match &state.backend_progress {
+        // 100 is reserved for the done event.
+        Some(reported) => (
+            reported
+                .fraction
+                .map(|f| ((f * 100.0).round() as u8).min(99))
+                .unwrap_or(percent),
+            reported.stage.clone().unwrap_or(stage),
+        ),
+        None => (percent, stage),
+    }
+}
+
+fn heuristic_progress(state: &StreamState) -> (u8, String) {
     if state.tools_started > 0 {
         let total = state.tools_started.max(state.tools_completed).max(1);
         let completed = state.tools_completed.min(total);
@@ -1026,12 +2488,71 @@ fn progress_snapshot(state: &StreamState, done: bool) -> (u8, String) {
     (12, "Understanding request".to_string())
 }
 
+// Marks `author` as the active sub-agent, adding a lane the first time it is seen.
+fn agent_lane(state: &mut StreamState, author: String) -> usize {
+    let index = match state.agents.iter().position(|lane| lane.author == author) {
+        Some(index) => index,
+        None => {
+            state.agents.push(AgentLane {
+                author: author.clone(),
+                ..AgentLane::default()
+            });
+            state.agents.len() - 1
+        }
+    };
+    state.active_agent = Some(author);
+    index
+}
+
+// The pipeline runs its sub-agents one after another, so any lane other than the latest author has finished.
+fn agent_lanes(state: &StreamState, done: bool) -> Vec<AgentLaneProgress> {
+    state
+        .agents
+        .iter()
+        .map(|lane| {
+            let active = !done && state.active_agent.as_deref() == Some(lane.author.as_str());
+            AgentLaneProgress {
+                author: lane.author.clone(),
+                status: if active { "active" } else { "done" },
+                tools_completed: lane.tools_completed,
+                tools_total: lane.tools_started,
+                saw_text: lane.saw_text,
+            }
+        })
+        .collect()
+}
+
+fn emit_agent_progress_if_changed(
+    app: &AppHandle,
+    request_id: &str,
+    state: &mut StreamState,
+    done: bool,
+) -> Result<(), AppError> {
+    let agents = agent_lanes(state, done);
+    if agents.is_empty() || state.last_agent_lanes.as_ref() == Some(&agents) {
+        return Ok(());
+    }
+    state.last_agent_lanes = Some(agents.clone());
+
+    emit(
+        app,
+        request_id,
+        StreamAgentProgress {
+            kind: "stream_agent_progress",
+            request_id: request_id.to_string(),
+            active_agent: state.active_agent.clone().filter(|_| !done),
+            agents,
+        },
+    )
+}
+
 fn emit_progress_if_changed(
     app: &AppHandle,
     request_id: &str,
     state: &mut StreamState,
     done: bool,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    emit_agent_progress_if_changed(app, request_id, state, done)?;
     let (percent, stage) = progress_snapshot(state, done);
     let changed = state.last_progress_percent != Some(percent)
         || state
@@ -1060,21 +2581,476 @@ fn emit_progress_if_changed(
     )
 }
 
-fn emit<T: Serialize + Clone>(app: &AppHandle, request_id: &str, payload: T) -> Result<(), String> {
-    let event_name = format!("agent-stream:{request_id}");
-    app.emit(&event_name, payload)
-        .map_err(|e| format!("failed to emit stream event: {e}"))
+fn sse_idle_timeout(raw: Option<String>) -> Option<Duration> {
+    match raw.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        None => Some(DEFAULT_SSE_IDLE_TIMEOUT),
+        Some(value) => match value.parse::<u64>() {
+            Ok(0) => None,
+            Ok(secs) => Some(Duration::from_secs(secs)),
+            Err(_) => Some(DEFAULT_SSE_IDLE_TIMEOUT),
+        },
+    }
+}
+
+async fn idle_sleep(limit: Option<Duration>) {
+    match limit {
+        Some(limit) => tokio::time::sleep(limit).await,
+        None => std::future::pending().await,
+    }
+}
+
+fn accumulate_usage(total: &mut Option<Value>, usage: &Value) {
+    let (Some(Value::Object(sum)), Value::Object(next)) = (total.as_mut(), usage) else {
+        *total = Some(usage.clone());
+        return;
+    };
+    for (key, value) in next {
+        match (sum.get(key).and_then(Value::as_u64), value.as_u64()) {
+            (Some(a), Some(b)) => {
+                sum.insert(key.clone(), Value::from(a + b));
+            }
+            _ => {
+                sum.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+async fn wait_while_paused(pause: &mut watch::Receiver<bool>, cancel: &CancellationToken) -> bool {
+    tokio::select! {
+        _ = cancel.cancelled() => false,
+        _ = pause.wait_for(|paused| !*paused) => true,
+    }
+}
+
+fn publish_artifact(
+    app: &AppHandle,
+    request_id: &str,
+    state: &mut StreamState,
+    part: ArtifactPart,
+) -> Result<(), AppError> {
+    let key = redact::fingerprint(
+        part.data
+            .as_deref()
+            .or(part.uri.as_deref())
+            .unwrap_or_default(),
+    );
+    if !state.seen_artifacts.insert(key) {
+        return Ok(());
+    }
+
+    let name = artifacts::file_name(
+        part.name.as_deref(),
+        &part.mime_type,
+        state.seen_artifacts.len(),
+    );
+    let mut path = None;
+    let mut size_bytes = None;
+    if let Some(data) = &part.data {
+        let saved = artifacts::decode_data(data)
+            .ok_or_else(|| "artifact data is not valid base64".to_string())
+            .and_then(|bytes| {
+                size_bytes = Some(bytes.len() as u64);
+                artifacts::save(app, request_id, &name, &bytes)
+            });
+        match saved {
+            Ok(saved) => path = Some(saved.to_string_lossy().to_string()),
+            Err(err) => {
+                degradation::note(
+                    app,
+                    request_id,
+                    "artifact_unavailable",
+                    format!("{name}: {}", truncate(&err.to_string(), 200)),
+                );
+                return Ok(());
+            }
+        }
+    }
+
+    emit(
+        app,
+        request_id,
+        StreamArtifact {
+            kind: "stream_artifact",
+            request_id: request_id.to_string(),
+            name,
+            mime_type: part.mime_type,
+            path,
+            uri: part.uri,
+            size_bytes,
+        },
+    )
+}
+
+// Runs once the final text is known; a structured plan seen earlier in the stream takes precedence.
+fn detect_text_plan(
+    app: &AppHandle,
+    request_id: &str,
+    state: &mut StreamState,
+) -> Result<(), AppError> {
+    if state.plan.is_some() {
+        return Ok(());
+    }
+    if let Some(plan) = plan_detect::from_text(&state.last_model_text) {
+        emit_plan(app, request_id, &plan)?;
+        state.plan = Some(plan);
+    }
+    Ok(())
+}
+
+fn emit_plan(app: &AppHandle, request_id: &str, plan: &DetectedPlan) -> Result<(), AppError> {
+    emit(
+        app,
+        request_id,
+        StreamPlan {
+            kind: "stream_plan",
+            request_id,
+            plan,
+        },
+    )
+}
+
+// The trailing section may still be growing, so it is only published once the stream ends.
+fn publish_sections(
+    app: &AppHandle,
+    request_id: &str,
+    state: &mut StreamState,
+    finished: bool,
+) -> Result<(), AppError> {
+    let sections = split_sections(&state.streamed_text);
+    let ready = if finished {
+        sections.len()
+    } else {
+        sections.len().saturating_sub(1)
+    };
+
+    for (index, section) in sections.into_iter().take(ready).enumerate() {
+        if state.emitted_sections.get(index) == Some(&section) {
+            continue;
+        }
+        emit(
+            app,
+            request_id,
+            StreamSection {
+                kind: "stream_section",
+                request_id: request_id.to_string(),
+                index,
+                level: section.level,
+                title: section.title.clone(),
+                body: section.body.clone(),
+            },
+        )?;
+        if index < state.emitted_sections.len() {
+            state.emitted_sections[index] = section;
+        } else {
+            state.emitted_sections.push(section);
+        }
+    }
+    Ok(())
+}
+
+async fn flush_artifacts(
+    app: &AppHandle,
+    base_url: &str,
+    input: &StreamRunInput,
+    state: &mut StreamState,
+) -> Result<(), AppError> {
+    for artifact in std::mem::take(&mut state.pending_artifacts) {
+        match artifacts::fetch_artifact(base_url, input, &artifact).await {
+            Ok(part) => publish_artifact(app, &input.request_id, state, part)?,
+            Err(err) => degradation::note(
+                app,
+                &input.request_id,
+                "artifact_unavailable",
+                truncate(&err.to_string(), 200),
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn report_empty_response(
+    app: &AppHandle,
+    request_id: &str,
+    state: &mut StreamState,
+) -> Result<(), AppError> {
+    if state.saw_error || state.saw_model_text {
+        return Ok(());
+    }
+    state.saw_error = true;
+    emit(app, request_id, StreamError::empty_response(request_id))
+}
+
+// Derives a run's state from its buffered events; the live token and queue position take precedence.
+pub fn run_status(
+    request_id: &str,
+    session_id: Option<String>,
+    events: &[Value],
+    active: bool,
+    queue_position: Option<usize>,
+    paused: bool,
+) -> RunStatus {
+    let kind = |event: &Value| {
+        event
+            .get("kind")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let progress = events
+        .iter()
+        .rev()
+        .find(|event| kind(event).as_deref() == Some("stream_progress"));
+    let last_error = events
+        .iter()
+        .rev()
+        .find(|event| kind(event).as_deref() == Some("stream_error"));
+    let done = events
+        .iter()
+        .any(|event| kind(event).as_deref() == Some("stream_done"));
+    let cancelled = last_error
+        .and_then(|event| event.get("category"))
+        .and_then(Value::as_str)
+        == Some("cancelled");
+
+    let state = match (queue_position, active) {
+        (Some(_), _) => RunState::Queued,
+        (None, true) if !done => RunState::Active,
+        _ if cancelled => RunState::Cancelled,
+        _ => RunState::Finished,
+    };
+    RunStatus {
+        request_id: request_id.to_string(),
+        session_id,
+        state,
+        paused: paused && state == RunState::Active,
+        queue_position,
+        stage: progress
+            .and_then(|event| event.get("stage"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        percent: progress
+            .and_then(|event| event.get("percent"))
+            .and_then(Value::as_u64),
+        error: last_error
+            .and_then(|event| event.get("message"))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        last_seq: events
+            .iter()
+            .filter_map(|event| event.get("seq").and_then(Value::as_u64))
+            .max(),
+    }
+}
+
+const THROTTLED_KINDS: &[&str] = &["stream_delta", "stream_event_raw"];
+const DEFAULT_EMITS_PER_SEC: u64 = 20;
+
+#[derive(Debug, Default)]
+struct PendingEmits {
+    targets: Vec<String>,
+    session_id: Option<String>,
+    events: Vec<Value>,
+    last_flush: Option<Instant>,
+    flush_scheduled: bool,
+}
+
+impl PendingEmits {
+    // Returns the payloads to emit right away, plus the delay for a deferred flush when one must be scheduled.
+    fn offer(
+        &mut self,
+        request_id: &str,
+        event: Value,
+        interval: Duration,
+        now: Instant,
+    ) -> (Vec<Value>, Option<Duration>) {
+        let throttled = event
+            .get("kind")
+            .and_then(Value::as_str)
+            .is_some_and(|kind| THROTTLED_KINDS.contains(&kind));
+        if !throttled {
+            let mut ready: Vec<Value> = self.take_batch(request_id, now).into_iter().collect();
+            ready.push(event);
+            return (ready, None);
+        }
+
+        self.events.push(event);
+        let wait = self
+            .last_flush
+            .map_or(Duration::ZERO, |last| interval.saturating_sub(now - last));
+        if wait.is_zero() {
+            return (self.take_batch(request_id, now).into_iter().collect(), None);
+        }
+        if self.flush_scheduled {
+            return (Vec::new(), None);
+        }
+        self.flush_scheduled = true;
+        (Vec::new(), Some(wait))
+    }
+
+    fn take_batch(&mut self, request_id: &str, now: Instant) -> Option<Value> {
+        if self.events.is_empty() {
+            return None;
+        }
+        self.last_flush = Some(now);
+        let mut events = std::mem::take(&mut self.events);
+        if events.len() == 1 {
+            return events.pop();
+        }
+        Some(json!({
+            "kind": "stream_batch",
+            "requestId": request_id,
+            "events": events,
+        }))
+    }
+}
+
+// Coalesces deltas and raw events into at most one `stream_batch` per interval; every other kind
+// flushes whatever is pending and goes out immediately, so ordering is preserved.
+pub struct EmitThrottle {
+    interval: Option<Duration>,
+    pending: std::sync::Mutex<HashMap<String, PendingEmits>>,
+}
+
+impl EmitThrottle {
+    pub fn from_env() -> Self {
+        Self {
+            interval: emit_interval(std::env::var("PV_DESKTOP_STREAM_EMITS_PER_SEC").ok()),
+            pending: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn deliver(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        request_id: &str,
+        session_id: Option<&str>,
+        targets: &[String],
+        event: &Value,
+    ) -> Result<(), AppError> {
+        let Some(interval) = self.interval else {
+            return emit_to(app, session_id, targets, event);
+        };
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = pending.entry(request_id.to_string()).or_default();
+        entry.targets = targets.to_vec();
+        entry.session_id = session_id.map(str::to_string);
+        let (ready, flush_in) = entry.offer(request_id, event.clone(), interval, Instant::now());
+        // Emitting under the lock keeps deferred flushes from overtaking later events.
+        for payload in &ready {
+            emit_to(app, session_id, targets, payload)?;
+        }
+        if event.get("kind").and_then(Value::as_str) == Some("stream_done") {
+            pending.remove(request_id);
+        }
+
+        if let Some(delay) = flush_in {
+            let throttle = Arc::clone(self);
+            let app = app.clone();
+            let request_id = request_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                throttle.flush(&app, &request_id);
+            });
+        }
+        Ok(())
+    }
+
+    fn flush(&self, app: &AppHandle, request_id: &str) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = pending.get_mut(request_id) else {
+            return;
+        };
+        entry.flush_scheduled = false;
+        if let Some(batch) = entry.take_batch(request_id, Instant::now()) {
+            let _ = emit_to(app, entry.session_id.as_deref(), &entry.targets, &batch);
+        }
+    }
+}
+
+fn emit_interval(raw: Option<String>) -> Option<Duration> {
+    let per_sec = raw
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_EMITS_PER_SEC);
+    (per_sec > 0).then(|| Duration::from_millis((1_000 / per_sec).max(1)))
+}
+
+// Windows opened for one session only hear about that session's runs.
+fn emit_to(
+    app: &AppHandle,
+    session_id: Option<&str>,
+    targets: &[String],
+    payload: &Value,
+) -> Result<(), AppError> {
+    for target in targets {
+        app.emit_filter(target, payload, |window| {
+            windows::receives(window, session_id)
+        })
+        .map_err(|e| AppError::Internal(format!("failed to emit stream event: {e}")))?;
+    }
+    Ok(())
+}
+
+pub fn emit<T: Serialize + Clone>(
+    app: &AppHandle,
+    request_id: &str,
+    payload: T,
+) -> Result<(), AppError> {
+    let event_name = events::agent_stream(request_id);
+    let mut payload = serde_json::to_value(payload)
+        .map_err(|e| AppError::Internal(format!("failed to serialize stream event: {e}")))?;
+    let Some(state) = app.try_state::<AppState>() else {
+        return app
+            .emit(&event_name, payload)
+            .map_err(|e| AppError::Internal(format!("failed to emit stream event: {e}")));
+    };
+
+    let mut targets = vec![event_name];
+    let mut session_id = state.event_buffer.session_of(request_id);
+    if let Some(tag) = state.multi_runs.tag(request_id) {
+        multi_run::apply_tag(&mut payload, &tag);
+        targets.push(events::agent_stream(&tag.parent_request_id));
+        session_id = session_id.or_else(|| state.event_buffer.session_of(&tag.parent_request_id));
+    }
+    state
+        .event_buffer
+        .record(request_id, payload, |event: &Value| {
+            state
+                .emit_throttle
+                .deliver(app, request_id, session_id.as_deref(), &targets, event)
+        })
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, Instant};
+
     use serde_json::json;
 
     use super::{
-        extract_event_source, extract_invocation_id, extract_model_text, extract_run_events,
-        extract_tool_signals,
+        accumulate_usage, agent_lane, agent_lanes, collapsed_replay_text, compute_delta,
+        emit_interval, events_after_id, events_for_current_run, extract_backend_progress,
+        extract_event_source, extract_finish_signal, extract_invocation_id, extract_model_text,
+        extract_run_events, extract_thought_text, extract_tool_signals, parse_retry_after,
+        progress_snapshot, replay_progress, run_status, sse_idle_timeout, take_sse_line,
+        upstream_rate_limit, PendingEmits, ReplayMessage, RetryPolicy, RunState, SseFrame,
+        StreamError, StreamState,
     };
 
+    #[test]
+    fn stream_errors_carry_code_params_and_detail() {
+        let error = StreamError::rate_limited(
+            "r1",
+            "Quota exceeded".to_string(),
+            Some(Duration::from_secs(2)),
+        );
+        let value = serde_json::to_value(error).expect("serializes");
+        assert_eq!(value["kind"], "stream_error");
+        assert_eq!(value["code"], "rate_limited");
+        assert_eq!(value["params"], json!({"retryAfterMs": 2_000}));
+        assert_eq!(value["retryAfterMs"], 2_000);
+        assert_eq!(value["detail"], "Quota exceeded");
+    }
+
     #[test]
     fn extracts_only_model_text() {
         let model_event = json!({
@@ -1090,8 +3066,24 @@ mod tests {
             }
         });
 
+        let thinking_event = json!({
+            "content": {
+                "role": "model",
+                "parts": [{"text": "Weighing TAM...", "thought": true}, {"text": "Verdict: GO"}]
+            }
+        });
+
         assert_eq!(extract_model_text(&model_event), Some("Hello".to_string()));
         assert_eq!(extract_model_text(&tool_event), None);
+        assert_eq!(
+            extract_model_text(&thinking_event),
+            Some("Verdict: GO".to_string())
+        );
+        assert_eq!(
+            extract_thought_text(&thinking_event),
+            Some("Weighing TAM...".to_string())
+        );
+        assert_eq!(extract_thought_text(&model_event), None);
     }
 
     #[test]
@@ -1127,6 +3119,243 @@ mod tests {
         assert_eq!(signals[1].name, "brave_search");
     }
 
+    #[test]
+    fn counts_brave_calls_from_tool_responses() {
+        let event = json!({
+            "content": {
+                "parts": [
+                    {"functionResponse": {"name": "search_brave", "response": {"results": []}}},
+                    {"functionResponse": {"name": "search_seo_intent", "response": {"queries": ["a", "b", "c"]}}},
+                    {"functionCall": {"name": "search_brave", "args": {"query": "x"}}}
+                ]
+            }
+        });
+        let total: u32 = extract_tool_signals(&event)
+            .iter()
+            .map(|s| s.brave_calls)
+            .sum();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn parses_sse_idle_timeout() {
+        assert_eq!(sse_idle_timeout(None), Some(Duration::from_secs(300)));
+        assert_eq!(
+            sse_idle_timeout(Some("45".to_string())),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(sse_idle_timeout(Some("0".to_string())), None);
+        assert_eq!(
+            sse_idle_timeout(Some("soon".to_string())),
+            Some(Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn tracks_progress_per_agent_lane() {
+        let mut state = StreamState::default();
+        let search = agent_lane(&mut state, "search_agent".to_string());
+        state.agents[search].tools_started = 2;
+        state.agents[search].tools_completed = 1;
+        let report = agent_lane(&mut state, "report_agent".to_string());
+        state.agents[report].saw_text = true;
+        assert_eq!(agent_lane(&mut state, "report_agent".to_string()), report);
+
+        let lanes = agent_lanes(&state, false);
+        assert_eq!(lanes.len(), 2);
+        assert_eq!(
+            (
+                lanes[0].status,
+                lanes[0].tools_completed,
+                lanes[0].tools_total
+            ),
+            ("done", 1, 2)
+        );
+        assert_eq!((lanes[1].status, lanes[1].saw_text), ("active", true));
+        assert!(agent_lanes(&state, true)
+            .iter()
+            .all(|lane| lane.status == "done"));
+    }
+
+    #[test]
+    fn backend_progress_overrides_heuristic() {
+        let mut state = StreamState {
+            tools_started: 2,
+            ..StreamState::default()
+        };
+        assert_eq!(progress_snapshot(&state, false).0, 25);
+
+        let nested = json!({"customMetadata": {"progress": {"stage": "Scoring", "fraction": 0.4}}});
+        state.backend_progress = extract_backend_progress(&nested);
+        assert_eq!(
+            progress_snapshot(&state, false),
+            (40, "Scoring".to_string())
+        );
+
+        let stage_only = json!({"metadata": {"stage": "Drafting report"}});
+        state.backend_progress = extract_backend_progress(&stage_only);
+        assert_eq!(
+            progress_snapshot(&state, false),
+            (25, "Drafting report".to_string())
+        );
+
+        state.backend_progress =
+            extract_backend_progress(&json!({"customMetadata": {"fraction": 3}}));
+        assert_eq!(progress_snapshot(&state, false).0, 99);
+        assert_eq!(progress_snapshot(&state, true).0, 100);
+        assert!(extract_backend_progress(&json!({"metadata": {"invocationId": "x"}})).is_none());
+    }
+
+    #[test]
+    fn extracts_abnormal_finish_reasons() {
+        assert!(extract_finish_signal(&json!({"finishReason": "STOP"})).is_none());
+
+        let truncated = extract_finish_signal(&json!({"finishReason": "MAX_TOKENS"})).unwrap();
+        assert_eq!(truncated.category, "truncated");
+
+        let blocked = extract_finish_signal(&json!({
+            "finish_reason": "SAFETY",
+            "safetyRatings": [
+                {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true},
+                {"category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            (blocked.reason.as_str(), blocked.category),
+            ("SAFETY", "safety")
+        );
+        assert_eq!(blocked.safety.len(), 1);
+        assert!(blocked.safety[0].blocked);
+
+        let prompt_blocked = extract_finish_signal(
+            &json!({"promptFeedback": {"blockReason": "PROHIBITED_CONTENT"}}),
+        )
+        .unwrap();
+        assert_eq!(prompt_blocked.category, "safety");
+    }
+
+    #[test]
+    fn parses_retry_after_and_upstream_quota_errors() {
+        let now = std::time::UNIX_EPOCH + Duration::from_secs(784_111_717);
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        assert_eq!(
+            upstream_rate_limit("429 RESOURCE_EXHAUSTED. {'retryDelay': '27s'}"),
+            Some(Some(Duration::from_secs(27)))
+        );
+        assert_eq!(upstream_rate_limit("quota: 429"), Some(None));
+        assert_eq!(upstream_rate_limit("model overloaded"), None);
+    }
+
+    #[test]
+    fn derives_run_status_from_buffered_events() {
+        let events = vec![
+            json!({"kind": "stream_open", "seq": 1}),
+            json!({"kind": "stream_progress", "seq": 2, "percent": 40, "stage": "Researching"}),
+        ];
+        let active = run_status("r1", None, &events, true, None, true);
+        assert_eq!(active.state, RunState::Active);
+        assert!(active.paused);
+        assert_eq!(
+            (active.stage.as_deref(), active.percent, active.last_seq),
+            (Some("Researching"), Some(40), Some(2))
+        );
+
+        let queued = run_status("r1", None, &[], true, Some(2), false);
+        assert_eq!(
+            (queued.state, queued.queue_position),
+            (RunState::Queued, Some(2))
+        );
+
+        let mut cancelled = events.clone();
+        cancelled.push(json!({"kind": "stream_error", "seq": 3, "category": "cancelled", "message": "Run cancelled."}));
+        cancelled.push(json!({"kind": "stream_done", "seq": 4}));
+        let status = run_status("r1", None, &cancelled, true, None, true);
+        assert_eq!(status.state, RunState::Cancelled);
+        assert!(!status.paused);
+        assert_eq!(status.error.as_deref(), Some("Run cancelled."));
+        assert_eq!(
+            run_status("r1", None, &events, false, None, false).state,
+            RunState::Finished
+        );
+    }
+
+    #[test]
+    fn throttles_deltas_but_not_terminal_events() {
+        let interval = emit_interval(None).expect("throttling is on by default");
+        assert_eq!(interval, Duration::from_millis(50));
+        assert_eq!(emit_interval(Some("0".to_string())), None);
+
+        let start = Instant::now();
+        let delta = |text: &str| json!({"kind": "stream_delta", "text": text});
+        let mut pending = PendingEmits::default();
+        let (ready, flush_in) = pending.offer("r1", delta("a"), interval, start);
+        assert_eq!((ready.len(), flush_in), (1, None));
+
+        let soon = start + Duration::from_millis(10);
+        let (ready, flush_in) = pending.offer("r1", delta("b"), interval, soon);
+        assert!(ready.is_empty());
+        assert_eq!(flush_in, Some(Duration::from_millis(40)));
+        let (_, flush_in) = pending.offer("r1", delta("c"), interval, soon);
+        assert_eq!(flush_in, None);
+
+        let (ready, _) = pending.offer("r1", json!({"kind": "stream_done"}), interval, soon);
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0]["kind"], "stream_batch");
+        assert_eq!(ready[0]["events"].as_array().map(Vec::len), Some(2));
+        assert_eq!(ready[1]["kind"], "stream_done");
+    }
+
+    #[test]
+    fn reassembles_utf8_split_across_chunks() {
+        let bytes = "data: 市場調査\r\n\n".as_bytes();
+        let mut buffer = bytes[..8].to_vec();
+        assert_eq!(take_sse_line(&mut buffer), None);
+        buffer.extend_from_slice(&bytes[8..]);
+        assert_eq!(
+            take_sse_line(&mut buffer).as_deref(),
+            Some("data: 市場調査")
+        );
+        assert_eq!(take_sse_line(&mut buffer).as_deref(), Some(""));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn parses_sse_event_and_id_fields() {
+        let mut frame = SseFrame::default();
+        for line in [
+            ": keep-alive",
+            "event: agent_update",
+            "id: evt-7",
+            "data: {\"a\":",
+            "data:1}",
+        ] {
+            assert!(!frame.push_line(line));
+        }
+        assert!(frame.push_line(""));
+        assert_eq!(frame.event.as_deref(), Some("agent_update"));
+        assert_eq!(frame.id.as_deref(), Some("evt-7"));
+        assert_eq!(frame.data.join("\n"), "{\"a\":\n1}");
+
+        let events = vec![
+            json!({"id": "evt-6"}),
+            json!({"id": "evt-7"}),
+            json!({"id": "evt-8"}),
+        ];
+        let after = events_after_id(events.clone(), Some("evt-7"));
+        assert_eq!(after, vec![json!({"id": "evt-8"})]);
+        assert_eq!(events_after_id(events.clone(), Some("gone")).len(), 3);
+    }
+
     #[test]
     fn extracts_event_source() {
         let event = json!({"author": "reddit_summary_agent"});
@@ -1157,4 +3386,109 @@ mod tests {
         let extracted = extract_run_events(&wrapped).expect("events should exist");
         assert_eq!(extracted.len(), 2);
     }
+
+    #[test]
+    fn computes_appended_delta_and_resets_on_divergence() {
+        assert_eq!(
+            compute_delta("Hello", "Hello, world"),
+            Some((", world".to_string(), false))
+        );
+        assert_eq!(compute_delta("", "Hi"), Some(("Hi".to_string(), false)));
+        assert_eq!(compute_delta("Hello", "Hello"), None);
+        assert_eq!(
+            compute_delta("Draft plan", "Final report"),
+            Some(("Final report".to_string(), true))
+        );
+    }
+
+    #[test]
+    fn resume_selects_events_of_current_run() {
+        let events = vec![
+            json!({"id": "1", "author": "user", "invocationId": "inv-old"}),
+            json!({"id": "2", "author": "model", "invocationId": "inv-old"}),
+            json!({"id": "3", "author": "user", "invocationId": "inv-new"}),
+            json!({"id": "4", "author": "model", "invocationId": "inv-new"}),
+        ];
+
+        let by_invocation = events_for_current_run(events.clone(), Some("inv-new"));
+        assert_eq!(by_invocation.len(), 2);
+
+        let after_user = events_for_current_run(events, None);
+        assert_eq!(after_user.len(), 1);
+        assert_eq!(after_user[0]["id"], "4");
+    }
+
+    #[test]
+    fn retry_delay_grows_exponentially_with_bounded_jitter() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(1_000),
+            max_delay: Duration::from_millis(3_000),
+            jitter: 0.2,
+        };
+        assert_eq!(policy.delay_for(1, 0.5), Duration::from_millis(1_000));
+        assert_eq!(policy.delay_for(2, 0.5), Duration::from_millis(2_000));
+        assert_eq!(policy.delay_for(3, 0.5), Duration::from_millis(3_000));
+        assert_eq!(policy.delay_for(1, 0.0), Duration::from_millis(800));
+        assert!(policy.delay_for(1, 0.999) < Duration::from_millis(1_200));
+    }
+
+    #[test]
+    fn sums_usage_across_invocations() {
+        let mut total = None;
+        accumulate_usage(
+            &mut total,
+            &json!({"promptTokenCount": 100, "candidatesTokenCount": 20, "trafficType": "ON_DEMAND"}),
+        );
+        accumulate_usage(
+            &mut total,
+            &json!({"promptTokenCount": 50, "candidatesTokenCount": 5, "totalTokenCount": 55}),
+        );
+        let total = total.unwrap();
+        assert_eq!(total["promptTokenCount"], 150);
+        assert_eq!(total["candidatesTokenCount"], 25);
+        assert_eq!(total["totalTokenCount"], 55);
+        assert_eq!(total["trafficType"], "ON_DEMAND");
+    }
+
+    #[test]
+    fn metrics_track_first_byte_and_totals() {
+        let mut state = StreamState::started(Instant::now());
+        assert_eq!(state.metrics().time_to_first_byte_ms, None);
+        state.note_bytes(10);
+        state.note_bytes(5);
+        state.event_count = 2;
+        let metrics = state.metrics();
+        assert!(metrics.time_to_first_byte_ms.is_some());
+        assert_eq!(metrics.time_to_first_text_ms, None);
+        assert_eq!((metrics.bytes_received, metrics.event_count), (15, 2));
+    }
+
+    #[test]
+    fn collapses_history_into_one_context_message() {
+        let message = |role: &str, text: &str| ReplayMessage {
+            role: role.to_string(),
+            text: text.to_string(),
+        };
+        let text = collapsed_replay_text(&[
+            message("user", "Validate a meal-kit app"),
+            message("assistant", " Report v1 \n"),
+            message("system", "Memory: budget is small"),
+        ]);
+        assert_eq!(
+            text,
+            "Previous conversation:\n\nUser:\nValidate a meal-kit app\n\nAssistant:\nReport v1\n\nContext:\nMemory: budget is small"
+        );
+    }
+
+    #[test]
+    fn replay_progress_stays_below_heuristic_floor() {
+        assert_eq!(
+            replay_progress(1, 20),
+            (5, "Rehydrating context (replayed 1 of 20)".to_string())
+        );
+        assert_eq!(replay_progress(10, 20).0, 8);
+        assert_eq!(replay_progress(20, 20).0, 11);
+        assert_eq!(replay_progress(3, 0).0, 11);
+    }
 }
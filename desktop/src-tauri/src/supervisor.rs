@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::sleep;
+
+use crate::backend::BackendManager;
+use crate::commands::{self, AppState};
+use crate::events;
+use crate::redact;
+use crate::tray;
+use crate::types::BackendStatus;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Watches the spawned backend so a crash is reported (and, in supervisor mode, restarted) even
+// when the frontend is not polling backend_status.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(POLL_INTERVAL).await;
+            if let Err(err) = supervise(&app).await {
+                redact::log("[supervisor]", err);
+            }
+        }
+    });
+}
+
+async fn supervise(app: &AppHandle) -> Result<(), String> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| "App state is not available".to_string())?;
+
+    let delay = {
+        let mut backend = state.backend.lock().await;
+        if backend.check_exited()? {
+            let (status, _) = backend.status().await?;
+            report_exit(app, &backend, &status);
+        }
+        match backend.restart_delay() {
+            Some(delay) => delay,
+            None => return Ok(()),
+        }
+    };
+
+    sleep(delay).await;
+    let mut backend = state.backend.lock().await;
+    // The user may have started or stopped the backend while we waited.
+    if backend.restart_delay().is_none() {
+        return Ok(());
+    }
+    let keys = commands::backend_keys(app, &state, backend.key_profile())?;
+    let restarted = backend.restart_after_crash(&keys).await;
+    let (status, _) = backend.status().await?;
+    tray::show_backend_status(app, &status);
+    let _ = app.emit(&events::name(events::BACKEND_STATUS), &status);
+    restarted.map(|_| ()).map_err(String::from)
+}
+
+// Whoever reaps the exited child (this loop or the health watcher) reports it exactly once.
+pub fn report_exit(app: &AppHandle, backend: &BackendManager, status: &BackendStatus) {
+    let delay = backend.restart_delay();
+    let _ = app.emit(
+        &events::name(events::BACKEND_EXITED),
+        serde_json::json!({
+            "message": status.last_error,
+            "restartInMs": delay.map(|d| d.as_millis() as u64),
+            "restartsExhausted": backend.restarts_exhausted()
+        }),
+    );
+}
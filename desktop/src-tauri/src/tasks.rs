@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use serde_json::json;
+
+use crate::types::SessionTask;
+
+const MAX_TASKS: usize = 25;
+const TODOIST_TASKS_URL: &str = "https://api.todoist.com/rest/v2/tasks";
+const LINEAR_GRAPHQL_URL: &str = "https://api.linear.app/graphql";
+
+pub fn extract_next_steps(report: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_section = false;
+
+    for raw_line in report.lines() {
+        let line = raw_line.trim();
+        if line.starts_with('#') || is_bold_heading(line) {
+            let heading = line
+                .trim_start_matches('#')
+                .replace("**", "")
+                .to_ascii_lowercase();
+            in_section = heading.contains("next step")
+                || heading.contains("action item")
+                || heading.contains("recommended action")
+                || heading.contains("recommendation");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        let Some(item) = list_item_text(line) else {
+            continue;
+        };
+        if !item.is_empty() && !out.contains(&item) {
+            out.push(item);
+        }
+        if out.len() >= MAX_TASKS {
+            break;
+        }
+    }
+
+    out
+}
+
+fn is_bold_heading(line: &str) -> bool {
+    line.starts_with("**") && line.trim_end_matches(':').ends_with("**")
+}
+
+fn list_item_text(line: &str) -> Option<String> {
+    let rest = if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        rest
+    } else {
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))?
+    };
+
+    let text = rest
+        .trim_start_matches("[ ] ")
+        .trim_start_matches("[x] ")
+        .replace("**", "")
+        .trim()
+        .to_string();
+    Some(text)
+}
+
+pub fn to_markdown_checklist(title: &str, tasks: &[SessionTask]) -> String {
+    let mut out = format!("## Next steps: {}\n\n", title.trim());
+    for task in tasks {
+        out.push_str(&format!(
+            "- [{}] {}\n",
+            if task.done { "x" } else { " " },
+            task.title
+        ));
+    }
+    out
+}
+
+fn client() -> Client {
+    crate::http::client_builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("reqwest client should build")
+}
+
+pub async fn export_todoist(
+    token: &str,
+    project_id: Option<&str>,
+    tasks: &[SessionTask],
+) -> Result<usize, String> {
+    let mut exported = 0;
+    for task in tasks.iter().filter(|t| !t.done) {
+        let mut body = json!({ "content": task.title });
+        if let Some(project_id) = project_id {
+            body["project_id"] = json!(project_id);
+        }
+
+        let response = client()
+            .post(TODOIST_TASKS_URL)
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to call Todoist: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Todoist returned HTTP {} after exporting {exported} task(s).",
+                response.status()
+            ));
+        }
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+pub async fn export_linear(
+    api_key: &str,
+    team_id: &str,
+    tasks: &[SessionTask],
+) -> Result<usize, String> {
+    let mut exported = 0;
+    for task in tasks.iter().filter(|t| !t.done) {
+        let body = json!({
+            "query": "mutation IssueCreate($input: IssueCreateInput!) { issueCreate(input: $input) { success } }",
+            "variables": { "input": { "teamId": team_id, "title": task.title } }
+        });
+
+        let response = client()
+            .post(LINEAR_GRAPHQL_URL)
+            .header("Authorization", api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to call Linear: {e}"))?;
+        let status = response.status();
+        let payload = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| format!("Failed to parse Linear response: {e}"))?;
+        let created = payload
+            .pointer("/data/issueCreate/success")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        if !status.is_success() || !created {
+            return Err(format!(
+                "Linear rejected the issue (HTTP {status}) after exporting {exported} task(s)."
+            ));
+        }
+        exported += 1;
+    }
+    Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_next_steps;
+
+    #[test]
+    fn extracts_list_items_under_next_steps_heading() {
+        let report = "## Verdict\n- Promising\n\n## Recommended Next Steps\n1. Interview 10 dog owners\n2. **Build** a landing page\n- [ ] Price test at $9/month\n\n## Sources\n- example.com\n";
+        assert_eq!(
+            extract_next_steps(report),
+            vec![
+                "Interview 10 dog owners".to_string(),
+                "Build a landing page".to_string(),
+                "Price test at $9/month".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_empty_when_report_has_no_next_steps() {
+        assert!(extract_next_steps("## Verdict\n- Promising\n").is_empty());
+    }
+}
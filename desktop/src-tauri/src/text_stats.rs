@@ -0,0 +1,38 @@
+use crate::types::TextStats;
+
+const WORDS_PER_MINUTE: u32 = 230;
+
+pub fn measure(text: &str) -> TextStats {
+    let word_count = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count() as u32;
+    TextStats {
+        word_count,
+        char_count: text.chars().filter(|c| !c.is_whitespace()).count() as u32,
+        reading_minutes: word_count.div_ceil(WORDS_PER_MINUTE),
+    }
+}
+
+pub fn applies_to_role(role: &str) -> bool {
+    matches!(
+        role.trim().to_ascii_lowercase().as_str(),
+        "assistant" | "model"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::measure;
+
+    #[test]
+    fn counts_words_and_rounds_reading_time_up() {
+        let stats = measure("## Verdict\n\n- Strong demand — but **thin** margins.");
+        assert_eq!(stats.word_count, 6);
+        assert_eq!(stats.reading_minutes, 1);
+
+        let long = "word ".repeat(461);
+        assert_eq!(measure(&long).reading_minutes, 3);
+        assert_eq!(measure("   ").reading_minutes, 0);
+    }
+}
@@ -0,0 +1,385 @@
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::debug_bundle::entry_stem;
+use crate::deep_link;
+use crate::types::{SessionMessage, TranscriptFormat};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTranscript {
+    pub request_id: String,
+    pub session_id: Option<String>,
+    pub started_at_ms: Option<i64>,
+    pub finished_at_ms: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub outcome: &'static str,
+    pub messages: Vec<TranscriptMessage>,
+    pub stages: Vec<TranscriptStage>,
+    pub tools: Vec<TranscriptTool>,
+    pub errors: Vec<String>,
+    pub citations: Vec<String>,
+    pub final_text: Option<String>,
+    pub usage: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub role: String,
+    pub text: String,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptStage {
+    pub offset_ms: i64,
+    pub percent: u64,
+    pub stage: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptTool {
+    pub offset_ms: i64,
+    pub phase: String,
+    pub name: String,
+    pub query: Option<String>,
+    pub detail: Option<String>,
+}
+
+// `events` are the buffered stream payloads with their record time; `messages` are the session's
+// stored messages, trimmed to the prompt that started this run and what followed it.
+pub fn build(
+    request_id: &str,
+    session_id: Option<String>,
+    events: &[(i64, Value)],
+    messages: &[SessionMessage],
+) -> RunTranscript {
+    let started_at_ms = events.first().map(|(at, _)| *at);
+    let finished_at_ms = events
+        .iter()
+        .find(|(_, e)| kind(e) == "stream_done")
+        .map(|(at, _)| *at);
+    let offset = |at: i64| at - started_at_ms.unwrap_or(at);
+    let text =
+        |event: &Value, key: &str| event.get(key).and_then(Value::as_str).map(str::to_string);
+
+    let mut stages: Vec<TranscriptStage> = Vec::new();
+    let mut tools = Vec::new();
+    let mut errors = Vec::new();
+    let mut final_text = None;
+    let mut usage = None;
+    for (at, event) in events {
+        match kind(event) {
+            "stream_progress" => {
+                let stage = text(event, "stage").unwrap_or_default();
+                if stages.last().is_none_or(|last| last.stage != stage) {
+                    stages.push(TranscriptStage {
+                        offset_ms: offset(*at),
+                        percent: event.get("percent").and_then(Value::as_u64).unwrap_or(0),
+                        stage,
+                    });
+                }
+            }
+            "stream_tool" => tools.push(TranscriptTool {
+                offset_ms: offset(*at),
+                phase: text(event, "phase").unwrap_or_default(),
+                name: text(event, "name").unwrap_or_default(),
+                query: text(event, "query"),
+                detail: text(event, "detail"),
+            }),
+            "stream_error" => errors.extend(text(event, "message")),
+            "stream_message" => final_text = text(event, "text"),
+            "stream_done" => usage = event.get("usage").filter(|u| !u.is_null()).cloned(),
+            _ => {}
+        }
+    }
+
+    let mut citations = Vec::new();
+    let sources = final_text.iter().chain(
+        tools
+            .iter()
+            .flat_map(|t| t.query.iter().chain(t.detail.iter())),
+    );
+    for source in sources {
+        for url in extract_urls(source) {
+            if !citations.contains(&url) {
+                citations.push(url);
+            }
+        }
+    }
+    for (_, event) in events.iter().filter(|(_, e)| kind(e) == "stream_artifact") {
+        if let Some(uri) = text(event, "uri").filter(|u| !citations.contains(u)) {
+            citations.push(uri);
+        }
+    }
+
+    let outcome = match (finished_at_ms, errors.is_empty()) {
+        (None, _) => "running",
+        (Some(_), true) => "completed",
+        (Some(_), false) => "failed",
+    };
+
+    RunTranscript {
+        request_id: request_id.to_string(),
+        session_id,
+        started_at_ms,
+        finished_at_ms,
+        duration_ms: started_at_ms.zip(finished_at_ms).map(|(s, f)| f - s),
+        outcome,
+        messages: run_messages(messages, started_at_ms, finished_at_ms),
+        stages,
+        tools,
+        errors,
+        citations,
+        final_text,
+        usage,
+    }
+}
+
+pub fn render_markdown(transcript: &RunTranscript) -> String {
+    let mut out = format!("# Run transcript `{}`\n\n", transcript.request_id);
+    if let Some(session_id) = &transcript.session_id {
+        out.push_str(&format!(
+            "- Session: [`{session_id}`]({})\n",
+            deep_link::session_url(session_id)
+        ));
+    }
+    out.push_str(&format!("- Outcome: {}\n", transcript.outcome));
+    if let Some(duration) = transcript.duration_ms {
+        out.push_str(&format!("- Duration: {:.1}s\n", duration as f64 / 1000.0));
+    }
+
+    if !transcript.messages.is_empty() {
+        out.push_str("\n## Messages\n");
+        for message in &transcript.messages {
+            out.push_str(&format!(
+                "\n**{}**\n\n{}\n",
+                message.role,
+                message.text.trim()
+            ));
+        }
+    }
+    if !transcript.stages.is_empty() {
+        out.push_str("\n## Timeline\n\n");
+        for stage in &transcript.stages {
+            out.push_str(&format!(
+                "- +{} {}% {}\n",
+                format_offset(stage.offset_ms),
+                stage.percent,
+                stage.stage
+            ));
+        }
+    }
+    if !transcript.tools.is_empty() {
+        out.push_str("\n## Tool calls\n\n");
+        for tool in &transcript.tools {
+            out.push_str(&format!(
+                "- +{} `{}` {}",
+                format_offset(tool.offset_ms),
+                tool.name,
+                tool.phase
+            ));
+            if let Some(query) = &tool.query {
+                out.push_str(&format!(" — {query}"));
+            }
+            if let Some(detail) = &tool.detail {
+                out.push_str(&format!(" ({detail})"));
+            }
+            out.push('\n');
+        }
+    }
+    if !transcript.errors.is_empty() {
+        out.push_str("\n## Errors\n\n");
+        for error in &transcript.errors {
+            out.push_str(&format!("- {error}\n"));
+        }
+    }
+    if !transcript.citations.is_empty() {
+        out.push_str("\n## Citations\n\n");
+        for url in &transcript.citations {
+            out.push_str(&format!("- <{url}>\n"));
+        }
+    }
+    if let Some(text) = &transcript.final_text {
+        out.push_str(&format!("\n## Final response\n\n{}\n", text.trim()));
+    }
+    out
+}
+
+pub fn write(
+    app: &AppHandle,
+    transcript: &RunTranscript,
+    format: TranscriptFormat,
+) -> Result<(PathBuf, u64), String> {
+    let (extension, body) = match format {
+        TranscriptFormat::Markdown => ("md", render_markdown(transcript)),
+        TranscriptFormat::Json => (
+            "json",
+            serde_json::to_string_pretty(transcript)
+                .map_err(|e| format!("Failed to serialize transcript: {e}"))?,
+        ),
+    };
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {e}"))?
+        .join("transcripts");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create transcripts dir: {e}"))?;
+    let path = dir.join(format!(
+        "run-{}-{stamp}.{extension}",
+        entry_stem(&transcript.request_id)
+    ));
+    fs::write(&path, &body).map_err(|e| format!("Failed to write transcript: {e}"))?;
+    Ok((path, body.len() as u64))
+}
+
+fn kind(event: &Value) -> &str {
+    event
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+fn run_messages(
+    messages: &[SessionMessage],
+    started_at_ms: Option<i64>,
+    finished_at_ms: Option<i64>,
+) -> Vec<TranscriptMessage> {
+    let Some(started) = started_at_ms else {
+        return Vec::new();
+    };
+    let from = messages
+        .iter()
+        .filter(|m| m.role == "user" && m.created_at_ms <= started)
+        .map(|m| m.created_at_ms)
+        .max()
+        .unwrap_or(started);
+    messages
+        .iter()
+        .filter(|m| m.created_at_ms >= from && finished_at_ms.is_none_or(|f| m.created_at_ms <= f))
+        .map(|m| TranscriptMessage {
+            role: m.role.clone(),
+            text: m.text.clone(),
+            created_at_ms: m.created_at_ms,
+        })
+        .collect()
+}
+
+pub(crate) fn extract_urls(text: &str) -> Vec<String> {
+    url_spans(text)
+        .into_iter()
+        .map(|span| text[span].to_string())
+        .collect()
+}
+
+// Byte ranges of the bare URLs in `text`, in order and never overlapping.
+pub(crate) fn url_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = [
+        text[offset..].find("http://"),
+        text[offset..].find("https://"),
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    {
+        let candidate = &text[offset + start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | ']' | '>' | '"' | '\'' | '`'))
+            .unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(['.', ',', ';', ':']);
+        if url.len() > "https://".len() {
+            spans.push(offset + start..offset + start + url.len());
+        }
+        offset += start + end;
+    }
+    spans
+}
+
+fn format_offset(ms: i64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{build, extract_urls, render_markdown};
+    use crate::types::SessionMessage;
+
+    #[test]
+    fn builds_transcript_from_buffered_events() {
+        let events = vec![
+            (1_000, json!({"kind": "stream_open"})),
+            (
+                1_100,
+                json!({"kind": "stream_progress", "percent": 12, "stage": "Understanding request"}),
+            ),
+            (
+                1_500,
+                json!({"kind": "stream_tool", "phase": "start", "name": "brave_search", "query": "pet sitting apps"}),
+            ),
+            (
+                1_600,
+                json!({"kind": "stream_progress", "percent": 12, "stage": "Understanding request"}),
+            ),
+            (
+                4_000,
+                json!({"kind": "stream_message", "text": "GO. See [Rover](https://rover.com/about), https://example.com/a."}),
+            ),
+            (
+                4_200,
+                json!({"kind": "stream_done", "usage": {"totalTokenCount": 42}}),
+            ),
+        ];
+        let message = |role: &str, at: i64| SessionMessage {
+            id: format!("m{at}"),
+            session_id: "s1".to_string(),
+            role: role.to_string(),
+            text: format!("{role} at {at}"),
+            status: "done".to_string(),
+            created_at_ms: at,
+            stats: None,
+        };
+        let messages = vec![
+            message("user", 100),
+            message("user", 900),
+            message("assistant", 4_100),
+            message("user", 9_000),
+        ];
+
+        let transcript = build("req-1", Some("s1".to_string()), &events, &messages);
+        assert_eq!(transcript.outcome, "completed");
+        assert_eq!(transcript.duration_ms, Some(3_200));
+        assert_eq!(transcript.stages.len(), 1);
+        assert_eq!(transcript.tools[0].offset_ms, 500);
+        assert_eq!(
+            transcript.citations,
+            vec!["https://rover.com/about", "https://example.com/a"]
+        );
+        assert_eq!(transcript.messages.len(), 2);
+        assert_eq!(transcript.messages[0].created_at_ms, 900);
+
+        let markdown = render_markdown(&transcript);
+        assert!(markdown.contains("## Tool calls"));
+        assert!(markdown.contains("- <https://rover.com/about>"));
+        assert_eq!(
+            extract_urls("see http://a.io and https://b.io"),
+            vec!["http://a.io", "https://b.io"]
+        );
+    }
+}
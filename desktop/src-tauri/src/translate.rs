@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+const DEFAULT_TRANSLATION_MODEL: &str = "gemini-3-flash-preview";
+pub const GEMINI_MODELS_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+const MAX_LANG_CHARS: usize = 40;
+
+pub fn translation_model() -> String {
+    ["PV_DESKTOP_TRANSLATION_MODEL", "PV_LLM_ANALYSIS_MODEL"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_TRANSLATION_MODEL.to_string())
+}
+
+pub fn normalize_lang(lang: &str) -> Result<String, String> {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        return Err("Target language is required.".to_string());
+    }
+    if lang.chars().count() > MAX_LANG_CHARS
+        || !lang
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+    {
+        return Err(format!("'{lang}' is not a valid target language."));
+    }
+    Ok(lang.to_lowercase())
+}
+
+fn translation_prompt(report: &str, lang: &str) -> String {
+    format!(
+        "Translate the following product validation report into {lang}. Preserve the Markdown structure, headings, tables, links, numbers and company or product names exactly. Return only the translated report.\n\n{report}"
+    )
+}
+
+pub async fn translate_report(
+    api_key: &str,
+    model: &str,
+    report: &str,
+    lang: &str,
+) -> Result<String, String> {
+    let client = crate::http::client_builder()
+        .timeout(Duration::from_secs(180))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+
+    let response = client
+        .post(format!("{GEMINI_MODELS_URL}/{model}:generateContent"))
+        .header("x-goog-api-key", api_key)
+        .json(&json!({
+            "contents": [{
+                "role": "user",
+                "parts": [{ "text": translation_prompt(report, lang) }]
+            }]
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to call translation model: {e}"))?;
+
+    let status = response.status();
+    let payload = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse translation response: {e}"))?;
+    if !status.is_success() {
+        let detail = payload
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .unwrap_or("no details");
+        return Err(format!(
+            "Translation model returned HTTP {status}: {detail}"
+        ));
+    }
+
+    extract_text(&payload).ok_or_else(|| "Translation model returned no text.".to_string())
+}
+
+fn extract_text(payload: &Value) -> Option<String> {
+    let parts = payload.pointer("/candidates/0/content/parts")?.as_array()?;
+    let text: String = parts
+        .iter()
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{extract_text, normalize_lang};
+
+    #[test]
+    fn joins_candidate_text_parts() {
+        let payload = json!({
+            "candidates": [{
+                "content": { "parts": [{ "text": "## Veredicto\n" }, { "text": "Prometedor" }] }
+            }]
+        });
+        assert_eq!(
+            extract_text(&payload).as_deref(),
+            Some("## Veredicto\nPrometedor")
+        );
+        assert_eq!(extract_text(&json!({ "candidates": [] })), None);
+    }
+
+    #[test]
+    fn normalizes_and_validates_target_language() {
+        assert_eq!(normalize_lang(" pt-BR ").unwrap(), "pt-br");
+        assert!(normalize_lang("").is_err());
+        assert!(normalize_lang("es; drop table").is_err());
+    }
+}
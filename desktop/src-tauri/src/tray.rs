@@ -0,0 +1,148 @@
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::commands::{self, AppState};
+use crate::events;
+use crate::redact;
+use crate::types::BackendStatus;
+
+const TRAY_ID: &str = "main";
+const MAIN_WINDOW: &str = "main";
+const STATUS_ITEM: &str = "tray-status";
+const START_ITEM: &str = "tray-backend-start";
+const STOP_ITEM: &str = "tray-backend-stop";
+const NEW_IDEA_ITEM: &str = "tray-new-idea";
+const SHOW_ITEM: &str = "tray-show";
+const QUIT_ITEM: &str = "tray-quit";
+
+// Items whose text or enabled state follows the backend status.
+struct TrayItems {
+    status: MenuItem<Wry>,
+    start: MenuItem<Wry>,
+    stop: MenuItem<Wry>,
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let status = MenuItem::with_id(app, STATUS_ITEM, "Backend: checking…", false, None::<&str>)?;
+    let start = MenuItem::with_id(app, START_ITEM, "Start backend", true, None::<&str>)?;
+    let stop = MenuItem::with_id(app, STOP_ITEM, "Stop backend", false, None::<&str>)?;
+    let new_idea = MenuItem::with_id(app, NEW_IDEA_ITEM, "New idea", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, SHOW_ITEM, "Show window", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT_ITEM, "Quit", true, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &start,
+            &stop,
+            &PredefinedMenuItem::separator(app)?,
+            &new_idea,
+            &show,
+            &quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Product Validator: backend stopped")
+        .menu(&menu)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    app.manage(TrayItems {
+        status,
+        start,
+        stop,
+    });
+    Ok(())
+}
+
+// Called wherever `backend-status` is emitted; a no-op when the tray failed to initialise.
+pub fn show_backend_status(app: &AppHandle, status: &BackendStatus) {
+    let Some(items) = app.try_state::<TrayItems>() else {
+        return;
+    };
+    let label = status_label(status.running, status.health);
+    let _ = items.status.set_text(format!("Backend: {label}"));
+    let _ = items.start.set_enabled(!status.running);
+    let _ = items.stop.set_enabled(status.running);
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("Product Validator: backend {label}")));
+    }
+}
+
+fn status_label(running: bool, health: bool) -> &'static str {
+    match (running, health) {
+        (false, _) => "stopped",
+        (true, true) => "healthy",
+        (true, false) => "unhealthy",
+    }
+}
+
+// Closing the window mid-run hides it instead, so a long validation keeps streaming from the
+// tray. With nothing in flight the window closes as usual, and session windows always do.
+pub fn should_hide_on_close(app: &AppHandle, label: &str) -> bool {
+    if label != MAIN_WINDOW || app.tray_by_id(TRAY_ID).is_none() {
+        return false;
+    }
+    app.state::<AppState>()
+        .stream_tokens
+        .try_lock()
+        .map(|tokens| !tokens.is_empty())
+        .unwrap_or(true)
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        START_ITEM => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                if let Err(err) = commands::backend_start(app.clone(), state, None).await {
+                    redact::log("[tray] backend start failed:", err);
+                }
+            });
+        }
+        STOP_ITEM => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<AppState>();
+                if let Err(err) = commands::backend_stop(app.clone(), state).await {
+                    redact::log("[tray] backend stop failed:", err);
+                }
+            });
+        }
+        NEW_IDEA_ITEM => new_idea(app),
+        SHOW_ITEM => show_main_window(app),
+        QUIT_ITEM => app.exit(0),
+        _ => {}
+    }
+}
+
+pub fn new_idea(app: &AppHandle) {
+    show_main_window(app);
+    let _ = app.emit(&events::name(events::NEW_IDEA), ());
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::status_label;
+
+    #[test]
+    fn labels_follow_running_and_health() {
+        assert_eq!(status_label(false, true), "stopped");
+        assert_eq!(status_label(true, true), "healthy");
+        assert_eq!(status_label(true, false), "unhealthy");
+    }
+}
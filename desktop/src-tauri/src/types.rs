@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum SessionPhase {
     IdeaInput,
@@ -30,13 +32,80 @@ pub enum RunMode {
     Approve,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl RunMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Idea => "idea",
+            Self::EditPlan => "edit_plan",
+            Self::Approve => "approve",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamTransport {
+    #[default]
+    Sse,
+    Live,
+}
+
+// Collapsed replay sends the whole history as one context message instead of one /run per turn.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMode {
+    #[default]
+    PerMessage,
+    Collapsed,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(
+    tag = "mode",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum PortStrategy {
+    #[default]
+    Fixed,
+    Range {
+        start: u16,
+        end: u16,
+    },
+    Ephemeral,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BackendStartConfig {
     pub host: Option<String>,
     pub port: Option<u16>,
     pub repo_root: Option<String>,
     pub force_restart: Option<bool>,
+    #[serde(default)]
+    pub remote_url: Option<String>,
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    #[serde(default)]
+    pub supervise: Option<bool>,
+    #[serde(default)]
+    pub health_path: Option<String>,
+    #[serde(default)]
+    pub health_status: Option<u16>,
+    #[serde(default)]
+    pub startup_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub port_strategy: Option<PortStrategy>,
+    // PEM bundle trusted in addition to the default roots; an empty string clears it.
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    #[serde(default)]
+    pub accept_invalid_certs: Option<bool>,
+    #[serde(default)]
+    pub unix_socket: Option<bool>,
+    // Key profile whose API keys the spawned backend gets; unset means the default keys.
+    #[serde(default)]
+    pub key_profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +118,211 @@ pub struct BackendStatus {
     pub host: String,
     pub base_url: String,
     pub last_error: Option<String>,
+    pub stale: bool,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(default)]
+    pub launcher: Option<String>,
+    #[serde(default)]
+    pub supervised: bool,
+    #[serde(default)]
+    pub restart_count: u32,
+    #[serde(default)]
+    pub adk_version: Option<String>,
+    #[serde(default)]
+    pub run_sse_supported: Option<bool>,
+    #[serde(default)]
+    pub compat_warning: Option<String>,
+    #[serde(default)]
+    pub resources: Option<BackendResources>,
+    #[serde(default)]
+    pub custom_ca: bool,
+    #[serde(default)]
+    pub insecure_tls: bool,
+    #[serde(default)]
+    pub guarded: bool,
+    #[serde(default)]
+    pub unix_socket: Option<String>,
+    #[serde(default)]
+    pub startup_failure: Option<StartupFailure>,
+    #[serde(default)]
+    pub key_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupFailureKind {
+    MissingApiKey,
+    ModuleNotFound,
+    AddressInUse,
+    PythonVersion,
+    Timeout,
+    Exited,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupFailure {
+    pub kind: StartupFailureKind,
+    pub hint: String,
+    pub log_tail: String,
+}
+
+// CPU is summed across the backend's process tree, so it can exceed 100 on multi-core machines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendResources {
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub process_count: u32,
+    pub sampled_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    pub id: String,
+    pub label: String,
+    pub status: DoctorStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInstanceStartInput {
+    pub name: String,
+    #[serde(default)]
+    pub config: Option<BackendStartConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInstanceStatus {
+    pub name: String,
+    pub status: BackendStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogsInput {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub contains: Option<String>,
+    #[serde(default)]
+    pub stderr_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogExport {
+    pub path: String,
+    pub current_path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendUpdateResult {
+    pub ok: bool,
+    pub updated: bool,
+    pub head_before: String,
+    pub head_after: String,
+    pub restarted: bool,
+    pub message: Option<String>,
+    pub tail: Vec<String>,
+    pub backend: Option<BackendStatus>,
+}
+
+// `phase` is `started`, `output` (one per line), or `finished`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInstallProgress {
+    pub phase: String,
+    pub stream: Option<String>,
+    pub line: Option<String>,
+    pub exit_code: Option<i32>,
+    pub timestamp_ms: i64,
+}
+
+// `phase` is `checking`, `restarting`, `recovered`, or `failed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendRecoveryProgress {
+    pub instance: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub phase: String,
+    pub message: Option<String>,
+    pub timestamp_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendInstallResult {
+    pub ok: bool,
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub tail: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendLogLine {
+    pub stream: String,
+    pub line: String,
+    pub timestamp_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendAuth {
+    pub base_url: String,
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<BackendHeader>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendAuthGetInput {
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendAuthSummary {
+    pub base_url: String,
+    pub bearer_token_set: bool,
+    pub bearer_token_masked: Option<String>,
+    pub header_names: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +384,22 @@ pub struct SessionMessage {
     pub text: String,
     pub status: String,
     pub created_at_ms: i64,
+    pub stats: Option<TextStats>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub word_count: u32,
+    pub char_count: u32,
+    pub reading_minutes: u32,
+}
+
+// Payload of `navigate-session`, raised by an idea-validator://session/<id> link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionNavigation {
+    pub session_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,6 +423,257 @@ pub struct SessionPhaseSetInput {
     pub read_only: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidationScheduleInput {
+    pub session_id: String,
+    pub months: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidationGetInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidationSchedule {
+    pub session_id: String,
+    pub months: u32,
+    pub due_at_ms: i64,
+    pub follow_up_session_id: Option<String>,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevalidationFollowUp {
+    pub schedule: RevalidationSchedule,
+    pub session: SessionMeta,
+    pub prefill_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEntry {
+    pub id: String,
+    pub source_session_id: Option<String>,
+    pub kind: String,
+    pub text: String,
+    pub keywords: String,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryDeleteInput {
+    pub entry_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySettings {
+    pub enabled: bool,
+}
+
+// Unset fields fall back to the matching PV_DESKTOP_* variable and then the built-in default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub backend_host: Option<String>,
+    pub backend_port: Option<u16>,
+    pub backend_repo_root: Option<String>,
+    pub backend_supervise: Option<bool>,
+    pub backend_startup_timeout_secs: Option<u64>,
+    pub sse_idle_timeout_secs: Option<u64>,
+    pub max_run_minutes: Option<u64>,
+    pub retry_max_attempts: Option<u32>,
+    pub replay_depth: Option<usize>,
+    pub max_concurrent_runs: Option<usize>,
+    pub rate_limit_auto_resubmit: Option<bool>,
+    pub sse_stall_retry: Option<bool>,
+    pub privacy_mode: Option<bool>,
+    // Global shortcut for a new validation; "off" disables it.
+    pub new_idea_shortcut: Option<String>,
+    pub memory_enabled: Option<bool>,
+    // Raw stream events for every run, not just the ones enabled from the debug panel.
+    pub debug_events: Option<bool>,
+    pub backend_unix_socket: Option<bool>,
+    // Starts the local backend without a launch token.
+    pub backend_open: Option<bool>,
+    pub backend_insecure_tls: Option<bool>,
+    // Refuses runs against a backend older than the supported google-adk release.
+    pub strict_backend_version: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTask {
+    pub id: String,
+    pub session_id: String,
+    pub position: i64,
+    pub title: String,
+    pub done: bool,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTasksGetInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTaskUpdateInput {
+    pub task_id: String,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskExportTarget {
+    Markdown,
+    Todoist,
+    Linear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTasksExportInput {
+    pub session_id: String,
+    pub target: TaskExportTarget,
+    pub todoist_project_id: Option<String>,
+    pub linear_team_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskExportResult {
+    pub target: TaskExportTarget,
+    pub exported: usize,
+    pub markdown: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTranslateInput {
+    pub session_id: String,
+    pub lang: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTranslationsGetInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTranslation {
+    pub session_id: String,
+    pub source_message_id: String,
+    pub lang: String,
+    pub model: String,
+    pub text: String,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub provider: String,
+    pub id: String,
+    pub display_name: Option<String>,
+    pub input_token_limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCatalog {
+    pub models: Vec<ModelInfo>,
+    pub errors: Vec<String>,
+    pub fetched_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolQuota {
+    pub tool: String,
+    pub day_calls: u64,
+    pub month_calls: u64,
+    pub daily_quota: Option<u64>,
+    pub monthly_quota: u64,
+    pub remaining_today: Option<u64>,
+    pub remaining_month: u64,
+    pub estimated_calls_per_run: u64,
+    pub likely_exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportBlock {
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph {
+        text: String,
+    },
+    List {
+        ordered: bool,
+        items: Vec<ReportListItem>,
+    },
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    Callout {
+        tone: String,
+        title: Option<String>,
+        children: Vec<ReportBlock>,
+    },
+    Quote {
+        children: Vec<ReportBlock>,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    Rule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportListItem {
+    pub text: String,
+    pub checked: Option<bool>,
+    pub children: Vec<ReportBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportBlocksGetInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredReportBlocks {
+    pub session_id: String,
+    pub blocks: Vec<ReportBlock>,
+    pub stats: Option<TextStats>,
+    pub created_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Degradation {
+    pub kind: String,
+    pub detail: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamRunInput {
@@ -143,25 +684,548 @@ pub struct StreamRunInput {
     pub text: String,
     pub run_mode: RunMode,
     pub invocation_id: Option<String>,
+    #[serde(default)]
+    pub transport: Option<StreamTransport>,
+    #[serde(default)]
+    pub max_duration_secs: Option<u64>,
+    #[serde(default)]
+    pub debug_events: Option<bool>,
+    #[serde(default)]
+    pub env_overrides: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    pub auto_resubmit: Option<bool>,
+    #[serde(default)]
+    pub replay_mode: Option<ReplayMode>,
+    #[serde(default)]
+    pub parts: Option<Vec<MessagePartInput>>,
+    // Named backend instance to run against; unset means the default backend.
+    #[serde(default)]
+    pub backend_instance: Option<String>,
+    // Runs on this key profile's API keys, restarting the backend if it uses different ones.
+    #[serde(default)]
+    pub key_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedRun {
+    pub session_id: String,
+    pub title: String,
+    pub request_id: Option<String>,
+    pub started_at_ms: Option<i64>,
+    pub resumable: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResumeInput {
+    pub session_id: String,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResumeResult {
+    pub request_id: String,
+    pub reattached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum MessagePartInput {
+    Text {
+        text: String,
+    },
+    File {
+        path: String,
+        #[serde(default)]
+        mime_type: Option<String>,
+    },
+    InlineData {
+        data: String,
+        mime_type: String,
+        #[serde(default)]
+        display_name: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDebugInput {
+    pub request_id: Option<String>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptEstimateInput {
+    pub app_name: String,
+    pub user_id: String,
+    pub session_id: String,
+    pub text: String,
+    pub run_mode: RunMode,
+    #[serde(default)]
+    pub context_window_tokens: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct PromptEstimate {
+    pub idea_tokens: u64,
+    pub replay_tokens: u64,
+    pub replay_messages: u32,
+    pub total_tokens: u64,
+    pub context_window: u64,
+    pub usage_ratio: f64,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamRunMultiInput {
+    #[serde(flatten)]
+    pub run: StreamRunInput,
+    pub app_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiRunTarget {
+    pub app_name: String,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct KeysInput {
     pub google_api_key: Option<String>,
     pub brave_api_key: Option<String>,
     pub gemini_api_key: Option<String>,
+    #[serde(default)]
+    pub todoist_api_token: Option<String>,
+    #[serde(default)]
+    pub linear_api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyValidity {
+    Valid,
+    Invalid,
+    Missing,
+    Unreachable,
+}
+
+// `key` uses the camelCase field name from `KeysInput`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyValidation {
+    pub key: String,
+    pub validity: KeyValidity,
+    pub detail: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct KeyProfileInput {
+    pub name: String,
+    pub keys: KeysInput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretSetInput {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretSummary {
+    pub name: String,
+    pub set: bool,
+    pub masked: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyProfileSummary {
+    pub name: String,
+    pub google_api_key_set: bool,
+    pub brave_api_key_set: bool,
+    pub gemini_api_key_set: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStorageKind {
+    #[default]
+    Keychain,
+    EncryptedFile,
+    // Key file sealed with the machine-bound passphrase: hidden from casual reads, not protected.
+    ObfuscatedFile,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct KeyPresence {
     pub google_api_key_set: bool,
+    pub google_oauth_set: bool,
     pub brave_api_key_set: bool,
     pub gemini_api_key_set: bool,
+    pub todoist_api_token_set: bool,
+    pub linear_api_key_set: bool,
     pub google_api_key_masked: Option<String>,
     pub brave_api_key_masked: Option<String>,
     pub gemini_api_key_masked: Option<String>,
+    pub todoist_api_token_masked: Option<String>,
+    pub linear_api_key_masked: Option<String>,
+    pub session_keys: Vec<String>,
+    pub storage: KeyStorageKind,
+    pub storage_detail: Option<String>,
+    pub locked: bool,
+    // Backend keys found outside the key store; filled in where the repo root is known.
+    #[serde(default)]
+    pub external_keys: Vec<ExternalKey>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyOrigin {
+    Environment,
+    DotEnv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalKey {
+    // camelCase field name from `KeysInput`.
+    pub name: String,
+    pub env_var: String,
+    pub origin: KeyOrigin,
+    // Human-readable source, e.g. "App environment" or the .env path.
+    pub source: String,
+    pub masked: String,
+    // False when a stored key takes precedence.
+    pub in_use: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleOAuthStatus {
+    pub signed_in: bool,
+    pub email: Option<String>,
+    pub project_id: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleOAuthSignInInput {
+    pub project_id: String,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub passphrase: bool,
+    pub biometric: bool,
+    pub biometric_available: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyLockConfigInput {
+    pub passphrase: Option<String>,
+    #[serde(default)]
+    pub biometric: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUnlockInput {
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvKeyCandidate {
+    pub name: String,
+    pub env_var: String,
+    pub masked: String,
+    pub stored_in_keychain: bool,
+    pub matches_keychain: bool,
+    pub active_for_session: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeysExportEnvInput {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeysEnvExport {
+    pub path: String,
+    pub variables: Vec<String>,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvKeysImportInput {
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub persist: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventScheme {
+    #[default]
+    Legacy,
+    Versioned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsHandshakeInput {
+    pub client_schema_version: Option<u32>,
+    pub scheme: Option<EventScheme>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventNames {
+    pub backend_status: String,
+    pub backend_exited: String,
+    pub backend_log: String,
+    pub backend_install: String,
+    pub backend_metrics: String,
+    pub backend_update: String,
+    pub backend_recovery: String,
+    pub revalidation_due: String,
+    pub new_idea: String,
+    pub navigate_session: String,
+    pub agent_stream_prefix: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsCapabilities {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub scheme: EventScheme,
+    pub names: EventNames,
+    pub stream_event_kinds: Vec<String>,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunnelStage {
+    pub stage: String,
+    pub count: u64,
+    pub conversion_from_previous: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseDuration {
+    pub phase: SessionPhase,
+    pub average_ms: i64,
+    pub samples: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyThroughput {
+    pub month: String,
+    pub ideas: u64,
+    pub completed: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsFunnel {
+    pub stages: Vec<FunnelStage>,
+    pub phase_durations: Vec<PhaseDuration>,
+    pub monthly: Vec<MonthlyThroughput>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingState {
+    #[default]
+    Idle,
+    Indexing,
+    Paused,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingPending {
+    pub fts: u64,
+    pub embeddings: u64,
+    pub summaries: u64,
+    pub thumbnails: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingStatus {
+    pub state: IndexingState,
+    pub pending: IndexingPending,
+    pub indexed_total: u64,
+    pub last_pass_at_ms: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdkSessionsGc {
+    pub deleted: u32,
+    pub failed: u32,
+    pub remaining: u64,
+    pub skipped_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDebugBundleInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTranscriptExportInput {
+    pub request_id: String,
+    #[serde(default)]
+    pub format: TranscriptFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTranscriptExport {
+    pub path: String,
+    pub format: TranscriptFormat,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportExportInput {
+    pub session_id: String,
+    #[serde(default)]
+    pub format: ReportFormat,
+    // A file or directory; defaults to the app's reports folder.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportOpenInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWindowInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWindow {
+    pub label: String,
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportExport {
+    pub path: String,
+    pub format: ReportFormat,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugBundle {
+    pub path: String,
+    pub size_bytes: u64,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunMetrics {
+    pub time_to_first_byte_ms: Option<u64>,
+    pub time_to_first_text_ms: Option<u64>,
+    pub duration_ms: u64,
+    pub bytes_received: u64,
+    pub event_count: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    Active,
+    Queued,
+    Cancelled,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunStatus {
+    pub request_id: String,
+    pub session_id: Option<String>,
+    pub state: RunState,
+    pub paused: bool,
+    pub queue_position: Option<usize>,
+    pub stage: Option<String>,
+    pub percent: Option<u64>,
+    pub error: Option<String>,
+    pub last_seq: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use tauri::{AppHandle, EventTarget, Manager, WebviewUrl, WebviewWindowBuilder};
+
+const SESSION_WINDOW_PREFIX: &str = "session-";
+
+// Window label -> the session that window is scoped to. The main window is never listed and
+// receives every event.
+fn scoped() -> MutexGuard<'static, HashMap<String, String>> {
+    static SCOPED: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    SCOPED
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+// Opens a window pinned to one session, or brings the existing one forward. Returns its label.
+pub fn open_session(app: &AppHandle, session_id: &str, title: &str) -> Result<String, String> {
+    let label = session_label(session_id)?;
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(label);
+    }
+
+    // Scoped before it exists, so the new window never sees another session's events.
+    scoped().insert(label.clone(), session_id.to_string());
+    let url = WebviewUrl::App(format!("index.html?session={session_id}").into());
+    WebviewWindowBuilder::new(app, label.clone(), url)
+        .title(format!("Product Validator: {title}"))
+        .inner_size(960.0, 760.0)
+        .min_inner_size(640.0, 480.0)
+        .build()
+        .map_err(|e| {
+            forget(&label);
+            format!("failed to open a window for session {session_id}: {e}")
+        })?;
+    Ok(label)
+}
+
+pub fn forget(label: &str) {
+    scoped().remove(label);
+}
+
+// Stream events for a run go to unscoped windows and to the window scoped to the run's session.
+pub fn receives(target: &EventTarget, session_id: Option<&str>) -> bool {
+    let label = match target {
+        EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label }
+        | EventTarget::AnyLabel { label } => label,
+        _ => return true,
+    };
+    accepts(scoped().get(label).map(String::as_str), session_id)
+}
+
+fn accepts(scope: Option<&str>, session_id: Option<&str>) -> bool {
+    match scope {
+        None => true,
+        Some(scope) => session_id == Some(scope),
+    }
+}
+
+// Labels only allow a limited character set, and session ids are validated the same way the
+// deep links are.
+fn session_label(session_id: &str) -> Result<String, String> {
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("'{session_id}' is not a valid session id"));
+    }
+    Ok(format!("{SESSION_WINDOW_PREFIX}{session_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{accepts, session_label};
+
+    #[test]
+    fn scoped_windows_only_receive_their_session() {
+        assert!(accepts(None, Some("a")));
+        assert!(accepts(None, None));
+        assert!(accepts(Some("a"), Some("a")));
+        assert!(!accepts(Some("a"), Some("b")));
+        assert!(!accepts(Some("a"), None));
+    }
+
+    #[test]
+    fn labels_reject_unsafe_session_ids() {
+        assert_eq!(session_label("abc-1").as_deref(), Ok("session-abc-1"));
+        assert!(session_label("").is_err());
+        assert!(session_label("../x").is_err());
+        assert!(session_label("a?b").is_err());
+    }
+}
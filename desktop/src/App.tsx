@@ -6,6 +6,8 @@ import {
   backendListApps,
   backendStart,
   backendStatus,
+  deepLinkTake,
+  errorMessage,
   keysGetMasked,
   keysSet,
   sessionMessagesAppend,
@@ -14,10 +16,12 @@ import {
   sessionCreate,
   sessionDelete,
   sessionList,
+  sessionOpenWindow,
   streamCancel,
   streamRun
 } from "./lib/api";
 import { appendMessage } from "./lib/chat";
+import { describeError } from "./lib/errors";
 import { upsertToolEvent } from "./lib/toolEvents";
 import type {
   AgentStreamPayload,
@@ -27,11 +31,14 @@ import type {
   RunMode,
   SessionPhase,
   RunState,
-  SessionMeta
+  SessionMeta,
+  SessionNavigation
 } from "./lib/types";
 
 const USER_ID = "local-user";
 const APP_STORAGE_KEY = "pv_desktop_selected_app";
+// Set in windows opened for one session; those stay on that session.
+const WINDOW_SESSION_ID = new URLSearchParams(window.location.search).get("session") ?? "";
 type StreamProgressPayload = Extract<AgentStreamPayload, { kind: "stream_progress" }>;
 type StreamToolDisplayEvent = ReturnType<typeof upsertToolEvent>[number];
 type SummarySection = { title: string; body: string };
@@ -243,7 +250,7 @@ export default function App() {
   const [apps, setApps] = useState<string[]>([]);
   const [selectedApp, setSelectedApp] = useState<string>("");
   const [sessions, setSessions] = useState<SessionMeta[]>([]);
-  const [activeSessionId, setActiveSessionId] = useState<string>("");
+  const [activeSessionId, setActiveSessionId] = useState<string>(WINDOW_SESSION_ID);
   const [messagesBySession, setMessagesBySession] = useState<Record<string, ChatMessage[]>>({});
   const [pendingAssistantBySession, setPendingAssistantBySession] = useState<Record<string, string>>({});
   const [progressBySession, setProgressBySession] = useState<Record<string, StreamProgressPayload>>({});
@@ -320,7 +327,7 @@ export default function App() {
         await refreshSessions(preferred);
       }
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setBusy(false);
     }
@@ -364,6 +371,7 @@ export default function App() {
 
     setSessions(effective);
     const nextActive =
+      (WINDOW_SESSION_ID && effective.some((s) => s.id === WINDOW_SESSION_ID) && WINDOW_SESSION_ID) ||
       (preferredSessionId && effective.some((s) => s.id === preferredSessionId) && preferredSessionId) ||
       (activeSessionId && effective.some((s) => s.id === activeSessionId) && activeSessionId) ||
       effective[0].id;
@@ -381,6 +389,35 @@ export default function App() {
     setToolEventsBySession((prev) => ({ ...prev, [created.id]: [] }));
   };
 
+  // The tray's "New idea" item and the global shortcut; the window is already in front.
+  const newSessionRef = useRef(newSession);
+  newSessionRef.current = newSession;
+  useEffect(() => {
+    if (WINDOW_SESSION_ID) return;
+    const unlisten = listen("new-idea", () => {
+      void newSessionRef.current().catch((e) => setError(errorMessage(e)));
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // idea-validator://session/<id> links, both the one that launched the app and later ones.
+  useEffect(() => {
+    if (WINDOW_SESSION_ID) return;
+    const open = (link: SessionNavigation | null) => {
+      if (link?.sessionId) setActiveSessionId(link.sessionId);
+    };
+    void deepLinkTake().then(open);
+    const unlisten = listen<SessionNavigation>("navigate-session", (event) => {
+      open(event.payload);
+      void deepLinkTake();
+    });
+    return () => {
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const removeSessionFromLocalState = (sessionId: string) => {
     setMessagesBySession((prev) => {
       const next = { ...prev };
@@ -422,7 +459,7 @@ export default function App() {
       const preferredNext = remaining[0]?.id;
       await refreshSessions(selectedApp, preferredNext);
     } catch (e) {
-      setError(`Failed to delete session: ${String(e)}`);
+      setError(`Failed to delete session: ${errorMessage(e)}`);
     } finally {
       setDeletingSessionId("");
     }
@@ -436,7 +473,7 @@ export default function App() {
     if (!activeSessionId || messagesBySession[activeSessionId]) {
       return;
     }
-    void loadMessagesForSession(activeSessionId).catch((e) => setError(String(e)));
+    void loadMessagesForSession(activeSessionId).catch((e) => setError(errorMessage(e)));
   }, [activeSessionId, messagesBySession]);
 
   useEffect(() => {
@@ -444,30 +481,25 @@ export default function App() {
       return;
     }
 
-    const timer = window.setInterval(() => {
-      void backendStatus()
-        .then((next) => {
-          setStatus(next);
-          if (!next.running && next.lastError) {
-            setError(next.lastError);
-          }
-        })
-        .catch(() => undefined);
-    }, 4000);
-
-    let unlisten: (() => void) | null = null;
+    const unlisteners: Array<() => void> = [];
+    void listen<BackendStatus>("backend-status", (event) => {
+      const next = event.payload;
+      setStatus(next);
+      if (!next.running && next.lastError) {
+        setError(next.lastError);
+      }
+    }).then((fn) => {
+      unlisteners.push(fn);
+    });
     void listen<{ message?: string }>("backend-exited", (event) => {
       setError(event.payload?.message || "Backend exited unexpectedly.");
       void backendStatus().then((next) => setStatus(next));
     }).then((fn) => {
-      unlisten = fn;
+      unlisteners.push(fn);
     });
 
     return () => {
-      window.clearInterval(timer);
-      if (unlisten) {
-        unlisten();
-      }
+      unlisteners.forEach((unlisten) => unlisten());
     };
   }, [needsInitialKeySetup]);
 
@@ -526,7 +558,7 @@ export default function App() {
       setKeyForm({ googleApiKey: "", braveApiKey: "", geminiApiKey: "" });
       await boot();
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setBusy(false);
     }
@@ -617,7 +649,7 @@ export default function App() {
       }
 
       if (payload.kind === "stream_error") {
-        const errorText = `Error: ${payload.message}`;
+        const errorText = `Error: ${describeError(payload)}`;
         setPendingAssistantBySession((prev) => ({
           ...prev,
           [sessionId]: ""
@@ -637,7 +669,7 @@ export default function App() {
         setRunState((prev) =>
           prev && prev.requestId === requestId ? { ...prev, running: false, error: payload.message } : prev
         );
-        void refreshSessions(appName, sessionId).catch((e) => setError(String(e)));
+        void refreshSessions(appName, sessionId).catch((e) => setError(errorMessage(e)));
         void unlisten();
       }
 
@@ -664,7 +696,7 @@ export default function App() {
         setRunState((prev) =>
           prev && prev.requestId === requestId ? { ...prev, running: false } : prev
         );
-        void refreshSessions(appName, sessionId).catch((e) => setError(String(e)));
+        void refreshSessions(appName, sessionId).catch((e) => setError(errorMessage(e)));
         void unlisten();
       }
     });
@@ -679,7 +711,7 @@ export default function App() {
         runMode
       });
     } catch (e) {
-      const failure = String(e);
+      const failure = errorMessage(e);
       setPendingAssistantBySession((prev) => ({
         ...prev,
         [sessionId]: ""
@@ -688,7 +720,7 @@ export default function App() {
       setRunState((prev) =>
         prev && prev.requestId === requestId ? { ...prev, running: false, error: failure } : prev
       );
-      void refreshSessions(appName, sessionId).catch((refreshError) => setError(String(refreshError)));
+      void refreshSessions(appName, sessionId).catch((refreshError) => setError(errorMessage(refreshError)));
       void unlisten();
     }
   };
@@ -1029,6 +1061,19 @@ export default function App() {
                     </div>
                     <div className="mt-1 font-mono text-[10px] text-slate-500">{s.id.slice(0, 8)}</div>
                   </button>
+                  {!WINDOW_SESSION_ID && (
+                    <button
+                      className="rounded-md border border-white/15 px-2 py-1 text-[10px] uppercase tracking-[0.12em] text-slate-300 hover:bg-white/10"
+                      type="button"
+                      onClick={(event) => {
+                        event.stopPropagation();
+                        void sessionOpenWindow(s.id).catch((e) => setError(errorMessage(e)));
+                      }}
+                      title="Open this session in its own window"
+                    >
+                      Window
+                    </button>
+                  )}
                   <button
                     className="rounded-md border border-neon-rose/40 px-2 py-1 text-[10px] uppercase tracking-[0.12em] text-rose-200 hover:bg-neon-rose/15"
                     type="button"
@@ -1,9 +1,49 @@
 import { invoke } from "@tauri-apps/api/core";
+import { describeError } from "./errors";
 import type {
   Ack,
+  AdkSessionsGc,
+  AppError,
+  AnalyticsFunnel,
+  BackendAuth,
+  BackendAuthSummary,
+  BackendInstallResult,
+  BackendInstanceStatus,
+  BackendLogExport,
+  BackendLogsInput,
   BackendStartConfig,
   BackendStatus,
+  BackendUpdateResult,
+  DebugBundle,
+  DoctorReport,
+  InterruptedRun,
+  RunResumeInput,
+  RunResumeResult,
+  RunStatus,
+  RunTranscriptExport,
+  TranscriptFormat,
+  ReportExport,
+  ReportFormat,
+  EnvKeyCandidate,
+  EnvKeysImportInput,
+  EventsCapabilities,
+  EventsHandshakeInput,
+  IndexingStatus,
+  PromptEstimate,
+  PromptEstimateInput,
+  GoogleOAuthSignInInput,
+  GoogleOAuthStatus,
+  SecretSummary,
+  KeyLockConfigInput,
+  KeyLockStatus,
   KeyPresence,
+  KeyProfileSummary,
+  KeyValidation,
+  KeysEnvExport,
+  KeysInput,
+  ModelCatalog,
+  StoredReportBlocks,
+  ToolQuota,
   SessionDeleteInput,
   SessionMessage,
   SessionMessageAppendInput,
@@ -14,7 +54,12 @@ import type {
   SessionCreateInput,
   SessionListInput,
   SessionMeta,
-  StreamRunInput
+  SessionNavigation,
+  SessionWindow,
+  Settings,
+  StreamRunInput,
+  StreamRunMultiInput,
+  MultiRunTarget
 } from "./types";
 
 export const backendStart = (config?: BackendStartConfig) =>
@@ -22,9 +67,55 @@ export const backendStart = (config?: BackendStartConfig) =>
 
 export const backendStop = () => invoke<BackendStatus>("backend_stop");
 
+export const backendInstanceStart = (name: string, config?: BackendStartConfig) =>
+  invoke<BackendInstanceStatus>("backend_instance_start", { input: { name, config } });
+
+export const backendInstanceStop = (name: string) =>
+  invoke<Ack>("backend_instance_stop", { name });
+
+export const backendInstances = () => invoke<BackendInstanceStatus[]>("backend_instances");
+
 export const backendStatus = () => invoke<BackendStatus>("backend_status");
 
-export const backendListApps = () => invoke<string[]>("backend_list_apps");
+export const backendListApps = (forceRefresh = false) =>
+  invoke<string[]>("backend_list_apps", { forceRefresh });
+
+export const backendLogs = (input?: BackendLogsInput) =>
+  invoke<string[]>("backend_logs", { input: input ?? null });
+
+export const backendLogExport = () => invoke<BackendLogExport>("backend_log_export");
+
+export const backendEnvGet = () => invoke<Record<string, string>>("backend_env_get");
+
+export const backendEnvSet = (env: Record<string, string>) =>
+  invoke<Record<string, string>>("backend_env_set", { env });
+
+// The session link that launched the app, if it has not been handled yet.
+export const deepLinkTake = () => invoke<SessionNavigation | null>("deep_link_take");
+
+// Opens (or focuses) a window that only follows this session's runs.
+export const sessionOpenWindow = (sessionId: string) =>
+  invoke<SessionWindow>("session_open_window", { input: { sessionId } });
+
+export const settingsGet = () => invoke<Settings>("settings_get");
+
+// Returns the settings as saved; backend defaults apply on the next backend start.
+export const settingsSet = (settings: Settings) => invoke<Settings>("settings_set", { settings });
+
+export const backendDoctor = () => invoke<DoctorReport>("backend_doctor");
+
+export const backendValidateRepo = (path: string) =>
+  invoke<DoctorReport>("backend_validate_repo", { path });
+
+export const backendInstall = () => invoke<BackendInstallResult>("backend_install");
+
+export const backendUpdate = () => invoke<BackendUpdateResult>("backend_update");
+
+export const backendAuthGet = (baseUrl?: string) =>
+  invoke<BackendAuthSummary>("backend_auth_get", { input: { baseUrl: baseUrl ?? null } });
+
+export const backendAuthSet = (input: BackendAuth) =>
+  invoke<BackendAuthSummary>("backend_auth_set", { input });
 
 export const sessionCreate = (input: SessionCreateInput) =>
   invoke<SessionMeta>("session_create", { input });
@@ -50,15 +141,125 @@ export const sessionPhaseSet = (input: SessionPhaseSetInput) =>
 export const streamRun = (input: StreamRunInput) =>
   invoke<Ack>("stream_run", { input });
 
+export const streamRunMulti = (input: StreamRunMultiInput) =>
+  invoke<MultiRunTarget[]>("stream_run_multi", { input });
+
 export const streamCancel = (requestId: string) =>
   invoke<Ack>("stream_cancel", { requestId });
 
-export const keysSet = (keys: {
-  googleApiKey?: string;
-  braveApiKey?: string;
-  geminiApiKey?: string;
-}) => invoke<Ack>("keys_set", { keys });
+export const streamAttach = (requestId: string, afterSeq?: number) =>
+  invoke<Ack>("stream_attach", { requestId, afterSeq });
+
+export const streamDebugEnable = (enabled: boolean, requestId?: string) =>
+  invoke<Ack>("stream_debug_enable", { input: { enabled, requestId: requestId ?? null } });
+
+export const streamStatus = (requestId?: string) =>
+  invoke<RunStatus[]>("stream_status", { requestId: requestId ?? null });
+
+export const runsInterrupted = () => invoke<InterruptedRun[]>("runs_interrupted");
+
+export const runResume = (input: RunResumeInput) =>
+  invoke<RunResumeResult>("run_resume", { input });
+
+export const streamPause = (requestId: string) =>
+  invoke<Ack>("stream_pause", { requestId });
+
+export const streamResume = (requestId: string) =>
+  invoke<Ack>("stream_resume", { requestId });
+
+export const keysSet = (keys: KeysInput) => invoke<Ack>("keys_set", { keys });
+
+export const keysValidate = (keys?: KeysInput) =>
+  invoke<KeyValidation[]>("keys_validate", { keys: keys ?? null });
+
+export const modelsList = (refresh?: boolean) =>
+  invoke<ModelCatalog>("models_list", { refresh });
+
+export const toolQuotaGet = () => invoke<ToolQuota>("tool_quota_get");
+
+export const reportBlocksGet = (sessionId: string) =>
+  invoke<StoredReportBlocks | null>("report_blocks_get", { input: { sessionId } });
+
+export const sessionDebugBundle = (sessionId: string) =>
+  invoke<DebugBundle>("session_debug_bundle", { input: { sessionId } });
+
+export const runTranscriptExport = (requestId: string, format: TranscriptFormat = "markdown") =>
+  invoke<RunTranscriptExport>("run_transcript_export", { input: { requestId, format } });
+
+// `path` may be a file or a directory; without it the report lands in the app's reports folder.
+export const reportExport = (sessionId: string, format: ReportFormat = "markdown", path?: string) =>
+  invoke<ReportExport>("report_export", { input: { sessionId, format, path } });
+
+// Opens a print-friendly HTML copy of the report in the default browser.
+export const reportOpenInBrowser = (sessionId: string) =>
+  invoke<ReportExport>("report_open_in_browser", { input: { sessionId } });
+
+export const eventsHandshake = (input?: EventsHandshakeInput) =>
+  invoke<EventsCapabilities>("events_handshake", { input: input ?? null });
+
+export const promptEstimate = (input: PromptEstimateInput) =>
+  invoke<PromptEstimate>("prompt_estimate", { input });
+
+export const analyticsFunnelGet = () => invoke<AnalyticsFunnel>("analytics_funnel_get");
+
+export const indexingStatus = () => invoke<IndexingStatus>("indexing_status");
+
+export const adkSessionsGc = () => invoke<AdkSessionsGc>("adk_sessions_gc");
 
 export const keysGetMasked = () => invoke<KeyPresence>("keys_get_masked");
 
 export const keysClear = () => invoke<Ack>("keys_clear");
+
+export const googleOAuthStatus = () => invoke<GoogleOAuthStatus>("google_oauth_status");
+
+// Opens the browser for consent and resolves once the redirect comes back (or times out).
+export const googleOAuthSignIn = (input: GoogleOAuthSignInInput) =>
+  invoke<GoogleOAuthStatus>("google_oauth_sign_in", { input });
+
+export const googleOAuthSignOut = () => invoke<GoogleOAuthStatus>("google_oauth_sign_out");
+
+export const secretsList = () => invoke<SecretSummary[]>("secrets_list");
+
+// `name` doubles as the environment variable the backend receives, e.g. SERPAPI_API_KEY.
+export const secretSet = (name: string, value: string) =>
+  invoke<SecretSummary>("secret_set", { input: { name, value } });
+
+export const secretGetMasked = (name: string) =>
+  invoke<SecretSummary>("secret_get_masked", { name });
+
+export const secretDelete = (name: string) => invoke<Ack>("secret_delete", { name });
+
+export const keysDoctor = () => invoke<DoctorReport>("keys_doctor");
+
+export const keyLockStatus = () => invoke<KeyLockStatus>("key_lock_status");
+
+// Omitting both the passphrase and biometric removes the lock.
+export const keyLockConfigure = (input: KeyLockConfigInput) =>
+  invoke<KeyLockStatus>("key_lock_configure", { input });
+
+// Without a passphrase this prompts for Touch ID.
+export const keyLockUnlock = (passphrase?: string) =>
+  invoke<KeyLockStatus>("key_lock_unlock", { input: { passphrase } });
+
+export const keyLockLock = () => invoke<KeyLockStatus>("key_lock_lock");
+
+export const keyProfilesList = () => invoke<KeyProfileSummary[]>("key_profiles_list");
+
+export const keyProfileSet = (name: string, keys: KeysInput) =>
+  invoke<KeyProfileSummary>("key_profile_set", { input: { name, keys } });
+
+export const keyProfileDelete = (name: string) => invoke<Ack>("key_profile_delete", { name });
+
+// Writes stored keys to the repo's .env in plain text; only call after the user confirms.
+export const keysExportEnv = (confirm: boolean) =>
+  invoke<KeysEnvExport>("keys_export_env", { input: { confirm } });
+
+export const keysEnvDetect = () => invoke<EnvKeyCandidate[]>("keys_env_detect");
+
+export const keysEnvImport = (input: EnvKeysImportInput) =>
+  invoke<Ack>("keys_env_import", { input });
+
+export const isAppError = (err: unknown): err is AppError =>
+  typeof err === "object" && err !== null && "kind" in err && "message" in err;
+
+export const errorMessage = (err: unknown) => (isAppError(err) ? describeError(err) : String(err));
@@ -0,0 +1,24 @@
+import { describe, expect, it } from "vitest";
+import { describeError } from "./errors";
+
+describe("error wording", () => {
+  it("renders known codes from their params", () => {
+    expect(describeError({ code: "backend_port_in_use", params: { host: "127.0.0.1", port: 8765 } })).toBe(
+      "Port 8765 is already in use on 127.0.0.1."
+    );
+    expect(describeError({ code: "rate_limited", params: { retryAfterMs: 1500 } })).toBe(
+      "Rate limited; retry in 2s."
+    );
+    expect(describeError({ code: "rate_limited", params: null })).toBe("Rate limited; retry in a moment.");
+  });
+
+  it("falls back to the message for generic codes and custom tables", () => {
+    expect(describeError({ code: "not_found", message: "Session 'x' was not found." })).toBe(
+      "Session 'x' was not found."
+    );
+    expect(describeError({ message: "boom" })).toBe("boom");
+    expect(describeError({ code: "run_cancelled" }, { run_cancelled: () => "Ausführung abgebrochen." })).toBe(
+      "Ausführung abgebrochen."
+    );
+  });
+});
@@ -0,0 +1,43 @@
+import type { ErrorCode, ErrorCodeName } from "./types";
+
+type Params = Record<string, unknown>;
+export type ErrorMessages = Partial<Record<ErrorCodeName, (params: Params) => string>>;
+
+const seconds = (ms: unknown) => (typeof ms === "number" ? Math.ceil(ms / 1000) : null);
+
+const retryIn = (ms: unknown, soon: string) => {
+  const s = seconds(ms);
+  return s === null ? soon : `retry in ${s}s`;
+};
+
+// Codes whose sentence is fully determined by their params. The generic per-kind codes are left
+// out on purpose: their `message` says more than the code does.
+export const ERROR_MESSAGES_EN: ErrorMessages = {
+  backend_startup_failed: (p) => `The backend failed to start (${String(p.reason).replace(/_/g, " ")}).`,
+  backend_no_launcher: () =>
+    "No backend launcher found. Install uv, create a .venv in the backend repo, or install Python with google-adk on PATH.",
+  backend_port_in_use: (p) => `Port ${p.port} is already in use on ${p.host}.`,
+  backend_no_free_port: (p) => `No free port on ${p.host}.`,
+  backend_unhealthy: (p) => `The backend at ${p.baseUrl} did not pass its health check.`,
+  backend_recovering: (p) => `The backend is recovering; ${retryIn(p.retryAfterMs, "retry shortly")}.`,
+  backend_http: (p) => `The backend returned HTTP ${p.status}.`,
+  rate_limited: (p) => `Rate limited; ${retryIn(p.retryAfterMs, "retry in a moment")}.`,
+  storage_busy: () => "The local session database is busy; retry in a moment.",
+  run_cancelled: () => "Run cancelled.",
+  run_cancelled_while_queued: () => "Run cancelled while queued.",
+  run_timed_out: () => "Run exceeded its time budget; partial results were kept.",
+  empty_response: () => "The agent finished without producing a response.",
+  fallback_failed: (p) =>
+    p.sseStatus == null
+      ? `Streaming failed and the fallback request returned HTTP ${p.status}.`
+      : `Streaming failed with HTTP ${p.sseStatus} and the fallback request returned HTTP ${p.status}.`
+};
+
+// Words an error from its code, falling back to the backend's English message.
+export const describeError = (
+  error: Partial<ErrorCode> & { message?: string },
+  messages: ErrorMessages = ERROR_MESSAGES_EN
+) => {
+  const render = error.code ? messages[error.code] : undefined;
+  return render ? render(error.params ?? {}) : (error.message ?? "Unexpected error.");
+};
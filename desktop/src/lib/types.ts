@@ -1,5 +1,5 @@
 export type MessageRole = "user" | "assistant" | "system";
-export type MessageStatus = "done" | "streaming" | "error";
+export type MessageStatus = "done" | "streaming" | "error" | "timed_out";
 export type SessionPhase = "idea_input" | "awaiting_approval" | "running" | "completed" | "failed";
 export type RunMode = "idea" | "edit_plan" | "approve";
 
@@ -27,6 +27,237 @@ export interface BackendStatus {
   host: string;
   baseUrl: string;
   lastError?: string;
+  stale: boolean;
+  remote: boolean;
+  launcher?: "uv" | "venv" | "python" | null;
+  supervised: boolean;
+  restartCount: number;
+  adkVersion?: string | null;
+  runSseSupported?: boolean | null;
+  compatWarning?: string | null;
+  resources?: BackendResources | null;
+  customCa: boolean;
+  insecureTls: boolean;
+  guarded: boolean;
+  unixSocket?: string | null;
+  startupFailure?: StartupFailure | null;
+  keyProfile?: string | null;
+}
+
+export type StartupFailureKind =
+  | "missing_api_key"
+  | "module_not_found"
+  | "address_in_use"
+  | "python_version"
+  | "timeout"
+  | "exited"
+  | "unknown";
+
+export interface StartupFailure {
+  kind: StartupFailureKind;
+  hint: string;
+  logTail: string;
+}
+
+export interface BackendResources {
+  pid: number;
+  cpuPercent: number;
+  memoryBytes: number;
+  processCount: number;
+  sampledAtMs: number;
+}
+
+export interface BackendLogExport {
+  path: string;
+  currentPath: string;
+  sizeBytes: number;
+}
+
+export type DoctorStatus = "pass" | "warn" | "fail";
+
+export interface DoctorCheck {
+  id: "uv" | "python" | "agent" | "keys" | "port";
+  label: string;
+  status: DoctorStatus;
+  detail: string;
+  hint?: string | null;
+}
+
+export interface DoctorReport {
+  ok: boolean;
+  checks: DoctorCheck[];
+}
+
+export interface BackendLogsInput {
+  limit?: number;
+  contains?: string;
+  stderrOnly?: boolean;
+}
+
+export interface BackendInstallProgress {
+  phase: "started" | "output" | "finished";
+  stream?: "stdout" | "stderr" | null;
+  line?: string | null;
+  exitCode?: number | null;
+  timestampMs: number;
+}
+
+export interface BackendRecoveryProgress {
+  instance: string;
+  attempt: number;
+  maxAttempts: number;
+  phase: "checking" | "restarting" | "recovered" | "failed";
+  message?: string | null;
+  timestampMs: number;
+}
+
+export interface BackendInstallResult {
+  ok: boolean;
+  command: string;
+  exitCode?: number | null;
+  durationMs: number;
+  tail: string[];
+}
+
+export interface BackendUpdateResult {
+  ok: boolean;
+  updated: boolean;
+  headBefore: string;
+  headAfter: string;
+  restarted: boolean;
+  message?: string | null;
+  tail: string[];
+  backend?: BackendStatus | null;
+}
+
+export interface BackendInstanceStatus {
+  name: string;
+  status: BackendStatus;
+}
+
+export interface BackendLogLine {
+  stream: "stdout" | "stderr";
+  line: string;
+  timestampMs: number;
+}
+
+export interface BackendHeader {
+  name: string;
+  value: string;
+}
+
+export interface BackendAuth {
+  baseUrl: string;
+  bearerToken?: string | null;
+  headers?: BackendHeader[];
+}
+
+export type EventScheme = "legacy" | "versioned";
+
+export interface EventsHandshakeInput {
+  clientSchemaVersion?: number;
+  scheme?: EventScheme;
+}
+
+export interface EventsCapabilities {
+  schemaVersion: number;
+  appVersion: string;
+  scheme: EventScheme;
+  names: {
+    backendStatus: string;
+    backendExited: string;
+    backendLog: string;
+    backendInstall: string;
+    backendMetrics: string;
+    backendUpdate: string;
+    backendRecovery: string;
+    revalidationDue: string;
+    newIdea: string;
+    navigateSession: string;
+    agentStreamPrefix: string;
+  };
+  streamEventKinds: string[];
+  features: string[];
+}
+
+export interface PromptEstimateInput {
+  appName: string;
+  userId: string;
+  sessionId: string;
+  text: string;
+  runMode: RunMode;
+  contextWindowTokens?: number;
+}
+
+export interface PromptEstimate {
+  ideaTokens: number;
+  replayTokens: number;
+  replayMessages: number;
+  totalTokens: number;
+  contextWindow: number;
+  usageRatio: number;
+  warning: string | null;
+}
+
+export interface AnalyticsFunnel {
+  stages: {
+    stage: "ideas_entered" | "plans_approved" | "completed" | "pursued";
+    count: number;
+    conversionFromPrevious: number | null;
+  }[];
+  phaseDurations: { phase: SessionPhase; averageMs: number; samples: number }[];
+  monthly: { month: string; ideas: number; completed: number }[];
+}
+
+export interface AdkSessionsGc {
+  deleted: number;
+  failed: number;
+  remaining: number;
+  skippedReason: string | null;
+}
+
+export type IndexingState = "idle" | "indexing" | "paused";
+
+export interface IndexingStatus {
+  state: IndexingState;
+  pending: {
+    fts: number;
+    embeddings: number;
+    summaries: number;
+    thumbnails: number;
+  };
+  indexedTotal: number;
+  lastPassAtMs: number | null;
+  lastError: string | null;
+}
+
+export interface DebugBundle {
+  path: string;
+  sizeBytes: number;
+  files: string[];
+}
+
+export type TranscriptFormat = "markdown" | "json";
+
+export type ReportFormat = "markdown" | "html" | "pdf";
+
+export interface ReportExport {
+  path: string;
+  format: ReportFormat;
+  sizeBytes: number;
+}
+
+export interface RunTranscriptExport {
+  path: string;
+  format: TranscriptFormat;
+  sizeBytes: number;
+}
+
+export interface BackendAuthSummary {
+  baseUrl: string;
+  bearerTokenSet: boolean;
+  bearerTokenMasked: string | null;
+  headerNames: string[];
 }
 
 export interface SessionMeta {
@@ -40,6 +271,16 @@ export interface SessionMeta {
   updatedAtMs: number;
 }
 
+// Payload of `navigate-session`, raised by an idea-validator://session/<id> link.
+export interface SessionNavigation {
+  sessionId: string;
+}
+
+export interface SessionWindow {
+  label: string;
+  sessionId: string;
+}
+
 export interface SessionMessage {
   id: string;
   sessionId: string;
@@ -47,6 +288,13 @@ export interface SessionMessage {
   text: string;
   status: MessageStatus;
   createdAtMs: number;
+  stats: TextStats | null;
+}
+
+export interface TextStats {
+  wordCount: number;
+  charCount: number;
+  readingMinutes: number;
 }
 
 export interface SessionPhaseState {
@@ -54,24 +302,338 @@ export interface SessionPhaseState {
   readOnly: boolean;
 }
 
+export interface RunMetrics {
+  timeToFirstByteMs: number | null;
+  timeToFirstTextMs: number | null;
+  durationMs: number;
+  bytesReceived: number;
+  eventCount: number;
+}
+
+export type RunState = "active" | "queued" | "cancelled" | "finished";
+
+export interface RunStatus {
+  requestId: string;
+  sessionId: string | null;
+  state: RunState;
+  paused: boolean;
+  queuePosition: number | null;
+  stage: string | null;
+  percent: number | null;
+  error: string | null;
+  lastSeq: number | null;
+}
+
+export interface InterruptedRun {
+  sessionId: string;
+  title: string;
+  requestId: string | null;
+  startedAtMs: number | null;
+  resumable: boolean;
+}
+
+export interface RunResumeInput {
+  sessionId: string;
+  requestId: string;
+}
+
+export interface RunResumeResult {
+  requestId: string;
+  reattached: boolean;
+}
+
 export interface Ack {
   ok: boolean;
   message?: string;
 }
 
+export type AppErrorKind =
+  | "invalid_input"
+  | "not_found"
+  | "missing_credentials"
+  | "keys_locked"
+  | "conflict"
+  | "backend_startup"
+  | "backend_unavailable"
+  | "backend_recovering"
+  | "backend"
+  | "network"
+  | "rate_limited"
+  | "storage"
+  | "internal";
+
+export type ErrorCodeName =
+  | AppErrorKind
+  | "backend_startup_failed"
+  | "backend_no_launcher"
+  | "backend_port_in_use"
+  | "backend_no_free_port"
+  | "backend_unhealthy"
+  | "backend_http"
+  | "run_cancelled"
+  | "run_cancelled_while_queued"
+  | "run_timed_out"
+  | "empty_response"
+  | "stream_failed"
+  | "fallback_failed"
+  | "agent_error"
+  | "storage_busy";
+
+// A stable code plus the values its sentence needs; see lib/errors.ts for the wording.
+export interface ErrorCode {
+  code: ErrorCodeName;
+  params?: Record<string, unknown> | null;
+}
+
+export interface AppError extends ErrorCode {
+  kind: AppErrorKind;
+  // English fallback for codes the UI has no wording for.
+  message: string;
+  // Raw cause (source chain, backend body) for logs and debug views; null when there is none.
+  detail: string | null;
+  retryable: boolean;
+  hint: string | null;
+  startupFailure?: StartupFailure;
+}
+
+export interface KeysInput {
+  googleApiKey?: string;
+  braveApiKey?: string;
+  geminiApiKey?: string;
+  todoistApiToken?: string;
+  linearApiKey?: string;
+}
+
+export interface KeyProfileSummary {
+  name: string;
+  googleApiKeySet: boolean;
+  braveApiKeySet: boolean;
+  geminiApiKeySet: boolean;
+}
+
+export interface KeysEnvExport {
+  path: string;
+  variables: string[];
+  warning?: string | null;
+}
+
+export type KeyValidity = "valid" | "invalid" | "missing" | "unreachable";
+
+export interface KeyValidation {
+  key: keyof KeysInput;
+  validity: KeyValidity;
+  detail?: string | null;
+}
+
+export type KeyStorageKind =
+  | "keychain"
+  | "encrypted_file"
+  | "obfuscated_file";
+
 export interface KeyPresence {
   googleApiKeySet: boolean;
+  googleOauthSet: boolean;
   braveApiKeySet: boolean;
   geminiApiKeySet: boolean;
+  todoistApiTokenSet: boolean;
+  linearApiKeySet: boolean;
   googleApiKeyMasked?: string;
   braveApiKeyMasked?: string;
   geminiApiKeyMasked?: string;
+  todoistApiTokenMasked?: string;
+  linearApiKeyMasked?: string;
+  sessionKeys: string[];
+  storage: KeyStorageKind;
+  storageDetail?: string | null;
+  locked: boolean;
+  externalKeys: ExternalKey[];
+}
+
+export type KeyOrigin = "environment" | "dot_env";
+
+export interface ExternalKey {
+  name: string;
+  envVar: string;
+  origin: KeyOrigin;
+  source: string;
+  masked: string;
+  inUse: boolean;
+}
+
+// Unset fields fall back to the matching PV_DESKTOP_* variable and then the built-in default.
+export interface Settings {
+  backendHost?: string | null;
+  backendPort?: number | null;
+  backendRepoRoot?: string | null;
+  backendSupervise?: boolean | null;
+  backendStartupTimeoutSecs?: number | null;
+  sseIdleTimeoutSecs?: number | null;
+  maxRunMinutes?: number | null;
+  retryMaxAttempts?: number | null;
+  replayDepth?: number | null;
+  maxConcurrentRuns?: number | null;
+  rateLimitAutoResubmit?: boolean | null;
+  sseStallRetry?: boolean | null;
+  privacyMode?: boolean | null;
+  // Global shortcut for a new validation, e.g. "CommandOrControl+Shift+I"; "off" disables it.
+  newIdeaShortcut?: string | null;
+  memoryEnabled?: boolean | null;
+  // Raw stream events for every run, not just the ones enabled from the debug panel.
+  debugEvents?: boolean | null;
+  backendUnixSocket?: boolean | null;
+  // Starts the local backend without a launch token.
+  backendOpen?: boolean | null;
+  backendInsecureTls?: boolean | null;
+  // Refuses runs against a backend older than the supported google-adk release.
+  strictBackendVersion?: boolean | null;
+}
+
+export interface SecretSummary {
+  name: string;
+  set: boolean;
+  masked?: string | null;
+}
+
+export interface GoogleOAuthStatus {
+  signedIn: boolean;
+  email?: string | null;
+  projectId?: string | null;
+  location?: string | null;
+}
+
+export interface GoogleOAuthSignInInput {
+  projectId: string;
+  location?: string;
+}
+
+export interface KeyLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  passphrase: boolean;
+  biometric: boolean;
+  biometricAvailable: boolean;
+}
+
+export interface KeyLockConfigInput {
+  passphrase?: string;
+  biometric?: boolean;
+}
+
+export interface EnvKeyCandidate {
+  name: string;
+  envVar: string;
+  masked: string;
+  storedInKeychain: boolean;
+  matchesKeychain: boolean;
+  activeForSession: boolean;
+}
+
+export interface EnvKeysImportInput {
+  names?: string[];
+  persist?: boolean;
+}
+
+export interface ModelInfo {
+  provider: string;
+  id: string;
+  displayName?: string;
+  inputTokenLimit?: number;
+}
+
+export interface ModelCatalog {
+  models: ModelInfo[];
+  errors: string[];
+  fetchedAtMs: number;
 }
 
-export type AgentStreamPayload =
+export interface ToolQuota {
+  tool: string;
+  dayCalls: number;
+  monthCalls: number;
+  dailyQuota?: number;
+  monthlyQuota: number;
+  remainingToday?: number;
+  remainingMonth: number;
+  estimatedCallsPerRun: number;
+  likelyExceeded: boolean;
+}
+
+export type ReportBlock =
+  | { type: "heading"; level: number; text: string }
+  | { type: "paragraph"; text: string }
+  | { type: "list"; ordered: boolean; items: ReportListItem[] }
+  | { type: "table"; headers: string[]; rows: string[][] }
+  | { type: "callout"; tone: string; title?: string; children: ReportBlock[] }
+  | { type: "quote"; children: ReportBlock[] }
+  | { type: "code"; language?: string; code: string }
+  | { type: "rule" };
+
+export interface ReportListItem {
+  text: string;
+  checked?: boolean;
+  children: ReportBlock[];
+}
+
+export interface StoredReportBlocks {
+  sessionId: string;
+  blocks: ReportBlock[];
+  stats: TextStats | null;
+  createdAtMs: number;
+}
+
+export interface Degradation {
+  kind: string;
+  detail: string;
+}
+
+export interface AgentLaneProgress {
+  author: string;
+  status: "active" | "done";
+  toolsCompleted: number;
+  toolsTotal: number;
+  sawText: boolean;
+}
+
+export type AgentStreamPayload = { seq?: number; appName?: string; parentRequestId?: string } & (
   | { kind: "stream_open"; requestId: string }
+  | { kind: "stream_queued"; requestId: string; position: number; limit: number }
+  | { kind: "stream_paused"; requestId: string; paused: boolean }
+  | { kind: "stream_report_blocks"; requestId: string; sessionId: string; blocks: ReportBlock[]; stats: TextStats | null }
   | { kind: "stream_meta"; requestId: string; invocationId: string }
-  | { kind: "stream_message"; requestId: string; text: string; source?: string }
+  | {
+      kind: "stream_artifact";
+      requestId: string;
+      name: string;
+      mimeType: string;
+      path: string | null;
+      uri: string | null;
+      sizeBytes: number | null;
+    }
+  | ({ kind: "prompt_estimate"; requestId: string } & PromptEstimate)
+  | {
+      kind: "stream_plan";
+      requestId: string;
+      source: "schema" | "markers";
+      productIdea: string | null;
+      items: { section: string; text: string }[];
+    }
+  | { kind: "stream_section"; requestId: string; index: number; level: number; title: string; body: string }
+  | { kind: "stream_thought"; requestId: string; text: string; source: string | null }
+  | { kind: "stream_message"; requestId: string; text: string; source?: string; sseEvent?: string }
+  | {
+      kind: "stream_delta";
+      requestId: string;
+      text: string;
+      reset: boolean;
+      source?: string;
+      sseEvent?: string;
+    }
+  | {
+      kind: "stream_agent_progress";
+      requestId: string;
+      activeAgent: string | null;
+      agents: AgentLaneProgress[];
+    }
   | {
       kind: "stream_progress";
       requestId: string;
@@ -88,9 +650,37 @@ export type AgentStreamPayload =
       query?: string;
       detail?: string;
     }
-  | { kind: "stream_event_raw"; requestId: string; event: unknown }
-  | { kind: "stream_error"; requestId: string; message: string; retryable: boolean }
-  | { kind: "stream_done"; requestId: string; usage?: unknown };
+  | { kind: "stream_event_raw"; requestId: string; event: unknown; sseEvent?: string }
+  | { kind: "stream_batch"; requestId: string; events: AgentStreamPayload[] }
+  | {
+      kind: "stream_finish";
+      requestId: string;
+      reason: string;
+      category: "truncated" | "safety" | "blocked" | "tool_call" | "other";
+      message: string;
+      source: string | null;
+      safety: { category: string; probability: string | null; blocked: boolean }[];
+    }
+  | {
+      kind: "stream_error";
+      requestId: string;
+      message: string;
+      retryable: boolean;
+      category?: "cancelled" | "timeout" | "empty_response" | AppErrorKind;
+      retryAfterMs?: number;
+      hint?: string | null;
+      code?: ErrorCodeName;
+      params?: Record<string, unknown> | null;
+      detail?: string | null;
+    }
+  | {
+      kind: "stream_done";
+      requestId: string;
+      usage?: unknown;
+      degradations?: Degradation[];
+      metrics?: RunMetrics | null;
+    }
+);
 
 export interface StreamRunInput {
   requestId: string;
@@ -100,6 +690,29 @@ export interface StreamRunInput {
   text: string;
   runMode: RunMode;
   invocationId?: string;
+  transport?: "sse" | "live";
+  maxDurationSecs?: number;
+  debugEvents?: boolean;
+  envOverrides?: Record<string, string>;
+  autoResubmit?: boolean;
+  replayMode?: "per_message" | "collapsed";
+  parts?: MessagePartInput[];
+  backendInstance?: string;
+  keyProfile?: string;
+}
+
+export type MessagePartInput =
+  | { type: "text"; text: string }
+  | { type: "file"; path: string; mimeType?: string }
+  | { type: "inline_data"; data: string; mimeType: string; displayName?: string };
+
+export interface StreamRunMultiInput extends StreamRunInput {
+  appNames: string[];
+}
+
+export interface MultiRunTarget {
+  appName: string;
+  requestId: string;
 }
 
 export interface SessionCreateInput {
@@ -144,4 +757,20 @@ export interface BackendStartConfig {
   port?: number;
   repoRoot?: string;
   forceRestart?: boolean;
+  remoteUrl?: string;
+  bearerToken?: string;
+  supervise?: boolean;
+  healthPath?: string;
+  healthStatus?: number;
+  startupTimeoutSecs?: number;
+  portStrategy?: PortStrategy;
+  caCertPath?: string;
+  acceptInvalidCerts?: boolean;
+  unixSocket?: boolean;
+  keyProfile?: string;
 }
+
+export type PortStrategy =
+  | { mode: "fixed" }
+  | { mode: "range"; start: number; end: number }
+  | { mode: "ephemeral" };
@@ -0,0 +1,108 @@
+"""ADK API server used by the desktop app, guarded by a per-launch shared secret.
+
+The desktop app generates a random token for every backend launch, passes it in
+``PV_DESKTOP_BACKEND_TOKEN``, and sends it back on every request in the
+``X-PV-Desktop-Token`` header. Requests without it are rejected, so other local
+processes cannot drive the agents through the loopback port.
+"""
+
+from __future__ import annotations
+
+import argparse
+import hmac
+import json
+import os
+from pathlib import Path
+from typing import Any, Awaitable, Callable
+
+TOKEN_ENV = "PV_DESKTOP_BACKEND_TOKEN"
+TOKEN_HEADER = b"x-pv-desktop-token"
+# Application-defined close code (4000-4999) mirroring HTTP 401.
+WEBSOCKET_UNAUTHORIZED = 4401
+
+Scope = dict[str, Any]
+Receive = Callable[[], Awaitable[dict[str, Any]]]
+Send = Callable[[dict[str, Any]], Awaitable[None]]
+AsgiApp = Callable[[Scope, Receive, Send], Awaitable[None]]
+
+
+class SharedSecretMiddleware:
+    """ASGI middleware that requires the shared secret on HTTP and WebSocket requests.
+
+    Attributes:
+        app: The wrapped ASGI application.
+        token: The secret every request must present.
+    """
+
+    def __init__(self, app: AsgiApp, token: str) -> None:
+        self.app = app
+        self.token = token
+
+    async def __call__(self, scope: Scope, receive: Receive, send: Send) -> None:
+        if scope["type"] not in ("http", "websocket") or self._authorized(scope):
+            await self.app(scope, receive, send)
+            return
+
+        if scope["type"] == "websocket":
+            await send({"type": "websocket.close", "code": WEBSOCKET_UNAUTHORIZED})
+            return
+
+        body = json.dumps({"detail": "Missing or invalid desktop token."}).encode()
+        await send(
+            {
+                "type": "http.response.start",
+                "status": 401,
+                "headers": [
+                    (b"content-type", b"application/json"),
+                    (b"content-length", str(len(body)).encode()),
+                ],
+            }
+        )
+        await send({"type": "http.response.body", "body": body})
+
+    def _authorized(self, scope: Scope) -> bool:
+        supplied = b""
+        for name, value in scope.get("headers") or []:
+            if name.lower() == TOKEN_HEADER:
+                supplied = value
+                break
+        return hmac.compare_digest(supplied, self.token.encode())
+
+
+def create_app(agents_dir: str, token: str | None) -> AsgiApp:
+    """Builds the ADK FastAPI app, wrapped in the shared-secret check when a token is set.
+
+    Args:
+        agents_dir: Directory containing the agent packages.
+        token: The per-launch secret, or None to serve without protection.
+
+    Returns:
+        The ASGI application to serve.
+    """
+    from google.adk.cli.fast_api import get_fast_api_app
+
+    app = get_fast_api_app(agents_dir=agents_dir, web=True)
+    return SharedSecretMiddleware(app, token) if token else app
+
+
+def main() -> None:
+    """Parses ``adk web``-style arguments and serves the app with uvicorn over TCP or a unix socket."""
+    import uvicorn
+
+    parser = argparse.ArgumentParser(description=__doc__)
+    parser.add_argument("agents_dir", nargs="?", default=str(Path.cwd()))
+    parser.add_argument("--host", default="127.0.0.1")
+    parser.add_argument("--port", type=int, default=8000)
+    parser.add_argument("--uds", help="Serve on this unix socket instead of TCP.")
+    args = parser.parse_args()
+
+    token = os.environ.get(TOKEN_ENV, "").strip() or None
+    app = create_app(str(Path(args.agents_dir).resolve()), token)
+    if args.uds:
+        uvicorn.run(app, uds=args.uds)
+    else:
+        uvicorn.run(app, host=args.host, port=args.port)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,54 @@
+"""Tests for the desktop backend's shared-secret middleware."""
+
+from product_validator_search.desktop_server import (
+    WEBSOCKET_UNAUTHORIZED,
+    SharedSecretMiddleware,
+)
+
+
+async def _call(headers, scope_type="http"):
+    reached = []
+    sent = []
+
+    async def inner(scope, receive, send):
+        reached.append(scope["path"])
+
+    async def receive():
+        return {}
+
+    async def send(message):
+        sent.append(message)
+
+    middleware = SharedSecretMiddleware(inner, "s3cret")
+    scope = {"type": scope_type, "path": "/run_sse", "headers": headers}
+    await middleware(scope, receive, send)
+    return reached, sent
+
+
+async def test_accepts_requests_with_the_launch_token():
+    reached, sent = await _call([(b"x-pv-desktop-token", b"s3cret")])
+    assert reached == ["/run_sse"]
+    assert sent == []
+
+
+async def test_rejects_http_requests_without_the_token():
+    reached, sent = await _call([(b"x-pv-desktop-token", b"wrong")])
+    assert reached == []
+    assert sent[0]["status"] == 401
+
+
+async def test_closes_websockets_without_the_token():
+    reached, sent = await _call([], scope_type="websocket")
+    assert reached == []
+    assert sent == [{"type": "websocket.close", "code": WEBSOCKET_UNAUTHORIZED}]
+
+
+async def test_lifespan_events_pass_through():
+    reached = []
+
+    async def inner(scope, receive, send):
+        reached.append(scope["type"])
+
+    middleware = SharedSecretMiddleware(inner, "s3cret")
+    await middleware({"type": "lifespan"}, None, None)
+    assert reached == ["lifespan"]